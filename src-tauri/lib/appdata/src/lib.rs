@@ -44,6 +44,13 @@ pub struct AppDataConfig {
     config_path: Option<PathBuf>,
     frontend_wdg_lvl: WdgLevel,
     deadlock_wdg_lvl: WdgLevel,
+    /// amount of full resolution values kept in memory per object entry.
+    #[serde(default = "default_history_limit")]
+    history_limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    100_000
 }
 
 
@@ -106,6 +113,13 @@ impl AppData {
         }
     }
 
+    pub fn set_history_limit(&mut self, limit: usize) {
+        if self.get_history_limit() != limit {
+            self.config.history_limit = limit;
+            self.config_change_flag = true;
+        }
+    }
+
     pub fn get_config_path(&self) -> Option<&PathBuf> {
         self.config.config_path.as_ref()
     }
@@ -118,6 +132,10 @@ impl AppData {
         self.config.deadlock_wdg_lvl
     }
 
+    pub fn get_history_limit(&self) -> usize {
+        self.config.history_limit
+    }
+
     fn appdata_path() -> PathBuf {
         let mut appdata_path = dirs::home_dir().expect("No home directory avaiable on the OS");
         appdata_path.push(".canzero");
@@ -189,6 +207,7 @@ impl Default for AppData {
                 config_path: None,
                 deadlock_wdg_lvl: WdgLevel::Active,
                 frontend_wdg_lvl: WdgLevel::Active,
+                history_limit: default_history_limit(),
             },
         }
     }
@@ -1,4 +1,9 @@
 
+#[cfg(feature = "socket-can")]
+use std::sync::Arc;
+
+#[cfg(feature = "socket-can")]
+use canzero_common::transport::CanTransport;
 use canzero_tcp::tcpcan::ConnectionId;
 
 use crate::errors::Result;
@@ -22,24 +27,7 @@ pub async fn command_client() -> Result<()> {
             );
             color_print::cprintln!("<green>Established connection to {} at {}:{}</green>", connection.server_name, connection.server_addr, connection.service_port);
 
-            let socketcan_rx = socketcan.clone();
-            let tcpcan_tx = tcpcan.clone();
-            let abort_handle = tokio::spawn(async move {
-                loop {
-                    let frame = socketcan_rx.recv().await.unwrap();
-                    let Ok(_) = tcpcan_tx.send(&frame).await else {
-                        break;
-                    };
-                }
-            }).abort_handle();
-
-            loop {
-                let Some(frame) = tcpcan.recv().await else {
-                    break;
-                };
-                socketcan.send(&frame).await.unwrap();
-            }
-            abort_handle.abort();
+            bridge(socketcan, tcpcan).await;
             color_print::cprintln!("<red>Connection closed</red>");
         }
     } else {
@@ -48,3 +36,24 @@ pub async fn command_client() -> Result<()> {
 
     Ok(())
 }
+
+/// Forwards all frames between the two transports, until one of them is closed.
+#[cfg(feature = "socket-can")]
+async fn bridge(a: Arc<dyn CanTransport>, b: Arc<dyn CanTransport>) {
+    async fn forward(from: &dyn CanTransport, to: &dyn CanTransport) {
+        while let Some(frame) = from.recv().await {
+            let result = match frame {
+                Ok(frame) => to.send(&frame).await,
+                // forwarded to the server, ignored by SocketCAN.
+                Err(error) => to.send_error(&error).await,
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+    }
+    tokio::select! {
+        _ = forward(a.as_ref(), b.as_ref()) => (),
+        _ = forward(b.as_ref(), a.as_ref()) => (),
+    }
+}
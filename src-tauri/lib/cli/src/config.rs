@@ -4,13 +4,124 @@ use std::{
     sync::Arc,
 };
 
-use canzero_appdata::AppData;
-use canzero_config::config::{self, Type};
+use canzero_appdata::{AppData, AppDataError};
+use canzero_config::config::{self, serialize::message_usage_name, Type};
+use color_print::cprintln;
 
 use crate::errors::{Error, Result};
 
-pub fn command_config_show() -> Result<()> {
-    Err(Error::NotYetImplemented)
+fn ty_to_name(ty: &Type) -> String {
+    match ty {
+        config::Type::Primitive(prim) => match prim {
+            config::SignalType::UnsignedInt { size } => format!("u{size}"),
+            config::SignalType::SignedInt { size } => format!("i{size}"),
+            config::SignalType::Decimal {
+                size,
+                offset,
+                scale,
+            } => {
+                let min = *offset;
+                let max = (2u128.pow(*size as u32) as f64 / *scale) + min;
+                format!("d{size}<{min}..{max}> (scale = {scale})")
+            }
+        },
+        config::Type::Struct {
+            name,
+            description: _,
+            attribs: _,
+            visibility: _,
+        } => format!("{name}"),
+        config::Type::Enum {
+            name,
+            description: _,
+            size: _,
+            entries: _,
+            visibility: _,
+        } => format!("{name}"),
+        config::Type::Array { len, ty } => {
+            format!("{}[{len}]", ty_to_name(ty))
+        }
+    }
+}
+
+pub fn command_config_show(json: bool) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+
+    if json {
+        let json = serde_json::to_string_pretty(network.as_ref()).map_err(std::io::Error::from)?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    println!("hash       : {}", network.portable_hash());
+    println!("build-time : {}", network.build_time());
+    println!();
+    println!("BUSES");
+    for bus in network.buses() {
+        println!("  {:6} id = {}, baudrate = {}", bus.name(), bus.id(), bus.baudrate());
+    }
+    println!();
+    println!("NODES");
+    for node in network.nodes() {
+        let buses: Vec<&str> = node.buses().iter().map(|b| b.name()).collect();
+        println!("  {} [{}] on {}", node.name(), node.id(), buses.join(", "));
+        println!("    object entries:");
+        for oe in node.object_entries() {
+            let unit = oe.unit().map_or(String::new(), |unit| format!(" [{unit}]"));
+            println!("      [{}] = {} : {}{unit}", oe.id(), oe.name(), ty_to_name(oe.ty()));
+        }
+        println!("    streams:");
+        for stream in node.tx_streams() {
+            let mapping: Vec<&str> = stream
+                .mapping()
+                .iter()
+                .map(|oe| oe.as_ref().map_or("-", |oe| oe.name()))
+                .collect();
+            println!(
+                "      {} via {} ({}ms..{}ms) : {}",
+                stream.name(),
+                stream.message().name(),
+                stream.min_interval().as_millis(),
+                stream.max_interval().as_millis(),
+                mapping.join(", ")
+            );
+        }
+        println!("    commands:");
+        for command in node.commands() {
+            println!(
+                "      {} (req = {}, resp = {})",
+                command.name(),
+                command.tx_message().name(),
+                command.rx_message().name()
+            );
+        }
+    }
+    println!();
+    println!("MESSAGES");
+    for msg in network.messages() {
+        let usage = msg
+            .__get_usage()
+            .get()
+            .map_or("unused".to_owned(), message_usage_name);
+        println!(
+            "  {}  {} [{}]  {}  ({usage})",
+            msg.bus().name(),
+            msg.id(),
+            msg.dlc(),
+            msg.name()
+        );
+        for signal in msg.signals() {
+            println!(
+                "      {:2}|{:<2} {} : {}",
+                signal.byte_offset(),
+                signal.size(),
+                signal.name(),
+                ty_to_name(&Type::Primitive(signal.ty().clone()))
+            );
+        }
+    }
+    Ok(())
 }
 
 pub fn command_config_nodes_list() -> Result<()> {
@@ -29,39 +140,6 @@ pub fn command_config_object_entries_list(node: String) -> Result<()> {
         return Err(Error::InvalidNodeName(node));
     };
     for oe in node.object_entries() {
-        fn ty_to_name(ty: &Type) -> String {
-            match ty {
-                config::Type::Primitive(prim) => match prim {
-                    config::SignalType::UnsignedInt { size } => format!("u{size}"),
-                    config::SignalType::SignedInt { size } => format!("i{size}"),
-                    config::SignalType::Decimal {
-                        size,
-                        offset,
-                        scale,
-                    } => {
-                        let min = *offset;
-                        let max = (2u128.pow(*size as u32) as f64 / *scale) + min;
-                        format!("d{size}<{min}..{max}> (scale = {scale})")
-                    }
-                },
-                config::Type::Struct {
-                    name,
-                    description: _,
-                    attribs: _,
-                    visibility: _,
-                } => format!("{name}"),
-                config::Type::Enum {
-                    name,
-                    description: _,
-                    size: _,
-                    entries: _,
-                    visibility: _,
-                } => format!("{name}"),
-                config::Type::Array { len, ty } => {
-                    format!("{}[{len}", ty_to_name(ty))
-                }
-            }
-        }
         println!("[{}] = {} : {}", oe.id(), oe.name(), ty_to_name(oe.ty()));
     }
 
@@ -158,8 +236,19 @@ pub fn command_config_messages_list(node: Option<String>, bus: Option<String>) -
 
 pub fn command_config_check() -> Result<()> {
     let appdata = AppData::read()?;
-    let _ = appdata.config()?;
-    Ok(())
+    match appdata.config() {
+        Ok(_) => {
+            cprintln!("<green>Config is valid</green>");
+            Ok(())
+        }
+        Err(AppDataError::ConfigError(canzero_yaml::errors::Error::Diagnostics(diagnostics))) => {
+            for diagnostic in &diagnostics {
+                cprintln!("<red>{diagnostic}</red>\n");
+            }
+            Err(Error::InvalidConfig(diagnostics.len()))
+        }
+        Err(err) => Err(err.into()),
+    }
 }
 
 pub fn command_config_hash() -> Result<()> {
@@ -169,3 +258,44 @@ pub fn command_config_hash() -> Result<()> {
     println!("{}", network.portable_hash());
     Ok(())
 }
+
+pub fn command_config_export_dbc(output_dir: PathBuf, bus: Option<String>) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+
+    match bus {
+        Some(bus_name) => {
+            let Some(bus) = network.buses().iter().find(|b| b.name() == bus_name) else {
+                return Err(Error::InvalidBusName(bus_name));
+            };
+            std::fs::create_dir_all(&output_dir)?;
+            std::fs::write(
+                output_dir.join(format!("{}.dbc", bus.name())),
+                network.export_dbc(bus),
+            )?;
+        }
+        None => network.export_dbc_files(&output_dir)?,
+    }
+    Ok(())
+}
+
+pub fn command_config_diff(old: PathBuf, new: PathBuf) -> Result<()> {
+    let parse = |path: &PathBuf| -> Result<config::NetworkRef> {
+        let Some(path) = path.to_str() else {
+            return Err(Error::FileNotFound(format!("{path:?}")));
+        };
+        Ok(canzero_yaml::parse_yaml_config_from_file(path).map_err(AppDataError::from)?)
+    };
+    let old = parse(&old)?;
+    let new = parse(&new)?;
+
+    println!("old hash = {}", old.portable_hash());
+    println!("new hash = {}", new.portable_hash());
+    let diff = old.diff(&new);
+    if diff.is_empty() {
+        println!("No differences");
+    } else {
+        print!("{diff}");
+    }
+    Ok(())
+}
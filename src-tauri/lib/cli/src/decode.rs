@@ -0,0 +1,169 @@
+use canzero_config::config::{
+    self, encoding::PrimitiveSignalEncoding, MessageRef, SignalRef, SignalType, Type,
+    TypeSignalEncoding,
+};
+
+/// Column names of an object entry, same layout as the export of the gui.
+pub fn type_columns(ty: &Type, column_name: Option<String>, columns: &mut Vec<String>) {
+    match ty {
+        Type::Primitive(_) | Type::Enum { .. } => {
+            columns.push(column_name.unwrap_or("value".to_owned()))
+        }
+        Type::Struct { attribs, .. } => {
+            for (attrib_name, attrib_type) in attribs {
+                let column_name = match &column_name {
+                    Some(column_name) => format!("{column_name}.{attrib_name}"),
+                    None => attrib_name.to_owned(),
+                };
+                type_columns(attrib_type, Some(column_name), columns);
+            }
+        }
+        Type::Array { len, ty } => {
+            let column_name = column_name.unwrap_or("value".to_owned());
+            for i in 0..*len {
+                type_columns(ty, Some(format!("{column_name}[{i}]")), columns);
+            }
+        }
+    }
+}
+
+fn primitive_to_string(signal_type: &SignalType, raw: u64) -> String {
+    match signal_type {
+        SignalType::UnsignedInt { .. } => format!("{raw}"),
+        SignalType::SignedInt { size } => {
+            let shift = 64 - *size as u32;
+            format!("{}", ((raw << shift) as i64) >> shift)
+        }
+        SignalType::Decimal { offset, scale, .. } => format!("{}", raw as f64 * scale + offset),
+    }
+}
+
+fn enum_to_string(entries: &[(String, u64)], raw: u64) -> String {
+    entries
+        .iter()
+        .find(|(_, value)| *value == raw)
+        .map_or("UNDEFINED".to_owned(), |(name, _)| name.clone())
+}
+
+/// reads size bits starting at bit offset, the least significant bit of the first word is bit 0.
+fn extract_bits(words: &[u32], offset: usize, size: usize) -> u64 {
+    let mut raw = 0u64;
+    for i in 0..size {
+        let bit = offset + i;
+        let word = words.get(bit / 32).copied().unwrap_or(0);
+        raw |= (((word >> (bit % 32)) & 1) as u64) << i;
+    }
+    raw
+}
+
+/// decodes a value that was transmitted as a bitstream (get responses).
+pub fn decode_type(ty: &Type, words: &[u32], offset: &mut usize, values: &mut Vec<String>) {
+    match ty {
+        Type::Primitive(signal_type) => {
+            let size = signal_type.size() as usize;
+            values.push(primitive_to_string(
+                signal_type,
+                extract_bits(words, *offset, size),
+            ));
+            *offset += size;
+        }
+        Type::Enum { size, entries, .. } => {
+            values.push(enum_to_string(
+                entries,
+                extract_bits(words, *offset, *size as usize),
+            ));
+            *offset += *size as usize;
+        }
+        Type::Struct { attribs, .. } => {
+            for (_, attrib_type) in attribs {
+                decode_type(attrib_type, words, offset, values);
+            }
+        }
+        Type::Array { len, ty } => {
+            for _ in 0..*len {
+                decode_type(ty, words, offset, values);
+            }
+        }
+    }
+}
+
+/// reads the signal from the payload, the least significant bit of the first byte is bit 0.
+fn signal_raw(signal: &SignalRef, data: &[u8]) -> u64 {
+    let mut raw = 0u64;
+    for i in 0..signal.size() as usize {
+        let bit = signal.byte_offset() + i;
+        let byte = data.get(bit / 8).copied().unwrap_or(0);
+        raw |= (((byte >> (bit % 8)) & 1) as u64) << i;
+    }
+    raw
+}
+
+/// decodes an attribute of a type encoded message (streams).
+pub fn decode_encoding(encoding: &TypeSignalEncoding, data: &[u8], values: &mut Vec<String>) {
+    match encoding {
+        TypeSignalEncoding::Composite(composite) => {
+            for attrib in composite.attributes() {
+                decode_encoding(attrib, data, values);
+            }
+        }
+        TypeSignalEncoding::Primitive(primitive) => values.push(decode_primitive(primitive, data)),
+    }
+}
+
+fn decode_primitive(primitive: &PrimitiveSignalEncoding, data: &[u8]) -> String {
+    let raw = signal_raw(primitive.signal(), data);
+    match primitive.ty() as &Type {
+        Type::Enum { entries, .. } => enum_to_string(entries, raw),
+        _ => primitive_to_string(primitive.signal().ty(), raw),
+    }
+}
+
+/// raw value of a primitive attribute of a type encoded message, path is "attrib.attrib"
+pub fn encoded_field(message: &MessageRef, data: &[u8], path: &str) -> Option<u64> {
+    let mut attributes = message.encoding()?.attributes();
+    let mut names = path.split('.').peekable();
+    while let Some(name) = names.next() {
+        let encoding = attributes.iter().find(|attrib| attrib.name() == name)?;
+        match encoding {
+            TypeSignalEncoding::Composite(composite) => attributes = composite.attributes(),
+            TypeSignalEncoding::Primitive(primitive) => {
+                return match names.peek() {
+                    Some(_) => None,
+                    None => Some(signal_raw(primitive.signal(), data)),
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn message_key(message: &config::Message) -> u32 {
+    if message.id().ide() {
+        message.id().as_u32() | 0x80000000
+    } else {
+        message.id().as_u32()
+    }
+}
+
+
+/// decodes all signals of a message as (name, value) pairs,
+/// signals of type encoded messages are named by their attribute path (e.g. "header.sof").
+pub fn decode_signals(message: &MessageRef, data: &[u8]) -> Vec<(String, String)> {
+    match message.encoding() {
+        Some(encoding) => encoding
+            .signal_paths()
+            .into_iter()
+            .map(|(path, primitive)| (path, decode_primitive(primitive, data)))
+            .collect(),
+        None => message
+            .signals()
+            .iter()
+            .map(|signal| {
+                (
+                    signal.name().to_owned(),
+                    primitive_to_string(signal.ty(), signal_raw(signal, data)),
+                )
+            })
+            .collect(),
+    }
+}
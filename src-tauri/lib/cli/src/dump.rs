@@ -1,12 +1,26 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{
+    io::{BufReader, BufWriter},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use canzero_appdata::AppData;
-use canzero_config::config::MessageId;
+use canzero_common::{
+    trace_format::{TraceBus, TraceFormat, TraceReader, TraceWriter},
+    TNetworkFrame,
+};
+use canzero_config::config::{MessageId, MessageRef, NetworkRef};
 use canzero_tcp::tcpcan::ConnectionId;
 use canzero_udp::{frame::NetworkDescription, scanner::UdpNetworkScanner};
 use color_print::cprintln;
 
-use crate::errors::{Error, Result};
+use regex::Regex;
+
+use crate::{
+    decode::{decode_signals, message_key},
+    errors::{Error, Result},
+};
 
 pub async fn discover() -> Result<NetworkDescription> {
     let scanner = UdpNetworkScanner::create().await?;
@@ -17,13 +31,239 @@ pub async fn discover() -> Result<NetworkDescription> {
     }
 }
 
-pub async fn command_dump(filter_msg_names: Vec<String>, filter_ids: Vec<String>) -> Result<()> {
-    if !filter_ids.is_empty() {
-        return Err(Error::NotYetImplemented);
+fn trace_format(path: &Path, format: &Option<String>) -> Result<TraceFormat> {
+    match format {
+        Some(format) => {
+            TraceFormat::from_name(format).ok_or(Error::UnknownTraceFormat(format.clone()))
+        }
+        None => TraceFormat::from_path(path)
+            .ok_or(Error::UnknownTraceFormat(format!("{path:?}"))),
+    }
+}
+
+fn trace_buses(network_config: &NetworkRef) -> Vec<TraceBus> {
+    network_config
+        .buses()
+        .iter()
+        .map(|bus| TraceBus::new(bus.id(), bus.name()))
+        .collect()
+}
+
+/// Filter of a single can id, the syntax follows candump:
+/// - "123" or "0x123" : exact id (hex)
+/// - "100-1FF" : inclusive range
+/// - "123:7FF" : id:mask, passes iff. received_id & mask == id & mask
+/// - "123~7FF" : inverted id:mask
+///
+/// Ids with 8 digits or values above 0x7FF are extended ids.
+#[derive(Debug)]
+enum IdFilter {
+    Exact { id: u32, ide: bool },
+    Range { from: u32, to: u32, ide: bool },
+    Mask { id: u32, mask: u32, ide: bool, inverted: bool },
+}
+
+fn parse_hex_id(id: &str) -> Option<(u32, bool)> {
+    let digits = id.strip_prefix("0x").unwrap_or(id);
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    if value > 0x1FFFFFFF {
+        return None;
     }
-    let filter_ids: Vec<MessageId> = vec![];
+    Some((value, digits.len() == 8 || value > 0x7FF))
+}
+
+impl IdFilter {
+    fn parse(filter: &str) -> Result<Self> {
+        let invalid = || Error::InvalidIdFilter(filter.to_owned());
+        let filter = filter.trim();
+        if let Some((id, mask)) = filter.split_once(':') {
+            let (id, ide) = parse_hex_id(id).ok_or_else(invalid)?;
+            let (mask, _) = parse_hex_id(mask).ok_or_else(invalid)?;
+            Ok(IdFilter::Mask { id, mask, ide, inverted: false })
+        } else if let Some((id, mask)) = filter.split_once('~') {
+            let (id, ide) = parse_hex_id(id).ok_or_else(invalid)?;
+            let (mask, _) = parse_hex_id(mask).ok_or_else(invalid)?;
+            Ok(IdFilter::Mask { id, mask, ide, inverted: true })
+        } else if let Some((from, to)) = filter.split_once('-') {
+            let (from, from_ide) = parse_hex_id(from).ok_or_else(invalid)?;
+            let (to, to_ide) = parse_hex_id(to).ok_or_else(invalid)?;
+            if from > to {
+                return Err(invalid());
+            }
+            Ok(IdFilter::Range { from, to, ide: from_ide || to_ide })
+        } else {
+            let (id, ide) = parse_hex_id(filter).ok_or_else(invalid)?;
+            Ok(IdFilter::Exact { id, ide })
+        }
+    }
+
+    fn pass(&self, frame_id: u32, frame_ide: bool) -> bool {
+        match *self {
+            IdFilter::Exact { id, ide } => ide == frame_ide && id == frame_id,
+            IdFilter::Range { from, to, ide } => {
+                ide == frame_ide && (from..=to).contains(&frame_id)
+            }
+            IdFilter::Mask { id, mask, ide, inverted } => {
+                (ide == frame_ide && frame_id & mask == id & mask) != inverted
+            }
+        }
+    }
+}
+
+/// Converts a glob pattern (* and ?) into an anchored regex.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// Messages pass if they match any name, id or node filter (or no such filter is given)
+/// and are received on one of the filtered buses (or no bus filter is given).
+struct DumpFilter {
+    names: Vec<Regex>,
+    ids: Vec<IdFilter>,
+    node_messages: Vec<MessageRef>,
+    bus_ids: Vec<u32>,
+}
+
+impl DumpFilter {
+    fn create(
+        network_config: &NetworkRef,
+        names: Vec<String>,
+        ids: Vec<String>,
+        buses: Vec<String>,
+        nodes: Vec<String>,
+        regex: bool,
+    ) -> Result<Self> {
+        let names = names
+            .iter()
+            .map(|name| {
+                let pattern = if regex { name.clone() } else { glob_to_regex(name) };
+                Regex::new(&pattern).map_err(|_| Error::InvalidMessageFilter(name.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ids = ids
+            .iter()
+            .map(|id| IdFilter::parse(id))
+            .collect::<Result<Vec<_>>>()?;
+        let mut node_messages = vec![];
+        for node_name in nodes {
+            let Some(node) = network_config.nodes().iter().find(|n| n.name() == node_name) else {
+                return Err(Error::InvalidNodeName(node_name));
+            };
+            node_messages.extend(node.tx_messages().iter().cloned());
+        }
+        let bus_ids = buses
+            .into_iter()
+            .map(|bus_name| {
+                network_config
+                    .buses()
+                    .iter()
+                    .find(|b| b.name() == bus_name)
+                    .map(|b| b.id())
+                    .ok_or(Error::InvalidBusName(bus_name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            names,
+            ids,
+            node_messages,
+            bus_ids,
+        })
+    }
+
+    fn pass(&self, frame: &TNetworkFrame, message: Option<&MessageRef>) -> bool {
+        if !self.bus_ids.is_empty() && !self.bus_ids.contains(&frame.bus_id) {
+            return false;
+        }
+        if self.names.is_empty() && self.ids.is_empty() && self.node_messages.is_empty() {
+            return true;
+        }
+        let can_frame = &frame.can_frame;
+        let name = message.map_or("???", |m| m.name());
+        self.names.iter().any(|regex| regex.is_match(name))
+            || self
+                .ids
+                .iter()
+                .any(|id| id.pass(can_frame.get_id(), can_frame.get_ide_flag()))
+            || message.is_some_and(|message| {
+                self.node_messages
+                    .iter()
+                    .any(|m| std::sync::Arc::ptr_eq(m, message))
+            })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn command_dump(
+    filter_msg_names: Vec<String>,
+    filter_ids: Vec<String>,
+    filter_buses: Vec<String>,
+    filter_nodes: Vec<String>,
+    regex: bool,
+    decode: bool,
+    output: Option<PathBuf>,
+    input: Option<PathBuf>,
+    format: Option<String>,
+) -> Result<()> {
     let appdata = AppData::read()?;
     let network_config = appdata.config()?;
+    let filter = DumpFilter::create(
+        &network_config,
+        filter_msg_names,
+        filter_ids,
+        filter_buses,
+        filter_nodes,
+        regex,
+    )?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards");
+    let create_writer = |timebase: Duration| -> Result<Option<TraceWriter<BufWriter<std::fs::File>>>> {
+        let Some(path) = &output else {
+            return Ok(None);
+        };
+        let date = chrono::DateTime::from_timestamp_micros(timebase.as_micros() as i64)
+            .unwrap_or_default()
+            .with_timezone(&chrono::Local);
+        Ok(Some(TraceWriter::create(
+            BufWriter::new(std::fs::File::create(path)?),
+            trace_format(path, &format)?,
+            trace_buses(&network_config),
+            timebase.as_micros() as u64,
+            &date.format("%a %b %d %I:%M:%S%.3f %P %Y").to_string(),
+        )?))
+    };
+
+    if let Some(path) = &input {
+        let mut reader = TraceReader::new(
+            BufReader::new(std::fs::File::open(path)?),
+            trace_format(path, &format)?,
+            trace_buses(&network_config),
+        );
+        let frames = reader.by_ref().collect::<std::io::Result<Vec<_>>>()?;
+        // keep the start of run of candump logs.
+        let mut trace_writer = create_writer(reader.start().unwrap_or(now))?;
+        for frame in frames {
+            if dump_frame(&network_config, &frame, &filter, decode) {
+                if let Some(writer) = &mut trace_writer {
+                    writer.write_frame(&frame)?;
+                }
+            }
+        }
+        if let Some(writer) = trace_writer {
+            writer.finish()?;
+        }
+        return Ok(());
+    }
 
     let network = discover().await?;
 
@@ -48,47 +288,208 @@ pub async fn command_dump(filter_msg_names: Vec<String>, filter_ids: Vec<String>
     )
     .await.unwrap();
 
+    // frames of the server are timestamped relative to its timebase.
+    let timebase = now.saturating_sub(network.timebase.elapsed());
+    let mut trace_writer = create_writer(timebase)?;
+
     loop {
         let Some(frame) = tcpcan.recv().await else {
             println!("Connection closed");
+            if let Some(writer) = trace_writer {
+                writer.finish()?;
+            }
             return Ok(());
         };
-        let timestamp = &frame.timestamp;
-        let tsec = timestamp.as_secs_f32();
-        let bus_id = &frame.bus_id;
-        let bus = network_config
-            .buses()
-            .iter()
-            .find(|b| b.id() == *bus_id)
-            .map_or("can?", |b| b.name());
-        let can_frame = &frame.can_frame;
-        let id = if can_frame.get_ide_flag() {
-            MessageId::ExtendedId(can_frame.get_id())
-        } else {
-            MessageId::StandardId(can_frame.get_id())
-        };
-        let msg_name = network_config
-            .messages()
-            .iter()
-            .find(|m| m.id() == &id)
-            .map_or("???", |m| m.name());
-        let pass = if !filter_msg_names.is_empty() {
-            filter_msg_names.iter().any(|msg| msg == msg_name)
+        if dump_frame(&network_config, &frame, &filter, decode) {
+            if let Some(writer) = &mut trace_writer {
+                writer.write_frame(&frame)?;
+                writer.flush()?;
+            }
+        }
+    }
+}
+
+/// prints the frame if it passes the filters, returns true iff. the frame passed.
+fn dump_frame(
+    network_config: &NetworkRef,
+    frame: &TNetworkFrame,
+    filter: &DumpFilter,
+    decode: bool,
+) -> bool {
+    let timestamp = &frame.timestamp;
+    let tsec = timestamp.as_secs_f32();
+    let bus_id = &frame.bus_id;
+    let bus = network_config
+        .buses()
+        .iter()
+        .find(|b| b.id() == *bus_id)
+        .map_or("can?", |b| b.name());
+    let can_frame = &frame.can_frame;
+    let id = if can_frame.get_ide_flag() {
+        MessageId::ExtendedId(can_frame.get_id())
+    } else {
+        MessageId::StandardId(can_frame.get_id())
+    };
+    let message = network_config
+        .messages()
+        .iter()
+        .find(|m| m.bus().id() == *bus_id && message_key(m) == can_frame.key());
+    let msg_name = message.map_or("???", |m| m.name());
+    let pass = filter.pass(frame, message);
+    if pass {
+        let data = can_frame.get_data();
+        if can_frame.is_fd() {
+            // NOTE the dlc of CAN FD frames is a code, print the bytes in order instead.
+            let len = data.len();
+            let hex: String = data.iter().map(|byte| format!("{byte:02X}")).collect();
+            println!("{tsec:08.2}s : {bus:4} {id:5} [{len:2}] {hex}  ({msg_name})");
         } else {
-            true
-        };
-        let pass = pass
-            || if !filter_ids.is_empty() {
-                filter_ids.iter().any(|x| x == &id)
-            } else {
-                false
-            };
-        if pass {
             let dlc = can_frame.get_dlc();
             let mask = 0xFFFFFFFFFFFFFFFFu64
-                .checked_shr(64u32 - dlc as u32 * 8u32).unwrap_or(0);
+                .checked_shr(64u32 - data.len() as u32 * 8u32).unwrap_or(0);
             let data = can_frame.get_data_u64() & mask;
             println!("{tsec:08.2}s : {bus:4} {id:5} [{dlc:1}] {data:016X}  ({msg_name})");
         }
+        if let (true, Some(message)) = (decode, message) {
+            for (signal, value) in decode_signals(message, &data) {
+                println!("{:38}{signal} = {value}", "");
+            }
+        }
+    }
+    pass
+}
+
+#[cfg(test)]
+mod tests {
+    use canzero_common::{CanFrame, NetworkFrame};
+    use canzero_config::builder::NetworkBuilder;
+
+    use super::*;
+
+    fn frame(id: u32, ide: bool) -> TNetworkFrame {
+        TNetworkFrame::new(
+            Duration::ZERO,
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new(id, ide, false, 0, 0),
+            },
+        )
+    }
+
+    #[test]
+    fn ids_are_hex() {
+        let filter = IdFilter::parse("123").unwrap();
+        assert!(filter.pass(0x123, false));
+        assert!(!filter.pass(123, false));
+        assert!(!filter.pass(0x123, true));
+        assert!(IdFilter::parse("0x123").unwrap().pass(0x123, false));
+        assert!(IdFilter::parse(" 7FF ").unwrap().pass(0x7FF, false));
+    }
+
+    #[test]
+    fn long_or_large_ids_are_extended() {
+        assert!(IdFilter::parse("00000123").unwrap().pass(0x123, true));
+        assert!(IdFilter::parse("800").unwrap().pass(0x800, true));
+        assert!(!IdFilter::parse("800").unwrap().pass(0x800, false));
+    }
+
+    #[test]
+    fn names_and_invalid_ids_are_rejected() {
+        for filter in ["secu_stream_state", "0x", "", "20000000", "1FF-100", "12G", "1:"] {
+            assert!(
+                matches!(IdFilter::parse(filter), Err(Error::InvalidIdFilter(_))),
+                "{filter:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn ranges_are_inclusive() {
+        let filter = IdFilter::parse("100-1FF").unwrap();
+        assert!(!filter.pass(0xFF, false));
+        assert!(filter.pass(0x100, false));
+        assert!(filter.pass(0x1FF, false));
+        assert!(!filter.pass(0x200, false));
+        assert!(!filter.pass(0x150, true));
+    }
+
+    #[test]
+    fn masks_and_inverted_masks() {
+        let filter = IdFilter::parse("120:7F0").unwrap();
+        assert!(filter.pass(0x120, false));
+        assert!(filter.pass(0x12F, false));
+        assert!(!filter.pass(0x130, false));
+        let filter = IdFilter::parse("120~7F0").unwrap();
+        assert!(!filter.pass(0x12F, false));
+        assert!(filter.pass(0x130, false));
+    }
+
+    #[test]
+    fn globs_match_whole_names() {
+        let glob = |glob: &str| Regex::new(&glob_to_regex(glob)).unwrap();
+        assert!(glob("secu_*").is_match("secu_stream_state"));
+        assert!(!glob("secu_*").is_match("master_secu_stream"));
+        assert!(glob("heartbeat_can?").is_match("heartbeat_can0"));
+        assert!(!glob("heartbeat_can?").is_match("heartbeat_can10"));
+    }
+
+    #[test]
+    fn regex_metacharacters_in_globs_are_escaped() {
+        let glob = |glob: &str| Regex::new(&glob_to_regex(glob)).unwrap();
+        assert!(glob("a.b").is_match("a.b"));
+        assert!(!glob("a.b").is_match("axb"));
+        assert!(glob("a+b").is_match("a+b"));
+        assert!(!glob("a+b").is_match("aab"));
+        assert!(glob("get(req)*").is_match("get(req)_secu"));
+        assert!(!glob("get(req)*").is_match("getreq"));
+    }
+
+    #[test]
+    fn messages_pass_any_name_or_id_filter() {
+        let network_builder = NetworkBuilder::new();
+        network_builder.create_bus("can0", None);
+        let secu = network_builder.create_node("secu");
+        secu.create_object_entry("position", "u8");
+        secu.create_stream("state").add_entry("position");
+        let network = network_builder.build().unwrap();
+        let state = network
+            .messages()
+            .iter()
+            .find(|m| m.name() == "secu_stream_state")
+            .unwrap();
+
+        let filter = |names: &[&str], ids: &[&str], regex: bool| {
+            DumpFilter::create(
+                &network,
+                names.iter().map(|n| n.to_string()).collect(),
+                ids.iter().map(|n| n.to_string()).collect(),
+                vec![],
+                vec![],
+                regex,
+            )
+            .unwrap()
+        };
+        let state_frame = frame(state.id().as_u32(), state.id().ide());
+        let other_frame = frame(0x7FF, false);
+
+        let no_filter = filter(&[], &[], false);
+        assert!(no_filter.pass(&state_frame, Some(state)));
+        assert!(no_filter.pass(&other_frame, None));
+
+        let by_name = filter(&["secu_*"], &[], false);
+        assert!(by_name.pass(&state_frame, Some(state)));
+        assert!(!by_name.pass(&other_frame, None));
+
+        let by_regex = filter(&["^secu_.*_state$"], &[], true);
+        assert!(by_regex.pass(&state_frame, Some(state)));
+
+        let by_name_or_id = filter(&["secu_*"], &["7FF"], false);
+        assert!(by_name_or_id.pass(&state_frame, Some(state)));
+        assert!(by_name_or_id.pass(&other_frame, None));
+
+        assert!(matches!(
+            DumpFilter::create(&network, vec!["(".to_owned()], vec![], vec![], vec![], true),
+            Err(Error::InvalidMessageFilter(_))
+        ));
     }
 }
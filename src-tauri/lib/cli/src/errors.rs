@@ -18,6 +18,12 @@ pub enum Error {
     InvalidNodeName(String),
     InvalidBusName(String),
     NoServerFound,
+    UnknownTraceFormat(String),
+    InvalidIdFilter(String),
+    InvalidMessageFilter(String),
+    InvalidConfig(usize),
+    InvalidFaultScript(String),
+    UnknownPlatform(String),
     NotYetImplemented,
 }
 
@@ -47,12 +53,19 @@ impl Display for Error {
             Error::FileNotFound(path) => write!(f, "Failed to find file {path:?}"),
             Error::CodegenError(err) => write!(f, "{err:?}"),
             Error::Io(err) => write!(f, "{err:?}"),
+            Error::AppDataError(AppDataError::ConfigError(err)) => write!(f, "{err}"),
             Error::AppDataError(err) => write!(f, "{err:?}"),
             Error::MissingDependency(dep) => write!(f, "Missing dependency {dep}"),
             Error::InvalidResponse => write!(f, "Invalid Response"),
             Error::InvalidNodeName(node_name) => write!(f, "Invalid node name : {node_name}"),
             Error::InvalidBusName(bus_name) => write!(f, "Invalid bus name : {bus_name}"),
             Error::NoServerFound => write!(f, "No server found"),
+            Error::UnknownTraceFormat(format) => write!(f, "Unknown trace format : {format} (expected candump or asc)"),
+            Error::InvalidIdFilter(filter) => write!(f, "Invalid id filter : {filter} (expected <id>, <from>-<to>, <id>:<mask> or <id>~<mask>)"),
+            Error::InvalidMessageFilter(filter) => write!(f, "Invalid message name pattern : {filter}"),
+            Error::InvalidConfig(count) => write!(f, "Config is invalid : {count} error(s) found"),
+            Error::InvalidFaultScript(err) => write!(f, "Invalid fault script : {err}"),
+            Error::UnknownPlatform(platform) => write!(f, "Unknown platform : {platform} (expected teensy, stm32-hal, linux or host)"),
             Error::NotYetImplemented => write!(f, "Not yet implemented"),
         }
     }
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use canzero_appdata::AppData;
+use canzero_codegen::options::Platform;
 
 use crate::errors::{Error, Result};
 
@@ -14,7 +15,10 @@ fn rec_create_dir(dir : &Path) -> Result<()>{
     Ok(())
 }
 
-pub fn command_generate(node_name : &str, output_dir : &PathBuf) -> Result<()> {
+pub fn command_generate(node_name : &str, output_dir : &PathBuf, platform : &str, rust : bool) -> Result<()> {
+    let Some(platform) = Platform::from_name(platform) else {
+        return Err(Error::UnknownPlatform(platform.to_owned()));
+    };
     let appdata = AppData::read()?;
     let network_config = appdata.config()?;
     let output_dir = output_dir.clone();
@@ -26,8 +30,18 @@ pub fn command_generate(node_name : &str, output_dir : &PathBuf) -> Result<()> {
 
     let mut options = canzero_codegen::options::Options::default();
 
+    if rust {
+        let mut source_file_path = output_dir.clone();
+        source_file_path.push("canzero.rs");
+        options.set_source_file_path(source_file_path.to_str().unwrap());
+        canzero_codegen::rust::generate_rust(node_name, network_config, options)?;
+        return Ok(());
+    }
+
+    options.set_platform(platform);
+
     let mut source_file_path = output_dir.clone();
-    source_file_path.push("canzero.cpp");
+    source_file_path.push(format!("canzero.{}", platform.source_extension()));
     options.set_source_file_path(source_file_path.to_str().unwrap());
 
     let mut header_file_path = output_dir.clone();
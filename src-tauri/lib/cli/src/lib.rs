@@ -6,15 +6,17 @@ use config::command_config_get;
 use crate::{
     client::command_client,
     config::{
-        command_config_check, command_config_hash, command_config_messages_list,
+        command_config_check, command_config_diff, command_config_export_dbc, command_config_hash, command_config_messages_list,
         command_config_nodes_list, command_config_object_entries_list, command_config_set,
         command_config_show,
     },
     dump::command_dump,
+    record::command_record,
     errors::Error,
     generate::command_generate,
     scan::command_scan,
     server::command_server,
+    simulate::command_simulate,
     ssh::{command_ssh, command_ssh_reboot},
     status::command_status,
     update::{command_update_self, command_update_server},
@@ -22,12 +24,15 @@ use crate::{
 
 mod client;
 mod config;
+mod decode;
 mod dump;
 mod errors;
 mod generate;
 mod get;
+mod record;
 mod scan;
 mod server;
+mod simulate;
 mod ssh;
 mod status;
 mod update;
@@ -57,6 +62,10 @@ enum Command {
     Generate {
         node_name: String,
         output_dir: PathBuf,
+        #[arg(short, long, default_value = "teensy", help = "Target platform: teensy, stm32-hal, linux or host")]
+        platform: String,
+        #[arg(long, help = "Generate a rust module (canzero.rs) instead of c code")]
+        rust: bool,
     },
     #[command(about = "Start canzero graphical user interface.")]
     Gui,
@@ -67,14 +76,42 @@ enum Command {
     },
     #[command(about = "Connect to a network (SocketCAN).")]
     Connect,
+    #[command(about = "Host a simulated network of the selected configuration behind a server.")]
+    Simulate {
+        #[arg(short, long, num_args=0.., help = "Only simulate these nodes [default: all nodes]")]
+        nodes: Vec<String>,
+        #[arg(short, long, help = "Script of faults which are injected into the simulation")]
+        faults: Option<PathBuf>,
+    },
     #[command(about = "Print the CAN trace to the control.")]
     Dump {
         #[clap(alias = "msg")]
-        #[arg(short, long, num_args=0..)]
+        #[arg(short, long, num_args=0.., help = "Message names to dump (glob patterns, e.g. \"secu_*\")")]
         messages: Vec<String>,
         #[clap(alias = "id")]
-        #[arg(short, long, num_args=0..)]
+        #[arg(short, long, num_args=0.., help = "Ids to dump (hex): <id>, <from>-<to>, <id>:<mask> or <id>~<mask>")]
         ids: Vec<String>,
+        #[arg(short, long, num_args=0.., help = "Only dump frames of these buses")]
+        bus: Vec<String>,
+        #[arg(short, long, num_args=0.., help = "Dump all messages transmitted by these nodes")]
+        nodes: Vec<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Interpret message names as regular expressions")]
+        regex: bool,
+        #[arg(short, long, action = clap::ArgAction::SetTrue, help = "Print the decoded signals of each frame")]
+        decode: bool,
+        #[arg(short, long, help = "Write the trace to a candump (.log) or ASC (.asc) file")]
+        output: Option<PathBuf>,
+        #[arg(long, help = "Read the trace from a candump (.log) or ASC (.asc) file instead of the network")]
+        input: Option<PathBuf>,
+        #[arg(short, long, help = "Trace file format (candump or asc), defaults to the file extension")]
+        format: Option<String>,
+    },
+    #[command(about = "Log all object entries of the network to csv files (one per object entry).")]
+    Record {
+        #[arg(short, long, help = "Directory in which the recording is created [default: .]")]
+        output_dir: Option<PathBuf>,
+        #[arg(short, long, num_args=0.., help = "Only record object entries of these nodes")]
+        nodes: Vec<String>,
     },
     #[command(about = "Check the status of all connected nodes.")]
     Status,
@@ -98,6 +135,8 @@ enum ConfigCommand {
         arg_required_else_help = false
     )]
     Show {
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Print the resolved network configuration as JSON")]
+        json: bool,
         #[command(subcommand)]
         command: Option<ConfigShowCommand>,
     },
@@ -107,6 +146,20 @@ enum ConfigCommand {
     )]
     Check,
     Where,
+    #[command(
+        about = "Compare two network configurations and list the differences.",
+        arg_required_else_help = true
+    )]
+    Diff { old: PathBuf, new: PathBuf },
+    #[command(
+        about = "Export one DBC file per bus of the network configuration.",
+        arg_required_else_help = true
+    )]
+    Dbc {
+        output_dir: PathBuf,
+        #[arg(short, long, help = "Only export the DBC of this bus")]
+        bus: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -126,7 +179,18 @@ enum ConfigShowCommand {
 
 #[derive(Subcommand, Debug)]
 enum ServerCommand {
-    Start,
+    Start {
+        #[arg(long, help = "Directory to which all frames are recorded [default: ~/.canzero/recordings]")]
+        record_dir: Option<PathBuf>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Disable the recording of frames")]
+        no_record: bool,
+        #[arg(long, default_value_t = 64, help = "Rotate recordings after reaching this size in MiB")]
+        max_file_size: u64,
+        #[arg(long, default_value_t = 16, help = "Delete the oldest recordings to keep at most this many files")]
+        max_file_count: usize,
+        #[arg(long, default_value_t = 300, help = "Seconds of history that are synced to new clients")]
+        history_window: u64,
+    },
     Scan,
     Restart,
     Reboot,
@@ -146,7 +210,7 @@ pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> bool {
         Some(cmd) => match cmd {
             Command::Config { command } => match command {
                 ConfigCommand::Set { path } => command_config_set(path),
-                ConfigCommand::Show { command } => match command {
+                ConfigCommand::Show { json, command } => match command {
                     Some(config_show_command) => match config_show_command {
                         ConfigShowCommand::Hash => command_config_hash(),
                         ConfigShowCommand::Messages { node, bus } => {
@@ -157,18 +221,37 @@ pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> bool {
                             command_config_object_entries_list(node)
                         }
                     },
-                    None => command_config_show(),
+                    None => command_config_show(json),
                 },
                 ConfigCommand::Check => command_config_check(),
                 ConfigCommand::Where => command_config_get(),
+                ConfigCommand::Diff { old, new } => command_config_diff(old, new),
+                ConfigCommand::Dbc { output_dir, bus } => command_config_export_dbc(output_dir, bus),
             },
             Command::Generate {
                 node_name,
                 output_dir,
-            } => command_generate(&node_name, &output_dir),
+                platform,
+                rust,
+            } => command_generate(&node_name, &output_dir, &platform, rust),
             Command::Gui => return true,
             Command::Server { command } => match command {
-                ServerCommand::Start => command_server().await,
+                ServerCommand::Start {
+                    record_dir,
+                    no_record,
+                    max_file_size,
+                    max_file_count,
+                    history_window,
+                } => {
+                    command_server(
+                        record_dir,
+                        no_record,
+                        max_file_size,
+                        max_file_count,
+                        history_window,
+                    )
+                    .await
+                }
                 ServerCommand::Scan => command_scan().await,
                 ServerCommand::Restart => Err(Error::NotYetImplemented),
                 ServerCommand::Reboot => command_ssh_reboot(None).await,
@@ -178,7 +261,24 @@ pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> bool {
                 }
             },
             Command::Connect => command_client().await,
-            Command::Dump { messages, ids } => command_dump(messages, ids).await,
+            Command::Simulate { nodes, faults } => command_simulate(nodes, faults).await,
+            Command::Dump {
+                messages,
+                ids,
+                bus,
+                nodes,
+                regex,
+                decode,
+                output,
+                input,
+                format,
+            } => {
+                command_dump(
+                    messages, ids, bus, nodes, regex, decode, output, input, format,
+                )
+                .await
+            }
+            Command::Record { output_dir, nodes } => command_record(output_dir, nodes).await,
             Command::Status => command_status().await,
             Command::Update { socketcan } => command_update_self(socketcan),
             Command::Version => {
@@ -0,0 +1,461 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use canzero_appdata::AppData;
+use canzero_common::{
+    fragmentation::{GetRespAssembler, GetRespFragment},
+    TNetworkFrame,
+};
+use canzero_config::config::{stream::StreamRef, NetworkRef, ObjectEntryRef};
+use canzero_tcp::tcpcan::ConnectionId;
+use chrono::{Datelike, Timelike};
+use color_print::cprintln;
+
+use crate::{
+    decode::{decode_encoding, decode_type, encoded_field, message_key, type_columns},
+    dump::discover,
+    errors::Result,
+};
+
+/// Writes one csv file per object entry into {dir}/{node}/{object_entry}.csv
+struct CsvRecorder {
+    dir: PathBuf,
+    files: HashMap<(u8, u32), BufWriter<File>>,
+}
+
+impl CsvRecorder {
+    fn create(output_dir: &Path) -> Result<Self> {
+        let time = chrono::Local::now();
+        let dir = output_dir.join(format!(
+            "{}-{}-{}_{}_{}_{}",
+            time.year(),
+            time.month(),
+            time.day(),
+            time.hour(),
+            time.minute(),
+            time.second()
+        ));
+        std::fs::create_dir_all(&dir)?;
+        cprintln!("<green>Recording to {dir:?}</green>");
+        Ok(Self {
+            dir,
+            files: HashMap::new(),
+        })
+    }
+
+    fn record(
+        &mut self,
+        object_entry: &ObjectEntryRef,
+        timestamp: Duration,
+        values: &[String],
+    ) -> Result<()> {
+        let key = (object_entry.node().id(), object_entry.id());
+        if !self.files.contains_key(&key) {
+            let node_dir = self.dir.join(object_entry.node().name());
+            std::fs::create_dir_all(&node_dir)?;
+            let mut path = node_dir.join(object_entry.name());
+            path.set_extension("csv");
+            let mut writer = BufWriter::new(File::create(path)?);
+            let mut columns = vec!["timestamp".to_owned()];
+            type_columns(object_entry.ty(), None, &mut columns);
+            writeln!(writer, "{}", columns.join(","))?;
+            self.files.insert(key, writer);
+        }
+        let writer = self.files.get_mut(&key).expect("file was just inserted");
+        writeln!(writer, "{},{}", timestamp.as_micros(), values.join(","))?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        for writer in self.files.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes streams and get responses into object entry values.
+struct Decoder {
+    network_config: NetworkRef,
+    streams: HashMap<(u32, u32), StreamRef>,
+    // reassembly of get responses (server_id, object entry id, client_id),
+    // responses to different clients are interleaved on the bus.
+    get_resp_assemblers: HashMap<(u8, u16, u8), GetRespAssembler>,
+    node_filter: Vec<String>,
+}
+
+impl Decoder {
+    fn new(network_config: &NetworkRef, node_filter: Vec<String>) -> Self {
+        let mut streams = HashMap::new();
+        for node in network_config.nodes() {
+            for stream in node.tx_streams() {
+                let message = stream.message();
+                streams.insert((message.bus().id(), message_key(message)), stream.clone());
+            }
+        }
+        Self {
+            network_config: network_config.clone(),
+            streams,
+            get_resp_assemblers: HashMap::new(),
+            node_filter,
+        }
+    }
+
+    fn accept(&self, object_entry: &ObjectEntryRef) -> bool {
+        self.node_filter.is_empty()
+            || self
+                .node_filter
+                .iter()
+                .any(|node| node == object_entry.node().name())
+    }
+
+    fn decode(&mut self, frame: &TNetworkFrame) -> Vec<(ObjectEntryRef, Vec<String>)> {
+        let can_frame = &frame.can_frame;
+        let data = can_frame.get_data();
+        let data = data.as_slice();
+        if let Some(stream) = self.streams.get(&(frame.bus_id, can_frame.key())) {
+            let Some(encoding) = stream.message().encoding() else {
+                return vec![];
+            };
+            return encoding
+                .attributes()
+                .iter()
+                .zip(stream.mapping())
+                .filter_map(|(attrib, oe)| {
+                    let oe = oe.as_ref()?;
+                    if !self.accept(oe) {
+                        return None;
+                    }
+                    let mut values = vec![];
+                    decode_encoding(attrib, data, &mut values);
+                    Some((oe.clone(), values))
+                })
+                .collect();
+        }
+
+        let get_resp = self.network_config.get_resp_message().clone();
+        if get_resp.bus().id() != frame.bus_id || message_key(&get_resp) != can_frame.key() {
+            return vec![];
+        }
+        let field = |path: &str| encoded_field(&get_resp, data, path).unwrap_or(0);
+        let fragment = GetRespFragment {
+            sof: field("header.sof") != 0,
+            eof: field("header.eof") != 0,
+            toggle: field("header.toggle") != 0,
+            data: field("data") as u32,
+        };
+        let server_id = field("header.server_id") as u8;
+        let client_id = field("header.client_id") as u8;
+        let oe_id = field("header.od_index") as u16;
+        let Some(node) = self
+            .network_config
+            .nodes()
+            .iter()
+            .find(|node| node.id() == server_id)
+        else {
+            return vec![];
+        };
+        let Some(oe) = node
+            .object_entries()
+            .iter()
+            .find(|oe| oe.id() == oe_id as u32)
+        else {
+            return vec![];
+        };
+        let assembler = self
+            .get_resp_assemblers
+            .entry((server_id, oe_id, client_id))
+            .or_insert_with(|| GetRespAssembler::new(oe.ty().size()));
+        let words = match assembler.receive(&fragment) {
+            Ok(Some(words)) => words,
+            Ok(None) => return vec![],
+            Err(err) => {
+                cprintln!(
+                    "<yellow>Dropping get response of {}::{} : {err:?}</yellow>",
+                    node.name(),
+                    oe.name()
+                );
+                return vec![];
+            }
+        };
+        if !self.accept(oe) {
+            return vec![];
+        }
+        let mut values = vec![];
+        decode_type(oe.ty(), &words, &mut 0, &mut values);
+        vec![(oe.clone(), values)]
+    }
+}
+
+pub async fn command_record(output_dir: Option<PathBuf>, nodes: Vec<String>) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network_config = appdata.config()?;
+
+    let network = discover().await?;
+
+    let connection =
+        tokio::net::TcpStream::connect(SocketAddr::new(network.server_addr, network.service_port))
+            .await?;
+
+    cprintln!(
+        "<green>Successfully connected to {} at {}:{}</green>",
+        network.server_name,
+        network.server_addr,
+        network.service_port
+    );
+
+    let tcpcan = canzero_tcp::tcpcan::TcpCan::new(
+        connection,
+        ConnectionId::Client {
+            request_id: false,
+            sync_history: false,
+        },
+    )
+    .await?;
+
+    let mut recorder = CsvRecorder::create(&output_dir.unwrap_or(PathBuf::from(".")))?;
+    let mut decoder = Decoder::new(&network_config, nodes);
+    let mut flush_interval = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        tokio::select! {
+            frame = tcpcan.recv() => {
+                let Some(frame) = frame else {
+                    println!("Connection closed");
+                    break;
+                };
+                for (oe, values) in decoder.decode(&frame) {
+                    recorder.record(&oe, frame.timestamp, &values)?;
+                }
+            }
+            _ = flush_interval.tick() => recorder.flush()?,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    recorder.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use canzero_common::{CanFrame, NetworkFrame};
+    use canzero_config::{builder::NetworkBuilder, config::MessageRef};
+
+    use super::*;
+
+    fn network() -> NetworkRef {
+        let network_builder = NetworkBuilder::new();
+        network_builder.create_bus("can0", None);
+        let mode = network_builder.define_enum("mode");
+        mode.add_entry("idle", Some(0)).unwrap();
+        mode.add_entry("levitate", Some(1)).unwrap();
+        let secu = network_builder.create_node("secu");
+        secu.create_object_entry("position", "u16");
+        secu.create_object_entry("mode", "mode");
+        secu.create_object_entry("offset", "u64");
+        let stream = secu.create_stream("state");
+        stream.add_entry("position");
+        stream.add_entry("mode");
+        let bms = network_builder.create_node("bms");
+        bms.create_object_entry("voltage", "u8");
+        bms.create_stream("state").add_entry("voltage");
+        network_builder.build().unwrap()
+    }
+
+    /// frame of a type encoded message, fields are (attribute path, raw value).
+    fn frame(message: &MessageRef, fields: &[(&str, u64)]) -> TNetworkFrame {
+        let mut data = 0u64;
+        for (path, primitive) in message.encoding().unwrap().signal_paths() {
+            let Some((_, value)) = fields.iter().find(|(name, _)| *name == path) else {
+                continue;
+            };
+            let signal = primitive.signal();
+            data |= (value & (u64::MAX >> (64 - signal.size()))) << signal.byte_offset();
+        }
+        TNetworkFrame::new(
+            Duration::from_millis(10),
+            NetworkFrame {
+                bus_id: message.bus().id(),
+                can_frame: CanFrame::new(
+                    message.id().as_u32(),
+                    message.id().ide(),
+                    false,
+                    message.dlc(),
+                    data,
+                ),
+            },
+        )
+    }
+
+    fn stream_message(network: &NetworkRef, node: &str) -> MessageRef {
+        network
+            .messages()
+            .iter()
+            .find(|m| m.name() == format!("{node}_stream_state"))
+            .unwrap()
+            .clone()
+    }
+
+    fn object_entry_id(network: &NetworkRef, node: &str, name: &str) -> u64 {
+        let node = network.nodes().iter().find(|n| n.name() == node).unwrap();
+        node.object_entries()
+            .iter()
+            .find(|oe| oe.name() == name)
+            .unwrap()
+            .id() as u64
+    }
+
+    fn decoded(values: Vec<(ObjectEntryRef, Vec<String>)>) -> Vec<(String, Vec<String>)> {
+        values
+            .into_iter()
+            .map(|(oe, values)| (oe.name().to_owned(), values))
+            .collect()
+    }
+
+    #[test]
+    fn streams_are_decoded_into_object_entries() {
+        let network = network();
+        let mut decoder = Decoder::new(&network, vec![]);
+        let frame = frame(
+            &stream_message(&network, "secu"),
+            &[("position", 1234), ("mode", 1)],
+        );
+        assert_eq!(
+            decoded(decoder.decode(&frame)),
+            vec![
+                ("position".to_owned(), vec!["1234".to_owned()]),
+                ("mode".to_owned(), vec!["levitate".to_owned()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn object_entries_of_other_nodes_are_filtered() {
+        let network = network();
+        let mut decoder = Decoder::new(&network, vec!["bms".to_owned()]);
+        let secu_frame = frame(&stream_message(&network, "secu"), &[("position", 1)]);
+        assert!(decoder.decode(&secu_frame).is_empty());
+        let bms_frame = frame(&stream_message(&network, "bms"), &[("voltage", 42)]);
+        assert_eq!(
+            decoded(decoder.decode(&bms_frame)),
+            vec![("voltage".to_owned(), vec!["42".to_owned()])]
+        );
+    }
+
+    #[test]
+    fn fragmented_get_responses_are_reassembled() {
+        let network = network();
+        let mut decoder = Decoder::new(&network, vec![]);
+        let get_resp = network.get_resp_message().clone();
+        let server_id = network
+            .nodes()
+            .iter()
+            .find(|n| n.name() == "secu")
+            .unwrap()
+            .id() as u64;
+        let od_index = object_entry_id(&network, "secu", "offset");
+        let fragment = |sof: u64, eof: u64, toggle: u64, data: u64| {
+            frame(
+                &get_resp,
+                &[
+                    ("header.sof", sof),
+                    ("header.eof", eof),
+                    ("header.toggle", toggle),
+                    ("header.od_index", od_index),
+                    ("header.server_id", server_id),
+                    ("header.client_id", 1),
+                    ("data", data),
+                ],
+            )
+        };
+        assert!(decoder.decode(&fragment(1, 0, 0, 0x0000_0002)).is_empty());
+        assert_eq!(
+            decoded(decoder.decode(&fragment(0, 1, 1, 0x0000_0001))),
+            vec![("offset".to_owned(), vec![(0x1_0000_0002u64).to_string()])]
+        );
+        // an end of frame without all fragments is dropped.
+        assert!(decoder.decode(&fragment(0, 1, 0, 0x0000_0001)).is_empty());
+    }
+
+    #[test]
+    fn interleaved_duplicated_and_out_of_toggle_get_responses() {
+        let network = network();
+        let mut decoder = Decoder::new(&network, vec![]);
+        let get_resp = network.get_resp_message().clone();
+        let server_id = network
+            .nodes()
+            .iter()
+            .find(|n| n.name() == "secu")
+            .unwrap()
+            .id() as u64;
+        let od_index = object_entry_id(&network, "secu", "offset");
+        let fragment = |client_id: u64, sof: u64, eof: u64, toggle: u64, data: u64| {
+            frame(
+                &get_resp,
+                &[
+                    ("header.sof", sof),
+                    ("header.eof", eof),
+                    ("header.toggle", toggle),
+                    ("header.od_index", od_index),
+                    ("header.server_id", server_id),
+                    ("header.client_id", client_id),
+                    ("data", data),
+                ],
+            )
+        };
+        let offset = |value: u64| vec![("offset".to_owned(), vec![value.to_string()])];
+
+        // responses to a client and unsolicited responses are reassembled separately.
+        assert!(decoder.decode(&fragment(1, 1, 0, 0, 0xA)).is_empty());
+        assert!(decoder.decode(&fragment(0xff, 1, 0, 0, 0xB)).is_empty());
+        assert_eq!(
+            decoded(decoder.decode(&fragment(1, 0, 1, 1, 0x1))),
+            offset(0x1_0000_000A)
+        );
+        assert_eq!(
+            decoded(decoder.decode(&fragment(0xff, 0, 1, 1, 0x2))),
+            offset(0x2_0000_000B)
+        );
+
+        // a duplicated start of frame restarts the response.
+        assert!(decoder.decode(&fragment(1, 1, 0, 0, 0xC)).is_empty());
+        assert!(decoder.decode(&fragment(1, 1, 0, 0, 0xC)).is_empty());
+        assert_eq!(
+            decoded(decoder.decode(&fragment(1, 0, 1, 1, 0x3))),
+            offset(0x3_0000_000C)
+        );
+
+        // fragments with an unexpected toggle bit are dropped.
+        assert!(decoder.decode(&fragment(1, 1, 0, 0, 0xD)).is_empty());
+        assert!(decoder.decode(&fragment(1, 0, 1, 0, 0x4)).is_empty());
+    }
+
+    #[test]
+    fn object_entries_are_written_to_csv_files() {
+        let network = network();
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = CsvRecorder::create(dir.path()).unwrap();
+        let mut decoder = Decoder::new(&network, vec![]);
+        let message = stream_message(&network, "secu");
+        for (position, mode) in [(1, 0), (2, 1)] {
+            let frame = frame(&message, &[("position", position), ("mode", mode)]);
+            for (oe, values) in decoder.decode(&frame) {
+                recorder.record(&oe, frame.timestamp, &values).unwrap();
+            }
+        }
+        recorder.flush().unwrap();
+
+        let read =
+            |name: &str| std::fs::read_to_string(recorder.dir.join("secu").join(name)).unwrap();
+        assert_eq!(read("position.csv"), "timestamp,value\n10000,1\n10000,2\n");
+        assert_eq!(
+            read("mode.csv"),
+            "timestamp,value\n10000,idle\n10000,levitate\n"
+        );
+    }
+}
@@ -1,14 +1,33 @@
 
+use std::{path::PathBuf, time::Duration};
+
 use canzero_appdata::AppData;
-use canzero_server::Server;
+use canzero_server::{Server, ServerOptions};
 
 use crate::errors::Result;
 
-pub async fn command_server() -> Result<()> {
+pub async fn command_server(
+    record_dir: Option<PathBuf>,
+    no_record: bool,
+    max_file_size_mib: u64,
+    max_file_count: usize,
+    history_window_secs: u64,
+) -> Result<()> {
 
     let appdata = AppData::read()?;
     let network_config = appdata.config()?;
-    let server = Server::create(network_config).await?;
+    let mut options = ServerOptions {
+        max_recording_size: max_file_size_mib * 1024 * 1024,
+        max_recording_count: max_file_count,
+        sync_history_window: Duration::from_secs(history_window_secs),
+        ..Default::default()
+    };
+    if no_record {
+        options.recording_dir = None;
+    } else if let Some(record_dir) = record_dir {
+        options.recording_dir = Some(record_dir);
+    }
+    let server = Server::create(network_config, options).await?;
 
     server.start();
     loop {
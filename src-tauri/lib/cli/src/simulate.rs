@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use canzero_appdata::AppData;
+use canzero_server::{
+    network::node::NetworkNode,
+    simulation::{fault::FaultScript, Simulation},
+    Server, ServerOptions,
+};
+use color_print::cprintln;
+
+use crate::errors::{Error, Result};
+
+pub async fn command_simulate(nodes: Vec<String>, faults: Option<PathBuf>) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network_config = appdata.config()?;
+
+    let simulated_nodes = if nodes.is_empty() {
+        network_config.nodes().clone()
+    } else {
+        nodes
+            .iter()
+            .map(|name| {
+                network_config
+                    .nodes()
+                    .iter()
+                    .find(|node| node.name() == name)
+                    .cloned()
+                    .ok_or_else(|| Error::InvalidNodeName(name.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    let fault_script = match faults {
+        Some(path) => {
+            let Ok(src) = std::fs::read_to_string(&path) else {
+                return Err(Error::FileNotFound(path.to_string_lossy().to_string()));
+            };
+            FaultScript::parse(&src, &network_config)
+                .map_err(|err| Error::InvalidFaultScript(err.to_string()))?
+        }
+        None => FaultScript::default(),
+    };
+
+    // the simulation replaces the real network.
+    let options = ServerOptions {
+        recording_dir: None,
+        connect_socketcan: false,
+        ..Default::default()
+    };
+    let server = Server::create(network_config.clone(), options).await?;
+    let simulation = Simulation::start(
+        &network_config,
+        &simulated_nodes,
+        fault_script,
+        server.timebase(),
+    );
+    cprintln!(
+        "<green>Simulating {}</green>",
+        simulation.node_names().join(", ")
+    );
+    server
+        .attach(NetworkNode::new("simulation", Box::new(simulation)))
+        .await;
+
+    server.start();
+    loop {
+        tokio::task::yield_now().await;
+    }
+}
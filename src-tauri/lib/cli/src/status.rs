@@ -156,6 +156,7 @@ pub async fn command_status() -> Result<()> {
         );
     }
 
+    let mut desync = false;
     for node in network_config.nodes() {
         let config_hash_oe = node
             .object_entries()
@@ -261,6 +262,7 @@ pub async fn command_status() -> Result<()> {
                     build_time.second(),
                     hash,
                 );
+                    desync = true;
                 }
             } else {
                 cprintln!("{:25} : <red> {:7}</red>", node.name(), "FUCKED");
@@ -269,6 +271,12 @@ pub async fn command_status() -> Result<()> {
             cprintln!("{:25} : <red> {:7}</red>", node.name(), "OFFLINE");
         }
     }
+    if desync {
+        println!(
+            "Some nodes were built from a different network configuration, \
+use \"canzero config diff <old> <new>\" to compare two revisions."
+        );
+    }
 
     Ok(())
 }
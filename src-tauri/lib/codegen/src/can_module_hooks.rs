@@ -2,12 +2,12 @@ use canzero_config::config::bus::BusRef;
 
 use crate::{
     errors::Result,
-    options::Options,
+    options::{Options, Platform},
 };
 
 pub fn generate_hooks(
     buses: &Vec<BusRef>,
-    _source: &mut String,
+    source: &mut String,
     header: &mut String,
     options: &Options,
 ) -> Result<()> {
@@ -19,7 +19,11 @@ pub fn generate_hooks(
     for bus in buses {
         let bus_name = bus.name();
         let can_setup_name = format!("{namespace}_{bus_name}_setup");
-        let can_setup_decl = format!("extern void {can_setup_name}(uint32_t baudrate, {namespace}_can_filter* filters, int filter_count);\n");
+        let can_setup_decl = if bus.fd() {
+            format!("extern void {can_setup_name}(uint32_t baudrate, uint32_t data_baudrate, {namespace}_can_filter* filters, int filter_count);\n")
+        } else {
+            format!("extern void {can_setup_name}(uint32_t baudrate, {namespace}_can_filter* filters, int filter_count);\n")
+        };
         header.push_str(&can_setup_decl);
 
         let can_send_name = format!("{namespace}_{bus_name}_send");
@@ -47,5 +51,390 @@ pub fn generate_hooks(
     let exit_cirtical_decl = format!("extern void {exit_cirtical_name}();\n");
     header.push_str(&exit_cirtical_decl);
 
+    // weak defaults, the application may still provide its own critical sections.
+    match options.platform() {
+        Platform::Teensy | Platform::Stm32Hal => {
+            // critical sections nest (e.g. a setter called from an interrupt handler or
+            // from within an application critical section), such that the outermost
+            // exit restores the interrupt state (PRIMASK) of the outermost enter.
+            source.push_str(&format!(
+                "static uint32_t {namespace}_critical_depth = 0;
+static uint32_t {namespace}_critical_primask = 0;
+__attribute__((weak)) void {enter_cirtical_name}() {{
+{indent}uint32_t primask;
+{indent}__asm__ volatile(\"mrs %0, primask\" : \"=r\"(primask));
+{indent}__disable_irq();
+{indent}if ({namespace}_critical_depth++ == 0) {{
+{indent}{indent}{namespace}_critical_primask = primask;
+{indent}}}
+}}
+__attribute__((weak)) void {exit_cirtical_name}() {{
+{indent}if (--{namespace}_critical_depth == 0 && !{namespace}_critical_primask) {{
+{indent}{indent}__enable_irq();
+{indent}}}
+}}
+"
+            ));
+        }
+        Platform::Linux => {
+            source.push_str(&format!(
+                "static pthread_mutex_t {namespace}_critical_mutex = PTHREAD_MUTEX_INITIALIZER;
+__attribute__((weak)) void {enter_cirtical_name}() {{
+{indent}pthread_mutex_lock(&{namespace}_critical_mutex);
+}}
+__attribute__((weak)) void {exit_cirtical_name}() {{
+{indent}pthread_mutex_unlock(&{namespace}_critical_mutex);
+}}
+"
+            ));
+        }
+        Platform::Host => {
+            source.push_str(&format!(
+                "__attribute__((weak)) void {enter_cirtical_name}() {{}}
+__attribute__((weak)) void {exit_cirtical_name}() {{}}
+"
+            ));
+        }
+    }
+
+    if options.platform().implements_can_hooks() {
+        generate_socketcan_hooks(buses, source, options);
+    }
+    if options.platform() == &Platform::Host && options.host_harness() {
+        generate_host_harness(buses, source, header, options);
+    }
+
     Ok(())
 }
+
+/// SocketCAN implementation of the can hooks. The network interface of a bus
+/// defaults to the bus name and can be overwritten with {NAMESPACE}_{BUS}_IFNAME.
+/// The baudrates are configured on the interface (ip link) and are ignored here.
+fn generate_socketcan_hooks(buses: &Vec<BusRef>, source: &mut String, options: &Options) {
+    let namespace = options.namespace();
+    let ns_upper = namespace.to_uppercase();
+    let mut indent = String::new();
+    for _ in 0..options.indent() {
+        indent.push(' ');
+    }
+    let indent2 = format!("{indent}{indent}");
+
+    source.push_str(&format!(
+        "static canid_t {namespace}_to_socketcan_id(uint32_t id) {{
+{indent}if (id & {ns_upper}_FRAME_IDE_BIT) {{
+{indent2}return (id & CAN_EFF_MASK) | CAN_EFF_FLAG | ((id & {ns_upper}_FRAME_RTR_BIT) ? CAN_RTR_FLAG : 0);
+{indent}}}
+{indent}return (id & CAN_SFF_MASK) | ((id & {ns_upper}_FRAME_RTR_BIT) ? CAN_RTR_FLAG : 0);
+}}
+static uint32_t {namespace}_from_socketcan_id(canid_t can_id) {{
+{indent}uint32_t rtr = (can_id & CAN_RTR_FLAG) ? {ns_upper}_FRAME_RTR_BIT : 0;
+{indent}if (can_id & CAN_EFF_FLAG) {{
+{indent2}return (can_id & CAN_EFF_MASK) | {ns_upper}_FRAME_IDE_BIT | rtr;
+{indent}}}
+{indent}return (can_id & CAN_SFF_MASK) | rtr;
+}}
+__attribute__((weak)) uint32_t {namespace}_get_time() {{
+{indent}struct timespec ts;
+{indent}clock_gettime(CLOCK_MONOTONIC, &ts);
+{indent}return (uint32_t)(ts.tv_sec * 1000 + ts.tv_nsec / 1000000);
+}}
+"
+    ));
+
+    for bus in buses {
+        let bus_name = bus.name();
+        let ifname_macro = format!("{ns_upper}_{}_IFNAME", bus_name.to_uppercase());
+        let socket_name = format!("{namespace}_{bus_name}_socket");
+        let setup_params = if bus.fd() {
+            format!("uint32_t baudrate, uint32_t data_baudrate, {namespace}_can_filter* filters, int filter_count")
+        } else {
+            format!("uint32_t baudrate, {namespace}_can_filter* filters, int filter_count")
+        };
+        let ignore_data_baudrate = if bus.fd() {
+            format!("{indent}(void)data_baudrate;\n")
+        } else {
+            String::new()
+        };
+        // bit rate switching requires a data baudrate.
+        let brs_flag = if bus.data_baudrate().is_some() {
+            format!("{indent2}cfd.flags = CANFD_BRS;\n")
+        } else {
+            String::new()
+        };
+        let enable_fd = if bus.fd() {
+            format!(
+                "{indent}int enable_fd = 1;
+{indent}setsockopt(s, SOL_CAN_RAW, CAN_RAW_FD_FRAMES, &enable_fd, sizeof(enable_fd));
+"
+            )
+        } else {
+            String::new()
+        };
+        source.push_str(&format!(
+            "#ifndef {ifname_macro}
+#define {ifname_macro} \"{bus_name}\"
+#endif
+static int {socket_name} = -1;
+void {namespace}_{bus_name}_setup({setup_params}) {{
+{indent}(void)baudrate;
+{ignore_data_baudrate}{indent}int s = socket(PF_CAN, SOCK_RAW, CAN_RAW);
+{indent}if (s < 0) {{
+{indent2}perror(\"{namespace}_{bus_name}_setup: socket\");
+{indent2}return;
+{indent}}}
+{indent}struct ifreq ifr;
+{indent}memset(&ifr, 0, sizeof(ifr));
+{indent}strncpy(ifr.ifr_name, {ifname_macro}, IFNAMSIZ - 1);
+{indent}if (ioctl(s, SIOCGIFINDEX, &ifr) < 0) {{
+{indent2}perror(\"{namespace}_{bus_name}_setup: \" {ifname_macro});
+{indent2}close(s);
+{indent2}return;
+{indent}}}
+{enable_fd}{indent}if (filter_count > 0) {{
+{indent2}struct can_filter rfilters[filter_count];
+{indent2}for (int i = 0; i < filter_count; ++i) {{
+{indent2}{indent}rfilters[i].can_id = {namespace}_to_socketcan_id(filters[i].id);
+{indent2}{indent}rfilters[i].can_mask = {namespace}_to_socketcan_id(filters[i].mask);
+{indent2}}}
+{indent2}setsockopt(s, SOL_CAN_RAW, CAN_RAW_FILTER, rfilters, sizeof(rfilters));
+{indent}}}
+{indent}struct sockaddr_can addr;
+{indent}memset(&addr, 0, sizeof(addr));
+{indent}addr.can_family = AF_CAN;
+{indent}addr.can_ifindex = ifr.ifr_ifindex;
+{indent}if (bind(s, (struct sockaddr*)&addr, sizeof(addr)) < 0) {{
+{indent2}perror(\"{namespace}_{bus_name}_setup: bind\");
+{indent2}close(s);
+{indent2}return;
+{indent}}}
+{indent}fcntl(s, F_SETFL, fcntl(s, F_GETFL, 0) | O_NONBLOCK);
+{indent}{socket_name} = s;
+}}
+void {namespace}_{bus_name}_send({namespace}_frame* frame) {{
+{indent}struct canfd_frame cfd;
+{indent}memset(&cfd, 0, sizeof(cfd));
+{indent}cfd.can_id = {namespace}_to_socketcan_id(frame->id);
+{indent}cfd.len = frame->dlc;
+{indent}memcpy(cfd.data, frame->data, frame->dlc);
+{indent}if (frame->dlc > CAN_MAX_DLEN) {{
+{brs_flag}{indent2}write({socket_name}, &cfd, CANFD_MTU);
+{indent}}} else {{
+{indent2}write({socket_name}, &cfd, CAN_MTU);
+{indent}}}
+}}
+int {namespace}_{bus_name}_recv({namespace}_frame* frame) {{
+{indent}struct canfd_frame cfd;
+{indent}ssize_t n = read({socket_name}, &cfd, sizeof(cfd));
+{indent}if (n != CAN_MTU && n != CANFD_MTU) {{
+{indent2}return 0;
+{indent}}}
+{indent}if (cfd.len > sizeof(frame->data)) {{
+{indent2}return 0;
+{indent}}}
+{indent}frame->id = {namespace}_from_socketcan_id(cfd.can_id);
+{indent}frame->dlc = cfd.len;
+{indent}memcpy(frame->data, cfd.data, cfd.len);
+{indent}return 1;
+}}
+"
+        ));
+    }
+}
+
+/// In-memory implementation of the can hooks for host tests. Frames are
+/// passed to the node with {namespace}_harness_receive, which applies the
+/// acceptance filters of the bus, and the frames sent by the node are taken
+/// from {namespace}_harness_sent. The time only advances with {namespace}_harness_set_time.
+///
+/// With {NAMESPACE}_HARNESS_MAIN defined, a main function drives the node with
+/// commands from stdin (one per line):
+///   init                        -> {namespace}_init()
+///   time <ms>                   -> sets the time
+///   rx <bus_id> <id> <dlc> <b0> ... -> prints accepted or rejected (id and bytes in hex)
+///   update                      -> polls and updates the node, prints the sent frames
+///                                  as "tx <bus_id> <id> <dlc> <b0> ..." followed by "next <ms>".
+fn generate_host_harness(
+    buses: &Vec<BusRef>,
+    source: &mut String,
+    header: &mut String,
+    options: &Options,
+) {
+    let namespace = options.namespace();
+    let ns_upper = namespace.to_uppercase();
+    let mut indent = String::new();
+    for _ in 0..options.indent() {
+        indent.push(' ');
+    }
+    let indent2 = format!("{indent}{indent}");
+    let indent3 = format!("{indent2}{indent}");
+
+    header.push_str(&format!(
+        "int {namespace}_harness_receive(uint32_t bus_id, {namespace}_frame* frame);
+int {namespace}_harness_sent(uint32_t* bus_id, {namespace}_frame* frame);
+void {namespace}_harness_set_time(uint32_t time);
+uint32_t {namespace}_harness_update();
+"
+    ));
+
+    source.push_str(&format!(
+        "#ifndef {ns_upper}_HARNESS_QUEUE_SIZE
+#define {ns_upper}_HARNESS_QUEUE_SIZE 1024
+#endif
+typedef struct {{
+{indent}uint32_t bus_id;
+{indent}{namespace}_frame frame;
+}} {namespace}_harness_frame;
+typedef struct {{
+{indent}{namespace}_harness_frame frames[{ns_upper}_HARNESS_QUEUE_SIZE];
+{indent}uint32_t head;
+{indent}uint32_t tail;
+}} {namespace}_harness_queue;
+static int {namespace}_harness_queue_push({namespace}_harness_queue* queue, uint32_t bus_id, {namespace}_frame* frame) {{
+{indent}uint32_t next = (queue->tail + 1) % {ns_upper}_HARNESS_QUEUE_SIZE;
+{indent}if (next == queue->head) {{
+{indent2}return 0;
+{indent}}}
+{indent}queue->frames[queue->tail].bus_id = bus_id;
+{indent}queue->frames[queue->tail].frame = *frame;
+{indent}queue->tail = next;
+{indent}return 1;
+}}
+static int {namespace}_harness_queue_pop({namespace}_harness_queue* queue, uint32_t* bus_id, {namespace}_frame* frame) {{
+{indent}if (queue->head == queue->tail) {{
+{indent2}return 0;
+{indent}}}
+{indent}*bus_id = queue->frames[queue->head].bus_id;
+{indent}*frame = queue->frames[queue->head].frame;
+{indent}queue->head = (queue->head + 1) % {ns_upper}_HARNESS_QUEUE_SIZE;
+{indent}return 1;
+}}
+static int {namespace}_harness_accepts({namespace}_can_filter* filters, int filter_count, uint32_t id) {{
+{indent}if (filter_count == 0) {{
+{indent2}return 1;
+{indent}}}
+{indent}for (int i = 0; i < filter_count; ++i) {{
+{indent2}if (((id ^ filters[i].id) & filters[i].mask) == 0) {{
+{indent3}return 1;
+{indent2}}}
+{indent}}}
+{indent}return 0;
+}}
+static {namespace}_harness_queue {namespace}_harness_tx;
+static uint32_t {namespace}_harness_time = 0;
+uint32_t {namespace}_get_time() {{
+{indent}return {namespace}_harness_time;
+}}
+void {namespace}_harness_set_time(uint32_t time) {{
+{indent}{namespace}_harness_time = time;
+}}
+// the harness is updated explicitly, requests are ignored.
+void {namespace}_request_update(uint32_t time) {{
+{indent}(void)time;
+}}
+int {namespace}_harness_sent(uint32_t* bus_id, {namespace}_frame* frame) {{
+{indent}return {namespace}_harness_queue_pop(&{namespace}_harness_tx, bus_id, frame);
+}}
+"
+    ));
+
+    let mut receive_cases = String::new();
+    let mut poll_logic = String::new();
+    for bus in buses {
+        let bus_name = bus.name();
+        let bus_id = bus.id();
+        let rx_queue = format!("{namespace}_harness_{bus_name}_rx");
+        let filters = format!("{namespace}_harness_{bus_name}_filters");
+        let filter_count = format!("{namespace}_harness_{bus_name}_filter_count");
+        let (setup_params, ignore_data_baudrate) = if bus.fd() {
+            (
+                format!("uint32_t baudrate, uint32_t data_baudrate, {namespace}_can_filter* filters, int filter_count"),
+                format!("{indent}(void)data_baudrate;\n"),
+            )
+        } else {
+            (
+                format!("uint32_t baudrate, {namespace}_can_filter* filters, int filter_count"),
+                String::new(),
+            )
+        };
+        source.push_str(&format!(
+            "static {namespace}_harness_queue {rx_queue};
+static {namespace}_can_filter* {filters} = NULL;
+static int {filter_count} = 0;
+void {namespace}_{bus_name}_setup({setup_params}) {{
+{indent}(void)baudrate;
+{ignore_data_baudrate}{indent}{filters} = filters;
+{indent}{filter_count} = filter_count;
+}}
+void {namespace}_{bus_name}_send({namespace}_frame* frame) {{
+{indent}{namespace}_harness_queue_push(&{namespace}_harness_tx, {bus_id}, frame);
+}}
+int {namespace}_{bus_name}_recv({namespace}_frame* frame) {{
+{indent}uint32_t bus_id;
+{indent}return {namespace}_harness_queue_pop(&{rx_queue}, &bus_id, frame);
+}}
+"
+        ));
+        receive_cases.push_str(&format!(
+            "{indent}case {bus_id}:
+{indent2}if (!{namespace}_harness_accepts({filters}, {filter_count}, frame->id)) {{
+{indent3}return 0;
+{indent2}}}
+{indent2}return {namespace}_harness_queue_push(&{rx_queue}, bus_id, frame);
+"
+        ));
+        poll_logic.push_str(&format!("{indent}{namespace}_can{bus_id}_poll();\n"));
+    }
+
+    source.push_str(&format!(
+        "int {namespace}_harness_receive(uint32_t bus_id, {namespace}_frame* frame) {{
+{indent}switch (bus_id) {{
+{receive_cases}{indent}default:
+{indent2}return 0;
+{indent}}}
+}}
+uint32_t {namespace}_harness_update() {{
+{poll_logic}{indent}return {namespace}_update_continue({namespace}_harness_time);
+}}
+#ifdef {ns_upper}_HARNESS_MAIN
+int main(void) {{
+{indent}char line[512];
+{indent}while (fgets(line, sizeof(line), stdin) != NULL) {{
+{indent2}if (strncmp(line, \"init\", 4) == 0) {{
+{indent3}{namespace}_init();
+{indent2}}} else if (strncmp(line, \"time \", 5) == 0) {{
+{indent3}{namespace}_harness_set_time((uint32_t)strtoul(line + 5, NULL, 10));
+{indent2}}} else if (strncmp(line, \"rx \", 3) == 0) {{
+{indent3}char* cursor = line + 3;
+{indent3}{namespace}_frame frame;
+{indent3}memset(&frame, 0, sizeof(frame));
+{indent3}uint32_t bus_id = (uint32_t)strtoul(cursor, &cursor, 10);
+{indent3}frame.id = (uint32_t)strtoul(cursor, &cursor, 16);
+{indent3}frame.dlc = (uint8_t)strtoul(cursor, &cursor, 10);
+{indent3}if (frame.dlc > sizeof(frame.data)) {{
+{indent3}{indent}frame.dlc = sizeof(frame.data);
+{indent3}}}
+{indent3}for (int i = 0; i < frame.dlc; ++i) {{
+{indent3}{indent}frame.data[i] = (uint8_t)strtoul(cursor, &cursor, 16);
+{indent3}}}
+{indent3}printf(\"%s\\n\", {namespace}_harness_receive(bus_id, &frame) ? \"accepted\" : \"rejected\");
+{indent2}}} else if (strncmp(line, \"update\", 6) == 0) {{
+{indent3}uint32_t next = {namespace}_harness_update();
+{indent3}uint32_t bus_id;
+{indent3}{namespace}_frame frame;
+{indent3}while ({namespace}_harness_sent(&bus_id, &frame)) {{
+{indent3}{indent}printf(\"tx %u %X %u\", (unsigned)bus_id, (unsigned)frame.id, (unsigned)frame.dlc);
+{indent3}{indent}for (int i = 0; i < frame.dlc; ++i) {{
+{indent3}{indent2}printf(\" %02X\", frame.data[i]);
+{indent3}{indent}}}
+{indent3}{indent}printf(\"\\n\");
+{indent3}}}
+{indent3}printf(\"next %u\\n\", (unsigned)next);
+{indent2}}}
+{indent2}fflush(stdout);
+{indent}}}
+{indent}return 0;
+}}
+#endif
+"
+    ));
+}
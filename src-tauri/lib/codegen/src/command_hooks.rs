@@ -67,12 +67,20 @@ pub fn generate_command_hooks(
                         entries: _,
                         visibility: _,
                     } => name.clone(),
-                    config::Type::Array { len: _, ty: _ } => todo!(),
+                    config::Type::Array { len: _, ty: _ } => {
+                        panic!("arrays don't have a c type name, use declare instead")
+                    }
+                }
+            }
+            // arrays are declared as "T name[len]".
+            fn declare(ty: &Type, name: &str) -> String {
+                match ty {
+                    config::Type::Array { len, ty } => declare(ty, &format!("{name}[{len}]")),
+                    _ => format!("{} {name}", ty_to_c_ty(ty)),
                 }
             }
             let name = attrib.name();
-            let ty = ty_to_c_ty(attrib.ty());
-            attribute_list += &format!("{ty} {name}");
+            attribute_list += &declare(attrib.ty(), name);
         }
 
         let hook_name = format!("{namespace}_{command_name}");
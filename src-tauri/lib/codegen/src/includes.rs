@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use crate::{errors::Result, options::Options};
+use crate::{
+    errors::Result,
+    options::{Language, Options, Platform},
+};
 
 pub fn generate_includes(
     source: &mut String,
@@ -16,10 +19,54 @@ pub fn generate_includes(
         .unwrap()
         .to_owned();
     source.push_str(&format!("#include \"{header_name}\"\n"));
-    source.push_str("#include <avr/pgmspace.h>\n");
-    
-    header.push_str("#include <cinttypes>\n");
-    header.push_str("#include <cstddef>\n");
+    match options.platform() {
+        Platform::Teensy => {
+            source.push_str("#include <Arduino.h>\n");
+        }
+        Platform::Stm32Hal => {
+            // main.h of a STM32CubeMX project includes the HAL of the MCU family.
+            source.push_str("#include \"main.h\"\n");
+        }
+        Platform::Linux => {
+            source.push_str("#include <fcntl.h>\n");
+            source.push_str("#include <linux/can.h>\n");
+            source.push_str("#include <linux/can/raw.h>\n");
+            source.push_str("#include <net/if.h>\n");
+            source.push_str("#include <pthread.h>\n");
+            source.push_str("#include <stdio.h>\n");
+            source.push_str("#include <string.h>\n");
+            source.push_str("#include <sys/ioctl.h>\n");
+            source.push_str("#include <sys/socket.h>\n");
+            source.push_str("#include <time.h>\n");
+            source.push_str("#include <unistd.h>\n");
+        }
+        Platform::Host => {
+            if options.host_harness() {
+                source.push_str("#include <stdio.h>\n");
+                source.push_str("#include <stdlib.h>\n");
+                source.push_str("#include <string.h>\n");
+            }
+        }
+    }
+    if options.platform().progmem() {
+        source.push_str("#include <avr/pgmspace.h>\n");
+    }
+
+    match options.platform().language() {
+        Language::Cpp => {
+            header.push_str("#include <cinttypes>\n");
+            header.push_str("#include <cstddef>\n");
+        }
+        Language::C => {
+            header.push_str("#include <inttypes.h>\n");
+            header.push_str("#include <stddef.h>\n");
+        }
+    }
+    // DMAMEM places large buffers in the DMA memory of the Teensy,
+    // other platforms don't distinguish.
+    if options.platform() != &Platform::Teensy {
+        header.push_str("#ifndef DMAMEM\n#define DMAMEM\n#endif\n");
+    }
 
     Ok(())
 }
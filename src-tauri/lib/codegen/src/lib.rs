@@ -35,6 +35,7 @@ mod header_guard;
 mod scheduler;
 mod setters;
 pub mod senders;
+pub mod rust;
 
 pub fn generate(
     node_name: &str,
@@ -61,7 +62,8 @@ pub fn generate(
 
     header += &format!("static const node_id CANZERO_NODE_ID = node_id_{};\n", node_name);
 
-    generate_pil(&mut src, &mut header, &options)?;
+    let fd = network_config.buses().iter().any(|bus| bus.fd());
+    generate_pil(fd, &mut src, &mut header, &options)?;
     generate_hooks(network_config.buses(), &mut src, &mut header, &options)?;
     generate_command_hooks(node_config.commands(), &mut src, &mut header, &options)?;
 
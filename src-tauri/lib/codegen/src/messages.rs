@@ -2,7 +2,7 @@ use canzero_config::config::{self, MessageRef, SignalType, Type, TypeSignalEncod
 
 use crate::errors::Result;
 use crate::options::Options;
-use crate::types::to_c_type_name;
+use crate::types::{c_identifier, composite_attribute_prefix, to_c_declaration};
 
 pub fn generate_messages(
     tx_messages: &Vec<MessageRef>,
@@ -33,9 +33,8 @@ pub fn generate_messages(
         match message.encoding() {
             Some(encoding) => {
                 for attrib in encoding.attributes() {
-                    let attrib_type_name = to_c_type_name(attrib.ty());
-                    let attrib_name = attrib.name();
-                    type_def.push_str(&format!("{indent}{attrib_type_name} m_{attrib_name};\n"));
+                    let decl = to_c_declaration(attrib.ty(), &format!("m_{}", attrib.name()));
+                    type_def.push_str(&format!("{indent}{decl};\n"));
                 }
             }
             None => {
@@ -69,12 +68,13 @@ pub fn generate_messages(
             }
         };
         let dlc = message.dlc();
+        let dlc_bytes = dlc.max(8);
         // function to serialize the message struct into a can frame!
         let serialize_func_name = format!("serialize_{message_type_name}");
         let mut serialize_def = format!(
 "static void {namespace}_{serialize_func_name}({message_type_name}* msg, {namespace}_frame* frame) {{
 {indent}uint8_t* data = frame->data;
-{indent}for(uint8_t i = 0; i < 8; ++i){{
+{indent}for(uint8_t i = 0; i < {dlc_bytes}; ++i){{
 {indent}{indent}data[i] = 0;
 {indent}}}
 {indent}frame->id = {id};
@@ -87,18 +87,20 @@ pub fn generate_messages(
                     serialized_def: &mut String,
                     attrib: &TypeSignalEncoding,
                     indent: &str,
+                    namespace: &str,
                     attribute_prefix: &str,
                     attrib_offset: &mut usize,
                 ) {
                     match attrib {
                         TypeSignalEncoding::Composite(composite) => {
-                            let attrib_name = composite.name();
-                            let attrib_prefix = format!("{attribute_prefix}{attrib_name}.m_");
+                            let attrib_prefix =
+                                composite_attribute_prefix(composite, attribute_prefix);
                             for attrib in composite.attributes() {
                                 write_attribute_parse_code(
                                     serialized_def,
                                     attrib,
                                     indent,
+                                    namespace,
                                     &attrib_prefix,
                                     attrib_offset,
                                 );
@@ -106,6 +108,7 @@ pub fn generate_messages(
                         }
                         TypeSignalEncoding::Primitive(primitive) => {
                             let attrib_name = primitive.name();
+                            let tmp_name = c_identifier(attrib_name);
                             match attrib.ty() as &Type {
                                 config::Type::Primitive(signal_type) => {
                                     let var = match signal_type {
@@ -122,27 +125,27 @@ pub fn generate_messages(
                                             scale,
                                         } => {
                                             if *size <= 32 {
-                                                serialized_def.push_str(&format!("{indent}uint32_t {attrib_name}_{attrib_offset} = (({attribute_prefix}{attrib_name} - {offset}) / {scale}) + 0.5f;\n"));
+                                                serialized_def.push_str(&format!("{indent}uint32_t {tmp_name}_{attrib_offset} = (({attribute_prefix}{attrib_name} - {offset}) / {scale}) + 0.5f;\n"));
                                                 let u32_max = (0xFFFFFFFF as u32)
                                                     >> (32 - *size as u32);
 
                                                 serialized_def.push_str(&format!(
-"{indent}if ({attrib_name}_{attrib_offset} > 0x{u32_max:X}) {{
-{indent}{indent}{attrib_name}_{attrib_offset} = 0x{u32_max:X};
+"{indent}if ({tmp_name}_{attrib_offset} > 0x{u32_max:X}) {{
+{indent}{indent}{tmp_name}_{attrib_offset} = 0x{u32_max:X};
 {indent}}}
 "));
-                                                format!("{attrib_name}_{attrib_offset}")
+                                                format!("{tmp_name}_{attrib_offset}")
                                             } else {
-                                                serialized_def.push_str(&format!("{indent}uint64_t {attrib_name}_{attrib_offset} = (({attribute_prefix}{attrib_name} - {offset}) / {scale}) + 0.5;\n"));
+                                                serialized_def.push_str(&format!("{indent}uint64_t {tmp_name}_{attrib_offset} = (({attribute_prefix}{attrib_name} - {offset}) / {scale}) + 0.5;\n"));
                                                 let u64_max = (0xFFFFFFFFFFFFFFFF as u64)
                                                     >> (64 - *size as u32);
 
                                                 serialized_def.push_str(&format!(
-"{indent}if ({attrib_name}_{attrib_offset} > 0x{u64_max:X}ull) {{
-{indent}{indent}{attrib_name}_{attrib_offset} = 0x{u64_max:X}ull;
+"{indent}if ({tmp_name}_{attrib_offset} > 0x{u64_max:X}ull) {{
+{indent}{indent}{tmp_name}_{attrib_offset} = 0x{u64_max:X}ull;
 {indent}}}
 "));
-                                                format!("{attrib_name}_{attrib_offset}")
+                                                format!("{tmp_name}_{attrib_offset}")
                                             }
                                         }
                                     };
@@ -175,6 +178,9 @@ pub fn generate_messages(
                                         format!(
                                             "{indent}((uint32_t*)data)[{word_offset}] = {val};\n"
                                         )
+                                    } else if *attrib_offset + size as usize > 64 {
+                                        // beyond the first long word (only CAN FD frames)
+                                        format!("{indent}{namespace}_write_bits(data, {attrib_offset}, {size}, (uint64_t){val});\n")
                                     } else if word_bit_offset == 0 && size > 32 {
                                         // long word aligned long wrd write
                                         // is asserted to be the first bit of the data frame
@@ -233,6 +239,9 @@ pub fn generate_messages(
                                         format!(
                                             "{indent}((uint32_t*)data)[{word_offset}] = {val};\n"
                                         )
+                                    } else if *attrib_offset + size as usize > 64 {
+                                        // beyond the first long word (only CAN FD frames)
+                                        format!("{indent}{namespace}_write_bits(data, {attrib_offset}, {size}, (uint64_t){val});\n")
                                     } else if word_bit_offset == 0 && size > 32 {
                                         // long word aligned long wrd write
                                         // is asserted to be the first bit of the data frame
@@ -254,7 +263,7 @@ pub fn generate_messages(
                                     serialized_def.push_str(&write_logic);
                                     *attrib_offset += size as usize;
                                 }
-                                config::Type::Array { len: _, ty: _ } => todo!(),
+                                config::Type::Array { len: _, ty: _ } => panic!("arrays are not primitive"),
                                 config::Type::Struct {
                                     name: _,
                                     description: _,
@@ -272,6 +281,7 @@ pub fn generate_messages(
                         &mut serialize_def,
                         attrib,
                         &indent,
+                        &namespace,
                         "msg->m_",
                         &mut attrib_offset,
                     );
@@ -327,6 +337,9 @@ pub fn generate_messages(
                         // is asserted to be the first write to the word!
                         let word_offset = attrib_offset / 32; // intentional floor
                         format!("{indent}((uint32_t*)data)[{word_offset}] = {val};\n")
+                    } else if attrib_offset + size as usize > 64 {
+                        // beyond the first long word (only CAN FD frames)
+                        format!("{indent}{namespace}_write_bits(data, {attrib_offset}, {size}, (uint64_t){val});\n")
                     } else if word_bit_offset == 0 && size > 32 {
                         // long word aligned long wrd write
                         // is asserted to be the first bit of the data frame
@@ -370,18 +383,20 @@ pub fn generate_messages(
                     deserialized_def: &mut String,
                     attrib: &TypeSignalEncoding,
                     indent: &str,
+                    namespace: &str,
                     attribute_prefix: &str,
                     attrib_bit_offset: &mut usize,
                 ) {
                     match attrib {
                         TypeSignalEncoding::Composite(composite) => {
-                            let attrib_name = composite.name();
-                            let attrib_prefix = format!("{attribute_prefix}{attrib_name}.m_");
+                            let attrib_prefix =
+                                composite_attribute_prefix(composite, attribute_prefix);
                             for attrib in composite.attributes() {
                                 write_attribute_write_code(
                                     deserialized_def,
                                     attrib,
                                     indent,
+                                    namespace,
                                     &attrib_prefix,
                                     attrib_bit_offset,
                                 );
@@ -400,6 +415,8 @@ pub fn generate_messages(
                                     let val_bits = if word_bit_offset == 0 && size <= 32 {
                                         let word_offset = *attrib_bit_offset / 32;
                                         format!("((uint32_t*)data)[{word_offset}] & (0xFFFFFFFF >> (32 - {size}))")
+                                    } else if *attrib_bit_offset + size > 64 {
+                                        format!("{namespace}_read_bits(data, {attrib_bit_offset}, {size})")
                                     } else if word_bit_offset == 0 && size > 32 {
                                         format!("((uint64_t*)data)[0] & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
                                     } else if word_bit_offset + size <= 32 {
@@ -457,6 +474,8 @@ pub fn generate_messages(
                                     let val_bits = if word_bit_offset == 0 && size <= 32 {
                                         let word_offset = *attrib_bit_offset / 32;
                                         format!("((uint32_t*)data)[{word_offset}] & (0xFFFFFFFF >> (32 - {size}))")
+                                    } else if *attrib_bit_offset + size > 64 {
+                                        format!("{namespace}_read_bits(data, {attrib_bit_offset}, {size})")
                                     } else if word_bit_offset == 0 && size > 32 {
                                         format!("((uint64_t*)data)[0] & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
                                     } else if word_bit_offset + size <= 32 {
@@ -473,7 +492,7 @@ pub fn generate_messages(
                                     deserialized_def.push_str(&format!("{var} = {val};\n"));
                                     *attrib_bit_offset += size;
                                 }
-                                config::Type::Array { len: _, ty: _ } => todo!(),
+                                config::Type::Array { len: _, ty: _ } => panic!("arrays are not primitive"),
                             };
                         }
                     }
@@ -485,6 +504,7 @@ pub fn generate_messages(
                         &mut deserialize_def,
                         attrib,
                         &indent,
+                        &namespace,
                         "msg->m_",
                         &mut attrib_offset,
                     );
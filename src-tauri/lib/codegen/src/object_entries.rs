@@ -1,7 +1,10 @@
-use canzero_config::config::ObjectEntryRef;
+use canzero_config::config::{ObjectEntryRef, Type};
 
 use crate::errors::Result;
-use crate::{options::Options, types::to_c_type_name};
+use crate::{
+    options::Options,
+    types::{to_c_declaration, to_c_type_name},
+};
 
 pub fn generate_object_entries(
     object_entries: &Vec<ObjectEntryRef>,
@@ -17,18 +20,35 @@ pub fn generate_object_entries(
 
     for object_entry in object_entries {
         println!("oe_name = {}", object_entry.name());
-        let type_name = to_c_type_name(object_entry.ty());
         let oe_name = object_entry.name();
         let oe_var = format!("__oe_{oe_name}");
 
-        let var_def = format!("{type_name} DMAMEM {oe_var};\n");
+        let var_def = format!("{};\n", to_c_declaration(object_entry.ty(), &format!("DMAMEM {oe_var}")));
         source.push_str(&var_def);
 
         let getter_name = format!("{namespace}_get_{oe_name}");
-        let mut getter_def = format!("static inline {type_name} {getter_name}() {{\n");
-        getter_def.push_str(&format!("{indent}extern {type_name} {oe_var};\n"));
-        getter_def.push_str(&format!("{indent}return {oe_var};\n"));
-        getter_def.push_str("}\n");
+        let extern_decl = to_c_declaration(object_entry.ty(), &oe_var);
+        let getter_def = match object_entry.ty() as &Type {
+            // c functions can't return arrays, therefor arrays are read element wise.
+            Type::Array { len: _, ty } => {
+                let type_name = to_c_type_name(ty);
+                format!(
+"static inline {type_name} {getter_name}(uint32_t index) {{
+{indent}extern {extern_decl};
+{indent}return {oe_var}[index];
+}}
+")
+            }
+            ty => {
+                let type_name = to_c_type_name(ty);
+                format!(
+"static inline {type_name} {getter_name}() {{
+{indent}extern {extern_decl};
+{indent}return {oe_var};
+}}
+")
+            }
+        };
         header.push_str(&getter_def);
         
         // NOTE: setters where moved to a seperate file
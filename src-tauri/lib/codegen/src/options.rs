@@ -1,6 +1,64 @@
 
+/// Target the generated code is compiled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Platform {
+    /// Teensy (Arduino core), C++ with constant tables in PROGMEM.
+    Teensy,
+    /// STM32 with the STM32Cube HAL, plain C.
+    Stm32Hal,
+    /// Linux (e.g. the Raspberry Pi of the pod), plain C with SocketCAN hooks.
     Linux,
+    /// Host build for tests, plain C, the hooks are provided by the test.
+    Host,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    C,
+    Cpp,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 4] = [
+        Platform::Teensy,
+        Platform::Stm32Hal,
+        Platform::Linux,
+        Platform::Host,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::Teensy => "teensy",
+            Platform::Stm32Hal => "stm32-hal",
+            Platform::Linux => "linux",
+            Platform::Host => "host",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Platform> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+    pub fn language(&self) -> Language {
+        match self {
+            Platform::Teensy => Language::Cpp,
+            Platform::Stm32Hal | Platform::Linux | Platform::Host => Language::C,
+        }
+    }
+    /// place constant lookup tables in flash (avr/pgmspace.h)
+    pub fn progmem(&self) -> bool {
+        matches!(self, Platform::Teensy)
+    }
+    /// generate the {namespace}_{bus}_setup/send/recv and {namespace}_get_time
+    /// implementations instead of only declaring them.
+    pub fn implements_can_hooks(&self) -> bool {
+        matches!(self, Platform::Linux)
+    }
+    /// file extension of the generated source file.
+    pub fn source_extension(&self) -> &'static str {
+        match self.language() {
+            Language::C => "c",
+            Language::Cpp => "cpp",
+        }
+    }
 }
 
 pub struct Options {
@@ -9,6 +67,7 @@ pub struct Options {
     platform : Platform,
     indent : usize,
     namespace : String,
+    host_harness : bool,
 }
 
 impl Default for Options {
@@ -16,9 +75,10 @@ impl Default for Options {
         Self {
             source_file_path : "canzero.c".to_owned(),
             header_file_path : "canzero.h".to_owned(),
-            platform : Platform::Linux,
+            platform : Platform::Teensy,
             indent : 2,
             namespace : "canzero".to_owned(),
+            host_harness : false,
         }
     }
 }
@@ -54,4 +114,12 @@ impl Options {
     pub fn set_namespace(&mut self, namespace : &str) {
         self.namespace = namespace.to_owned();
     }
+    /// implement the can hooks with an in-memory bus and a fake time,
+    /// only used with Platform::Host.
+    pub fn host_harness(&self) -> bool {
+        self.host_harness
+    }
+    pub fn set_host_harness(&mut self, host_harness : bool) {
+        self.host_harness = host_harness;
+    }
 }
@@ -1,9 +1,11 @@
 use crate::{
     errors::Result,
-    options::Options,
+    options::{Language, Options},
 };
 
+/// fd enlarges the frames to the 64 byte payload of CAN FD frames.
 pub fn generate_pil(
+    fd: bool,
     source: &mut String,
     header: &mut String,
     options: &Options,
@@ -15,24 +17,36 @@ pub fn generate_pil(
     }
 
     // =====================can_frame definition===================
+    // NOTE dlc is the length of the payload in bytes, frames with more
+    // than 8 bytes have to be transmitted as CAN FD frames.
     let can_frame_name = format!("{namespace}_frame");
+    let data_len = if fd { 64 } else { 8 };
     let can_frame_type_def = format!(
         "typedef struct {{
 {indent}uint32_t id;
 {indent}uint8_t dlc;
-{indent}uint8_t data[8];
+{indent}uint8_t data[{data_len}];
 }} {can_frame_name};\n"
     );
     header.push_str(&can_frame_type_def);
 
-    let can_frame_id_bits_def = format!(
-        "typedef enum : uint32_t {{
+    // enums with a fixed underlying type are not available in C.
+    let can_frame_id_bits_def = match options.platform().language() {
+        Language::Cpp => format!(
+            "typedef enum : uint32_t {{
 {indent}{}_FRAME_IDE_BIT = 0x40000000, // 1 << 30
 {indent}{}_FRAME_RTR_BIT = 0x80000000, // 1 << 31
 }} can_frame_id_bits;\n",
-        namespace.to_uppercase(),
-        namespace.to_uppercase()
-    );
+            namespace.to_uppercase(),
+            namespace.to_uppercase()
+        ),
+        Language::C => format!(
+            "#define {}_FRAME_IDE_BIT 0x40000000u // 1 << 30
+#define {}_FRAME_RTR_BIT 0x80000000u // 1 << 31\n",
+            namespace.to_uppercase(),
+            namespace.to_uppercase()
+        ),
+    };
     header.push_str(&can_frame_id_bits_def);
 
 
@@ -61,5 +75,25 @@ uint64_t min_u64(uint64_t v, uint64_t max) {
 }
 ");
 
+    if fd {
+        // bit access beyond the first 64 bits of a CAN FD frame.
+        source.push_str(&format!(
+"static void {namespace}_write_bits(uint8_t* data, uint32_t bit_offset, uint8_t size, uint64_t value) {{
+{indent}for (uint8_t i = 0; i < size; ++i) {{
+{indent}{indent}if ((value >> i) & 1) {{
+{indent}{indent}{indent}data[(bit_offset + i) / 8] |= (uint8_t)(1 << ((bit_offset + i) % 8));
+{indent}{indent}}}
+{indent}}}
+}}
+static uint64_t {namespace}_read_bits(const uint8_t* data, uint32_t bit_offset, uint8_t size) {{
+{indent}uint64_t value = 0;
+{indent}for (uint8_t i = 0; i < size; ++i) {{
+{indent}{indent}value |= (uint64_t)((data[(bit_offset + i) / 8] >> ((bit_offset + i) % 8)) & 1) << i;
+{indent}}}
+{indent}return value;
+}}
+"));
+    }
+
     Ok(())
 }
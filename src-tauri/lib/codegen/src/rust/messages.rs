@@ -0,0 +1,171 @@
+use canzero_config::config::{self, MessageRef, Type, TypeSignalEncoding};
+
+use crate::{errors::Result, options::Options};
+
+use super::{
+    indent, rust_ident, rust_type_ident,
+    types::{primitive_from_raw, primitive_to_raw, rust_type_name, signal_type_to_rust_type},
+};
+
+pub fn message_type_name(message: &MessageRef) -> String {
+    format!("{}Message", rust_type_ident(message.name()))
+}
+
+/// One struct per message sent or received by the node,
+/// with the same attributes as the message structs of the c code.
+pub fn generate_messages(
+    node_config: &config::NodeRef,
+    src: &mut String,
+    options: &Options,
+) -> Result<()> {
+    let i1 = indent(options, 1);
+    let i2 = indent(options, 2);
+    let i3 = indent(options, 3);
+
+    let mut all_messages = node_config.tx_messages().clone();
+    for rx_message in node_config.rx_messages() {
+        if all_messages.iter().any(|m| m.name() == rx_message.name()) {
+            continue;
+        }
+        all_messages.push(rx_message.clone());
+    }
+
+    for message in &all_messages {
+        let type_name = message_type_name(message);
+        let mut fields = String::new();
+        let mut write_logic = String::new();
+        let mut read_fields = String::new();
+        match message.encoding() {
+            Some(encoding) => {
+                for attrib in encoding.attributes() {
+                    let attrib_name = rust_ident(attrib.name());
+                    let attrib_type = rust_type_name(attrib.ty());
+                    fields.push_str(&format!("{i1}pub {attrib_name}: {attrib_type},\n"));
+                    write_attribute(
+                        &mut write_logic,
+                        attrib,
+                        &format!("self.{attrib_name}"),
+                        &i2,
+                    );
+                    let value = read_attribute(attrib);
+                    read_fields.push_str(&format!("{i3}{attrib_name}: {value},\n"));
+                }
+            }
+            None => {
+                for signal in message.signals() {
+                    let signal_name = rust_ident(signal.name());
+                    let signal_type = signal_type_to_rust_type(signal.ty());
+                    fields.push_str(&format!("{i1}pub {signal_name}: {signal_type},\n"));
+                    let offset = signal.byte_offset();
+                    let size = signal.size();
+                    let raw = primitive_to_raw(signal.ty(), &format!("self.{signal_name}"), true);
+                    write_logic.push_str(&format!(
+                        "{i2}write_bits(&mut data, {offset}, {size}, {raw});\n"
+                    ));
+                    let value =
+                        primitive_from_raw(signal.ty(), &format!("read_bits(&data, {offset}, {size})"));
+                    read_fields.push_str(&format!("{i3}{signal_name}: {value},\n"));
+                }
+            }
+        }
+
+        let bus_id = message.bus().id();
+        let id = message.id().as_u32();
+        let ide = message.id().ide();
+        let dlc = message.dlc();
+        // bit rate switching for CAN FD frames on buses with a data baudrate.
+        let brs = message.bus().fd() && message.bus().data_baudrate().is_some();
+        if let Some(description) = message.description() {
+            src.push_str(&format!("/// {description}\n"));
+        }
+        src.push_str(&format!(
+            "#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct {type_name} {{
+{fields}}}
+
+impl {type_name} {{
+{i1}pub const BUS_ID: u32 = {bus_id};
+{i1}pub const ID: u32 = 0x{id:X};
+{i1}pub const IDE: bool = {ide};
+{i1}pub const DLC: u8 = {dlc};
+{i1}pub const BRS: bool = {brs};
+
+{i1}pub fn serialize(&self) -> NetworkFrame {{
+{i2}let mut data = [0u8; 64];
+{write_logic}{i2}frame(Self::BUS_ID, Self::ID, Self::IDE, Self::DLC, Self::BRS, &data)
+{i1}}}
+
+{i1}/// None if the frame is not an instance of this message.
+{i1}pub fn deserialize(frame: &NetworkFrame) -> Option<Self> {{
+{i2}if !matches(frame, Self::BUS_ID, Self::ID, Self::IDE) {{
+{i3}return None;
+{i2}}}
+{i2}let data = payload(frame);
+{i2}Some(Self {{
+{read_fields}{i2}}})
+{i1}}}
+}}
+
+"
+        ));
+    }
+    Ok(())
+}
+
+fn write_attribute(logic: &mut String, attrib: &TypeSignalEncoding, path: &str, indent: &str) {
+    match attrib {
+        TypeSignalEncoding::Composite(composite) => {
+            for child in composite.attributes() {
+                let child_path = match composite.ty() as &Type {
+                    // the attributes of arrays are named [0], [1], ...
+                    Type::Array { .. } => format!("{path}{}", child.name()),
+                    _ => format!("{path}.{}", rust_ident(child.name())),
+                };
+                write_attribute(logic, child, &child_path, indent);
+            }
+        }
+        TypeSignalEncoding::Primitive(primitive) => {
+            let offset = primitive.signal().byte_offset();
+            let size = primitive.signal().size();
+            let raw = match primitive.ty() as &Type {
+                Type::Primitive(signal_type) => primitive_to_raw(signal_type, path, true),
+                Type::Enum { .. } => format!("({path}) as u64"),
+                _ => panic!("primitive signal encodings are expected to be primitives or enums"),
+            };
+            logic.push_str(&format!("{indent}write_bits(&mut data, {offset}, {size}, {raw});\n"));
+        }
+    }
+}
+
+fn read_attribute(attrib: &TypeSignalEncoding) -> String {
+    match attrib {
+        TypeSignalEncoding::Composite(composite) => match composite.ty() as &Type {
+            Type::Array { .. } => {
+                let elements: Vec<String> =
+                    composite.attributes().iter().map(read_attribute).collect();
+                format!("[{}]", elements.join(", "))
+            }
+            Type::Struct { name, .. } => {
+                let fields: Vec<String> = composite
+                    .attributes()
+                    .iter()
+                    .map(|child| format!("{}: {}", rust_ident(child.name()), read_attribute(child)))
+                    .collect();
+                format!("{} {{ {} }}", rust_type_ident(name), fields.join(", "))
+            }
+            _ => panic!("composite signal encodings are expected to be structs or arrays"),
+        },
+        TypeSignalEncoding::Primitive(primitive) => {
+            let offset = primitive.signal().byte_offset();
+            let size = primitive.signal().size();
+            let raw = format!("read_bits(&data, {offset}, {size})");
+            match primitive.ty() as &Type {
+                Type::Primitive(signal_type) => primitive_from_raw(signal_type, &raw),
+                Type::Enum { name, .. } => {
+                    format!("{}::from_raw({raw}).unwrap_or_default()", rust_type_ident(name))
+                }
+                _ => panic!("primitive signal encodings are expected to be primitives or enums"),
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use canzero_config::config;
+
+use crate::{
+    errors::{Error, Result},
+    options::Options,
+};
+
+use self::{
+    messages::generate_messages, node::generate_node, types::generate_types,
+};
+
+mod messages;
+mod node;
+mod types;
+
+/// Generates a rust module (options.source_file_path) for the node,
+/// which implements the same protocol as the generated c code
+/// (object dictionary, get/set requests, heartbeats and streams)
+/// on top of a canzero_common::transport::CanTransport.
+///
+/// The crate including the module requires canzero-common and
+/// tokio (features "sync", "time", "macros") as dependencies.
+pub fn generate_rust(
+    node_name: &str,
+    network_config: config::NetworkRef,
+    options: Options,
+) -> Result<()> {
+    let Some(node_config) = network_config
+        .nodes()
+        .iter()
+        .find(|n| n.name() == node_name)
+    else {
+        return Err(Error::InvalidNodeName);
+    };
+
+    let mut src = String::new();
+    src.push_str(&format!(
+        "// Generated by canzero-codegen for the node {node_name}, do not edit.
+#![allow(dead_code, clippy::all)]
+
+use std::{{
+{indent}collections::{{HashMap, VecDeque}},
+{indent}sync::{{Arc, Mutex}},
+{indent}time::Duration,
+}};
+
+use canzero_common::{{transport::CanTransport, CanFrame, NetworkFrame, Timestamped}};
+use tokio::{{
+{indent}sync::{{mpsc, Notify}},
+{indent}time::Instant,
+}};
+
+",
+        indent = indent(&options, 1)
+    ));
+    generate_runtime(&mut src, &options);
+    generate_types(node_config, &mut src, &options)?;
+    generate_messages(node_config, &mut src, &options)?;
+    generate_node(&network_config, node_config, &mut src, &options)?;
+
+    std::fs::write(options.source_file_path(), &src).expect(&format!(
+        "failed to write to {}",
+        options.source_file_path()
+    ));
+    Ok(())
+}
+
+pub(crate) fn indent(options: &Options, depth: usize) -> String {
+    " ".repeat(options.indent() * depth)
+}
+
+/// snake_case config names to UpperCamelCase rust type names.
+pub(crate) fn rust_type_ident(name: &str) -> String {
+    let mut ident = String::new();
+    let mut upper = true;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            upper = true;
+            continue;
+        }
+        if upper {
+            ident.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            ident.push(c);
+        }
+    }
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// config names to rust field and function names.
+pub(crate) fn rust_ident(name: &str) -> String {
+    const KEYWORDS: [&str; 38] = [
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+        "where", "while", "abstract", "box", "final", "yield",
+    ];
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident
+    }
+}
+
+/// Helpers shared by all generated nodes.
+fn generate_runtime(src: &mut String, options: &Options) {
+    let i1 = indent(options, 1);
+    let i2 = indent(options, 2);
+    let i3 = indent(options, 3);
+    src.push_str(&format!(
+        "/// Reads size bits starting at the bit offset, bits are counted from the
+/// least significant bit of the first byte (like in the c code).
+fn read_bits(data: &[u8], offset: usize, size: usize) -> u64 {{
+{i1}let mut value = 0u64;
+{i1}for i in 0..size {{
+{i2}let bit = offset + i;
+{i2}value |= (((data[bit / 8] >> (bit % 8)) & 1) as u64) << i;
+{i1}}}
+{i1}value
+}}
+
+fn write_bits(data: &mut [u8], offset: usize, size: usize, value: u64) {{
+{i1}for i in 0..size {{
+{i2}let bit = offset + i;
+{i2}if (value >> i) & 1 != 0 {{
+{i3}data[bit / 8] |= 1 << (bit % 8);
+{i2}}} else {{
+{i3}data[bit / 8] &= !(1 << (bit % 8));
+{i2}}}
+{i1}}}
+}}
+
+fn sign_extend(raw: u64, size: usize) -> i64 {{
+{i1}((raw << (64 - size)) as i64) >> (64 - size)
+}}
+
+/// Payload of the frame padded with zeros to 64 bytes.
+fn payload(frame: &NetworkFrame) -> [u8; 64] {{
+{i1}let mut data = [0u8; 64];
+{i1}let bytes = frame.can_frame.get_data();
+{i1}data[..bytes.len()].copy_from_slice(&bytes);
+{i1}data
+}}
+
+/// Frames with more than 8 bytes are transmitted as CAN FD frames.
+fn frame(bus_id: u32, id: u32, ide: bool, dlc: u8, brs: bool, data: &[u8]) -> NetworkFrame {{
+{i1}let can_frame = if dlc > 8 {{
+{i2}CanFrame::new_fd(id, ide, brs, false, &data[..dlc as usize])
+{i1}}} else {{
+{i2}let mut first = [0u8; 8];
+{i2}first.copy_from_slice(&data[..8]);
+{i2}CanFrame::new(id, ide, false, dlc, u64::from_le_bytes(first))
+{i1}}};
+{i1}NetworkFrame {{ bus_id, can_frame }}
+}}
+
+fn matches(frame: &NetworkFrame, bus_id: u32, id: u32, ide: bool) -> bool {{
+{i1}frame.bus_id == bus_id
+{i2}&& frame.can_frame.get_id() == id
+{i2}&& frame.can_frame.get_ide_flag() == ide
+{i2}&& !frame.can_frame.get_rtr_flag()
+}}
+
+"
+    ));
+}
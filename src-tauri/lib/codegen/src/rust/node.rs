@@ -0,0 +1,572 @@
+use std::time::Duration;
+
+use canzero_config::config::{self, message::MessageUsage, ObjectEntryRef, Type};
+
+use crate::{errors::Result, options::Options};
+
+use super::{
+    indent,
+    messages::message_type_name,
+    rust_ident, rust_type_ident,
+    types::{read_value, rust_type_name, write_value},
+};
+
+/// Same schedule as the generated c code.
+const HEARTBEAT_TICK: Duration = Duration::from_millis(50);
+
+pub fn generate_node(
+    network_config: &config::NetworkRef,
+    node_config: &config::NodeRef,
+    src: &mut String,
+    options: &Options,
+) -> Result<()> {
+    let i1 = indent(options, 1);
+    let i2 = indent(options, 2);
+    let i3 = indent(options, 3);
+    let i4 = indent(options, 4);
+    let i5 = indent(options, 5);
+
+    let node_id = node_config.id();
+    let config_hash = network_config.portable_hash();
+    let ticks_next = node_config
+        .heartbeat_timeout()
+        .as_millis()
+        .div_ceil(HEARTBEAT_TICK.as_millis())
+        .min(0x7F);
+    let heartbeat_tick_ms = HEARTBEAT_TICK.as_millis();
+    src.push_str(&format!(
+        "pub const NODE_ID: u8 = {node_id};
+/// Hash of the network config, which the node was generated from.
+pub const CONFIG_HASH: u64 = {config_hash};
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+const HEARTBEAT_TICK: Duration = Duration::from_millis({heartbeat_tick_ms});
+const HEARTBEAT_TICKS_NEXT: u8 = {ticks_next};
+const GET_RESP_FRAGMENTATION_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeEvent {{
+{i1}/// a node, which registered its heartbeat, missed its heartbeats on the bus.
+{i1}HeartbeatTimeout {{ bus_id: u32, node_id: u8 }},
+{i1}/// a node sends heartbeats again after a timeout.
+{i1}HeartbeatRecovered {{ bus_id: u32, node_id: u8 }},
+}}
+
+"
+    ));
+
+    // ============ object dictionary ===============
+    let object_entries = node_config.object_entries();
+    let mut oe_fields = String::new();
+    let mut oe_init = String::new();
+    for object_entry in object_entries {
+        let oe_name = rust_ident(object_entry.name());
+        let oe_type = rust_type_name(object_entry.ty());
+        if let Some(description) = object_entry.description() {
+            oe_fields.push_str(&format!("{i1}/// {description}\n"));
+        }
+        oe_fields.push_str(&format!("{i1}pub {oe_name}: {oe_type},\n"));
+        let is_config_hash = object_entry.name() == "config_hash"
+            && matches!(object_entry.ty() as &Type, Type::Primitive(config::SignalType::UnsignedInt { size: 64 }));
+        if is_config_hash {
+            oe_init.push_str(&format!("{i3}{oe_name}: CONFIG_HASH,\n"));
+        } else {
+            let bytes = oe_byte_size(object_entry);
+            oe_init.push_str(&format!(
+                "{i3}{oe_name}: deserialize_{}(&[0u8; {bytes}]),\n",
+                object_entry.name()
+            ));
+        }
+    }
+    src.push_str(&format!(
+        "/// Object dictionary of the node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObjectEntries {{
+{oe_fields}}}
+
+impl ObjectEntries {{
+{i1}fn new() -> Self {{
+{i2}Self {{
+{oe_init}{i2}}}
+{i1}}}
+}}
+
+"
+    ));
+
+    // serialization of the object entries in get and set requests.
+    for object_entry in object_entries {
+        let name = object_entry.name();
+        let oe_type = rust_type_name(object_entry.ty());
+        let bytes = oe_byte_size(object_entry);
+        let value = match object_entry.ty() as &Type {
+            Type::Primitive(_) | Type::Enum { .. } => "*value",
+            _ => "value",
+        };
+        let write_logic = write_value(object_entry.ty(), value, "&mut buffer", "0", 0, &i1, &i1);
+        let read_logic = read_value(object_entry.ty(), "buffer", "0", 0);
+        src.push_str(&format!(
+            "fn serialize_{name}(value: &{oe_type}) -> Vec<u32> {{
+{i1}let mut buffer = [0u8; {bytes}];
+{write_logic}{i1}buffer
+{i2}.chunks(4)
+{i2}.map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+{i2}.collect()
+}}
+
+fn deserialize_{name}(buffer: &[u8]) -> {oe_type} {{
+{i1}{read_logic}
+}}
+
+"
+        ));
+    }
+
+    // ============ state ===============
+    let mut setters = String::new();
+    for object_entry in object_entries {
+        let name = object_entry.name();
+        let oe_name = rust_ident(name);
+        let oe_type = rust_type_name(object_entry.ty());
+        // the tx streams, which are sent early (after their min interval) if the value changes.
+        let promoted_streams: Vec<(usize, u128)> = node_config
+            .tx_streams()
+            .iter()
+            .enumerate()
+            .filter(|(_, stream)| {
+                stream
+                    .mapping()
+                    .iter()
+                    .any(|mapping| mapping.as_ref().is_some_and(|oe| oe.id() == object_entry.id()))
+                    && stream.min_interval() != stream.max_interval()
+            })
+            .map(|(i, stream)| (i, stream.min_interval().as_millis()))
+            .collect();
+        if promoted_streams.is_empty() {
+            setters.push_str(&format!(
+                "{i1}fn set_{name}(&mut self, value: {oe_type}) {{
+{i2}self.oe.{oe_name} = value;
+{i1}}}
+"
+            ));
+        } else {
+            let mut promote_logic = String::new();
+            for (stream_index, min_interval) in promoted_streams {
+                promote_logic.push_str(&format!(
+                    "{i3}let stream = &mut self.streams[{stream_index}];
+{i3}let promoted = stream.last + Duration::from_millis({min_interval});
+{i3}if stream.next > promoted {{
+{i4}stream.next = promoted;
+{i3}}}
+"
+                ));
+            }
+            setters.push_str(&format!(
+                "{i1}fn set_{name}(&mut self, value: {oe_type}) {{
+{i2}if self.oe.{oe_name} != value {{
+{i3}self.oe.{oe_name} = value;
+{promote_logic}{i2}}}
+{i1}}}
+"
+            ));
+        }
+    }
+
+    // ============ scheduler ===============
+    let mut heartbeat_logic = String::new();
+    for heartbeat in network_config.heartbeat_messages() {
+        let heartbeat_type = message_type_name(heartbeat);
+        heartbeat_logic.push_str(&format!(
+            "{i3}frames.push(
+{i4}{heartbeat_type} {{
+{i5}node_id: NODE_ID,
+{i5}unregister: 0,
+{i5}ticks_next: HEARTBEAT_TICKS_NEXT,
+{i4}}}
+{i4}.serialize(),
+{i3});
+"
+        ));
+    }
+
+    let mut stream_logic = String::new();
+    for (stream_index, tx_stream) in node_config.tx_streams().iter().enumerate() {
+        let message_type = message_type_name(tx_stream.message());
+        let max_interval = tx_stream.max_interval().as_millis();
+        let encoding = tx_stream
+            .message()
+            .encoding()
+            .expect("stream messages are expected to define a encoding");
+        let mut attribs = String::new();
+        for (mapping, attrib) in std::iter::zip(tx_stream.mapping(), encoding.attributes()) {
+            let Some(object_entry) = mapping else {
+                panic!("tx_streams are expected to define a complete mapping");
+            };
+            attribs.push_str(&format!(
+                "{i4}{}: self.oe.{},\n",
+                rust_ident(attrib.name()),
+                rust_ident(object_entry.name())
+            ));
+        }
+        stream_logic.push_str(&format!(
+            "{i2}if now >= self.streams[{stream_index}].next {{
+{i3}self.streams[{stream_index}].last = now;
+{i3}self.streams[{stream_index}].next = now + Duration::from_millis({max_interval});
+{i3}let message = {message_type} {{
+{attribs}{i3}}};
+{i3}frames.push(message.serialize());
+{i2}}}
+"
+        ));
+    }
+    let stream_count = node_config.tx_streams().len();
+
+    // ============ rx handlers ===============
+    let mut rx_logic = String::new();
+    for message in node_config.rx_messages() {
+        let message_type = message_type_name(message);
+        let handler = match message.usage() {
+            MessageUsage::GetReq => generate_get_req_handler(network_config, object_entries, options),
+            MessageUsage::SetReq => generate_set_req_handler(network_config, object_entries, options),
+            MessageUsage::Heartbeat => format!(
+                "{i3}let key = ({message_type}::BUS_ID, message.node_id);
+{i3}if message.node_id == NODE_ID {{
+{i4}// own heartbeat.
+{i3}}} else if message.unregister != 0 {{
+{i4}self.watchdogs.remove(&key);
+{i3}}} else {{
+{i4}let previous = self.watchdogs.insert(key, message.ticks_next as i32);
+{i4}if previous.is_some_and(|countdown| countdown <= 0) && message.ticks_next > 0 {{
+{i5}let _ = events.send(NodeEvent::HeartbeatRecovered {{ bus_id: key.0, node_id: key.1 }});
+{i4}}}
+{i3}}}
+"
+            ),
+            MessageUsage::Stream(_) => {
+                let Some(rx_stream) = node_config
+                    .rx_streams()
+                    .iter()
+                    .find(|stream| stream.message().name() == message.name())
+                else {
+                    continue;
+                };
+                let encoding = message
+                    .encoding()
+                    .expect("stream messages are expected to define a encoding");
+                let mut logic = String::new();
+                for (mapping, attrib) in std::iter::zip(rx_stream.mapping(), encoding.attributes()) {
+                    if let Some(object_entry) = mapping {
+                        logic.push_str(&format!(
+                            "{i3}self.set_{}(message.{});\n",
+                            object_entry.name(),
+                            rust_ident(attrib.name())
+                        ));
+                    }
+                }
+                logic
+            }
+            // commands and external messages are not handled by generated rust nodes.
+            _ => continue,
+        };
+        rx_logic.push_str(&format!(
+            "{i2}if let Some(message) = {message_type}::deserialize(frame) {{
+{handler}{i3}return frames;
+{i2}}}
+"
+        ));
+    }
+
+    src.push_str(&format!(
+        "struct StreamSchedule {{
+{i1}next: Instant,
+{i1}last: Instant,
+}}
+
+struct State {{
+{i1}oe: ObjectEntries,
+{i1}/// by index of the tx stream.
+{i1}streams: Vec<StreamSchedule>,
+{i1}/// fragmented set requests by client id (od_index, received words).
+{i1}set_fragments: HashMap<u8, (u16, Vec<u32>)>,
+{i1}/// pending fragments of get responses.
+{i1}get_fragments: VecDeque<(Instant, NetworkFrame)>,
+{i1}next_heartbeat: Instant,
+{i1}next_wdg_tick: Instant,
+{i1}/// tick countdowns of the registered heartbeats by (bus_id, node_id).
+{i1}watchdogs: HashMap<(u32, u8), i32>,
+}}
+
+impl State {{
+{setters}
+{i1}/// Frames, which are due at now, and the time at which the next frames are due.
+{i1}fn poll(
+{i2}&mut self,
+{i2}now: Instant,
+{i2}events: &mpsc::UnboundedSender<NodeEvent>,
+{i1}) -> (Vec<NetworkFrame>, Instant) {{
+{i2}let mut frames = vec![];
+{i2}if now >= self.next_heartbeat {{
+{i3}self.next_heartbeat = now + HEARTBEAT_INTERVAL;
+{heartbeat_logic}{i2}}}
+{i2}if now >= self.next_wdg_tick {{
+{i3}self.next_wdg_tick = now + HEARTBEAT_TICK;
+{i3}for ((bus_id, node_id), countdown) in self.watchdogs.iter_mut() {{
+{i4}*countdown -= 1;
+{i4}if *countdown == 0 {{
+{i5}let _ = events.send(NodeEvent::HeartbeatTimeout {{ bus_id: *bus_id, node_id: *node_id }});
+{i4}}}
+{i3}}}
+{i2}}}
+{stream_logic}{i2}while self.get_fragments.front().is_some_and(|(due, _)| *due <= now) {{
+{i3}if let Some((_, fragment)) = self.get_fragments.pop_front() {{
+{i4}frames.push(fragment);
+{i3}}}
+{i2}}}
+
+{i2}let mut deadline = self.next_heartbeat.min(self.next_wdg_tick);
+{i2}for stream in &self.streams {{
+{i3}deadline = deadline.min(stream.next);
+{i2}}}
+{i2}if let Some((due, _)) = self.get_fragments.front() {{
+{i3}deadline = deadline.min(*due);
+{i2}}}
+{i2}(frames, deadline)
+{i1}}}
+
+{i1}/// Handles a received frame, returns the responses.
+{i1}fn handle(
+{i2}&mut self,
+{i2}frame: &NetworkFrame,
+{i2}now: Instant,
+{i2}events: &mpsc::UnboundedSender<NodeEvent>,
+{i1}) -> Vec<NetworkFrame> {{
+{i2}let mut frames = vec![];
+{rx_logic}{i2}let _ = (now, events);
+{i2}frames
+{i1}}}
+}}
+
+"
+    ));
+
+    // ============ node ===============
+    let mut accessors = String::new();
+    for object_entry in object_entries {
+        let name = object_entry.name();
+        let oe_name = rust_ident(name);
+        let oe_type = rust_type_name(object_entry.ty());
+        accessors.push_str(&format!(
+            "
+{i1}pub fn get_{name}(&self) -> {oe_type} {{
+{i2}self.lock().oe.{oe_name}
+{i1}}}
+
+{i1}pub fn set_{name}(&self, value: {oe_type}) {{
+{i2}self.lock().set_{name}(value);
+{i2}self.wakeup.notify_one();
+{i1}}}
+"
+        ));
+    }
+    src.push_str(&format!(
+        "/// The node {node_name} on top of a transport, which carries all buses of the network.
+pub struct Node {{
+{i1}transport: Arc<dyn CanTransport>,
+{i1}state: Mutex<State>,
+{i1}wakeup: Notify,
+{i1}events: mpsc::UnboundedSender<NodeEvent>,
+{i1}start: Instant,
+}}
+
+impl Node {{
+{i1}pub fn new(transport: Arc<dyn CanTransport>) -> (Self, mpsc::UnboundedReceiver<NodeEvent>) {{
+{i2}let now = Instant::now();
+{i2}let (events, rx) = mpsc::unbounded_channel();
+{i2}let state = State {{
+{i3}oe: ObjectEntries::new(),
+{i3}streams: (0..{stream_count})
+{i4}.map(|_| StreamSchedule {{ next: now, last: now }})
+{i4}.collect(),
+{i3}set_fragments: HashMap::new(),
+{i3}get_fragments: VecDeque::new(),
+{i3}next_heartbeat: now,
+{i3}next_wdg_tick: now + HEARTBEAT_TICK,
+{i3}watchdogs: HashMap::new(),
+{i2}}};
+{i2}let node = Self {{
+{i3}transport,
+{i3}state: Mutex::new(state),
+{i3}wakeup: Notify::new(),
+{i3}events,
+{i3}start: now,
+{i2}}};
+{i2}(node, rx)
+{i1}}}
+
+{i1}fn lock(&self) -> std::sync::MutexGuard<'_, State> {{
+{i2}self.state.lock().expect(\"Failed to acquire node state lock\")
+{i1}}}
+
+{i1}pub fn object_entries(&self) -> ObjectEntries {{
+{i2}self.lock().oe
+{i1}}}
+{accessors}
+{i1}/// Handles received frames and transmits the heartbeats, streams
+{i1}/// and get responses of the node until the transport is closed.
+{i1}pub async fn run(&self) -> std::io::Result<()> {{
+{i2}loop {{
+{i3}let (frames, deadline) = self.lock().poll(Instant::now(), &self.events);
+{i3}self.send(frames).await?;
+{i3}tokio::select! {{
+{i4}frame = self.transport.recv() => match frame {{
+{i5}Some(Ok(frame)) => {{
+{i5}{i1}let frames = self.lock().handle(&frame, Instant::now(), &self.events);
+{i5}{i1}self.send(frames).await?;
+{i5}}}
+{i5}// error frames are not handled by the node.
+{i5}Some(Err(_)) => (),
+{i5}None => return Ok(()),
+{i4}}},
+{i4}_ = tokio::time::sleep_until(deadline) => (),
+{i4}_ = self.wakeup.notified() => (),
+{i3}}}
+{i2}}}
+{i1}}}
+
+{i1}async fn send(&self, frames: Vec<NetworkFrame>) -> std::io::Result<()> {{
+{i2}for frame in frames {{
+{i3}let frame = Timestamped::new(self.start.elapsed(), frame);
+{i3}self.transport.send(&frame).await?;
+{i2}}}
+{i2}Ok(())
+{i1}}}
+}}
+",
+        node_name = node_config.name()
+    ));
+    Ok(())
+}
+
+fn oe_byte_size(object_entry: &ObjectEntryRef) -> usize {
+    (object_entry.ty().size() as usize).div_ceil(32) * 4
+}
+
+fn generate_get_req_handler(
+    network_config: &config::NetworkRef,
+    object_entries: &Vec<ObjectEntryRef>,
+    options: &Options,
+) -> String {
+    let i3 = indent(options, 3);
+    let i4 = indent(options, 4);
+    let i5 = indent(options, 5);
+    let i6 = indent(options, 6);
+    let resp_type = message_type_name(network_config.get_resp_message());
+    let header_type = resp_header_type(network_config.get_resp_message());
+    let mut cases = String::new();
+    for object_entry in object_entries {
+        let od_index = object_entry.id();
+        let name = object_entry.name();
+        let oe_name = rust_ident(name);
+        cases.push_str(&format!(
+            "{i4}{od_index} => serialize_{name}(&self.oe.{oe_name}),\n"
+        ));
+    }
+    format!(
+        "{i3}if message.header.server_id != NODE_ID {{
+{i4}return frames;
+{i3}}}
+{i3}let words = match message.header.od_index {{
+{cases}{i4}_ => return frames,
+{i3}}};
+{i3}let fragments = words.len();
+{i3}for (i, word) in words.into_iter().enumerate() {{
+{i4}let resp = {resp_type} {{
+{i5}header: {header_type} {{
+{i6}sof: (i == 0) as u8,
+{i6}eof: (i + 1 == fragments) as u8,
+{i6}toggle: (i % 2) as u8,
+{i6}od_index: message.header.od_index,
+{i6}client_id: message.header.client_id,
+{i6}server_id: message.header.server_id,
+{i5}}},
+{i5}data: word,
+{i4}}}
+{i4}.serialize();
+{i4}if i == 0 {{
+{i5}frames.push(resp);
+{i4}}} else {{
+{i5}self.get_fragments
+{i6}.push_back((now + GET_RESP_FRAGMENTATION_INTERVAL * i as u32, resp));
+{i4}}}
+{i3}}}
+"
+    )
+}
+
+fn generate_set_req_handler(
+    network_config: &config::NetworkRef,
+    object_entries: &Vec<ObjectEntryRef>,
+    options: &Options,
+) -> String {
+    let i3 = indent(options, 3);
+    let i4 = indent(options, 4);
+    let i5 = indent(options, 5);
+    let resp_type = message_type_name(network_config.set_resp_message());
+    let header_type = resp_header_type(network_config.set_resp_message());
+    let erno_type = rust_type_ident("set_resp_erno");
+    let mut cases = String::new();
+    for object_entry in object_entries {
+        let od_index = object_entry.id();
+        let name = object_entry.name();
+        let bytes = oe_byte_size(object_entry);
+        cases.push_str(&format!(
+            "{i4}{od_index} if bytes.len() == {bytes} => self.set_{name}(deserialize_{name}(&bytes)),\n"
+        ));
+    }
+    format!(
+        "{i3}if message.header.server_id != NODE_ID {{
+{i4}return frames;
+{i3}}}
+{i3}let client_id = message.header.client_id;
+{i3}if message.header.sof == 1 {{
+{i4}self.set_fragments
+{i5}.insert(client_id, (message.header.od_index, vec![]));
+{i3}}}
+{i3}let Some((od_index, words)) = self.set_fragments.get_mut(&client_id) else {{
+{i4}return frames;
+{i3}}};
+{i3}if *od_index != message.header.od_index || message.header.toggle != (words.len() % 2) as u8 {{
+{i4}self.set_fragments.remove(&client_id);
+{i4}return frames;
+{i3}}}
+{i3}words.push(message.data);
+{i3}if message.header.eof == 0 {{
+{i4}return frames;
+{i3}}}
+{i3}let Some((_, words)) = self.set_fragments.remove(&client_id) else {{
+{i4}return frames;
+{i3}}};
+{i3}let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+{i3}match message.header.od_index {{
+{cases}{i4}_ => return frames,
+{i3}}}
+{i3}let resp = {resp_type} {{
+{i4}header: {header_type} {{
+{i5}od_index: message.header.od_index,
+{i5}client_id: message.header.client_id,
+{i5}server_id: message.header.server_id,
+{i5}erno: {erno_type}::Success,
+{i4}}},
+{i3}}};
+{i3}frames.push(resp.serialize());
+"
+    )
+}
+
+/// Type of the header attribute of get and set responses.
+fn resp_header_type(message: &config::MessageRef) -> String {
+    let header = message
+        .encoding()
+        .and_then(|encoding| encoding.attributes().iter().find(|attrib| attrib.name() == "header"))
+        .expect("responses are expected to define a header");
+    rust_type_name(header.ty())
+}
@@ -0,0 +1,248 @@
+use canzero_config::config::{self, SignalType, Type};
+
+use crate::{errors::Result, options::Options};
+
+use super::{indent, rust_ident, rust_type_ident};
+
+pub fn generate_types(
+    node_config: &config::NodeRef,
+    src: &mut String,
+    options: &Options,
+) -> Result<()> {
+    let i1 = indent(options, 1);
+    let i2 = indent(options, 2);
+    let i3 = indent(options, 3);
+    for ty in node_config.types() {
+        match ty as &Type {
+            Type::Struct {
+                name,
+                description,
+                attribs,
+                visibility: _,
+            } => {
+                let type_name = rust_type_ident(name);
+                if let Some(description) = description {
+                    src.push_str(&format!("/// {description}\n"));
+                }
+                src.push_str(&format!(
+                    "#[derive(Debug, Clone, Copy, PartialEq)]\npub struct {type_name} {{\n"
+                ));
+                for (attrib_name, attrib_type) in attribs {
+                    let attrib_name = rust_ident(attrib_name);
+                    let attrib_type = rust_type_name(attrib_type);
+                    src.push_str(&format!("{i1}pub {attrib_name}: {attrib_type},\n"));
+                }
+                src.push_str("}\n\n");
+            }
+            Type::Enum {
+                name,
+                description,
+                size: _,
+                entries,
+                visibility: _,
+            } => {
+                let type_name = rust_type_ident(name);
+                if let Some(description) = description {
+                    src.push_str(&format!("/// {description}\n"));
+                }
+                src.push_str(&format!(
+                    "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]\n#[repr(u64)]\npub enum {type_name} {{\n"
+                ));
+                let mut from_raw_arms = String::new();
+                for (i, (entry_name, entry_value)) in entries.iter().enumerate() {
+                    let entry_name = rust_type_ident(entry_name);
+                    if i == 0 {
+                        src.push_str(&format!("{i1}#[default]\n"));
+                    }
+                    src.push_str(&format!("{i1}{entry_name} = {entry_value},\n"));
+                    from_raw_arms.push_str(&format!(
+                        "{i3}{entry_value} => Some(Self::{entry_name}),\n"
+                    ));
+                }
+                src.push_str(&format!(
+                    "}}
+
+impl {type_name} {{
+{i1}pub fn from_raw(raw: u64) -> Option<Self> {{
+{i2}match raw {{
+{from_raw_arms}{i3}_ => None,
+{i2}}}
+{i1}}}
+}}
+
+"
+                ));
+            }
+            Type::Array { len: _, ty: _ } => {
+                panic!("arrays are anonymous and should not be explicitly defined as node types")
+            }
+            Type::Primitive(_) => {
+                panic!("primitives should not be explicitly defined as node types")
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn signal_type_to_rust_type(signal_type: &SignalType) -> &'static str {
+    match signal_type {
+        SignalType::UnsignedInt { size } => match size {
+            0..=8 => "u8",
+            9..=16 => "u16",
+            17..=32 => "u32",
+            _ => "u64",
+        },
+        SignalType::SignedInt { size } => match size {
+            0..=8 => "i8",
+            9..=16 => "i16",
+            17..=32 => "i32",
+            _ => "i64",
+        },
+        SignalType::Decimal { size, .. } => {
+            if *size <= 32 {
+                "f32"
+            } else {
+                "f64"
+            }
+        }
+    }
+}
+
+pub fn rust_type_name(ty: &Type) -> String {
+    match ty {
+        Type::Primitive(signal_type) => signal_type_to_rust_type(signal_type).to_owned(),
+        Type::Struct { name, .. } | Type::Enum { name, .. } => rust_type_ident(name),
+        Type::Array { len, ty } => format!("[{}; {len}]", rust_type_name(ty)),
+    }
+}
+
+/// Raw bits of a primitive value. Decimals are rounded
+/// to the nearest step if round is set (messages), otherwise truncated
+/// (object entries in get responses), like in the c code.
+pub fn primitive_to_raw(signal_type: &SignalType, value: &str, round: bool) -> String {
+    match signal_type {
+        SignalType::UnsignedInt { .. } | SignalType::SignedInt { .. } => {
+            format!("({value}) as u64")
+        }
+        SignalType::Decimal {
+            size,
+            offset,
+            scale,
+        } => {
+            let max = u64::MAX >> (64 - *size as u32);
+            let rounding = if round { " + 0.5" } else { "" };
+            format!("((((({value}) as f64) - {offset:?}) / {scale:?}){rounding}).min({max}u64 as f64) as u64")
+        }
+    }
+}
+
+/// Primitive value from its raw bits.
+pub fn primitive_from_raw(signal_type: &SignalType, raw: &str) -> String {
+    let rust_type = signal_type_to_rust_type(signal_type);
+    match signal_type {
+        SignalType::UnsignedInt { .. } => format!("({raw}) as {rust_type}"),
+        SignalType::SignedInt { size } => format!("sign_extend({raw}, {size}) as {rust_type}"),
+        SignalType::Decimal { offset, scale, .. } => {
+            format!("((({raw}) as f64) * {scale:?} + {offset:?}) as {rust_type}")
+        }
+    }
+}
+
+/// Statements, which serialize value into buffer at the bit offset,
+/// in the same layout as object entries in get and set requests.
+/// depth distinguishes the loop variables of nested arrays.
+pub fn write_value(
+    ty: &Type,
+    value: &str,
+    buffer: &str,
+    offset: &str,
+    depth: usize,
+    indent: &str,
+    step: &str,
+) -> String {
+    match ty {
+        Type::Primitive(signal_type) => {
+            let size = signal_type.size();
+            let raw = primitive_to_raw(signal_type, value, false);
+            format!("{indent}write_bits({buffer}, {offset}, {size}, {raw});\n")
+        }
+        Type::Enum { size, .. } => {
+            format!("{indent}write_bits({buffer}, {offset}, {size}, ({value}) as u64);\n")
+        }
+        Type::Struct { attribs, .. } => {
+            let mut logic = String::new();
+            let mut attrib_offset = 0;
+            for (attrib_name, attrib_ty) in attribs {
+                let attrib_name = rust_ident(attrib_name);
+                logic.push_str(&write_value(
+                    attrib_ty,
+                    &format!("{value}.{attrib_name}"),
+                    buffer,
+                    &format!("{offset} + {attrib_offset}"),
+                    depth,
+                    indent,
+                    step,
+                ));
+                attrib_offset += attrib_ty.size();
+            }
+            logic
+        }
+        Type::Array { len, ty } => {
+            let i = format!("i{depth}");
+            let elem_size = ty.size();
+            let elem_logic = write_value(
+                ty,
+                &format!("{value}[{i}]"),
+                buffer,
+                &format!("{offset} + {i} * {elem_size}"),
+                depth + 1,
+                &format!("{indent}{step}"),
+                step,
+            );
+            format!("{indent}for {i} in 0..{len} {{\n{elem_logic}{indent}}}\n")
+        }
+    }
+}
+
+/// Expression, which deserializes a value from the buffer at the bit offset (see write_value).
+/// Enum values, which are not defined, are read as the first entry of the enum.
+pub fn read_value(ty: &Type, buffer: &str, offset: &str, depth: usize) -> String {
+    match ty {
+        Type::Primitive(signal_type) => {
+            let size = signal_type.size();
+            primitive_from_raw(signal_type, &format!("read_bits({buffer}, {offset}, {size})"))
+        }
+        Type::Enum { name, size, .. } => {
+            let type_name = rust_type_ident(name);
+            format!("{type_name}::from_raw(read_bits({buffer}, {offset}, {size})).unwrap_or_default()")
+        }
+        Type::Struct { name, attribs, .. } => {
+            let type_name = rust_type_ident(name);
+            let mut attrib_offset = 0;
+            let mut fields = vec![];
+            for (attrib_name, attrib_ty) in attribs {
+                let attrib_name = rust_ident(attrib_name);
+                let attrib_value = read_value(
+                    attrib_ty,
+                    buffer,
+                    &format!("{offset} + {attrib_offset}"),
+                    depth,
+                );
+                fields.push(format!("{attrib_name}: {attrib_value}"));
+                attrib_offset += attrib_ty.size();
+            }
+            format!("{type_name} {{ {} }}", fields.join(", "))
+        }
+        Type::Array { len: _, ty } => {
+            let i = format!("i{depth}");
+            let elem_size = ty.size();
+            let elem_value = read_value(
+                ty,
+                buffer,
+                &format!("{offset} + {i} * {elem_size}"),
+                depth + 1,
+            );
+            format!("std::array::from_fn(|{i}| {elem_value})")
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use canzero_config::config::{self, message, Type};
 
-use crate::{errors::Result, options::Options, types::to_c_type_name};
+use crate::{
+    errors::Result,
+    options::{Language, Options},
+    types::to_c_declaration,
+};
 
 pub fn generate_rx_handlers(
     network_config: &config::NetworkRef,
@@ -17,6 +21,16 @@ pub fn generate_rx_handlers(
     let indent2 = format!("{indent}{indent}");
     let indent3 = format!("{indent2}{indent}");
     let indent4 = format!("{indent2}{indent2}");
+    // value initialization with {} is only available in C++.
+    let zero_init = match options.platform().language() {
+        Language::Cpp => "{}",
+        Language::C => " = {0}",
+    };
+    let static_progmem = if options.platform().progmem() {
+        "static PROGMEM"
+    } else {
+        "static"
+    };
 
     let frame_type_name = format!("{namespace}_frame");
     for message in node_config.rx_messages() {
@@ -76,7 +90,7 @@ pub fn generate_rx_handlers(
                 let command_name = command.name();
                 (
                     format!(
-                        "{indent}{namespace}_message_{resp_msg_name} resp{{}};
+                        "{indent}{namespace}_message_{resp_msg_name} resp{zero_init};
 {indent}resp.erno = {namespace}_{command_name}({attribute_list});
 {indent}{frame_type_name} resp_frame;
 {indent}{namespace}_serialize_{namespace}_message_{resp_msg_name}(&resp, &resp_frame);
@@ -211,7 +225,14 @@ pub fn generate_rx_handlers(
                                     parse_code.push_str(&parse_enum);
                                     *bit_offset += size;
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        let oe_name = format!("{oe_name}[{i}]");
+                                        generate_parse_logic(
+                                            parse_code, &oe_name, ty, bit_offset, indent2,
+                                        );
+                                    }
+                                }
                             };
                         }
                         let mut parse_code = String::new();
@@ -468,7 +489,19 @@ pub fn generate_rx_handlers(
                                         );
                                     }
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        generate_fragmentation_logic(
+                                            logic,
+                                            ty,
+                                            &format!("{var}[{i}]"),
+                                            buffer,
+                                            bit_offset,
+                                            indent2,
+                                            indent3,
+                                        );
+                                    }
+                                }
                             }
                         }
                         generate_fragmentation_logic(
@@ -503,7 +536,7 @@ pub fn generate_rx_handlers(
                     "{indent}if (msg.m_header.m_server_id != node_id_{node_name}) {{
 {indent2}return;
 {indent}}}
-{indent}{namespace}_message_get_resp resp{{}};
+{indent}{namespace}_message_get_resp resp{zero_init};
 {case_logic}{indent}resp.m_header.m_od_index = msg.m_header.m_od_index;
 {indent}resp.m_header.m_client_id = msg.m_header.m_client_id;
 {indent}resp.m_header.m_server_id = msg.m_header.m_server_id;
@@ -512,7 +545,7 @@ pub fn generate_rx_handlers(
 {indent}{namespace}_{resp_bus_name}_send(&resp_frame);
 "
                 );
-                (logic, "static PROGMEM")
+                (logic, static_progmem)
             },
             message::MessageUsage::SetResp => panic!(),
             message::MessageUsage::SetReq => {
@@ -522,8 +555,7 @@ pub fn generate_rx_handlers(
                     let od_index = object_entry.id();
                     let size = ty_size(object_entry.ty());
                     let oe_name = object_entry.name();
-                    let ty = to_c_type_name(object_entry.ty());
-                    let oe_tmp_var = format!("{ty} {oe_name}_tmp;\n");
+                    let oe_tmp_var = format!("{};\n", to_c_declaration(object_entry.ty(), &format!("{oe_name}_tmp")));
                     let mut parse_logic = format!("{oe_tmp_var}");
                     let oe_var = format!("{oe_name}_tmp");
                     if size <= 32 {
@@ -622,7 +654,16 @@ pub fn generate_rx_handlers(
                                     parse_logic.push_str(&format!("{var} = {parsed_val};\n"));
                                     *attrib_offset += size as usize;
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        generate_parse_logic(
+                                            parse_logic,
+                                            ty,
+                                            &format!("{var}[{i}]"),
+                                            attrib_offset,
+                                        );
+                                    }
+                                }
                             }
                         }
                         generate_parse_logic(
@@ -759,7 +800,18 @@ pub fn generate_rx_handlers(
                                     write_logic.push_str(&format!("{indent}{var} = {val};\n"));
                                     *bit_offset += size;
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        generate_write_logic(
+                                            write_logic,
+                                            ty,
+                                            bit_offset,
+                                            buffer_name,
+                                            &format!("{var}[{i}]"),
+                                            indent,
+                                        )
+                                    }
+                                }
                             }
                         }
                         generate_write_logic(
@@ -770,7 +822,7 @@ pub fn generate_rx_handlers(
                             &oe_var,
                             &indent2,
                         );
-                        let oe_ty = to_c_type_name(object_entry.ty());
+                        let oe_decl = to_c_declaration(object_entry.ty(), &oe_var);
 
                         case_logic.push_str(&format!(
                             "{indent}case {od_index} : {{
@@ -789,7 +841,7 @@ pub fn generate_rx_handlers(
 {indent2}if (msg.m_header.m_eof == 0) {{
 {indent3}return;
 {indent2}}}
-{indent2}{oe_ty} {oe_var};
+{indent2}{oe_decl};
 {write_logic}{indent2}{namespace}_set_{oe_name}({oe_var});
 {indent2}break;
 {indent}}}
@@ -803,7 +855,7 @@ pub fn generate_rx_handlers(
                     "{indent}if (msg.m_header.m_server_id != {node_id}) {{
 {indent2}return;
 {indent}}}
-{indent}{namespace}_message_set_resp resp{{}};
+{indent}{namespace}_message_set_resp resp{zero_init};
 {case_logic}
 {indent}resp.m_header.m_od_index = msg.m_header.m_od_index;
 {indent}resp.m_header.m_client_id = msg.m_header.m_client_id;
@@ -815,7 +867,7 @@ pub fn generate_rx_handlers(
 "
                 );
 
-                (logic, "static PROGMEM")
+                (logic, static_progmem)
             },
             message::MessageUsage::Heartbeat => {
                 let mut logic = String::new();
@@ -4,6 +4,7 @@ use canzero_config::config;
 
 use crate::errors::Result;
 use crate::options::Options;
+use crate::types::c_assignment;
 
 pub fn generate_scheduler(
     network_config: &config::NetworkRef,
@@ -64,13 +65,15 @@ pub fn generate_scheduler(
 
         schedule_stream_job_def.push_str(&format!(
             "static job_t {stream_name}_interval_job;
-static const uint32_t {stream_name}_interval = {stream_min_interval};
+static const uint32_t {stream_name}_min_interval = {stream_min_interval};
+static const uint32_t {stream_name}_max_interval = {stream_max_interval};
 static void schedule_{stream_name}_interval_job(){{
 {indent}uint32_t time = {namespace}_get_time();
-{indent}{stream_name}_interval_job.climax = time + {stream_name}_interval;
+{indent}{stream_name}_interval_job.climax = time + {stream_name}_min_interval;
 {indent}{stream_name}_interval_job.tag = STREAM_INTERVAL_JOB_TAG;
 {indent}{stream_name}_interval_job.job.stream_job.stream_id = {stream_id};
 {indent}{stream_name}_interval_job.job.stream_job.last_schedule = time;
+{indent}{stream_name}_interval_job.job.stream_job.dirty = 0;
 {indent}scheduler_schedule(&{stream_name}_interval_job);
 }}
 "
@@ -95,9 +98,12 @@ static void schedule_{stream_name}_interval_job(){{
                     let oe_name = object_entry.name();
                     let oe_var = format!("__oe_{oe_name}");
                     let msg_attrib = encoding.name();
-                    write_attribs_logic.push_str(&format!(
-                        "{indent4}stream_message.m_{msg_attrib} = {oe_var};"
-                    ));
+                    let assignment = c_assignment(
+                        object_entry.ty(),
+                        &format!("stream_message.m_{msg_attrib}"),
+                        &oe_var,
+                    );
+                    write_attribs_logic.push_str(&format!("{indent4}{assignment}"));
                 }
                 None => panic!("tx_streams are expected to define a complete mapping"),
             }
@@ -107,7 +113,8 @@ static void schedule_{stream_name}_interval_job(){{
         stream_case_logic.push_str(&format!(
 "{indent3}case {stream_id}: {{
 {indent4}job->job.stream_job.last_schedule = time;
-{indent4}scheduler_reschedule(time + {stream_max_interval});
+{indent4}job->job.stream_job.dirty = 0;
+{indent4}scheduler_reschedule(time + {stream_name}_max_interval);
 {indent4}{namespace}_exit_critical();
 {indent4}{namespace}_message_{node_name}_stream_{stream_name} stream_message;
 {write_attribs_logic}
@@ -146,9 +153,12 @@ typedef struct {{
   uint8_t client_id;
 }} get_resp_fragmentation_job;
 
+// streams are sent at least every max_interval and if a mapped object entry
+// changed (dirty) after min_interval.
 typedef struct {{
   uint32_t last_schedule; 
   uint32_t stream_id;
+  uint8_t dirty;
 }} stream_interval_job;
 
 typedef struct {{"
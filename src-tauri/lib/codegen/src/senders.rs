@@ -2,7 +2,6 @@ use canzero_config::config::{NodeRef, Type, NetworkRef, self};
 
 use crate::errors::Result;
 use crate::options::Options;
-use crate::types::to_c_type_name;
 
 
 const UNSOLICITED_ID: u8 = 0xff;
@@ -32,7 +31,6 @@ pub fn generate_senders(
     for object_entry in node_config.object_entries() {
         let oe_name = object_entry.name();
         let oe_id = object_entry.id();
-        let oe_type_name = to_c_type_name(object_entry.ty());
         let oe_var = format!("__oe_{oe_name}");
         let sender_name = format!("{namespace}_send_{oe_name}");
 
@@ -159,7 +157,14 @@ pub fn generate_senders(
                         parse_code.push_str(&parse_enum);
                         *bit_offset += size;
                     }
-                    Type::Array { len: _, ty: _ } => todo!(),
+                    Type::Array { len, ty } => {
+                        for i in 0..*len {
+                            let oe_name = format!("{oe_name}[{i}]");
+                            generate_parse_logic(
+                                parse_code, &oe_name, ty, bit_offset, base_indent, msg_name
+                            );
+                        }
+                    }
                 };
             }
             let mut parse_code = String::new();
@@ -411,7 +416,19 @@ pub fn generate_senders(
                             );
                         }
                     }
-                    Type::Array { len: _, ty: _ } => todo!(),
+                    Type::Array { len, ty } => {
+                        for i in 0..*len {
+                            generate_fragmentation_logic(
+                                logic,
+                                ty,
+                                &format!("{var_name}[{i}]"),
+                                buffer,
+                                bit_offset,
+                                indent2,
+                                indent3,
+                            );
+                        }
+                    }
                 }
             }
             let mut fragmentation_logic = String::new();
@@ -3,7 +3,7 @@ use canzero_config::config::{NodeRef, Type};
 
 use crate::errors::Result;
 use crate::options::Options;
-use crate::types::to_c_type_name;
+use crate::types::{c_assignment, to_c_declaration};
 
 pub fn generate_setters(
     node_config: &NodeRef,
@@ -19,12 +19,19 @@ pub fn generate_setters(
     }
     let indent2 = format!("{indent}{indent}");
     let indent3 = format!("{indent}{indent2}");
+    let indent4 = format!("{indent2}{indent2}");
 
     for object_entry in node_config.object_entries() {
         let oe_name = object_entry.name();
-        let type_name = to_c_type_name(object_entry.ty());
         let oe_var = format!("__oe_{oe_name}");
         let setter_name = format!("{namespace}_set_{oe_name}");
+        // arrays are passed as "const T value[len]".
+        let param_decl = match object_entry.ty() as &Type {
+            Type::Array { .. } => format!("const {}", to_c_declaration(object_entry.ty(), "value")),
+            ty => to_c_declaration(ty, "value"),
+        };
+        let extern_decl = to_c_declaration(object_entry.ty(), &oe_var);
+        let assignment = c_assignment(object_entry.ty(), &oe_var, "value");
 
         // find all tx-streams this object entry is a part of
         let tx_streams: Vec<&StreamRef> = node_config
@@ -39,17 +46,17 @@ pub fn generate_setters(
             .collect();
 
         if tx_streams.is_empty() {
-            let mut setter_def = format!("static inline void {setter_name}({type_name} value){{\n");
-            setter_def.push_str(&format!("{indent}extern {type_name} {oe_var};\n"));
-            setter_def.push_str(&format!("{indent}{oe_var} = value;\n"));
+            let mut setter_def = format!("static inline void {setter_name}({param_decl}){{\n");
+            setter_def.push_str(&format!("{indent}extern {extern_decl};\n"));
+            setter_def.push_str(&format!("{indent}{assignment}\n"));
             setter_def.push_str("}\n\n");
             header.push_str(&setter_def);
         }else {
-            let setter_decl = format!("void {setter_name}({type_name} value);\n\n");
+            let setter_decl = format!("void {setter_name}({param_decl});\n\n");
             header.push_str(&setter_decl);
 
 
-            let mut setter_def = format!("void {setter_name}({type_name} value) {{\n");
+            let mut setter_def = format!("void {setter_name}({param_decl}) {{\n");
             
             fn gen_condition(left : &str, right : &str, ty: &Type) -> String{
                 match ty {
@@ -60,30 +67,40 @@ pub fn generate_setters(
                             gen_condition(&format!("{left}.{attrib_name}"), &format!("{right}.{attrib_name}"), attrib_ty)
                         }).fold("0".to_owned(), |acc, x| format!("{acc} || {x}"))
                     },
-                    Type::Array { len : _, ty : _ } => panic!(),
+                    Type::Array { len, ty } => {
+                        (0..*len).map(|i| {
+                            gen_condition(&format!("{left}[{i}]"), &format!("{right}[{i}]"), ty)
+                        }).fold("0".to_owned(), |acc, x| format!("{acc} || {x}"))
+                    },
                 }
             }
 
             let condition = gen_condition(&oe_var, "value", object_entry.ty());
 
             setter_def.push_str(&format!(
-"{indent}extern {type_name} {oe_var};
+"{indent}extern {extern_decl};
 {indent}if ({condition}) {{
-{indent2}{oe_var} = value;
+{indent2}{assignment}
 "));
 
+            // mark the streams dirty, the first change after a transmission
+            // moves the stream job to min_interval after the last transmission.
+            setter_def.push_str(&format!("{indent2}{namespace}_enter_critical();\n"));
             for stream in tx_streams {
-                println!("{:?}{:?}", stream.min_interval(), stream.max_interval());
                 let stream_name = stream.name();
-                let min_interval_ms = stream.min_interval().as_millis();
                 let job_var = format!("{stream_name}_interval_job");
                 setter_def.push_str(&format!(
-"{indent2}if ({job_var}.climax > {job_var}.job.stream_job.last_schedule + {min_interval_ms}) {{
-{indent3}{job_var}.climax = {job_var}.job.stream_job.last_schedule + {min_interval_ms};
-{indent3}scheduler_promote_job(&{job_var});
+"{indent2}if (!{job_var}.job.stream_job.dirty) {{
+{indent3}{job_var}.job.stream_job.dirty = 1;
+{indent3}uint32_t climax = {job_var}.job.stream_job.last_schedule + {stream_name}_min_interval;
+{indent3}if ({job_var}.climax > climax) {{
+{indent4}{job_var}.climax = climax;
+{indent4}scheduler_promote_job(&{job_var});
+{indent3}}}
 {indent2}}}
 "));
             }
+            setter_def.push_str(&format!("{indent2}{namespace}_exit_critical();\n"));
 
             setter_def.push_str(&format!("{indent}}}\n"));
 
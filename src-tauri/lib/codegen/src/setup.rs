@@ -1,6 +1,6 @@
 use canzero_config::config;
 
-use crate::options::Options;
+use crate::options::{Language, Options};
 
 use crate::errors::Result;
 
@@ -19,11 +19,38 @@ pub fn generate_setup(node_config : &config::NodeRef, network_config : &config::
     let init_decl = format!("void {init_name}();\n");
     header.push_str(&init_decl);
 
+    let ide_bit = format!("{}_FRAME_IDE_BIT", namespace.to_uppercase());
     let mut setup_cans = String::new();
     for bus in network_config.buses() {
         let bus_name = bus.name();
         let baudrate = bus.baudrate();
-        setup_cans.push_str(&format!("{indent}{namespace}_{bus_name}_setup({baudrate}, NULL, 0);\n"));
+        let filters = node_config.bus_filters(bus.id());
+        let (filter_arg, filter_count) = if filters.is_empty() {
+            ("NULL".to_owned(), 0)
+        } else {
+            let filters_name = format!("{namespace}_{bus_name}_filters");
+            let mut filters_def = format!("static {namespace}_can_filter {filters_name}[{}] = {{\n", filters.len());
+            for filter in filters {
+                let mask = filter.mask();
+                let id = filter.id();
+                // the ide bit is part of the frame id, so it has to be matched as well.
+                let id = if filter.ide() {
+                    format!("(0x{id:X} | {ide_bit})")
+                } else {
+                    format!("0x{id:X}")
+                };
+                filters_def.push_str(&format!("{indent}{{ .mask = (0x{mask:X} | {ide_bit}), .id = {id} }},\n"));
+            }
+            filters_def.push_str("};\n");
+            source.push_str(&filters_def);
+            (filters_name, filters.len())
+        };
+        if bus.fd() {
+            let data_baudrate = bus.data_baudrate().unwrap_or(baudrate);
+            setup_cans.push_str(&format!("{indent}{namespace}_{bus_name}_setup({baudrate}, {data_baudrate}, {filter_arg}, {filter_count});\n"));
+        } else {
+            setup_cans.push_str(&format!("{indent}{namespace}_{bus_name}_setup({baudrate}, {filter_arg}, {filter_count});\n"));
+        }
     }
 
     let mut schedule_stream_jobs_logic = String::new();
@@ -86,10 +113,15 @@ pub fn generate_setup(node_config : &config::NodeRef, network_config : &config::
 ");
     
     let config_hash = network_config.portable_hash();
+    // C only allows braced initializers in assignments as compound literals.
+    let build_time_literal = match options.platform().language() {
+        Language::C => "(date_time)",
+        Language::Cpp => "",
+    };
 
     let init_def = format!("void {init_name}() {{
 {indent}__oe_config_hash = {config_hash}ull;
-{indent}__oe_build_time = {{
+{indent}__oe_build_time = {build_time_literal}{{
 {indent}{indent}.m_year = BUILD_YEAR,
 {indent}{indent}.m_month = BUILD_MONTH,
 {indent}{indent}.m_day = BUILD_DAY,
@@ -1,4 +1,4 @@
-use canzero_config::config::{self, Type};
+use canzero_config::config::{self, encoding::CompositeSignalEncoding, Type};
 
 use crate::errors::Result;
 use crate::messages::signal_type_to_c_type;
@@ -24,8 +24,8 @@ pub fn generate_types(
             } => {
                 let mut def = format!("typedef struct {{\n");
                 for (attrib_name, attrib_type) in attribs {
-                    let ctype = to_c_type_name(attrib_type);
-                    def.push_str(&format!("{indent}{ctype} m_{attrib_name};\n"));
+                    let decl = to_c_declaration(attrib_type, &format!("m_{attrib_name}"));
+                    def.push_str(&format!("{indent}{decl};\n"));
                 }
                 def.push_str(&format!("}} {name};\n"));
                 header.push_str(&def);
@@ -44,7 +44,9 @@ pub fn generate_types(
                 def.push_str(&format!("}} {name};\n"));
                 header.push_str(&def);
             }
-            config::Type::Array { len: _, ty: _ } => todo!(),
+            config::Type::Array { len: _, ty: _ } => {
+                panic!("arrays are anonymous and should not be explicitly defined as node types")
+            }
             config::Type::Primitive(_) => {
                 panic!("primitives should not be explicitly defined as node types")
             }
@@ -69,6 +71,44 @@ pub fn to_c_type_name(ty: &Type) -> &str {
             entries: _,
             visibility: _,
         } => name,
-        config::Type::Array { len: _, ty: _ } => todo!(),
+        config::Type::Array { len: _, ty: _ } => {
+            panic!("arrays don't have a c type name, use to_c_declaration instead")
+        }
+    }
+}
+
+/// declaration of a variable (or struct member) of the given type,
+/// arrays are declared as "T var_name[len]".
+pub fn to_c_declaration(ty: &Type, var_name: &str) -> String {
+    match ty {
+        config::Type::Array { len, ty } => to_c_declaration(ty, &format!("{var_name}[{len}]")),
+        _ => format!("{} {var_name}", to_c_type_name(ty)),
+    }
+}
+
+/// assignment of a value of the given type, arrays are copied element wise.
+/// (nested arrays are rejected by the config, so the elements are always assignable)
+pub fn c_assignment(ty: &Type, left: &str, right: &str) -> String {
+    match ty {
+        config::Type::Array { len, ty: _ } => {
+            format!("for (uint32_t i = 0; i < {len}; ++i) {{ {left}[i] = {right}[i]; }}")
+        }
+        _ => format!("{left} = {right};"),
+    }
+}
+
+/// access prefix of the attributes of a composite encoding, e.g. "msg->m_pos.m_" for
+/// a struct or "msg->m_cells" for an array (the elements are named "[i]").
+pub fn composite_attribute_prefix(composite: &CompositeSignalEncoding, prefix: &str) -> String {
+    match composite.ty() as &Type {
+        config::Type::Array { .. } => format!("{prefix}{}", composite.name()),
+        _ => format!("{prefix}{}.m_", composite.name()),
     }
 }
+
+/// converts an attribute name into a valid c identifier (e.g. "[0]" -> "_0_").
+pub fn c_identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,217 @@
+//! Main of the crate, which is built by rust_test.rs around the generated node (secu.rs).
+//! Acts as client of the node and exits with a panic if the node doesn't behave.
+
+use std::{sync::Arc, time::Duration};
+
+use canzero_common::{
+    transport::{BoxFuture, CanTransport, TransportBus},
+    NetworkFrame, TNetworkError, TNetworkFrame, Timestamped,
+};
+use tokio::sync::{mpsc, Mutex};
+
+mod od_index;
+mod secu;
+
+use secu::*;
+
+const CLIENT_ID: u8 = NodeId::Master as u8;
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+type WireFrame = Result<TNetworkFrame, TNetworkError>;
+
+/// One end of an in-memory bus, frames sent are received by the other end.
+struct Wire {
+    tx: mpsc::UnboundedSender<WireFrame>,
+    rx: Mutex<mpsc::UnboundedReceiver<WireFrame>>,
+}
+
+fn wire() -> (Wire, Wire) {
+    let (a_tx, a_rx) = mpsc::unbounded_channel();
+    let (b_tx, b_rx) = mpsc::unbounded_channel();
+    (
+        Wire {
+            tx: a_tx,
+            rx: Mutex::new(b_rx),
+        },
+        Wire {
+            tx: b_tx,
+            rx: Mutex::new(a_rx),
+        },
+    )
+}
+
+impl CanTransport for Wire {
+    fn buses(&self) -> Vec<TransportBus> {
+        vec![TransportBus::new(0, "can0")]
+    }
+
+    fn send<'a>(&'a self, frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>> {
+        let result = self
+            .tx
+            .send(Ok(frame.clone()))
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::NotConnected, "wire closed"));
+        Box::pin(async move { result })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>> {
+        Box::pin(async move { self.rx.lock().await.recv().await })
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+struct Client {
+    wire: Wire,
+}
+
+impl Client {
+    async fn send(&self, frame: NetworkFrame) {
+        let frame = Timestamped::new(Duration::ZERO, frame);
+        self.wire.send(&frame).await.expect("failed to send");
+    }
+
+    /// Next frame, which deserializes to a message of the node.
+    async fn recv<T>(&self, deserialize: impl Fn(&NetworkFrame) -> Option<T>) -> (NetworkFrame, T) {
+        let wait = async {
+            loop {
+                let frame = self
+                    .wire
+                    .recv()
+                    .await
+                    .expect("wire closed")
+                    .expect("unexpected error frame");
+                if let Some(message) = deserialize(&frame) {
+                    return (frame.destruct().1, message);
+                }
+            }
+        };
+        tokio::time::timeout(TIMEOUT, wait)
+            .await
+            .expect("timeout while waiting for the node")
+    }
+
+    async fn get(&self, od_index: u16) -> Vec<u32> {
+        let req = GetReqMessage {
+            header: GetReqHeader {
+                od_index,
+                client_id: CLIENT_ID,
+                server_id: NODE_ID,
+            },
+        };
+        self.send(req.serialize()).await;
+        let mut words = vec![];
+        loop {
+            let (_, resp) = self.recv(GetRespMessage::deserialize).await;
+            assert_eq!(resp.header.od_index, od_index);
+            assert_eq!(resp.header.client_id, CLIENT_ID);
+            assert_eq!(resp.header.sof, (words.is_empty()) as u8);
+            assert_eq!(resp.header.toggle, (words.len() % 2) as u8);
+            words.push(resp.data);
+            if resp.header.eof == 1 {
+                return words;
+            }
+        }
+    }
+
+    async fn set(&self, od_index: u16, words: &[u32]) -> SetRespErno {
+        for (i, word) in words.iter().enumerate() {
+            let req = SetReqMessage {
+                header: SetReqHeader {
+                    sof: (i == 0) as u8,
+                    eof: (i + 1 == words.len()) as u8,
+                    toggle: (i % 2) as u8,
+                    od_index,
+                    client_id: CLIENT_ID,
+                    server_id: NODE_ID,
+                },
+                data: *word,
+            };
+            self.send(req.serialize()).await;
+        }
+        let (_, resp) = self.recv(SetRespMessage::deserialize).await;
+        assert_eq!(resp.header.od_index, od_index);
+        assert_eq!(resp.header.client_id, CLIENT_ID);
+        resp.header.erno
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (node_end, client_end) = wire();
+    let (node, _events) = Node::new(Arc::new(node_end));
+    let node = Arc::new(node);
+    let task_node = node.clone();
+    tokio::spawn(async move { task_node.run().await });
+    let client = Client { wire: client_end };
+
+    // heartbeats are sent right away.
+    let (_, heartbeat) = client.recv(HeartbeatCan0Message::deserialize).await;
+    assert_eq!(heartbeat.node_id, NODE_ID);
+
+    // get.
+    node.set_position(0x0123_4567_89AB_CDEF);
+    assert_eq!(
+        client.get(od_index::POSITION).await,
+        vec![0x89AB_CDEF, 0x0123_4567]
+    );
+    node.set_velocity(-42);
+    assert_eq!(client.get(od_index::VELOCITY).await, vec![-42i32 as u32]);
+
+    // set.
+    assert_eq!(
+        client
+            .set(od_index::POSITION, &[0xDEAD_BEEF, 0x1234_5678])
+            .await,
+        SetRespErno::Success
+    );
+    assert_eq!(node.get_position(), 0x1234_5678_DEAD_BEEF);
+    assert_eq!(client.set(od_index::MODE, &[1]).await, SetRespErno::Success);
+    assert_eq!(node.get_mode(), Mode::Levitate);
+    let cells = [
+        Cell {
+            voltage: 0.0,
+            temperature: -5,
+        },
+        Cell {
+            voltage: 5.0,
+            temperature: 20,
+        },
+    ];
+    node.set_cells(cells);
+    let words = client.get(od_index::CELLS).await;
+    node.set_cells(
+        [Cell {
+            voltage: 0.0,
+            temperature: 0,
+        }; 2],
+    );
+    assert_eq!(
+        client.set(od_index::CELLS, &words).await,
+        SetRespErno::Success
+    );
+    for (cell, expected) in node.get_cells().iter().zip(cells) {
+        assert!((cell.voltage - expected.voltage).abs() < 1e-3, "{cell:?}");
+        assert_eq!(cell.temperature, expected.temperature);
+    }
+
+    // stream, sent within min_interval after the values changed.
+    node.set_velocity(1000);
+    let (frame, state) = client
+        .recv(|frame| SecuStreamStateMessage::deserialize(frame).filter(|s| s.velocity == 1000))
+        .await;
+    assert_eq!(
+        state,
+        SecuStreamStateMessage {
+            position: 0x1234_5678_DEAD_BEEF,
+            velocity: 1000,
+            mode: Mode::Levitate,
+        }
+    );
+    assert!(frame.can_frame.is_fd());
+    assert_eq!(frame.can_frame.get_brs_flag(), SecuStreamStateMessage::BRS);
+    assert_eq!(frame.can_frame.len(), SecuStreamStateMessage::DLC as usize);
+
+    println!("ok");
+}
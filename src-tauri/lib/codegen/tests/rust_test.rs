@@ -0,0 +1,121 @@
+use std::{path::Path, process::Command, time::Duration};
+
+use canzero_codegen::{options::Options, rust::generate_rust};
+use canzero_config::{builder::NetworkBuilder, config::NetworkRef};
+
+/// fd : CAN FD bus, data_baudrate : bit rate switching.
+fn network(fd: bool, data_baudrate: Option<u32>) -> NetworkRef {
+    let network_builder = NetworkBuilder::new();
+    let bus = network_builder.create_bus("can0", Some(1000000));
+    bus.fd(fd);
+    if let Some(data_baudrate) = data_baudrate {
+        bus.data_baudrate(data_baudrate);
+    }
+    let cell = network_builder.define_struct("cell");
+    cell.add_attribute("voltage", "d8<0..5>").unwrap();
+    cell.add_attribute("temperature", "i8").unwrap();
+    let mode = network_builder.define_enum("mode");
+    mode.add_entry("idle", Some(0)).unwrap();
+    mode.add_entry("levitate", Some(1)).unwrap();
+    let secu = network_builder.create_node("secu");
+    network_builder.create_node("master");
+    secu.create_object_entry("position", "u64");
+    secu.create_object_entry("velocity", "i32");
+    secu.create_object_entry("mode", "mode");
+    secu.create_object_entry("cells", "cell[2]");
+    let stream = secu.create_stream("state");
+    stream.add_entry("position");
+    stream.add_entry("velocity");
+    stream.add_entry("mode");
+    stream.set_interval(Duration::from_millis(10), Duration::from_millis(500));
+    network_builder
+        .build()
+        .expect("failed to build the network")
+}
+
+fn generate(network: &NetworkRef, path: &Path) -> String {
+    let mut options = Options::default();
+    options.set_source_file_path(path.to_str().unwrap());
+    generate_rust("secu", network.clone(), options).expect("failed to generate secu");
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// Builds the generated node together with tests/rust_node/harness.rs,
+/// which acts as client of the node (get, set and streams).
+#[test]
+fn generated_node_answers_get_set_and_streams() {
+    let network = network(true, Some(4000000));
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir(&src).unwrap();
+    generate(&network, &src.join("secu.rs"));
+
+    let mut od_index = String::new();
+    let secu = network.nodes().iter().find(|n| n.name() == "secu").unwrap();
+    for oe in secu.object_entries() {
+        od_index.push_str(&format!(
+            "pub const {}: u16 = {};\n",
+            oe.name().to_uppercase(),
+            oe.id()
+        ));
+    }
+    std::fs::write(src.join("od_index.rs"), od_index).unwrap();
+
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::fs::copy(
+        manifest_dir.join("tests/rust_node/harness.rs"),
+        src.join("main.rs"),
+    )
+    .unwrap();
+    let common = manifest_dir.join("../common");
+    std::fs::write(
+        dir.path().join("Cargo.toml"),
+        format!(
+            "[package]
+name = \"secu-node\"
+version = \"0.1.0\"
+edition = \"2021\"
+
+[dependencies]
+canzero-common = {{ path = {:?} }}
+tokio = {{ version = \"1.36.0\", features = [\"rt\", \"sync\", \"time\", \"macros\"] }}
+
+[workspace]
+",
+            common.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    // same dependency versions as canzero-common.
+    std::fs::copy(common.join("Cargo.lock"), dir.path().join("Cargo.lock")).unwrap();
+
+    let cargo = std::env::var("CARGO").unwrap_or("cargo".to_owned());
+    let output = Command::new(cargo)
+        .arg("run")
+        .arg("--quiet")
+        .current_dir(dir.path())
+        // shared between runs, such that dependencies are only built once.
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("rust_node"),
+        )
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "the generated node failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+}
+
+#[test]
+fn bit_rate_switching_requires_a_data_baudrate() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("secu.rs");
+    let brs = generate(&network(true, Some(4000000)), &path);
+    assert!(brs.contains("pub const BRS: bool = true;"));
+    assert!(!brs.contains("pub const BRS: bool = false;"));
+    let src = generate(&network(true, None), &path);
+    assert!(!src.contains("pub const BRS: bool = true;"));
+}
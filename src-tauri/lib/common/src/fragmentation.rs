@@ -0,0 +1,113 @@
+/// Fragment of a get response, the header fields which are required for reassembly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GetRespFragment {
+    pub sof: bool,
+    pub eof: bool,
+    pub toggle: bool,
+    pub data: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetRespFragmentError {
+    InvalidToggleFlag,
+    InvalidEofFlag,
+}
+
+// expecting toggle low on first fragment
+struct GetRespState {
+    fragmentation_offset: usize,
+    single_lookahead: bool,
+}
+
+/// Reassembles the fragments of the get responses of a single object entry.
+/// A single pair of swapped fragments is tolerated.
+///
+/// NOTE the toggle bit can't distinguish a duplicate of the previous fragment
+/// from the next fragment arriving early, therefor only duplicates of the sof and
+/// (for more than two fragments) the eof fragment are handled gracefully,
+/// other duplicates may be reassembled in place of a later fragment.
+pub struct GetRespAssembler {
+    state: GetRespState,
+    fragments: usize,
+    buffer: Vec<u32>,
+}
+
+impl GetRespAssembler {
+    /// size of the object entry type in bits.
+    pub fn new(size: u32) -> Self {
+        let fragments = size.div_ceil(32) as usize;
+        Self {
+            state: GetRespState {
+                fragmentation_offset: 0,
+                single_lookahead: false,
+            },
+            fragments,
+            buffer: vec![0; fragments],
+        }
+    }
+
+    /// Returns the reassembled data after the last fragment was received.
+    pub fn receive(
+        &mut self,
+        fragment: &GetRespFragment,
+    ) -> Result<Option<Vec<u32>>, GetRespFragmentError> {
+        if fragment.sof {
+            let (expected_toggle, expected_eof) = (false, self.fragments == 1);
+            if expected_toggle != fragment.toggle {
+                return Err(GetRespFragmentError::InvalidToggleFlag);
+            }
+            if expected_eof != fragment.eof {
+                return Err(GetRespFragmentError::InvalidEofFlag);
+            }
+            self.state.fragmentation_offset = 0;
+            self.buffer[self.state.fragmentation_offset] = fragment.data;
+            match self.state.single_lookahead {
+                true => {
+                    self.state.fragmentation_offset += 2;
+                    self.state.single_lookahead = false;
+                }
+                false => self.state.fragmentation_offset += 1,
+            }
+        } else {
+            let expected_toggle = self.state.fragmentation_offset % 2 == 1;
+            let expected_eof = self.state.fragmentation_offset + 1 == self.fragments;
+            if expected_toggle == fragment.toggle
+                && expected_eof == fragment.eof
+                && self.state.fragmentation_offset != 0
+            {
+                // everything as expected
+                self.buffer[self.state.fragmentation_offset] = fragment.data;
+                match self.state.single_lookahead {
+                    true => {
+                        self.state.fragmentation_offset += 2;
+                        self.state.single_lookahead = false;
+                    }
+                    false => self.state.fragmentation_offset += 1,
+                }
+            } else if !self.state.single_lookahead
+                && self.state.fragmentation_offset + 1 < self.fragments
+            {
+                let accepted_toggle = !expected_toggle;
+                let accepted_eof = self.state.fragmentation_offset + 2 == self.fragments;
+                if accepted_toggle == fragment.toggle && accepted_eof == fragment.eof {
+                    // assume that this fragment arrived before previous one
+                    self.buffer[self.state.fragmentation_offset + 1] = fragment.data;
+                    self.state.single_lookahead = true;
+                }
+            } else {
+                // TODO: actually split this up for correct error
+                return Err(GetRespFragmentError::InvalidToggleFlag);
+            }
+        }
+
+        if self.state.fragmentation_offset >= self.fragments {
+            let data = self.buffer.clone();
+            self.state.fragmentation_offset = 0;
+            self.state.single_lookahead = false;
+            self.buffer.fill(0);
+            Ok(Some(data))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -4,6 +4,11 @@ use std::{
     time::{Duration, Instant},
 };
 
+pub mod fragmentation;
+pub mod recording;
+pub mod trace_format;
+pub mod transport;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Timestamped<T> {
     pub timestamp: Duration,
@@ -75,9 +80,40 @@ pub struct CanFrame {
     id: u32,
     dlc: u8,
     data: u64,
+    /// only present for CAN FD frames.
+    #[serde(default)]
+    fd: Option<Box<CanFdPayload>>,
+}
+
+/// Payload of a CAN FD frame, the first 8 bytes are also stored in the
+/// data attribute of the CanFrame, such that frames, which fit into
+/// a classical frame, can be decoded without caring about FD.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CanFdPayload {
+    brs: bool,
+    esi: bool,
+    data: Vec<u8>,
+}
+
+/// Data length of a CAN FD frame indexed by the dlc.
+pub const CAN_FD_LENGTHS: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+pub const CAN_FD_MAX_LEN: usize = 64;
+
+/// Smallest dlc, which can hold len bytes.
+pub fn can_fd_len_to_dlc(len: usize) -> Option<u8> {
+    CAN_FD_LENGTHS
+        .iter()
+        .position(|fd_len| *fd_len as usize >= len)
+        .map(|dlc| dlc as u8)
+}
+
+pub fn can_fd_dlc_to_len(dlc: u8) -> usize {
+    CAN_FD_LENGTHS[dlc.min(15) as usize] as usize
 }
 
 #[repr(u32)]
+#[allow(clippy::enum_variant_names)]
 enum CanFrameIdFlags {
     IdeMask = 0x80000000,
     RtrMask = 0x40000000,
@@ -106,6 +142,7 @@ impl CanFrame {
                 }),
             dlc,
             data,
+            fd: None,
         }
     }
 
@@ -113,10 +150,34 @@ impl CanFrame {
         Self {
             id : id_key,
             dlc,
-            data
+            data,
+            fd: None,
         }
     }
 
+    /// CAN FD frame, the data is padded with zeros to the next valid length.
+    /// Panics if data is longer than 64 bytes.
+    pub fn new_fd(id: u32, ide: bool, brs: bool, esi: bool, data: &[u8]) -> Self {
+        let Some(dlc) = can_fd_len_to_dlc(data.len()) else {
+            panic!("CAN FD frames can't hold more than {CAN_FD_MAX_LEN} bytes");
+        };
+        let mut data = data.to_vec();
+        data.resize(can_fd_dlc_to_len(dlc), 0);
+        let mut first = [0u8; 8];
+        let n = data.len().min(8);
+        first[..n].copy_from_slice(&data[..n]);
+        let mut frame = Self::new(id, ide, false, dlc, u64::from_le_bytes(first));
+        frame.fd = Some(Box::new(CanFdPayload { brs, esi, data }));
+        frame
+    }
+
+    /// Like new_fd, but id_key already contains the ide and rtr flags (see key).
+    pub fn new_raw_fd(id_key: u32, brs: bool, esi: bool, data: &[u8]) -> Self {
+        let mut frame = Self::new_fd(0, false, brs, esi, data);
+        frame.id = id_key;
+        frame
+    }
+
     pub fn key(&self) -> u32 {
         self.id
     }
@@ -142,19 +203,87 @@ impl CanFrame {
     }
     #[allow(dead_code)]
     pub fn get_data_8u8(&self) -> [u8; 8] {
-        unsafe { std::mem::transmute::<u64, [u8; 8]>(self.data) }
+        self.data.to_le_bytes()
+    }
+
+    pub fn is_fd(&self) -> bool {
+        self.fd.is_some()
+    }
+    /// bit rate switch, always false for classical frames.
+    pub fn get_brs_flag(&self) -> bool {
+        self.fd.as_ref().is_some_and(|fd| fd.brs)
+    }
+    /// error state indicator, always false for classical frames.
+    pub fn get_esi_flag(&self) -> bool {
+        self.fd.as_ref().is_some_and(|fd| fd.esi)
+    }
+    /// Number of data bytes, for classical frames the dlc is capped at 8.
+    pub fn len(&self) -> usize {
+        match &self.fd {
+            Some(fd) => fd.data.len(),
+            None => self.dlc.min(8) as usize,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// All data bytes (see len).
+    pub fn get_data(&self) -> Vec<u8> {
+        match &self.fd {
+            Some(fd) => fd.data.clone(),
+            None => self.data.to_le_bytes()[..self.len()].to_vec(),
+        }
     }
 }
 
 pub type TCanError = Timestamped<CanError>;
 
+/// Error frame reported by a CAN controller.
+/// The lower 32 bits are flags (see CanError::BIT_ERROR, ...),
+/// bits 32..40 and 40..48 hold the tx and rx error counters of the controller,
+/// which are only valid if CanError::COUNTERS is set.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CanError(pub u64);
 
 impl CanError {
+    pub const BIT_ERROR: u64 = 0x1;
+    pub const STUFF_ERROR: u64 = 0x2;
+    pub const FORM_ERROR: u64 = 0x4;
+    pub const ACK_ERROR: u64 = 0x8;
+    pub const CRC_ERROR: u64 = 0x10;
+    /// one of the error counters reached the warning level (96).
+    pub const ERROR_WARNING: u64 = 0x20;
+    pub const ERROR_PASSIVE: u64 = 0x40;
+    pub const BUS_OFF: u64 = 0x80;
+    /// the controller recovered from bus-off.
+    pub const RESTARTED: u64 = 0x100;
+    pub const COUNTERS: u64 = 0x200;
+
+    pub fn new(flags: u64, counters: Option<(u8, u8)>) -> Self {
+        let flags = flags & 0xFFFFFFFF;
+        match counters {
+            Some((tx_errors, rx_errors)) => CanError(
+                flags | Self::COUNTERS | ((tx_errors as u64) << 32) | ((rx_errors as u64) << 40),
+            ),
+            None => CanError(flags & !Self::COUNTERS),
+        }
+    }
+
     pub fn erno(&self) -> u64 {
         self.0
     }
+
+    pub fn contains(&self, flag: u64) -> bool {
+        self.0 & flag != 0
+    }
+
+    pub fn tx_error_counter(&self) -> Option<u8> {
+        self.contains(Self::COUNTERS).then_some((self.0 >> 32) as u8)
+    }
+
+    pub fn rx_error_counter(&self) -> Option<u8> {
+        self.contains(Self::COUNTERS).then_some((self.0 >> 40) as u8)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -164,3 +293,11 @@ pub struct NetworkFrame {
 }
 
 pub type TNetworkFrame = Timestamped<NetworkFrame>;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NetworkError {
+    pub bus_id: u32,
+    pub can_error: CanError,
+}
+
+pub type TNetworkError = Timestamped<NetworkError>;
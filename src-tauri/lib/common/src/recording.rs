@@ -0,0 +1,257 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{can_fd_dlc_to_len, CanFrame, NetworkFrame, TNetworkFrame};
+
+const MAGIC: [u8; 7] = *b"CANZREC";
+/// Version 2 added CAN FD frames, version 1 recordings are still readable.
+const VERSION: u8 = 2;
+pub const HEADER_SIZE: usize = 32;
+/// Size of a classical frame, see frame_size.
+pub const FRAME_SIZE: usize = 24;
+
+const FLAG_FD: u8 = 0x1;
+const FLAG_BRS: u8 = 0x2;
+const FLAG_ESI: u8 = 0x4;
+
+/// Header of a recording, written once at the start of the file.
+///
+/// Layout (little endian):
+/// | magic (7) | version (1) | config_hash (8) | timebase_us (8) | reserved (8) |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingHeader {
+    /// portable hash of the network configuration the recording was made with.
+    pub config_hash: u64,
+    /// start of run as microseconds since the unix epoch, all frame
+    /// timestamps are relative to this point in time.
+    pub timebase_us: u64,
+}
+
+impl RecordingHeader {
+    pub fn into_bin(&self, buf: &mut [u8; HEADER_SIZE]) {
+        buf[0..7].copy_from_slice(&MAGIC);
+        buf[7] = VERSION;
+        buf[8..16].copy_from_slice(&self.config_hash.to_le_bytes());
+        buf[16..24].copy_from_slice(&self.timebase_us.to_le_bytes());
+        buf[24..32].fill(0);
+    }
+
+    pub fn from_bin(buf: &[u8; HEADER_SIZE]) -> std::io::Result<Self> {
+        if buf[0..7] != MAGIC {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "not a canzero recording (invalid magic)",
+            ));
+        }
+        if buf[7] == 0 || buf[7] > VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unsupported recording version {}", buf[7]),
+            ));
+        }
+        Ok(Self {
+            config_hash: u64::from_le_bytes(buf[8..16].try_into().unwrap()),
+            timebase_us: u64::from_le_bytes(buf[16..24].try_into().unwrap()),
+        })
+    }
+}
+
+/// Name of the file with sequence number seq of a rotated recording.
+pub fn recording_file_name(header: &RecordingHeader, seq: u32) -> String {
+    format!("canzero-{}-{seq:04}.rec", header.timebase_us / 1_000_000)
+}
+
+/// (timebase in seconds, sequence number) of a file named by recording_file_name.
+fn parse_recording_file_name(name: &str) -> Option<(u64, u32)> {
+    let (timebase, seq) = name
+        .strip_prefix("canzero-")?
+        .strip_suffix(".rec")?
+        .split_once('-')?;
+    Some((timebase.parse().ok()?, seq.parse().ok()?))
+}
+
+/// All files of the rotated recording, which path is part of, ordered by their sequence number.
+/// Files that are not named like a rotated recording are returned on their own.
+pub fn recording_files(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let Some((timebase, _)) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(parse_recording_file_name)
+    else {
+        return Ok(vec![path.to_owned()]);
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let Some((other_timebase, seq)) = entry
+            .file_name()
+            .to_str()
+            .and_then(parse_recording_file_name)
+        else {
+            continue;
+        };
+        if other_timebase == timebase {
+            files.push((seq, entry.path()));
+        }
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// All rotated recordings in dir, ordered from the oldest to the newest file.
+pub fn rotated_recordings(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let Some(key) = entry
+            .file_name()
+            .to_str()
+            .and_then(parse_recording_file_name)
+        else {
+            continue;
+        };
+        files.push((key, entry.path()));
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Number of bytes of the frame in a recording.
+pub fn frame_size(frame: &TNetworkFrame) -> usize {
+    FRAME_SIZE + fd_extension_size(&frame.can_frame)
+}
+
+fn fd_extension_size(can_frame: &CanFrame) -> usize {
+    match can_frame.is_fd() {
+        true => can_frame.len().saturating_sub(8),
+        false => 0,
+    }
+}
+
+/// Layout of a single frame (little endian):
+/// | timestamp_us (8) | can_id (4) | bus_id (2) | dlc (1) | flags (1) | data (8) |
+/// CAN FD frames are followed by the data bytes, which exceed the first 8 bytes.
+fn frame_into_bin(frame: &TNetworkFrame, buf: &mut [u8; FRAME_SIZE]) {
+    let can_frame = &frame.can_frame;
+    buf[0..8].copy_from_slice(&(frame.timestamp.as_micros() as u64).to_le_bytes());
+    buf[8..12].copy_from_slice(&can_frame.key().to_le_bytes());
+    buf[12..14].copy_from_slice(&(frame.bus_id as u16).to_le_bytes());
+    buf[14] = can_frame.get_dlc();
+    buf[15] = 0;
+    if can_frame.is_fd() {
+        buf[15] |= FLAG_FD;
+    }
+    if can_frame.get_brs_flag() {
+        buf[15] |= FLAG_BRS;
+    }
+    if can_frame.get_esi_flag() {
+        buf[15] |= FLAG_ESI;
+    }
+    buf[16..24].copy_from_slice(&can_frame.get_data_u64().to_le_bytes());
+}
+
+/// Ok(None) if the frame is truncated.
+fn frame_from_bin<R: Read>(
+    buf: &[u8; FRAME_SIZE],
+    reader: &mut R,
+) -> std::io::Result<Option<TNetworkFrame>> {
+    let timestamp = Duration::from_micros(u64::from_le_bytes(buf[0..8].try_into().unwrap()));
+    let can_id = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+    let bus_id = u16::from_le_bytes(buf[12..14].try_into().unwrap()) as u32;
+    let dlc = buf[14];
+    let flags = buf[15];
+    let can_frame = if flags & FLAG_FD != 0 {
+        let mut data = vec![0u8; can_fd_dlc_to_len(dlc).max(8)];
+        data[..8].copy_from_slice(&buf[16..24]);
+        match reader.read_exact(&mut data[8..]) {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        data.truncate(can_fd_dlc_to_len(dlc));
+        CanFrame::new_raw_fd(can_id, flags & FLAG_BRS != 0, flags & FLAG_ESI != 0, &data)
+    } else {
+        let data = u64::from_le_bytes(buf[16..24].try_into().unwrap());
+        CanFrame::new_raw(can_id, dlc, data)
+    };
+    Ok(Some(TNetworkFrame::new(
+        timestamp,
+        NetworkFrame { bus_id, can_frame },
+    )))
+}
+
+pub struct RecordingWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    pub fn create(mut writer: W, header: &RecordingHeader) -> std::io::Result<Self> {
+        let mut buf = [0u8; HEADER_SIZE];
+        header.into_bin(&mut buf);
+        writer.write_all(&buf)?;
+        Ok(Self { writer })
+    }
+
+    pub fn write_frame(&mut self, frame: &TNetworkFrame) -> std::io::Result<()> {
+        let mut buf = [0u8; FRAME_SIZE];
+        frame_into_bin(frame, &mut buf);
+        self.writer.write_all(&buf)?;
+        let extension = fd_extension_size(&frame.can_frame);
+        if extension != 0 {
+            self.writer.write_all(&frame.can_frame.get_data()[8..8 + extension])?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+pub struct RecordingReader<R: Read> {
+    reader: R,
+    header: RecordingHeader,
+}
+
+impl<R: Read> RecordingReader<R> {
+    pub fn open(mut reader: R) -> std::io::Result<Self> {
+        let mut buf = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut buf)?;
+        let header = RecordingHeader::from_bin(&buf)?;
+        Ok(Self { reader, header })
+    }
+
+    pub fn header(&self) -> &RecordingHeader {
+        &self.header
+    }
+
+    /// returns Ok(None) at the end of the recording.
+    /// A truncated last frame (e.g. from a crashed writer) is treated as end of recording.
+    pub fn read_frame(&mut self) -> std::io::Result<Option<TNetworkFrame>> {
+        let mut buf = [0u8; FRAME_SIZE];
+        match self.reader.read_exact(&mut buf) {
+            Ok(()) => frame_from_bin(&buf, &mut self.reader),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = std::io::Result<TNetworkFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
@@ -0,0 +1,113 @@
+use std::{io::Write, time::Duration};
+
+use crate::{CanFrame, NetworkFrame, TNetworkFrame};
+
+use super::{data_bytes, invalid_data, parse_data_bytes, TraceBus};
+
+pub fn write_header<W: Write>(writer: &mut W, date: &str) -> std::io::Result<()> {
+    writeln!(writer, "date {date}")?;
+    writeln!(writer, "base hex  timestamps absolute")?;
+    writeln!(writer, "no internal events logged")?;
+    writeln!(writer, "Begin Triggerblock {date}")?;
+    writeln!(writer, "   0.000000 Start of measurement")
+}
+
+pub fn write_trailer<W: Write>(writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, "End TriggerBlock")
+}
+
+///   1.234567 1  1ABCDEF0x       Rx   d 4 01 02 03 04
+pub fn write_frame<W: Write>(
+    writer: &mut W,
+    frame: &TNetworkFrame,
+    channel: u32,
+) -> std::io::Result<()> {
+    let can_frame = &frame.can_frame;
+    if can_frame.is_fd() {
+        return Err(invalid_data(
+            "CAN FD frames are not supported by the ASC writer".to_owned(),
+        ));
+    }
+    let id = if can_frame.get_ide_flag() {
+        format!("{:X}x", can_frame.get_id())
+    } else {
+        format!("{:X}", can_frame.get_id())
+    };
+    write!(
+        writer,
+        "{:>11.6} {channel:<2} {id:<15} Rx   ",
+        frame.timestamp.as_secs_f64()
+    )?;
+    if can_frame.get_rtr_flag() {
+        write!(writer, "r {:X}", can_frame.get_dlc())?;
+    } else {
+        write!(writer, "d {:X}", can_frame.get_dlc())?;
+        for byte in data_bytes(can_frame.get_data_u64(), can_frame.get_dlc()) {
+            write!(writer, " {byte:02X}")?;
+        }
+    }
+    writeln!(writer)
+}
+
+/// hex is updated by the "base hex|dec" header line.
+pub fn parse_line(
+    line: &str,
+    buses: &[TraceBus],
+    hex: &mut bool,
+) -> std::io::Result<Option<TNetworkFrame>> {
+    let mut tokens = line.split_whitespace();
+    let Some(first) = tokens.next() else {
+        return Ok(None);
+    };
+    if first == "base" {
+        *hex = tokens.next() != Some("dec");
+        return Ok(None);
+    }
+    // header lines, comments and events do not start with a timestamp.
+    let Ok(timestamp) = first.parse::<f64>() else {
+        return Ok(None);
+    };
+    // rejects negative, infinite and NaN timestamps.
+    let timestamp = Duration::try_from_secs_f64(timestamp)
+        .map_err(|_| invalid_data(format!("invalid timestamp {first:?}")))?;
+    let (Some(channel), Some(id)) = (tokens.next(), tokens.next()) else {
+        return Ok(None);
+    };
+    // "Start of measurement" or CAN FD ("CANFD") and statistic events.
+    let Ok(channel) = channel.parse::<usize>() else {
+        return Ok(None);
+    };
+    if id == "ErrorFrame" || id == "Statistic:" {
+        return Ok(None);
+    }
+    let Some(bus) = channel.checked_sub(1).and_then(|index| buses.get(index)) else {
+        return Err(invalid_data(format!("no bus for channel {channel}")));
+    };
+    let (id, ide) = match id.strip_suffix('x') {
+        Some(id) => (id, true),
+        None => (id, false),
+    };
+    let radix = if *hex { 16 } else { 10 };
+    let id = u32::from_str_radix(id, radix)
+        .map_err(|_| invalid_data(format!("invalid id {id:?}")))?;
+    let _direction = tokens.next();
+    let (Some(ty), Some(dlc)) = (tokens.next(), tokens.next()) else {
+        return Err(invalid_data(format!("incomplete line {line:?}")));
+    };
+    let dlc = u8::from_str_radix(dlc, 16).map_err(|_| invalid_data(format!("invalid dlc {dlc:?}")))?;
+    let can_frame = match ty {
+        "d" => {
+            let data = parse_data_bytes(tokens.take(dlc.min(8) as usize), radix)?;
+            CanFrame::new(id, ide, false, dlc, data)
+        }
+        "r" => CanFrame::new(id, ide, true, dlc, 0),
+        _ => return Err(invalid_data(format!("invalid frame type {ty:?}"))),
+    };
+    Ok(Some(TNetworkFrame::new(
+        timestamp,
+        NetworkFrame {
+            bus_id: bus.id,
+            can_frame,
+        },
+    )))
+}
@@ -0,0 +1,133 @@
+use std::{io::Write, time::Duration};
+
+use crate::{CanFrame, NetworkFrame, TNetworkFrame, CAN_FD_MAX_LEN};
+
+use super::{data_bytes, invalid_data, parse_data_bytes, TraceBus};
+
+/// (1436509052.249713) can0 044#2A366C2BBA
+/// (1436509052.449847) can1 1ABCDEF0#R
+/// (1436509052.649981) can0 123##1001122334455667788 (CAN FD, flags BRS)
+pub fn write_frame<W: Write>(
+    writer: &mut W,
+    frame: &TNetworkFrame,
+    bus_name: &str,
+    timebase_us: u64,
+) -> std::io::Result<()> {
+    let timestamp_us = timebase_us + frame.timestamp.as_micros() as u64;
+    let can_frame = &frame.can_frame;
+    write!(
+        writer,
+        "({}.{:06}) {bus_name} ",
+        timestamp_us / 1_000_000,
+        timestamp_us % 1_000_000
+    )?;
+    if can_frame.get_ide_flag() {
+        write!(writer, "{:08X}#", can_frame.get_id())?;
+    } else {
+        write!(writer, "{:03X}#", can_frame.get_id())?;
+    }
+    if can_frame.is_fd() {
+        let flags = can_frame.get_brs_flag() as u8 | (can_frame.get_esi_flag() as u8) << 1;
+        write!(writer, "#{flags:X}")?;
+        for byte in can_frame.get_data() {
+            write!(writer, "{byte:02X}")?;
+        }
+    } else if can_frame.get_rtr_flag() {
+        write!(writer, "R")?;
+    } else {
+        for byte in data_bytes(can_frame.get_data_u64(), can_frame.get_dlc()) {
+            write!(writer, "{byte:02X}")?;
+        }
+    }
+    writeln!(writer)
+}
+
+pub fn parse_line(line: &str, buses: &[TraceBus]) -> std::io::Result<Option<TNetworkFrame>> {
+    let mut tokens = line.split_whitespace();
+    let Some(timestamp) = tokens.next() else {
+        return Ok(None);
+    };
+    let timestamp = timestamp
+        .strip_prefix('(')
+        .and_then(|t| t.strip_suffix(')'))
+        .and_then(parse_timestamp)
+        .ok_or_else(|| invalid_data(format!("invalid timestamp in {line:?}")))?;
+    let (Some(interface), Some(frame)) = (tokens.next(), tokens.next()) else {
+        return Err(invalid_data(format!("incomplete line {line:?}")));
+    };
+    let Some(bus) = buses.iter().find(|bus| bus.name == interface) else {
+        return Err(invalid_data(format!("unknown bus {interface:?}")));
+    };
+    let Some((id, data)) = frame.split_once('#') else {
+        return Err(invalid_data(format!("invalid frame {frame:?}")));
+    };
+    // data is sliced by byte offsets below
+    if !data.is_ascii() {
+        return Err(invalid_data(format!("invalid data {data:?}")));
+    }
+    let raw_id =
+        u32::from_str_radix(id, 16).map_err(|_| invalid_data(format!("invalid id {id:?}")))?;
+    // error frames carry the CAN_ERR_FLAG
+    if raw_id & 0x20000000 != 0 {
+        return Ok(None);
+    }
+    let ide = id.len() > 3;
+    let can_frame = if let Some(fd_data) = data.strip_prefix('#') {
+        let flags = fd_data
+            .get(..1)
+            .and_then(|flags| u8::from_str_radix(flags, 16).ok())
+            .ok_or_else(|| invalid_data(format!("invalid CAN FD flags in {data:?}")))?;
+        let fd_data = &fd_data[1..];
+        if fd_data.len() % 2 != 0 || fd_data.len() / 2 > CAN_FD_MAX_LEN {
+            return Err(invalid_data(format!("invalid data {data:?}")));
+        }
+        let bytes = (0..fd_data.len())
+            .step_by(2)
+            .map(|i| {
+                u8::from_str_radix(&fd_data[i..i + 2], 16)
+                    .map_err(|_| invalid_data(format!("invalid data {data:?}")))
+            })
+            .collect::<std::io::Result<Vec<u8>>>()?;
+        CanFrame::new_fd(raw_id, ide, flags & 0x1 != 0, flags & 0x2 != 0, &bytes)
+    } else if let Some(dlc) = data.strip_prefix('R') {
+        let dlc = match dlc {
+            "" => 0,
+            dlc => dlc
+                .parse::<u8>()
+                .ok()
+                .filter(|dlc| *dlc <= 8)
+                .ok_or_else(|| invalid_data(format!("invalid dlc in {data:?}")))?,
+        };
+        CanFrame::new(raw_id, ide, true, dlc, 0)
+    } else {
+        if data.len() % 2 != 0 {
+            return Err(invalid_data(format!("invalid data {data:?}")));
+        }
+        let bytes = (0..data.len()).step_by(2).map(|i| &data[i..i + 2]);
+        CanFrame::new(
+            raw_id,
+            ide,
+            false,
+            (data.len() / 2) as u8,
+            parse_data_bytes(bytes, 16)?,
+        )
+    };
+    Ok(Some(TNetworkFrame::new(
+        timestamp,
+        NetworkFrame {
+            bus_id: bus.id,
+            can_frame,
+        },
+    )))
+}
+
+/// parsed as integers, f64 can not represent epoch timestamps with microsecond precision.
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let (secs, frac) = timestamp.split_once('.').unwrap_or((timestamp, "0"));
+    let secs = secs.parse::<u64>().ok()?;
+    if frac.is_empty() || frac.len() > 9 {
+        return None;
+    }
+    let nanos = frac.parse::<u32>().ok()? * 10u32.pow(9 - frac.len() as u32);
+    Some(Duration::new(secs, nanos))
+}
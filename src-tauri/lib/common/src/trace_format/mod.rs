@@ -0,0 +1,204 @@
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::TNetworkFrame;
+
+mod asc;
+mod candump;
+
+/// Standard text formats for CAN traces, used to exchange traces with
+/// other tools (e.g. can-utils, SavvyCAN, CANalyzer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Linux can-utils log format (`candump -L`),
+    /// bus names are used as interface names.
+    Candump,
+    /// Vector ASC format, channel n refers to the n-th bus.
+    Asc,
+}
+
+impl TraceFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "candump" | "log" => Some(TraceFormat::Candump),
+            "asc" => Some(TraceFormat::Asc),
+            _ => None,
+        }
+    }
+
+    /// guesses the format from the file extension (`.log` or `.asc`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TraceFormat::Candump => "log",
+            TraceFormat::Asc => "asc",
+        }
+    }
+}
+
+/// A bus of the network, the trace formats refer to buses by name (candump)
+/// or by channel number (ASC), which have to be mapped to the bus ids of the network.
+#[derive(Debug, Clone)]
+pub struct TraceBus {
+    pub id: u32,
+    pub name: String,
+}
+
+impl TraceBus {
+    pub fn new(id: u32, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_owned(),
+        }
+    }
+}
+
+fn invalid_data(msg: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+fn data_bytes(data: u64, dlc: u8) -> impl Iterator<Item = u8> {
+    data.to_le_bytes().into_iter().take(dlc.min(8) as usize)
+}
+
+fn parse_data_bytes<'a>(
+    bytes: impl Iterator<Item = &'a str>,
+    radix: u32,
+) -> std::io::Result<u64> {
+    let mut data = [0u8; 8];
+    for (i, byte) in bytes.enumerate() {
+        if i >= 8 {
+            return Err(invalid_data("more than 8 data bytes".to_owned()));
+        }
+        data[i] = u8::from_str_radix(byte, radix)
+            .map_err(|_| invalid_data(format!("invalid data byte {byte:?}")))?;
+    }
+    Ok(u64::from_le_bytes(data))
+}
+
+pub struct TraceWriter<W: Write> {
+    writer: W,
+    format: TraceFormat,
+    buses: Vec<TraceBus>,
+    timebase_us: u64,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// timebase_us is the start of run in microseconds since the unix epoch,
+    /// date is the human readable start of run, which is written to the ASC header.
+    pub fn create(
+        mut writer: W,
+        format: TraceFormat,
+        buses: Vec<TraceBus>,
+        timebase_us: u64,
+        date: &str,
+    ) -> std::io::Result<Self> {
+        if format == TraceFormat::Asc {
+            asc::write_header(&mut writer, date)?;
+        }
+        Ok(Self {
+            writer,
+            format,
+            buses,
+            timebase_us,
+        })
+    }
+
+    pub fn write_frame(&mut self, frame: &TNetworkFrame) -> std::io::Result<()> {
+        match self.format {
+            TraceFormat::Candump => {
+                let Some(bus) = self.buses.iter().find(|bus| bus.id == frame.bus_id) else {
+                    return Err(invalid_data(format!("unknown bus id {}", frame.bus_id)));
+                };
+                candump::write_frame(&mut self.writer, frame, &bus.name, self.timebase_us)
+            }
+            TraceFormat::Asc => {
+                let Some(channel) = self.buses.iter().position(|bus| bus.id == frame.bus_id)
+                else {
+                    return Err(invalid_data(format!("unknown bus id {}", frame.bus_id)));
+                };
+                asc::write_frame(&mut self.writer, frame, channel as u32 + 1)
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// writes the trailer of the trace (if the format requires one).
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if self.format == TraceFormat::Asc {
+            asc::write_trailer(&mut self.writer)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+pub struct TraceReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+    format: TraceFormat,
+    buses: Vec<TraceBus>,
+    hex: bool,
+    // timestamp that frames are returned relative to.
+    start: Option<Duration>,
+}
+
+impl<R: BufRead> TraceReader<R> {
+    pub fn new(reader: R, format: TraceFormat, buses: Vec<TraceBus>) -> Self {
+        Self {
+            lines: reader.lines(),
+            format,
+            buses,
+            hex: true,
+            // ASC timestamps are already relative to the start of measurement.
+            start: match format {
+                TraceFormat::Candump => None,
+                TraceFormat::Asc => Some(Duration::ZERO),
+            },
+        }
+    }
+
+    /// returns the start of run relative to the unix epoch for candump logs,
+    /// which is the absolute timestamp of the first frame.
+    pub fn start(&self) -> Option<Duration> {
+        self.start
+    }
+
+    /// returns Ok(None) at the end of the trace.
+    /// Lines that do not describe a data or remote frame (comments, events,
+    /// error frames) are skipped.
+    pub fn read_frame(&mut self) -> std::io::Result<Option<TNetworkFrame>> {
+        loop {
+            let Some(line) = self.lines.next() else {
+                return Ok(None);
+            };
+            let line = line?;
+            let frame = match self.format {
+                TraceFormat::Candump => candump::parse_line(&line, &self.buses)?,
+                TraceFormat::Asc => asc::parse_line(&line, &self.buses, &mut self.hex)?,
+            };
+            let Some(mut frame) = frame else {
+                continue;
+            };
+            let start = *self.start.get_or_insert(frame.timestamp);
+            frame.timestamp = frame.timestamp.saturating_sub(start);
+            return Ok(Some(frame));
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for TraceReader<R> {
+    type Item = std::io::Result<TNetworkFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
@@ -0,0 +1,103 @@
+use std::{future::Future, pin::Pin};
+
+use tokio::sync::{mpsc, Mutex};
+
+use crate::{TNetworkError, TNetworkFrame};
+
+/// Boxed future returned by CanTransport, such that transports can be used as trait objects.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportBus {
+    pub id: u32,
+    pub name: String,
+}
+
+impl TransportBus {
+    pub fn new(id: u32, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_owned(),
+        }
+    }
+}
+
+/// Connection to a CAN network, which carries the frames of all buses.
+/// Implemented by every backend (TCP, SocketCAN, replays, ...),
+/// such that the control panel, the cli and the server can be wired to any of them.
+pub trait CanTransport: Send + Sync {
+    /// Buses reachable through this transport. Empty if the transport
+    /// doesn't know about buses (e.g. a tcp client, where the server owns the buses).
+    fn buses(&self) -> Vec<TransportBus>;
+
+    fn send<'a>(&'a self, frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>>;
+
+    /// Forwards an error frame, which was received by another transport.
+    /// Transports, which can't carry error frames (e.g. a physical bus), ignore them.
+    fn send_error<'a>(&'a self, _error: &'a TNetworkError) -> BoxFuture<'a, std::io::Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Receives the next frame or error frame, None after the transport was closed.
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>>;
+
+    fn close(&self) -> BoxFuture<'_, ()>;
+}
+
+/// In-memory transport, every frame sent is received again by the same transport.
+pub struct LoopbackTransport {
+    buses: Vec<TransportBus>,
+    tx: std::sync::Mutex<Option<mpsc::UnboundedSender<Result<TNetworkFrame, TNetworkError>>>>,
+    rx: Mutex<mpsc::UnboundedReceiver<Result<TNetworkFrame, TNetworkError>>>,
+}
+
+impl LoopbackTransport {
+    pub fn new(buses: Vec<TransportBus>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            buses,
+            tx: std::sync::Mutex::new(Some(tx)),
+            rx: Mutex::new(rx),
+        }
+    }
+
+    /// Error frames can't be sent, but a loopback can pretend to have received one.
+    pub fn inject_error(&self, error: TNetworkError) -> std::io::Result<()> {
+        self.push(Err(error))
+    }
+
+    fn push(&self, frame: Result<TNetworkFrame, TNetworkError>) -> std::io::Result<()> {
+        let tx = self.tx.lock().expect("Failed to acquire loopback lock");
+        match tx.as_ref().map(|tx| tx.send(frame)) {
+            Some(Ok(())) => Ok(()),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "Loopback transport closed",
+            )),
+        }
+    }
+}
+
+impl CanTransport for LoopbackTransport {
+    fn buses(&self) -> Vec<TransportBus> {
+        self.buses.clone()
+    }
+
+    fn send<'a>(&'a self, frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>> {
+        let result = self.push(Ok(frame.clone()));
+        Box::pin(async move { result })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>> {
+        Box::pin(async move { self.rx.lock().await.recv().await })
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        // frames sent before closing can still be received.
+        self.tx
+            .lock()
+            .expect("Failed to acquire loopback lock")
+            .take();
+        Box::pin(async {})
+    }
+}
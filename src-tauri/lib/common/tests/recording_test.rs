@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use canzero_common::{
+    recording::{
+        frame_size, recording_file_name, recording_files, RecordingHeader, RecordingReader,
+        RecordingWriter, HEADER_SIZE,
+    },
+    CanFrame, NetworkFrame, TNetworkFrame,
+};
+
+fn frame(timestamp_us: u64, bus_id: u32, id: u32, ide: bool, dlc: u8, data: u64) -> TNetworkFrame {
+    TNetworkFrame::new(
+        Duration::from_micros(timestamp_us),
+        NetworkFrame {
+            bus_id,
+            can_frame: CanFrame::new(id, ide, false, dlc, data),
+        },
+    )
+}
+
+#[test]
+fn recording_roundtrip() {
+    let header = RecordingHeader {
+        config_hash: 0xDEAD_BEEF_CAFE_BABE,
+        timebase_us: 1_700_000_000_000_000,
+    };
+    let frames = vec![
+        frame(0, 0, 0x123, false, 8, 0x0102_0304_0506_0708),
+        frame(150, 1, 0x1ABCDEF, true, 4, 0xFFFF_FFFF),
+        frame(1_000_000, 0, 0x7FF, false, 0, 0),
+    ];
+    let mut writer = RecordingWriter::create(vec![], &header).unwrap();
+    for f in &frames {
+        writer.write_frame(f).unwrap();
+    }
+    let bin = writer.into_inner();
+
+    let reader = RecordingReader::open(bin.as_slice()).unwrap();
+    assert_eq!(reader.header(), &header);
+    let read: Vec<TNetworkFrame> = reader.map(Result::unwrap).collect();
+    assert_eq!(read.len(), frames.len());
+    for (a, b) in read.iter().zip(&frames) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.bus_id, b.bus_id);
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert_eq!(a.can_frame.get_dlc(), b.can_frame.get_dlc());
+        assert_eq!(a.can_frame.get_data_u64(), b.can_frame.get_data_u64());
+    }
+}
+
+#[test]
+fn recording_truncated_frame_ends_recording() {
+    let header = RecordingHeader {
+        config_hash: 42,
+        timebase_us: 0,
+    };
+    let mut writer = RecordingWriter::create(vec![], &header).unwrap();
+    writer.write_frame(&frame(10, 0, 0x10, false, 8, 1)).unwrap();
+    let mut bin = writer.into_inner();
+    bin.extend_from_slice(&[0u8; 5]);
+
+    let mut reader = RecordingReader::open(bin.as_slice()).unwrap();
+    assert!(reader.read_frame().unwrap().is_some());
+    assert!(reader.read_frame().unwrap().is_none());
+}
+
+#[test]
+fn recording_rejects_invalid_magic() {
+    let bin = [0u8; 32];
+    assert!(RecordingReader::open(bin.as_slice()).is_err());
+}
+
+#[test]
+fn recording_roundtrip_fd_frames() {
+    let header = RecordingHeader {
+        config_hash: 7,
+        timebase_us: 0,
+    };
+    let payload: Vec<u8> = (0..20).collect();
+    let frames = vec![
+        TNetworkFrame::new(
+            Duration::from_micros(5),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new_fd(0x1ABCDEF, true, true, false, &payload),
+            },
+        ),
+        frame(10, 0, 0x10, false, 8, 1),
+        TNetworkFrame::new(
+            Duration::from_micros(20),
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new_fd(0x123, false, false, true, &[1, 2, 3]),
+            },
+        ),
+    ];
+    let mut writer = RecordingWriter::create(vec![], &header).unwrap();
+    for f in &frames {
+        writer.write_frame(f).unwrap();
+    }
+    let bin = writer.into_inner();
+    assert_eq!(
+        bin.len(),
+        HEADER_SIZE + frames.iter().map(frame_size).sum::<usize>()
+    );
+
+    let read: Vec<TNetworkFrame> = RecordingReader::open(bin.as_slice())
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(read.len(), frames.len());
+    for (a, b) in read.iter().zip(&frames) {
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert_eq!(a.can_frame.is_fd(), b.can_frame.is_fd());
+        assert_eq!(a.can_frame.get_brs_flag(), b.can_frame.get_brs_flag());
+        assert_eq!(a.can_frame.get_esi_flag(), b.can_frame.get_esi_flag());
+        assert_eq!(a.can_frame.get_dlc(), b.can_frame.get_dlc());
+        assert_eq!(a.can_frame.get_data(), b.can_frame.get_data());
+    }
+}
+
+#[test]
+fn rotated_files_are_ordered_by_their_sequence_number() {
+    let dir = tempfile::tempdir().unwrap();
+    let header = RecordingHeader {
+        config_hash: 0,
+        timebase_us: 1_700_000_000_123_456,
+    };
+    let other = RecordingHeader {
+        config_hash: 0,
+        timebase_us: 1_800_000_000_000_000,
+    };
+    assert_eq!(
+        recording_file_name(&header, 3),
+        "canzero-1700000000-0003.rec"
+    );
+    for name in [
+        recording_file_name(&header, 10),
+        recording_file_name(&header, 2),
+        recording_file_name(&header, 0),
+        recording_file_name(&other, 1),
+        "canzero-1700000000-notes.rec".to_owned(),
+        "canzero-1700000000-0001.txt".to_owned(),
+    ] {
+        std::fs::write(dir.path().join(name), []).unwrap();
+    }
+    let expected: Vec<_> = [0, 2, 10]
+        .iter()
+        .map(|seq| dir.path().join(recording_file_name(&header, *seq)))
+        .collect();
+    assert_eq!(recording_files(&expected[1]).unwrap(), expected);
+
+    // files, which are not part of a rotated recording, are replayed on their own.
+    let single = dir.path().join("canzero-1700000000-notes.rec");
+    assert_eq!(recording_files(&single).unwrap(), vec![single]);
+}
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use canzero_common::{
+    trace_format::{TraceBus, TraceFormat, TraceReader, TraceWriter},
+    CanFrame, NetworkFrame, TNetworkFrame,
+};
+
+fn frame(timestamp_us: u64, bus_id: u32, id: u32, ide: bool, dlc: u8, data: u64) -> TNetworkFrame {
+    TNetworkFrame::new(
+        Duration::from_micros(timestamp_us),
+        NetworkFrame {
+            bus_id,
+            can_frame: CanFrame::new(id, ide, false, dlc, data),
+        },
+    )
+}
+
+fn buses() -> Vec<TraceBus> {
+    vec![TraceBus::new(0, "can0"), TraceBus::new(1, "can1")]
+}
+
+fn roundtrip(format: TraceFormat) {
+    let frames = vec![
+        frame(0, 0, 0x123, false, 8, 0x0807_0605_0403_0201),
+        frame(150, 1, 0x1ABCDEF, true, 4, 0xDDCC_BBAA),
+        frame(1_000_000, 0, 0x7FF, false, 0, 0),
+    ];
+    let mut writer = TraceWriter::create(
+        vec![],
+        format,
+        buses(),
+        1_700_000_000_000_000,
+        "Tue Nov 14 10:13:20.000 pm 2023",
+    )
+    .unwrap();
+    for f in &frames {
+        writer.write_frame(f).unwrap();
+    }
+    let bin = writer.finish().unwrap();
+
+    let mut reader = TraceReader::new(bin.as_slice(), format, buses());
+    let read = reader
+        .by_ref()
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(read.len(), frames.len());
+    for (a, b) in frames.iter().zip(read.iter()) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.bus_id, b.bus_id);
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert_eq!(a.can_frame.get_dlc(), b.can_frame.get_dlc());
+        assert_eq!(a.can_frame.get_data_u64(), b.can_frame.get_data_u64());
+    }
+    if format == TraceFormat::Candump {
+        assert_eq!(
+            reader.start(),
+            Some(Duration::from_micros(1_700_000_000_000_000))
+        );
+    }
+}
+
+#[test]
+fn candump_roundtrip() {
+    roundtrip(TraceFormat::Candump);
+}
+
+#[test]
+fn asc_roundtrip() {
+    roundtrip(TraceFormat::Asc);
+}
+
+#[test]
+fn candump_parse() {
+    let log = "(1436509052.249713) can1 044#2A366C2BBA\n(1436509052.449847) can0 20000004#0004000000000000\n";
+    let frames = TraceReader::new(log.as_bytes(), TraceFormat::Candump, buses())
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    // error frames are skipped
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].bus_id, 1);
+    assert_eq!(frames[0].can_frame.get_id(), 0x44);
+    assert!(!frames[0].can_frame.get_ide_flag());
+    assert_eq!(frames[0].can_frame.get_dlc(), 5);
+    assert_eq!(frames[0].can_frame.get_data_u64(), 0xBA2B6C362A);
+}
+
+#[test]
+fn unknown_bus() {
+    let log = "(1436509052.249713) vcan7 044#2A\n";
+    let mut reader = TraceReader::new(log.as_bytes(), TraceFormat::Candump, buses());
+    assert!(reader.read_frame().is_err());
+}
+
+#[test]
+fn candump_invalid_data() {
+    for frame in ["123#0ü0", "123##10ü0", "123#Rü", "123#Rx", "123#R9"] {
+        let log = format!("(1436509052.249713) can0 {frame}\n");
+        let mut reader = TraceReader::new(log.as_bytes(), TraceFormat::Candump, buses());
+        assert!(reader.read_frame().is_err(), "accepted frame {frame:?}");
+    }
+}
+
+#[test]
+fn asc_invalid_timestamps() {
+    for timestamp in ["-1.0", "inf", "NaN"] {
+        let log =
+            format!("base hex  timestamps absolute\n{timestamp} 1  123             Rx   d 1 2A\n");
+        let mut reader = TraceReader::new(log.as_bytes(), TraceFormat::Asc, buses());
+        assert!(
+            reader.read_frame().is_err(),
+            "accepted timestamp {timestamp}"
+        );
+    }
+}
+
+#[test]
+fn candump_roundtrip_fd_frames() {
+    let payload: Vec<u8> = (0..48).collect();
+    let frames = vec![
+        TNetworkFrame::new(
+            Duration::ZERO,
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new_fd(0x123, false, true, false, &payload),
+            },
+        ),
+        TNetworkFrame::new(
+            Duration::from_micros(10),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new_fd(0x1ABCDEF, true, false, true, &payload[..9]),
+            },
+        ),
+    ];
+    let mut writer =
+        TraceWriter::create(vec![], TraceFormat::Candump, buses(), 0, "").unwrap();
+    for f in &frames {
+        writer.write_frame(f).unwrap();
+    }
+    let bin = writer.finish().unwrap();
+    assert!(String::from_utf8_lossy(&bin).starts_with("(0.000000) can0 123##1000102"));
+
+    let read = TraceReader::new(bin.as_slice(), TraceFormat::Candump, buses())
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(read.len(), frames.len());
+    for (a, b) in frames.iter().zip(read.iter()) {
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert!(b.can_frame.is_fd());
+        assert_eq!(a.can_frame.get_brs_flag(), b.can_frame.get_brs_flag());
+        assert_eq!(a.can_frame.get_esi_flag(), b.can_frame.get_esi_flag());
+        // 9 bytes are padded to 12.
+        assert_eq!(a.can_frame.get_data(), b.can_frame.get_data());
+    }
+}
+
+#[test]
+fn can_fd_frames_are_padded_to_valid_lengths() {
+    let frame = CanFrame::new_fd(0x10, false, false, false, &[0xAA; 9]);
+    assert_eq!(frame.get_dlc(), 9);
+    assert_eq!(frame.len(), 12);
+    assert_eq!(frame.get_data()[9..], [0, 0, 0]);
+    assert_eq!(frame.get_data_u64(), u64::from_le_bytes([0xAA; 8]));
+    assert_eq!(canzero_common::can_fd_len_to_dlc(64), Some(15));
+    assert_eq!(canzero_common::can_fd_len_to_dlc(65), None);
+}
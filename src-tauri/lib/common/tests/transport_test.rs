@@ -0,0 +1,62 @@
+use std::{sync::Arc, time::Duration};
+
+use canzero_common::{
+    transport::{CanTransport, LoopbackTransport, TransportBus},
+    CanError, CanFrame, NetworkError, NetworkFrame, TNetworkError, TNetworkFrame,
+};
+
+fn frame(timestamp_us: u64, bus_id: u32, id: u32, data: u64) -> TNetworkFrame {
+    TNetworkFrame::new(
+        Duration::from_micros(timestamp_us),
+        NetworkFrame {
+            bus_id,
+            can_frame: CanFrame::new(id, false, false, 8, data),
+        },
+    )
+}
+
+#[tokio::test]
+async fn loopback_receives_sent_frames_in_order() {
+    let transport = LoopbackTransport::new(vec![TransportBus::new(0, "can0")]);
+    transport.send(&frame(0, 0, 0x10, 1)).await.unwrap();
+    transport.send(&frame(5, 0, 0x20, 2)).await.unwrap();
+
+    let first = transport.recv().await.unwrap().unwrap();
+    let second = transport.recv().await.unwrap().unwrap();
+    assert_eq!(first.can_frame.key(), 0x10);
+    assert_eq!(second.can_frame.key(), 0x20);
+    assert_eq!(second.can_frame.get_data_u64(), 2);
+}
+
+#[tokio::test]
+async fn loopback_forwards_injected_errors() {
+    let transport = LoopbackTransport::new(vec![]);
+    transport
+        .inject_error(TNetworkError::new(
+            Duration::ZERO,
+            NetworkError {
+                bus_id: 1,
+                can_error: CanError(42),
+            },
+        ))
+        .unwrap();
+    let error = transport.recv().await.unwrap().unwrap_err();
+    assert_eq!(error.bus_id, 1);
+    assert_eq!(error.can_error.erno(), 42);
+}
+
+#[tokio::test]
+async fn loopback_drains_and_ends_after_close() {
+    // used as trait object, like the control panel does.
+    let transport: Arc<dyn CanTransport> = Arc::new(LoopbackTransport::new(vec![
+        TransportBus::new(0, "can0"),
+        TransportBus::new(1, "can1"),
+    ]));
+    assert_eq!(transport.buses().len(), 2);
+
+    transport.send(&frame(0, 1, 0x10, 0)).await.unwrap();
+    transport.close().await;
+    assert!(transport.send(&frame(1, 1, 0x10, 0)).await.is_err());
+    assert!(transport.recv().await.unwrap().is_ok());
+    assert!(transport.recv().await.is_none());
+}
@@ -9,6 +9,8 @@ pub struct BusData {
     pub name : String,
     pub id : u32,
     pub baudrate : u32,
+    pub fd : bool,
+    pub data_baudrate : Option<u32>,
     pub expected_utilization : u32,
 }
 
@@ -20,6 +22,8 @@ impl BusBuilder {
             name : name.to_owned(),
             id,
             baudrate : baudrate.unwrap_or(1000000),
+            fd : false,
+            data_baudrate : None,
             expected_utilization : 0,
         }))
     }
@@ -27,5 +31,16 @@ impl BusBuilder {
     pub fn baudrate(&self, baudrate : u32) {
         self.0.borrow_mut().baudrate = baudrate;
     }
+
+    pub fn fd(&self, fd : bool) {
+        self.0.borrow_mut().fd = fd;
+    }
+
+    /// implies fd.
+    pub fn data_baudrate(&self, data_baudrate : u32) {
+        let mut bus_data = self.0.borrow_mut();
+        bus_data.fd = true;
+        bus_data.data_baudrate = Some(data_baudrate);
+    }
 }
 
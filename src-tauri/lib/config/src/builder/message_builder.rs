@@ -262,10 +262,9 @@ impl MessageSignalFormatBuilder {
     pub fn add_signal(&self, signal: Signal) -> errors::Result<()> {
         let mut builder_data = self.0.borrow_mut();
         if builder_data.0.iter().any(|s| s.name() == signal.name()) {
-            return Err(errors::ConfigError::DuplicatedSignal(format!(
-                "Dupplicated signal name in message: {}",
-                signal.name()
-            )));
+            return Err(errors::ConfigError::DuplicatedSignal(
+                signal.name().to_owned(),
+            ));
         }
         builder_data.0.push(signal);
         Ok(())
@@ -162,8 +162,19 @@ pub fn assign_messages_ids(
             }
         };
         let setcode = assigned_set.setcode;
-        let mut reserved_ids: Vec<u32> =
-            Vec::from_iter(assigned_set.fixed_ids.clone().into_iter());
+        // fixed messages, which didn't fit into any set, keep their id
+        // and may share the setcode with this set.
+        let mut reserved_ids: Vec<u32> = assigned_set
+            .fixed_ids
+            .iter()
+            .copied()
+            .chain(
+                fixed_messages
+                    .iter()
+                    .filter(|f| f.ide() == assigned_set.ide)
+                    .map(|f| f.id()),
+            )
+            .collect();
 
         let bucket_layout = minimized_network.bucket_layout();
 
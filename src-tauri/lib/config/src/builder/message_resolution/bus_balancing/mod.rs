@@ -41,14 +41,16 @@ impl MessageBusSplit {
 struct BusInfo {
     bus_id: u32,
     bus_name: String,
+    fd: bool,
     load: f64,
 }
 
 impl BusInfo {
-    pub fn new(bus_id: u32, bus_name: &str) -> Self {
+    pub fn new(bus_id: u32, bus_name: &str, fd: bool) -> Self {
         Self {
             bus_id,
             bus_name: bus_name.to_owned(),
+            fd,
             load: 0f64,
         }
     }
@@ -96,7 +98,10 @@ impl Buses {
             buses: buses
                 .iter()
                 .enumerate()
-                .map(|(bus_id, builder)| BusInfo::new(bus_id as u32, &builder.0.borrow().name))
+                .map(|(bus_id, builder)| {
+                    let bus_data = builder.0.borrow();
+                    BusInfo::new(bus_id as u32, &bus_data.name, bus_data.fd)
+                })
                 .collect(),
         }
     }
@@ -105,8 +110,14 @@ impl Buses {
         let bus = self.buses.iter_mut().find(|b| b.bus_name == message.bus).expect("invalid bus");
         bus.load += message.message.load;
     }
+    /// Messages, which don't fit into a classical frame, are only assigned to CAN FD buses
+    /// (if there are any).
     pub fn add_message_to_min_load(&mut self, message : MessageWithLoad) {
-        let bus = self.buses.iter_mut().min().unwrap();
+        let requires_fd = message.size > 64;
+        let bus = match self.buses.iter_mut().filter(|b| b.fd || !requires_fd).min() {
+            Some(bus) => bus,
+            None => self.buses.iter_mut().min().unwrap(),
+        };
         message.message.assign_bus(&bus.bus_name);
         bus.load += message.load;
     }
@@ -115,6 +126,8 @@ impl Buses {
 #[derive(Clone)]
 struct MessageWithLoad {
     message: MessageBuilder,
+    /// in bits.
+    size: usize,
     load: f64,
 }
 
@@ -155,7 +168,7 @@ impl MessageWithLoad {
                             entries: _,
                             visibility: _,
                         } => *size as usize,
-                        crate::config::Type::Array { len: _, ty: _ } => todo!(),
+                        crate::config::Type::Array { len, ty } => len * acc_dlc(ty),
                     }
                 }
                 let mut dlc = 0usize;
@@ -196,6 +209,7 @@ impl MessageWithLoad {
         let load = (max_bitlen as f64 / interval.as_millis() as f64) * 1e3f64;
         Self {
             message : msg.clone(),
+            size : dlc,
             load,
         }
     }
@@ -19,6 +19,7 @@ impl NodeFilterBank {
 pub struct Filter {
     mask: u32,
     id: u32,
+    ide: bool,
 }
 impl Filter {
     pub fn mask(&self) -> u32 {
@@ -27,6 +28,9 @@ impl Filter {
     pub fn id(&self) -> u32 {
         self.id
     }
+    pub fn ide(&self) -> bool {
+        self.ide
+    }
 }
 
 pub fn find_filter_configuration(filter_infos: Vec<NodeFilterInfo>) -> Vec<NodeFilterBank> {
@@ -35,14 +39,16 @@ pub fn find_filter_configuration(filter_infos: Vec<NodeFilterInfo>) -> Vec<NodeF
         .map(|node_filter_info| NodeFilterBank {
             filters: node_filter_info.filter_infos().iter().map(|filter| -> Filter{
                 match filter {
-                    super::assign_messages::FilterInfo::Setcode { setcode, setcode_len, ide : _ } => Filter {
-                        mask : u32::MAX >> (32 - *setcode_len),
-                        id : *setcode
+                    super::assign_messages::FilterInfo::Setcode { setcode, setcode_len, ide } => Filter {
+                        mask : 0xFFFFFFFFu32.checked_shr(32 - *setcode_len).unwrap_or(0),
+                        id : *setcode,
+                        ide : *ide,
                     },
-                    super::assign_messages::FilterInfo::Single { id, ide : _ } => {
+                    super::assign_messages::FilterInfo::Single { id, ide } => {
                         Filter {
-                            mask : 0xFFFFFFFFu32,
+                            mask : if *ide { 0x1FFFFFFFu32 } else { 0x7FFu32 },
                             id : *id,
+                            ide : *ide,
                         }
                     }
                 }
@@ -89,7 +89,7 @@ fn load_estimation(types: &Vec<TypeRef>, msg: &MessageBuilder) -> f64 {
                         entries: _,
                         visibility: _,
                     } => *size as usize,
-                    crate::config::Type::Array { len: _, ty: _ } => todo!(),
+                    crate::config::Type::Array { len, ty } => len * acc_dlc(ty),
                 }
             }
             let mut dlc = 0usize;
@@ -157,7 +157,7 @@ impl ReceiverSet {
                                     entries: _,
                                     visibility: _,
                                 } => *size as usize,
-                                crate::config::Type::Array { len: _, ty: _ } => todo!(),
+                                crate::config::Type::Array { len, ty } => len * acc_dlc(ty),
                             }
                         }
                         let mut dlc = 0usize;
@@ -67,13 +67,16 @@ impl MinimizedNetwork {
 
 pub fn minimize_sets(network_info: NetworkInfo) -> MinimizedNetwork {
     if network_info.node_receive_sets().is_empty() {
-        assert_eq!(network_info.receive_sets().len(), 1, "It is assumed that if no receiver exists in 
-                   the network that all messages are defined by the config itself are are std and non fixed");
-        let set = network_info.receive_sets().first().unwrap();
+        // without receivers, all sets (e.g. std and ext messages) can share
+        // a single bucket layout.
         let mut bucket_layout = BucketLayout::new();
 
         loop {
-            let Some(commit) = set.min_commit_to_merge(&bucket_layout) else {
+            let Some(commit) = network_info
+                .receive_sets()
+                .iter()
+                .find_map(|set| set.min_commit_to_merge(&bucket_layout))
+            else {
                 break;
             };
             bucket_layout.apply_commit(commit);
@@ -82,7 +85,11 @@ pub fn minimize_sets(network_info: NetworkInfo) -> MinimizedNetwork {
             bucket_layout.prio_bit_size() + 1 < STD_ID_LENGTH,
             "Failed to find message id configuration"
         );
-        let minimized_sets = set.to_minimized_sets(&bucket_layout);
+        let minimized_sets = network_info
+            .receive_sets()
+            .iter()
+            .flat_map(|set| set.to_minimized_sets(&bucket_layout))
+            .collect();
         return MinimizedNetwork {
             sets: minimized_sets,
             bucket_layout,
@@ -16,7 +16,7 @@ use crate::{
         message::MessageUsage,
         signal::Signal,
         stream::Stream,
-        Command, ConfigRef, Message, MessageEncoding, MessageId, Network, NetworkRef, Node,
+        node::CanFilter, Command, ConfigRef, Message, MessageEncoding, MessageId, Network, NetworkRef, Node,
         ObjectEntry, SignalRef, SignalType, Type, TypeRef, TypeSignalEncoding,
     },
     errors::Result,
@@ -286,9 +286,8 @@ impl NetworkBuilder {
                 let max = &cap["max"];
                 let max = max.parse::<f64>().unwrap();
                 if min >= max {
-                    return Err(errors::ConfigError::InvalidRange(
-                        "invalid decimal range min has to be less than max".to_owned(),
-                    ));
+                    // the error carries the type name, such that it can be located in the config.
+                    return Err(errors::ConfigError::InvalidRange(type_name.to_owned()));
                 }
                 let range = max - min;
                 let scale = range / ((0xFFFFFFFFFFFFFFFF as u64 >> (64 - size)) as f64);
@@ -307,8 +306,10 @@ impl NetworkBuilder {
                 regex::Regex::new(r#"^(?<type>[a-zA-Z][a-zA-Z0-9]*(<[+-]?([0-9]*[.])?[0-9]+\.\.[+-]?([0-9]*[.])?[0-9]+>)?)\[(?<len>[0-9]+)\]$"#).unwrap();
         match array_regex.captures(type_name) {
             Some(cap) => {
-                let len = &cap["len"];
-                let len = len.parse::<usize>().unwrap();
+                let len = match cap["len"].parse::<usize>() {
+                    Ok(len) if len > 0 => len,
+                    _ => return Err(errors::ConfigError::InvalidArrayLength(type_name.to_owned())),
+                };
                 let ty = &cap["type"];
                 let inner_type = Self::resolve_type(defined_types, ty)?;
                 return Ok(make_config_ref(Type::Array {
@@ -336,9 +337,7 @@ impl NetworkBuilder {
                 _ => (),
             }
         }
-        return Err(errors::ConfigError::InvalidType(format!(
-            "failed to resolve type : {type_name:?}"
-        )));
+        return Err(errors::ConfigError::InvalidType(type_name.to_owned()));
     }
 
     fn topo_sort_types(types: &Vec<TypeRef>) -> Vec<TypeRef> {
@@ -498,6 +497,8 @@ impl NetworkBuilder {
                     &bus_data.name,
                     bus_data.id,
                     bus_data.baudrate,
+                    bus_data.fd,
+                    bus_data.data_baudrate,
                 ))
             })
             .collect();
@@ -606,7 +607,7 @@ impl NetworkBuilder {
         drop(builder);
         #[cfg(feature = "logging_info")]
         println!("[CANZERO-CONFIG::build] Resolving message ids and bus assignments");
-        resolve_ids_filters_and_buses(&tmp_buses, &tmp_messages, &nodes, &types)?;
+        let filter_banks = resolve_ids_filters_and_buses(&tmp_buses, &tmp_messages, &nodes, &types)?;
         let builder = self.0.borrow();
 
         #[cfg(feature = "logging_info")]
@@ -651,8 +652,10 @@ impl NetworkBuilder {
                     ) -> TypeSignalEncoding {
                         match ty as &Type {
                             Type::Primitive(signal_type) => {
+                                // array elements are named "[i]", which is not a valid signal name.
+                                let signal_name = name.trim_start_matches('[').trim_end_matches(']');
                                 let signal = make_config_ref(Signal::new(
-                                    &format!("{prefix}_{name}"),
+                                    &format!("{prefix}_{signal_name}"),
                                     None,
                                     signal_type.clone(),
                                     *offset,
@@ -709,7 +712,24 @@ impl NetworkBuilder {
                                     signal,
                                 ))
                             }
-                            Type::Array { len: _, ty: _ } => todo!(),
+                            Type::Array { len, ty: inner_ty } => {
+                                // arrays are encoded as composites, with the index as attribute name.
+                                let mut attributes = vec![];
+                                for i in 0..*len {
+                                    attributes.push(build_attribute(
+                                        inner_ty,
+                                        &format!("[{i}]"),
+                                        offset,
+                                        &format!("{prefix}_{name}"),
+                                        signals,
+                                    ));
+                                }
+                                TypeSignalEncoding::Composite(CompositeSignalEncoding::new(
+                                    name.to_owned(),
+                                    attributes,
+                                    ty.clone(),
+                                ))
+                            }
                         }
                     }
 
@@ -735,14 +755,21 @@ impl NetworkBuilder {
                 let signal_max_bit = signal.byte_offset() + signal.size() as usize;
                 max_bit = max_bit.max(signal_max_bit);
             }
-            let dlc = ((max_bit + 8 - 1) / 8) as u8;
-
             let bus = buses
                 .iter()
                 .find(|bus| bus.id() == message_data.bus.clone().unwrap().0.borrow().id)
                 .unwrap()
                 .clone();
 
+            let mut dlc = ((max_bit + 8 - 1) / 8) as u8;
+            if bus.fd() && dlc > 8 {
+                // CAN FD frames are only defined for these lengths.
+                dlc = [12, 16, 20, 24, 32, 48, 64]
+                    .into_iter()
+                    .find(|len| *len >= dlc)
+                    .unwrap_or(dlc);
+            }
+
             messages.push(make_config_ref(Message::new(
                 message_data.name.clone(),
                 message_data.description.clone(),
@@ -788,14 +815,19 @@ impl NetworkBuilder {
         }
 
 
-        pub fn rec_type_acc(node_types: &mut Vec<TypeRef>, encoding: &TypeSignalEncoding) {
+        pub fn rec_type_acc(
+            node_types: &mut Vec<TypeRef>,
+            encoding: &TypeSignalEncoding,
+        ) -> errors::Result<()> {
             match encoding {
                 TypeSignalEncoding::Composite(composite) => {
-                    if !node_types.contains(composite.ty()) {
+                    // array types are anonymous, only their element types are collected.
+                    let is_array = matches!(composite.ty() as &Type, Type::Array { .. });
+                    if !is_array && !node_types.contains(composite.ty()) {
                         node_types.push(composite.ty().clone());
                     }
                     for attribute in composite.attributes() {
-                        rec_type_acc(node_types, attribute);
+                        rec_type_acc(node_types, attribute)?;
                     }
                 }
                 TypeSignalEncoding::Primitive(primitive) => match primitive.ty() as &Type {
@@ -805,7 +837,7 @@ impl NetworkBuilder {
                         description: _,
                         attribs: _,
                         visibility: _,
-                    } => panic!("not a primitive"),
+                    } => return Err(errors::ConfigError::InvalidType(primitive.ty().name())),
                     Type::Enum {
                         name: _,
                         description: _,
@@ -817,9 +849,12 @@ impl NetworkBuilder {
                             node_types.push(primitive.ty().clone());
                         }
                     }
-                    Type::Array { len: _, ty: _ } => todo!(),
+                    Type::Array { len: _, ty: _ } => {
+                        return Err(errors::ConfigError::InvalidType(primitive.ty().name()))
+                    }
                 },
             }
+            Ok(())
         }
 
         // add get and set req,resp to all nodes
@@ -858,7 +893,7 @@ impl NetworkBuilder {
                 match &message_ref.encoding() {
                     Some(encoding) => {
                         for attribute in encoding.attributes() {
-                            rec_type_acc(&mut node_types, attribute);
+                            rec_type_acc(&mut node_types, attribute)?;
                         }
                     }
                     None => (),
@@ -879,7 +914,7 @@ impl NetworkBuilder {
                 match &message_ref.encoding() {
                     Some(encoding) => {
                         for attribute in encoding.attributes() {
-                            rec_type_acc(&mut node_types, attribute);
+                            rec_type_acc(&mut node_types, attribute)?;
                         }
                     }
                     None => (),
@@ -956,7 +991,7 @@ impl NetworkBuilder {
                                 node_types.push(ty.clone());
                             }
                         }
-                        Type::Array { len: _, ty: _ } => todo!(),
+                        Type::Array { len: _, ty } => rec_add_type(node_types, ty),
                     };
                 }
                 rec_add_type(&mut node_types, &ty);
@@ -1024,7 +1059,7 @@ impl NetworkBuilder {
             );
             let node_types = Self::topo_sort_types(&node_types);
 
-            let node_buses = node_data
+            let node_buses: Vec<BusRef> = node_data
                 .buses
                 .iter()
                 .map(|bus_builder| {
@@ -1041,6 +1076,42 @@ impl NetworkBuilder {
                 node_data.name
             );
 
+            #[cfg(feature = "logging_info")]
+            println!(
+                "[CANZERO-CONFIG::build] Selecting acceptance filters of node {}",
+                node_data.name
+            );
+            let filter_bank = filter_banks
+                .iter()
+                .find(|bank| bank.node().0.borrow().name == node_data.name);
+            let node_filters = node_buses
+                .iter()
+                .map(|bus| {
+                    // only keep the filters that accept at least one received message
+                    // on this bus, messages that are not covered by any filter of the bank
+                    // (e.g. fixed ids) get a single id filter.
+                    let mut bus_filters: Vec<CanFilter> = vec![];
+                    for rx_message in rx_messages.iter().filter(|m| m.bus().id() == bus.id()) {
+                        let id = rx_message.id();
+                        let filter = filter_bank
+                            .and_then(|bank| {
+                                bank.filters().iter().find(|f| {
+                                    f.ide() == id.ide() && id.as_u32() & f.mask() == f.id()
+                                })
+                            })
+                            .map(|f| CanFilter::new(f.mask(), f.id(), f.ide()))
+                            .unwrap_or_else(|| {
+                                let mask = if id.ide() { 0x1FFFFFFF } else { 0x7FF };
+                                CanFilter::new(mask, id.as_u32(), id.ide())
+                            });
+                        if !bus_filters.contains(&filter) {
+                            bus_filters.push(filter);
+                        }
+                    }
+                    (bus.clone(), bus_filters)
+                })
+                .collect();
+
             #[cfg(feature = "logging_info")]
             println!(
                 "[CANZERO-CONFIG::build] Successfully build transmitting part of node {}",
@@ -1059,6 +1130,7 @@ impl NetworkBuilder {
                 tx_messages,
                 object_entries,
                 node_buses,
+                node_filters,
                 node_data.heartbeat_timeout,
             )));
         }
@@ -1274,9 +1346,16 @@ impl NetworkBuilder {
             if !valid_c_var.is_match(msg_name) || is_c_keyword.is_match(msg_name) {
                 panic!("{msg_name} is not a valid name for a message.");
             }
-            if dlc > 8 {
+            if message.bus().fd() {
+                if dlc > 64 {
+                    panic!(
+                        "Messages on CAN FD buses have to have a dlc less than 64. \n{msg_name} has dlc = {dlc}."
+                    );
+                }
+            } else if dlc > 8 {
                 panic!(
-                    "All messages have to have a dlc less than 8. \n{msg_name} has dlc = {dlc}."
+                    "All messages on classical CAN buses have to have a dlc less than 8. \n{msg_name} has dlc = {dlc} on bus {}, which could be declared as CAN FD bus.",
+                    message.bus().name()
                 );
             }
 
@@ -1285,9 +1364,9 @@ impl NetworkBuilder {
             }
         }
 
-        fn check_ty(ty: &Type, valid_c_var: &Regex, c_keyword: &Regex) {
+        fn check_ty(ty: &Type, valid_c_var: &Regex, c_keyword: &Regex) -> errors::Result<()> {
             match &ty as &Type {
-                Type::Primitive(_) => (),
+                Type::Primitive(_) => Ok(()),
                 Type::Struct {
                     name,
                     description : _,
@@ -1301,8 +1380,9 @@ impl NetworkBuilder {
                         if !valid_c_var.is_match(attrib_name) || c_keyword.is_match(attrib_name) {
                             panic!("{attrib_name} is not a valid attribute for a struct (in struct {name})");
                         }
-                        check_ty(attrib_ty, valid_c_var, c_keyword)
+                        check_ty(attrib_ty, valid_c_var, c_keyword)?;
                     }
+                    Ok(())
                 }
                 Type::Enum {
                     name,
@@ -1314,13 +1394,24 @@ impl NetworkBuilder {
                     if !valid_c_var.is_match(name) || c_keyword.is_match(name) {
                         panic!("{name} is not a valid name for a struct");
                     }
+                    Ok(())
+                }
+                Type::Array { len, ty: inner_ty } => {
+                    // arrays have to contain at least one element.
+                    if *len == 0 {
+                        return Err(errors::ConfigError::InvalidArrayLength(ty.name()));
+                    }
+                    // nested arrays are not supported, use an array of structs instead.
+                    if let Type::Array { .. } = inner_ty as &Type {
+                        return Err(errors::ConfigError::NestedArray(ty.name()));
+                    }
+                    check_ty(inner_ty, valid_c_var, c_keyword)
                 }
-                Type::Array { len : _, ty } => check_ty(ty, valid_c_var, c_keyword),
             }
         }
 
         for ty in network_ref.types() {
-            check_ty(ty, &valid_c_var, &is_c_keyword);
+            check_ty(ty, &valid_c_var, &is_c_keyword)?;
         }
 
         Ok(network_ref)
@@ -12,14 +12,18 @@ pub struct Bus {
     id : u32,
     baudrate : u32,
     name : String,
+    fd : bool,
+    data_baudrate : Option<u32>,
 }
 
 impl Bus {
-    pub fn new(name : &str, id : u32, baudrate : u32) -> Self{
+    pub fn new(name : &str, id : u32, baudrate : u32, fd : bool, data_baudrate : Option<u32>) -> Self{
         Self {
             id,
             baudrate,
             name : name.to_owned(),
+            fd,
+            data_baudrate,
         }
     }
     pub fn id(&self) -> u32 {
@@ -31,6 +35,15 @@ impl Bus {
     pub fn name(&self) -> &str {
         &self.name
     }
+    /// CAN FD bus, which carries frames with up to 64 bytes.
+    pub fn fd(&self) -> bool {
+        self.fd
+    }
+    /// baudrate of the data phase (bit rate switching),
+    /// None if the data phase uses the nominal baudrate.
+    pub fn data_baudrate(&self) -> Option<u32> {
+        self.data_baudrate
+    }
 }
 
 
@@ -41,5 +54,10 @@ impl Hash for Bus {
         for b in self.name.bytes() {
             state.write_u8(b);
         }
+        // classical buses keep their previous hash.
+        if self.fd {
+            state.write_u8(1);
+            state.write_u32(self.data_baudrate.unwrap_or(0));
+        }
     }
 }
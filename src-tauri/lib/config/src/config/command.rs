@@ -1,6 +1,6 @@
 use std::{hash::Hash, time::Duration};
 
-use super::{ConfigRef, MessageRef, Visibility, Message};
+use super::{ConfigRef, MessageRef, Visibility};
 
 
 pub type CommandRef = ConfigRef<Command>;
@@ -57,10 +57,10 @@ impl Command {
             None => None,
         }
     }
-    pub fn tx_message(&self) -> &Message {
+    pub fn tx_message(&self) -> &MessageRef {
         &self.tx_message
     }
-    pub fn rx_message(&self) -> &Message {
+    pub fn rx_message(&self) -> &MessageRef {
         &self.rx_message
     }
 }
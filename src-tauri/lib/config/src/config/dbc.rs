@@ -0,0 +1,230 @@
+use std::{fmt::Write, path::Path};
+
+use super::{
+    bus::Bus, MessageRef, Network, SignalRef, SignalSign, SignalType, Type,
+};
+
+/// DBC identifiers are C identifiers.
+fn dbc_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.chars().next().map_or(true, |c| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+fn dbc_string(text: &str) -> String {
+    text.replace('"', "'")
+}
+
+fn dbc_message_id(message: &MessageRef) -> u32 {
+    if message.id().ide() {
+        message.id().as_u32() | 0x80000000
+    } else {
+        message.id().as_u32()
+    }
+}
+
+fn dbc_range(ty: &SignalType) -> (f64, f64) {
+    let size = ty.size() as i32;
+    let (raw_min, raw_max) = match ty.sign() {
+        SignalSign::Unsigned => (0.0, 2.0f64.powi(size) - 1.0),
+        SignalSign::Signed => (-(2.0f64.powi(size - 1)), 2.0f64.powi(size - 1) - 1.0),
+    };
+    (
+        raw_min * ty.scale() + ty.offset(),
+        raw_max * ty.scale() + ty.offset(),
+    )
+}
+
+struct DbcSignal<'a> {
+    name: String,
+    signal: &'a SignalRef,
+    value_table: Option<&'a Vec<(String, u64)>>,
+}
+
+/// Signal names of type encoded messages are derived from the attribute paths,
+/// because the generated signal names are not unique for repeated types.
+fn dbc_signals(message: &MessageRef) -> Vec<DbcSignal> {
+    match message.encoding() {
+        Some(encoding) => encoding
+            .signal_paths()
+            .into_iter()
+            .map(|(path, primitive)| DbcSignal {
+                // "cells[2].voltage" becomes "cells_2_voltage".
+                name: dbc_identifier(&path.replace('[', ".").replace(']', "")),
+                signal: primitive.signal(),
+                value_table: match primitive.ty() as &Type {
+                    Type::Enum { entries, .. } => Some(entries),
+                    _ => None,
+                },
+            })
+            .collect(),
+        None => message
+            .signals()
+            .iter()
+            .map(|signal| DbcSignal {
+                name: dbc_identifier(signal.name()),
+                signal,
+                value_table: None,
+            })
+            .collect(),
+    }
+}
+
+impl Network {
+    /// Generates a DBC file that describes all messages on the given bus.
+    /// Enum types are exported as value tables (VAL_),
+    /// transmitting and receiving nodes as BU_.
+    pub fn export_dbc(&self, bus: &Bus) -> String {
+        let messages: Vec<&MessageRef> = self
+            .messages()
+            .iter()
+            .filter(|message| message.bus().id() == bus.id())
+            .collect();
+        let transmitters = |message: &MessageRef| -> Vec<String> {
+            self.nodes()
+                .iter()
+                .filter(|node| {
+                    node.tx_messages()
+                        .iter()
+                        .any(|tx| std::ptr::eq(tx.as_ref(), message.as_ref()))
+                })
+                .map(|node| dbc_identifier(node.name()))
+                .collect()
+        };
+        let receivers = |message: &MessageRef| -> Vec<String> {
+            self.nodes()
+                .iter()
+                .filter(|node| {
+                    node.rx_messages()
+                        .iter()
+                        .any(|rx| std::ptr::eq(rx.as_ref(), message.as_ref()))
+                })
+                .map(|node| dbc_identifier(node.name()))
+                .collect()
+        };
+
+        // writing to a String never fails.
+        let mut dbc = String::new();
+        writeln!(dbc, "VERSION \"\"").unwrap();
+        writeln!(dbc).unwrap();
+        writeln!(dbc, "NS_ :").unwrap();
+        for ns in ["CM_", "BA_DEF_", "BA_", "VAL_", "BO_TX_BU_"] {
+            writeln!(dbc, "\t{ns}").unwrap();
+        }
+        writeln!(dbc).unwrap();
+        writeln!(dbc, "BS_:").unwrap();
+        writeln!(dbc).unwrap();
+        let nodes: Vec<String> = self
+            .nodes()
+            .iter()
+            .filter(|node| node.buses().iter().any(|b| b.id() == bus.id()))
+            .map(|node| dbc_identifier(node.name()))
+            .collect();
+        writeln!(dbc, "BU_: {}", nodes.join(" ")).unwrap();
+        writeln!(dbc).unwrap();
+
+        for message in &messages {
+            let transmitters = transmitters(message);
+            let receivers = receivers(message);
+            let receivers = if receivers.is_empty() {
+                "Vector__XXX".to_owned()
+            } else {
+                receivers.join(",")
+            };
+            writeln!(
+                dbc,
+                "BO_ {} {}: {} {}",
+                dbc_message_id(message),
+                dbc_identifier(message.name()),
+                message.dlc(),
+                transmitters.first().map_or("Vector__XXX", |tx| tx.as_str())
+            )
+            .unwrap();
+            for signal in dbc_signals(message) {
+                let ty = signal.signal.ty();
+                let (min, max) = dbc_range(ty);
+                let sign = match ty.sign() {
+                    SignalSign::Signed => '-',
+                    SignalSign::Unsigned => '+',
+                };
+                writeln!(
+                    dbc,
+                    " SG_ {} : {}|{}@1{sign} ({},{}) [{min}|{max}] \"\" {receivers}",
+                    signal.name,
+                    signal.signal.byte_offset(),
+                    ty.size(),
+                    ty.scale(),
+                    ty.offset(),
+                )
+                .unwrap();
+            }
+            writeln!(dbc).unwrap();
+        }
+
+        for message in &messages {
+            let transmitters = transmitters(message);
+            if transmitters.len() > 1 {
+                writeln!(
+                    dbc,
+                    "BO_TX_BU_ {} : {};",
+                    dbc_message_id(message),
+                    transmitters.join(",")
+                )
+                .unwrap();
+            }
+        }
+
+        for message in &messages {
+            if let Some(description) = message.description() {
+                writeln!(
+                    dbc,
+                    "CM_ BO_ {} \"{}\";",
+                    dbc_message_id(message),
+                    dbc_string(description)
+                )
+                .unwrap();
+            }
+            for signal in dbc_signals(message) {
+                if let Some(description) = signal.signal.description() {
+                    writeln!(
+                        dbc,
+                        "CM_ SG_ {} {} \"{}\";",
+                        dbc_message_id(message),
+                        signal.name,
+                        dbc_string(description)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        for message in &messages {
+            for signal in dbc_signals(message) {
+                let Some(entries) = signal.value_table else {
+                    continue;
+                };
+                write!(dbc, "VAL_ {} {}", dbc_message_id(message), signal.name).unwrap();
+                for (name, value) in entries {
+                    write!(dbc, " {value} \"{}\"", dbc_string(name)).unwrap();
+                }
+                writeln!(dbc, " ;").unwrap();
+            }
+        }
+        dbc
+    }
+
+    /// Writes one DBC file per bus ({bus_name}.dbc) into dir.
+    pub fn export_dbc_files(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for bus in self.buses() {
+            let path = dir.join(format!("{}.dbc", bus.name()));
+            std::fs::write(path, self.export_dbc(bus))?;
+        }
+        Ok(())
+    }
+}
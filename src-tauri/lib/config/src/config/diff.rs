@@ -0,0 +1,311 @@
+use std::fmt::Display;
+
+use super::{
+    bus::Bus, stream::Stream, MessageRef, Network, Node, ObjectEntry, SignalRef, SignalSign,
+    Type, TypeRef,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    /// human readable descriptions of what changed (e.g. "id : 3 -> 4").
+    Changed(Vec<String>),
+}
+
+/// A single difference between two networks.
+/// The path identifies the config object (e.g. "node secu / object entry position").
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub path: String,
+    pub kind: DiffKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NetworkDiff {
+    entries: Vec<DiffEntry>,
+}
+
+impl NetworkDiff {
+    pub fn entries(&self) -> &Vec<DiffEntry> {
+        &self.entries
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Display for NetworkDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            match &entry.kind {
+                DiffKind::Added => writeln!(f, "+ {}", entry.path)?,
+                DiffKind::Removed => writeln!(f, "- {}", entry.path)?,
+                DiffKind::Changed(changes) => {
+                    writeln!(f, "~ {}", entry.path)?;
+                    for change in changes {
+                        writeln!(f, "    {change}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects the changes of a single config object.
+struct Changes(Vec<String>);
+
+impl Changes {
+    fn cmp<T: PartialEq + std::fmt::Debug>(&mut self, what: &str, old: T, new: T) {
+        if old != new {
+            self.0.push(format!("{what} : {old:?} -> {new:?}"));
+        }
+    }
+}
+
+/// Compares two lists of config objects by name.
+fn diff_named<'a, T>(
+    diff: &mut NetworkDiff,
+    prefix: &str,
+    old: impl IntoIterator<Item = &'a T>,
+    new: impl IntoIterator<Item = &'a T>,
+    name: impl Fn(&T) -> &str,
+    mut changes: impl FnMut(&mut NetworkDiff, &str, &T, &T) -> Vec<String>,
+) where
+    T: 'a,
+{
+    let old: Vec<&T> = old.into_iter().collect();
+    let new: Vec<&T> = new.into_iter().collect();
+    for o in &old {
+        let path = format!("{prefix}{}", name(o));
+        match new.iter().find(|n| name(n) == name(o)) {
+            Some(n) => {
+                // changes of nested config objects are listed after their parent.
+                let mut nested = NetworkDiff::default();
+                let changes = changes(&mut nested, &path, o, n);
+                if !changes.is_empty() {
+                    diff.entries.push(DiffEntry {
+                        path,
+                        kind: DiffKind::Changed(changes),
+                    });
+                }
+                diff.entries.extend(nested.entries);
+            }
+            None => diff.entries.push(DiffEntry {
+                path,
+                kind: DiffKind::Removed,
+            }),
+        }
+    }
+    for n in &new {
+        if !old.iter().any(|o| name(o) == name(n)) {
+            diff.entries.push(DiffEntry {
+                path: format!("{prefix}{}", name(n)),
+                kind: DiffKind::Added,
+            });
+        }
+    }
+}
+
+/// (name, bit offset, size, sign, scale, offset) of all signals of a message.
+/// Type encoded messages use the attribute path as name, because the generated signal
+/// names are not stable.
+fn signal_layout(message: &MessageRef) -> Vec<(String, usize, u8, bool, f64, f64)> {
+    fn layout(name: String, signal: &SignalRef) -> (String, usize, u8, bool, f64, f64) {
+        (
+            name,
+            signal.byte_offset(),
+            signal.size(),
+            matches!(signal.sign(), SignalSign::Signed),
+            signal.scale(),
+            signal.offset(),
+        )
+    }
+    match message.encoding() {
+        Some(encoding) => encoding
+            .signal_paths()
+            .into_iter()
+            .map(|(path, primitive)| layout(path, primitive.signal()))
+            .collect(),
+        None => message
+            .signals()
+            .iter()
+            .map(|signal| layout(signal.name().to_owned(), signal))
+            .collect(),
+    }
+}
+
+fn message_changes(old: &MessageRef, new: &MessageRef) -> Vec<String> {
+    let mut changes = Changes(vec![]);
+    changes.cmp("id", format!("{}", old.id()), format!("{}", new.id()));
+    changes.cmp("bus", old.bus().name(), new.bus().name());
+    changes.cmp("dlc", old.dlc(), new.dlc());
+    let old_layout = signal_layout(old);
+    let new_layout = signal_layout(new);
+    for o in &old_layout {
+        match new_layout.iter().find(|n| n.0 == o.0) {
+            Some(n) => changes.cmp(
+                &format!("signal {} (offset, size, signed, scale, offset)", o.0),
+                (o.1, o.2, o.3, o.4, o.5),
+                (n.1, n.2, n.3, n.4, n.5),
+            ),
+            None => changes.0.push(format!("signal {} removed", o.0)),
+        }
+    }
+    for n in &new_layout {
+        if !old_layout.iter().any(|o| o.0 == n.0) {
+            changes.0.push(format!("signal {} added", n.0));
+        }
+    }
+    changes.0
+}
+
+fn object_entry_changes(old: &ObjectEntry, new: &ObjectEntry) -> Vec<String> {
+    let mut changes = Changes(vec![]);
+    changes.cmp("id", old.id(), new.id());
+    if old.ty() != new.ty() {
+        changes
+            .0
+            .push(format!("type : {} -> {}", old.ty().name(), new.ty().name()));
+    }
+    changes.cmp("access", old.access(), new.access());
+    changes.cmp("unit", old.unit(), new.unit());
+    changes.0
+}
+
+fn stream_changes(old: &Stream, new: &Stream) -> Vec<String> {
+    let mut changes = Changes(vec![]);
+    changes.cmp("message", old.message().name(), new.message().name());
+    changes.cmp("interval", old.interval(), new.interval());
+    let mapping = |stream: &Stream| -> Vec<Option<String>> {
+        stream
+            .mapping()
+            .iter()
+            .map(|oe| oe.as_ref().map(|oe| oe.name().to_owned()))
+            .collect()
+    };
+    changes.cmp("mapping", mapping(old), mapping(new));
+    changes.0
+}
+
+/// Name of user defined (struct and enum) types.
+fn type_name(ty: &Type) -> Option<&str> {
+    match ty {
+        Type::Struct { name, .. } | Type::Enum { name, .. } => Some(name),
+        Type::Primitive(_) | Type::Array { .. } => None,
+    }
+}
+
+fn type_changes(old: &Type, new: &Type) -> Vec<String> {
+    let mut changes = Changes(vec![]);
+    match (old, new) {
+        (Type::Struct { attribs: old, .. }, Type::Struct { attribs: new, .. }) => {
+            let attribs = |attribs: &Vec<(String, TypeRef)>| -> Vec<(String, String)> {
+                attribs
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), ty.name()))
+                    .collect()
+            };
+            changes.cmp("attributes", attribs(old), attribs(new));
+        }
+        (
+            Type::Enum {
+                size: old_size,
+                entries: old_entries,
+                ..
+            },
+            Type::Enum {
+                size: new_size,
+                entries: new_entries,
+                ..
+            },
+        ) => {
+            changes.cmp("size", old_size, new_size);
+            changes.cmp("entries", old_entries, new_entries);
+        }
+        (Type::Struct { .. }, _) => changes.0.push("kind : struct -> enum".to_owned()),
+        _ => changes.0.push("kind : enum -> struct".to_owned()),
+    }
+    changes.0
+}
+
+fn node_changes(diff: &mut NetworkDiff, path: &str, old: &Node, new: &Node) -> Vec<String> {
+    let mut changes = Changes(vec![]);
+    changes.cmp("id", old.id(), new.id());
+    let buses = |node: &Node| -> Vec<String> {
+        node.buses().iter().map(|b| b.name().to_owned()).collect()
+    };
+    changes.cmp("buses", buses(old), buses(new));
+
+    diff_named(
+        diff,
+        &format!("{path} / object entry "),
+        old.object_entries().iter().map(|oe| oe as &ObjectEntry),
+        new.object_entries().iter().map(|oe| oe as &ObjectEntry),
+        |oe| oe.name(),
+        |_, _, o, n| object_entry_changes(o, n),
+    );
+    diff_named(
+        diff,
+        &format!("{path} / stream "),
+        old.tx_streams().iter().map(|s| s as &Stream),
+        new.tx_streams().iter().map(|s| s as &Stream),
+        |s| s.name(),
+        |_, _, o, n| stream_changes(o, n),
+    );
+    let command_names = |node: &Node| -> Vec<String> {
+        node.commands().iter().map(|c| c.name().to_owned()).collect()
+    };
+    changes.cmp("commands", command_names(old), command_names(new));
+    changes.0
+}
+
+impl Network {
+    /// Compares this (old) network with a new network and reports added, removed
+    /// and changed buses, types, nodes, object entries, streams and messages.
+    /// Config objects are matched by name.
+    pub fn diff(&self, new: &Network) -> NetworkDiff {
+        let mut diff = NetworkDiff::default();
+        diff_named(
+            &mut diff,
+            "bus ",
+            self.buses().iter().map(|b| b as &Bus),
+            new.buses().iter().map(|b| b as &Bus),
+            |b| b.name(),
+            |_, _, o, n| {
+                let mut changes = Changes(vec![]);
+                changes.cmp("id", o.id(), n.id());
+                changes.cmp("baudrate", o.baudrate(), n.baudrate());
+                changes.cmp("fd", o.fd(), n.fd());
+                changes.cmp("data_baudrate", o.data_baudrate(), n.data_baudrate());
+                changes.0
+            },
+        );
+        diff_named(
+            &mut diff,
+            "type ",
+            self.types().iter().map(|t| t as &Type).filter(|t| type_name(t).is_some()),
+            new.types().iter().map(|t| t as &Type).filter(|t| type_name(t).is_some()),
+            |t| type_name(t).unwrap_or_default(),
+            |_, _, o, n| type_changes(o, n),
+        );
+        diff_named(
+            &mut diff,
+            "node ",
+            self.nodes().iter().map(|n| n as &Node),
+            new.nodes().iter().map(|n| n as &Node),
+            |n| n.name(),
+            node_changes,
+        );
+        diff_named(
+            &mut diff,
+            "message ",
+            self.messages().iter(),
+            new.messages().iter(),
+            |m| m.name(),
+            |_, _, o, n| message_changes(o, n),
+        );
+        diff
+    }
+}
@@ -29,6 +29,34 @@ impl MessageEncoding {
     pub fn attributes(&self) -> &Vec<TypeSignalEncoding> {
         &self.attributes
     }
+    /// All primitive attributes with their attribute path. Attributes are joined
+    /// by "." and array elements are appended as "[i]" (e.g. "cells[2].voltage").
+    pub fn signal_paths(&self) -> Vec<(String, &PrimitiveSignalEncoding)> {
+        fn walk<'a>(encoding : &'a TypeSignalEncoding, prefix : &str,
+                    paths : &mut Vec<(String, &'a PrimitiveSignalEncoding)>) {
+            let path = if prefix.is_empty() {
+                encoding.name().to_owned()
+            } else if encoding.name().starts_with('[') {
+                // array elements are named "[i]".
+                format!("{prefix}{}", encoding.name())
+            } else {
+                format!("{prefix}.{}", encoding.name())
+            };
+            match encoding {
+                TypeSignalEncoding::Composite(composite) => {
+                    for attrib in composite.attributes() {
+                        walk(attrib, &path, paths);
+                    }
+                }
+                TypeSignalEncoding::Primitive(primitive) => paths.push((path, primitive)),
+            }
+        }
+        let mut paths = vec![];
+        for attrib in &self.attributes {
+            walk(attrib, "", &mut paths);
+        }
+        paths
+    }
 }
 
 #[derive(Debug)]
@@ -141,6 +141,8 @@ impl Message {
     pub fn signals(&self) -> &Vec<SignalRef> {
         &self.signals
     }
+    /// length of the payload in bytes, messages on CAN FD buses
+    /// may exceed 8 bytes (rounded up to a valid CAN FD length).
     pub fn dlc(&self) -> u8 { 
         self.dlc
     }
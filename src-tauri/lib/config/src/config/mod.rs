@@ -34,6 +34,9 @@ pub mod stream;
 pub mod types;
 pub mod visibility;
 pub mod bus;
+pub mod dbc;
+pub mod diff;
+pub mod serialize;
 
 pub type ConfigRef<T> = Arc<T>;
 
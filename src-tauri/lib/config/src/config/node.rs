@@ -1,11 +1,40 @@
 use std::{hash::Hash, time::Duration};
 
 use super::{
-    bus::BusRef, stream::StreamRef, CommandRef, ConfigRef, MessageRef, ObjectEntryRef, TypeRef,
+    bus::BusRef, stream::StreamRef, CommandRef, ConfigRef, MessageId, MessageRef, ObjectEntryRef,
+    TypeRef,
 };
 
 pub type NodeRef = ConfigRef<Node>;
 
+/// Hardware acceptance filter of a node on one bus.
+/// A frame is accepted if its id is of the same kind (ide)
+/// and `frame_id & mask == id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CanFilter {
+    mask: u32,
+    id: u32,
+    ide: bool,
+}
+
+impl CanFilter {
+    pub fn new(mask: u32, id: u32, ide: bool) -> Self {
+        Self { mask, id, ide }
+    }
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn ide(&self) -> bool {
+        self.ide
+    }
+    pub fn accepts(&self, id: &MessageId) -> bool {
+        self.ide == id.ide() && id.as_u32() & self.mask == self.id
+    }
+}
+
 #[derive(Debug)]
 pub struct Node {
     name: String,
@@ -25,6 +54,7 @@ pub struct Node {
 
     object_entries: Vec<ObjectEntryRef>,
     buses: Vec<BusRef>,
+    filters: Vec<(BusRef, Vec<CanFilter>)>,
 
     heartbeat_timeout : Duration,
 }
@@ -70,6 +100,7 @@ impl Node {
         tx_messages: Vec<MessageRef>,
         object_entries: Vec<ObjectEntryRef>,
         buses: Vec<BusRef>,
+        filters: Vec<(BusRef, Vec<CanFilter>)>,
         heartbeat_timeout : Duration,
     ) -> Self {
         Self {
@@ -85,6 +116,7 @@ impl Node {
             tx_messages,
             object_entries,
             buses,
+            filters,
             heartbeat_timeout,
         }
     }
@@ -137,4 +169,16 @@ impl Node {
     pub fn buses(&self) -> &Vec<BusRef> {
         &self.buses
     }
+    pub fn filters(&self) -> &Vec<(BusRef, Vec<CanFilter>)> {
+        &self.filters
+    }
+    /// Acceptance filters of the node on the bus with the given id.
+    /// Empty if the node is not connected to the bus.
+    pub fn bus_filters(&self, bus_id: u32) -> &[CanFilter] {
+        self.filters
+            .iter()
+            .find(|(bus, _)| bus.id() == bus_id)
+            .map(|(_, filters)| filters.as_slice())
+            .unwrap_or(&[])
+    }
 }
@@ -4,7 +4,7 @@ use super::{ConfigRef, NodeRef, TypeRef, Visibility};
 
 pub type ObjectEntryRef = ConfigRef<ObjectEntry>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ObjectEntryAccess {
     Const,  // no write
     Local,  // local write public read
@@ -0,0 +1,396 @@
+use serde::{Serialize, Serializer};
+
+use super::{
+    bus::Bus, message::MessageUsage, stream::Stream, Command, Message, Network, Node,
+    ObjectEntry, ObjectEntryAccess, SignalRef, SignalSign, Type, Visibility,
+};
+
+// The resolved network is a graph (e.g. object entries reference their node),
+// therefor it is serialized as a tree in which references to other
+// config objects are replaced by their names.
+
+#[derive(Serialize)]
+struct NetworkView<'a> {
+    build_time: String,
+    hash: u64,
+    buses: Vec<BusView<'a>>,
+    nodes: Vec<NodeView<'a>>,
+    messages: Vec<MessageView<'a>>,
+    types: Vec<TypeView<'a>>,
+}
+
+#[derive(Serialize)]
+struct BusView<'a> {
+    name: &'a str,
+    id: u32,
+    baudrate: u32,
+    fd: bool,
+    data_baudrate: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct NodeView<'a> {
+    name: &'a str,
+    id: u8,
+    description: Option<&'a str>,
+    buses: Vec<&'a str>,
+    object_entries: Vec<ObjectEntryView<'a>>,
+    tx_streams: Vec<StreamView<'a>>,
+    rx_streams: Vec<&'a str>,
+    commands: Vec<CommandView<'a>>,
+    extern_commands: Vec<ExternCommandView<'a>>,
+    tx_messages: Vec<&'a str>,
+    rx_messages: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ObjectEntryView<'a> {
+    name: &'a str,
+    id: u32,
+    description: Option<&'a str>,
+    unit: Option<&'a str>,
+    friend: Option<&'a str>,
+    access: &'static str,
+    ty: TypeView<'a>,
+}
+
+#[derive(Serialize)]
+struct StreamView<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    message: &'a str,
+    min_interval_us: u128,
+    max_interval_us: u128,
+    visibility: &'static str,
+    mapping: Vec<Option<&'a str>>,
+}
+
+#[derive(Serialize)]
+struct CommandView<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    tx_message: &'a str,
+    rx_message: &'a str,
+    expected_interval_us: u128,
+    visibility: &'static str,
+}
+
+#[derive(Serialize)]
+struct ExternCommandView<'a> {
+    node: &'a str,
+    command: &'a str,
+}
+
+#[derive(Serialize)]
+struct MessageView<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    id: u32,
+    ide: bool,
+    bus: &'a str,
+    dlc: u8,
+    usage: String,
+    visibility: &'static str,
+    signals: Vec<SignalView<'a>>,
+}
+
+#[derive(Serialize)]
+struct SignalView<'a> {
+    name: String,
+    description: Option<&'a str>,
+    bit_offset: usize,
+    size: u8,
+    sign: &'static str,
+    scale: f64,
+    offset: f64,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum TypeView<'a> {
+    Primitive {
+        name: String,
+        size: u8,
+        sign: &'static str,
+        scale: f64,
+        offset: f64,
+    },
+    Struct {
+        name: &'a str,
+        description: Option<&'a str>,
+        attributes: Vec<AttributeView<'a>>,
+    },
+    Enum {
+        name: &'a str,
+        description: Option<&'a str>,
+        size: u8,
+        entries: Vec<EnumEntryView<'a>>,
+    },
+    Array {
+        len: usize,
+        ty: Box<TypeView<'a>>,
+    },
+}
+
+#[derive(Serialize)]
+struct AttributeView<'a> {
+    name: &'a str,
+    ty: TypeView<'a>,
+}
+
+#[derive(Serialize)]
+struct EnumEntryView<'a> {
+    name: &'a str,
+    value: u64,
+}
+
+fn visibility_name(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Global => "global",
+        Visibility::Static => "static",
+    }
+}
+
+fn sign_name(sign: SignalSign) -> &'static str {
+    match sign {
+        SignalSign::Signed => "signed",
+        SignalSign::Unsigned => "unsigned",
+    }
+}
+
+impl<'a> From<&'a Bus> for BusView<'a> {
+    fn from(bus: &'a Bus) -> Self {
+        Self {
+            name: bus.name(),
+            id: bus.id(),
+            baudrate: bus.baudrate(),
+            fd: bus.fd(),
+            data_baudrate: bus.data_baudrate(),
+        }
+    }
+}
+
+impl<'a> From<&'a Type> for TypeView<'a> {
+    fn from(ty: &'a Type) -> Self {
+        match ty {
+            Type::Primitive(signal_type) => TypeView::Primitive {
+                name: ty.name(),
+                size: signal_type.size(),
+                sign: sign_name(signal_type.sign()),
+                scale: signal_type.scale(),
+                offset: signal_type.offset(),
+            },
+            Type::Struct {
+                name,
+                description,
+                attribs,
+                ..
+            } => TypeView::Struct {
+                name,
+                description: description.as_deref(),
+                attributes: attribs
+                    .iter()
+                    .map(|(name, ty)| AttributeView {
+                        name,
+                        ty: TypeView::from(ty as &Type),
+                    })
+                    .collect(),
+            },
+            Type::Enum {
+                name,
+                description,
+                size,
+                entries,
+                ..
+            } => TypeView::Enum {
+                name,
+                description: description.as_deref(),
+                size: *size,
+                entries: entries
+                    .iter()
+                    .map(|(name, value)| EnumEntryView {
+                        name,
+                        value: *value,
+                    })
+                    .collect(),
+            },
+            Type::Array { len, ty } => TypeView::Array {
+                len: *len,
+                ty: Box::new(TypeView::from(ty as &Type)),
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a ObjectEntry> for ObjectEntryView<'a> {
+    fn from(oe: &'a ObjectEntry) -> Self {
+        Self {
+            name: oe.name(),
+            id: oe.id(),
+            description: oe.description(),
+            unit: oe.unit(),
+            friend: oe.friend(),
+            access: match oe.access() {
+                ObjectEntryAccess::Const => "const",
+                ObjectEntryAccess::Local => "local",
+                ObjectEntryAccess::Global => "global",
+            },
+            ty: TypeView::from(oe.ty() as &Type),
+        }
+    }
+}
+
+impl<'a> From<&'a Stream> for StreamView<'a> {
+    fn from(stream: &'a Stream) -> Self {
+        Self {
+            name: stream.name(),
+            description: stream.description(),
+            message: stream.message().name(),
+            min_interval_us: stream.min_interval().as_micros(),
+            max_interval_us: stream.max_interval().as_micros(),
+            visibility: visibility_name(stream.visibility()),
+            mapping: stream
+                .mapping()
+                .iter()
+                .map(|oe| oe.as_ref().map(|oe| oe.name()))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a Command> for CommandView<'a> {
+    fn from(command: &'a Command) -> Self {
+        Self {
+            name: command.name(),
+            description: command.description().map(|d| d.as_str()),
+            tx_message: command.tx_message().name(),
+            rx_message: command.rx_message().name(),
+            expected_interval_us: command.expected_interval().as_micros(),
+            visibility: visibility_name(command.visibility()),
+        }
+    }
+}
+
+impl<'a> From<&'a Node> for NodeView<'a> {
+    fn from(node: &'a Node) -> Self {
+        Self {
+            name: node.name(),
+            id: node.id(),
+            description: node.description().map(|d| d.as_str()),
+            buses: node.buses().iter().map(|bus| bus.name()).collect(),
+            object_entries: node
+                .object_entries()
+                .iter()
+                .map(|oe| ObjectEntryView::from(oe as &ObjectEntry))
+                .collect(),
+            tx_streams: node
+                .tx_streams()
+                .iter()
+                .map(|stream| StreamView::from(stream as &Stream))
+                .collect(),
+            rx_streams: node.rx_streams().iter().map(|s| s.name()).collect(),
+            commands: node
+                .commands()
+                .iter()
+                .map(|command| CommandView::from(command as &Command))
+                .collect(),
+            extern_commands: node
+                .extern_commands()
+                .iter()
+                .map(|(node, command)| ExternCommandView {
+                    node,
+                    command: command.name(),
+                })
+                .collect(),
+            tx_messages: node.tx_messages().iter().map(|m| m.name()).collect(),
+            rx_messages: node.rx_messages().iter().map(|m| m.name()).collect(),
+        }
+    }
+}
+
+/// Signal names of type encoded messages are their attribute path.
+fn signal_views(message: &Message) -> Vec<SignalView> {
+    fn view<'a>(name: String, signal: &'a SignalRef) -> SignalView<'a> {
+        SignalView {
+            name,
+            description: signal.description(),
+            bit_offset: signal.byte_offset(),
+            size: signal.size(),
+            sign: sign_name(signal.sign()),
+            scale: signal.scale(),
+            offset: signal.offset(),
+        }
+    }
+    match message.encoding() {
+        Some(encoding) => encoding
+            .signal_paths()
+            .into_iter()
+            .map(|(path, primitive)| view(path, primitive.signal()))
+            .collect(),
+        None => message
+            .signals()
+            .iter()
+            .map(|signal| view(signal.name().to_owned(), signal))
+            .collect(),
+    }
+}
+
+/// Short description of what a message is used for (e.g. "stream:node.stream").
+pub fn message_usage_name(usage: &MessageUsage) -> String {
+    match usage {
+        MessageUsage::Stream(stream) => format!("stream:{}", stream.name()),
+        MessageUsage::CommandReq(command) => format!("command_req:{}", command.name()),
+        MessageUsage::CommandResp(command) => format!("command_resp:{}", command.name()),
+        MessageUsage::GetResp => "get_resp".to_owned(),
+        MessageUsage::GetReq => "get_req".to_owned(),
+        MessageUsage::SetResp => "set_resp".to_owned(),
+        MessageUsage::SetReq => "set_req".to_owned(),
+        MessageUsage::Heartbeat => "heartbeat".to_owned(),
+        MessageUsage::External { .. } => "external".to_owned(),
+    }
+}
+
+impl<'a> From<&'a Message> for MessageView<'a> {
+    fn from(message: &'a Message) -> Self {
+        Self {
+            name: message.name(),
+            description: message.description(),
+            id: message.id().as_u32(),
+            ide: message.id().ide(),
+            bus: message.bus().name(),
+            dlc: message.dlc(),
+            usage: message
+                .__get_usage()
+                .get()
+                .map_or("unused".to_owned(), message_usage_name),
+            visibility: visibility_name(message.visibility()),
+            signals: signal_views(message),
+        }
+    }
+}
+
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        NetworkView {
+            build_time: self.build_time().to_rfc3339(),
+            hash: self.portable_hash(),
+            buses: self.buses().iter().map(|bus| BusView::from(bus as &Bus)).collect(),
+            nodes: self
+                .nodes()
+                .iter()
+                .map(|node| NodeView::from(node as &Node))
+                .collect(),
+            messages: self
+                .messages()
+                .iter()
+                .map(|message| MessageView::from(message as &Message))
+                .collect(),
+            types: self
+                .types()
+                .iter()
+                .map(|ty| TypeView::from(ty as &Type))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
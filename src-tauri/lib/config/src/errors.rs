@@ -5,11 +5,16 @@ pub type Result<T> = std::result::Result<T, ConfigError>;
 pub enum ConfigError {
     InvalidRange(String),
     InvalidType(String),
+    /// holds the signal name.
     DuplicatedSignal(String),
     DuplicatedEnumEntry(String),
     DuplicatedStructAttribute(String),
     UndefinedType(String),
     InvalidDecimalDefinition(String),
+    /// zero or too large length, holds the type name.
+    InvalidArrayLength(String),
+    /// array of arrays, holds the type name.
+    NestedArray(String),
     FailedToResolveId,
     NoBusAvaiable,
     Io(std::io::Error),
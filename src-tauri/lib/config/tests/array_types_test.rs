@@ -0,0 +1,51 @@
+use canzero_config::{
+    builder::NetworkBuilder,
+    config::{Type, TypeRef},
+    errors::ConfigError,
+};
+
+fn build_with_object_entry(type_name: &str) -> Result<TypeRef, ConfigError> {
+    let network_builder = NetworkBuilder::new();
+    let node = network_builder.create_node("secu");
+    node.create_object_entry("cell_voltages", type_name);
+    let network = network_builder.build()?;
+    let node = network
+        .nodes()
+        .iter()
+        .find(|n| n.name() == "secu")
+        .expect("node secu is missing");
+    let object_entry = node
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == "cell_voltages")
+        .expect("object entry cell_voltages is missing");
+    Ok(object_entry.ty().clone())
+}
+
+#[test]
+fn arrays_are_resolved() {
+    let ty = build_with_object_entry("u12[5]").expect("failed to build the network");
+    let Type::Array { len, ty: element_ty } = &ty as &Type else {
+        panic!("expected an array type, got {}", ty.name());
+    };
+    assert_eq!(*len, 5);
+    assert_eq!(element_ty.name(), "u12");
+    assert_eq!(ty.size(), 60);
+}
+
+#[test]
+fn empty_arrays_are_config_errors() {
+    match build_with_object_entry("u8[0]") {
+        Err(ConfigError::InvalidArrayLength(type_name)) => assert_eq!(type_name, "u8[0]"),
+        other => panic!("expected an invalid array length, got {other:?}"),
+    }
+}
+
+#[test]
+fn array_length_overflows_are_config_errors() {
+    let type_name = "u8[99999999999999999999999]";
+    match build_with_object_entry(type_name) {
+        Err(ConfigError::InvalidArrayLength(name)) => assert_eq!(name, type_name),
+        other => panic!("expected an invalid array length, got {other:?}"),
+    }
+}
@@ -0,0 +1,125 @@
+use canzero_config::{
+    builder::NetworkBuilder,
+    config::{MessageRef, NetworkRef, SignalRef, SignalSign},
+};
+
+fn network() -> NetworkRef {
+    let network_builder = NetworkBuilder::new();
+    network_builder.create_bus("can0", Some(1000000));
+    let cell = network_builder.define_struct("cell");
+    cell.add_attribute("voltage", "d8<0..5>").unwrap();
+    cell.add_attribute("temperature", "i8").unwrap();
+    let mode = network_builder.define_enum("mode");
+    mode.add_entry("idle", Some(0)).unwrap();
+    mode.add_entry("levitate", Some(1)).unwrap();
+    let secu = network_builder.create_node("secu");
+    network_builder.create_node("master");
+    secu.create_object_entry("position", "u16");
+    secu.create_object_entry("mode", "mode");
+    secu.create_object_entry("cells", "cell[2]");
+    let stream = secu.create_stream("state");
+    stream.add_entry("position");
+    stream.add_entry("mode");
+    stream.add_entry("cells");
+    network_builder.build().expect("failed to build the network")
+}
+
+/// (dbc name, signal) of all signals of a message,
+/// type encoded messages use their attribute path (e.g. "cells_0_voltage").
+fn expected_signals(message: &MessageRef) -> Vec<(String, &SignalRef)> {
+    match message.encoding() {
+        Some(encoding) => encoding
+            .signal_paths()
+            .into_iter()
+            .map(|(path, primitive)| {
+                let name = path.replace('[', "_").replace(']', "").replace('.', "_");
+                (name, primitive.signal())
+            })
+            .collect(),
+        None => message
+            .signals()
+            .iter()
+            .map(|signal| (signal.name().to_owned(), signal))
+            .collect(),
+    }
+}
+
+#[test]
+fn array_signals_are_named_by_their_path() {
+    let network = network();
+    let dbc = network.export_dbc(&network.buses()[0]);
+    for signal in [
+        "position",
+        "cells_0_voltage",
+        "cells_0_temperature",
+        "cells_1_voltage",
+        "cells_1_temperature",
+    ] {
+        assert!(
+            dbc.contains(&format!(" SG_ {signal} : ")),
+            "signal {signal} is missing in\n{dbc}"
+        );
+    }
+}
+
+#[test]
+fn exported_messages_are_imported_with_the_same_layout() {
+    let network = network();
+    let bus = &network.buses()[0];
+    let exported = network.export_dbc(bus);
+    let dbc = can_dbc::DBC::from_slice(exported.as_bytes())
+        .unwrap_or_else(|err| panic!("failed to import the exported dbc {err:?}\n{exported}"));
+
+    let messages: Vec<&MessageRef> = network
+        .messages()
+        .iter()
+        .filter(|m| m.bus().id() == bus.id())
+        .collect();
+    assert_eq!(dbc.messages().len(), messages.len());
+
+    for message in messages {
+        let imported = dbc
+            .messages()
+            .iter()
+            .find(|m| m.message_name() == message.name())
+            .unwrap_or_else(|| panic!("message {} is missing", message.name()));
+        let id = imported.message_id().0;
+        assert_eq!(id & 0x80000000 != 0, message.id().ide());
+        assert_eq!(id & 0x1FFFFFFF, message.id().as_u32());
+        assert_eq!(*imported.message_size(), message.dlc() as u64);
+
+        let signals = expected_signals(message);
+        assert_eq!(imported.signals().len(), signals.len(), "{}", message.name());
+        for (name, signal) in signals {
+            let imported_signal = imported
+                .signals()
+                .iter()
+                .find(|s| s.name() == &name)
+                .unwrap_or_else(|| panic!("signal {name} of {} is missing", message.name()));
+            assert_eq!(imported_signal.start_bit, signal.byte_offset() as u64);
+            assert_eq!(imported_signal.signal_size, signal.size() as u64);
+            assert_eq!(
+                matches!(imported_signal.value_type(), can_dbc::ValueType::Signed),
+                matches!(signal.sign(), SignalSign::Signed),
+            );
+            assert_eq!(imported_signal.factor, signal.scale());
+            assert_eq!(imported_signal.offset, signal.offset());
+        }
+    }
+
+    let state = network
+        .messages()
+        .iter()
+        .find(|m| m.name() == "secu_stream_state")
+        .unwrap();
+    let values: Vec<(f64, String)> = dbc
+        .value_descriptions_for_signal(can_dbc::MessageId(state.id().as_u32()), "mode")
+        .expect("the value table of mode is missing")
+        .iter()
+        .map(|value| (*value.a(), value.b().clone()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![(0.0, "idle".to_owned()), (1.0, "levitate".to_owned())]
+    );
+}
@@ -0,0 +1,208 @@
+use canzero_config::{
+    builder::NetworkBuilder,
+    config::{
+        diff::{DiffEntry, DiffKind, NetworkDiff},
+        NetworkRef,
+    },
+};
+
+/// Revision of a small network, every flag describes one change against the base revision.
+#[derive(Default)]
+struct Revision {
+    add_node: bool,
+    remove_cells: bool,
+    change_position: bool,
+    change_cell: bool,
+    add_enum_entry: bool,
+    grow_array: bool,
+}
+
+fn network(revision: Revision) -> NetworkRef {
+    let network_builder = NetworkBuilder::new();
+    network_builder.create_bus("can0", Some(1000000));
+    let cell = network_builder.define_struct("cell");
+    cell.add_attribute("voltage", "d8<0..5>").unwrap();
+    cell.add_attribute(
+        "temperature",
+        if revision.change_cell { "i16" } else { "i8" },
+    )
+    .unwrap();
+    let mode = network_builder.define_enum("mode");
+    mode.add_entry("idle", Some(0)).unwrap();
+    mode.add_entry("levitate", Some(1)).unwrap();
+    if revision.add_enum_entry {
+        mode.add_entry("brake", Some(2)).unwrap();
+    }
+
+    let secu = network_builder.create_node("secu");
+    network_builder.create_node("master");
+    if revision.add_node {
+        let bms = network_builder.create_node("bms");
+        bms.create_object_entry("voltage", "u8");
+        bms.create_stream("state").add_entry("voltage");
+    }
+    secu.create_object_entry(
+        "position",
+        if revision.change_position {
+            "u32"
+        } else {
+            "u16"
+        },
+    );
+    secu.create_object_entry("mode", "mode");
+    if !revision.remove_cells {
+        secu.create_object_entry(
+            "cells",
+            if revision.grow_array {
+                "cell[2]"
+            } else {
+                "cell[1]"
+            },
+        );
+    }
+    let stream = secu.create_stream("state");
+    stream.add_entry("position");
+    if !revision.remove_cells {
+        stream.add_entry("cells");
+    }
+    network_builder
+        .build()
+        .expect("failed to build the network")
+}
+
+fn find<'a>(diff: &'a NetworkDiff, path: &str) -> &'a DiffEntry {
+    diff.entries()
+        .iter()
+        .find(|entry| entry.path == path)
+        .unwrap_or_else(|| panic!("{path} is missing in\n{diff}"))
+}
+
+fn changes<'a>(diff: &'a NetworkDiff, path: &str) -> &'a Vec<String> {
+    match &find(diff, path).kind {
+        DiffKind::Changed(changes) => changes,
+        kind => panic!("expected {path} to be changed, got {kind:?}"),
+    }
+}
+
+#[test]
+fn identical_networks_have_no_diff() {
+    let diff = network(Revision::default()).diff(&network(Revision::default()));
+    assert!(diff.is_empty(), "{diff}");
+}
+
+#[test]
+fn added_and_removed_nodes() {
+    let old = network(Revision::default());
+    let new = network(Revision {
+        add_node: true,
+        ..Default::default()
+    });
+    assert_eq!(find(&old.diff(&new), "node bms").kind, DiffKind::Added);
+    assert_eq!(find(&new.diff(&old), "node bms").kind, DiffKind::Removed);
+}
+
+#[test]
+fn added_removed_and_changed_object_entries() {
+    let old = network(Revision::default());
+    let new = network(Revision {
+        remove_cells: true,
+        change_position: true,
+        ..Default::default()
+    });
+    let diff = old.diff(&new);
+    assert_eq!(
+        find(&diff, "node secu / object entry cells").kind,
+        DiffKind::Removed
+    );
+    assert_eq!(
+        changes(&diff, "node secu / object entry position"),
+        &vec!["type : u16 -> u32".to_owned()]
+    );
+    assert_eq!(
+        find(&new.diff(&old), "node secu / object entry cells").kind,
+        DiffKind::Added
+    );
+}
+
+#[test]
+fn changed_messages_list_their_signals_by_path() {
+    let old = network(Revision::default());
+    let new = network(Revision {
+        grow_array: true,
+        ..Default::default()
+    });
+    let diff = old.diff(&new);
+    let changes = changes(&diff, "message secu_stream_state");
+    assert!(changes.contains(&"dlc : 4 -> 6".to_owned()), "{changes:?}");
+    assert!(
+        changes.contains(&"signal cells[1].voltage added".to_owned()),
+        "{changes:?}"
+    );
+    assert!(
+        changes.contains(&"signal cells[1].temperature added".to_owned()),
+        "{changes:?}"
+    );
+    assert!(
+        !changes.iter().any(|c| c.contains("cells[0]")),
+        "{changes:?}"
+    );
+}
+
+#[test]
+fn added_and_removed_messages() {
+    let old = network(Revision::default());
+    let new = network(Revision {
+        add_node: true,
+        ..Default::default()
+    });
+    let diff = old.diff(&new);
+    assert!(
+        diff.entries()
+            .iter()
+            .any(|entry| entry.path == "message bms_stream_state" && entry.kind == DiffKind::Added),
+        "{diff}"
+    );
+    assert_eq!(
+        find(&new.diff(&old), "message bms_stream_state").kind,
+        DiffKind::Removed
+    );
+}
+
+#[test]
+fn changed_types() {
+    let old = network(Revision::default());
+    let new = network(Revision {
+        change_cell: true,
+        add_enum_entry: true,
+        ..Default::default()
+    });
+    let diff = old.diff(&new);
+    let cell_changes = changes(&diff, "type cell");
+    assert_eq!(cell_changes.len(), 1, "{cell_changes:?}");
+    assert!(
+        cell_changes[0].starts_with("attributes : ")
+            && cell_changes[0].ends_with("(\"temperature\", \"i16\")]"),
+        "{cell_changes:?}"
+    );
+    let mode_changes = changes(&diff, "type mode");
+    assert!(
+        mode_changes.iter().any(|c| c.starts_with("entries : ")),
+        "{mode_changes:?}"
+    );
+}
+
+#[test]
+fn added_and_removed_types() {
+    let old = network(Revision::default());
+    let builder = NetworkBuilder::new();
+    builder.create_bus("can0", Some(1000000));
+    builder.create_node("secu");
+    builder
+        .define_enum("state")
+        .add_entry("init", Some(0))
+        .unwrap();
+    let new = builder.build().unwrap();
+    let diff = old.diff(&new);
+    assert_eq!(find(&diff, "type cell").kind, DiffKind::Removed);
+    assert_eq!(find(&diff, "type state").kind, DiffKind::Added);
+}
@@ -3,7 +3,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use can_config_rs::{
+use canzero_config::{
     builder::{MessagePriority, NetworkBuilder},
     config::MessageId,
 };
@@ -61,12 +61,25 @@ fn check_builder(det: impl Fn() -> NetworkBuilder) {
         }
         id_set.insert(m.id().clone());
     }
+
+    // Check that the acceptance filters of every node let all received messages pass
+    for node in base.nodes() {
+        for m in node.rx_messages() {
+            let filters = node.bus_filters(m.bus().id());
+            assert!(
+                filters.iter().any(|f| f.accepts(m.id())),
+                "message {} is rejected by the filters of node {}",
+                m.name(),
+                node.name()
+            );
+        }
+    }
 }
 
 #[test]
 fn message_resolution_empty_config() {
     check_builder(|| {
-        let network_builder = can_config_rs::builder::NetworkBuilder::new();
+        let network_builder = canzero_config::builder::NetworkBuilder::new();
 
         network_builder
     });
@@ -77,11 +90,12 @@ fn perf1(node_count: u32, non_message_count: u32, fixed_message_count: u32, ide:
         let mut node_gen = MessageNameGen::new();
         let mut name_gen = MessageNameGen::new();
 
-        let network_builder = can_config_rs::builder::NetworkBuilder::new();
+        let network_builder = canzero_config::builder::NetworkBuilder::new();
         let mut message_ids_used: HashSet<u32> = HashSet::new();
         for _ in 0..node_count {
             let node_name = node_gen.next();
-            network_builder.create_node(&node_name);
+            // like the yaml parser, connect every node to all buses.
+            network_builder.create_node(&node_name).assign_bus("can0");
             for _ in 0..non_message_count {
                 let name = name_gen.next();
                 let message = network_builder.create_message(&name, None);
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use can_config_rs::{
+use canzero_config::{
     builder::{MessagePriority, NetworkBuilder},
     config::MessageId,
 };
@@ -62,7 +62,7 @@ fn check_builder(det: impl Fn() -> NetworkBuilder) {
 #[test]
 fn message_resolution_empty_config() {
     check_builder(|| {
-        let network_builder = can_config_rs::builder::NetworkBuilder::new();
+        let network_builder = canzero_config::builder::NetworkBuilder::new();
 
         network_builder
     });
@@ -73,7 +73,7 @@ fn perf1(node_count: u32, message_count: u32, _: Vec<MessagePriority>) {
         let mut node_gen = MessageNameGen::new();
         let mut name_gen = MessageNameGen::new();
 
-        let network_builder = can_config_rs::builder::NetworkBuilder::new();
+        let network_builder = canzero_config::builder::NetworkBuilder::new();
         for _ in 0..node_count {
             let node_name = node_gen.next();
             network_builder.create_node(&node_name);
@@ -3,7 +3,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use can_config_rs::{
+use canzero_config::{
     builder::{MessagePriority, NetworkBuilder},
     config::MessageId,
 };
@@ -65,7 +65,7 @@ fn check_builder(det: impl Fn() -> NetworkBuilder) {
 #[test]
 fn message_resolution_empty_config() {
     check_builder(|| {
-        let network_builder = can_config_rs::builder::NetworkBuilder::new();
+        let network_builder = canzero_config::builder::NetworkBuilder::new();
 
         network_builder
     });
@@ -76,7 +76,7 @@ fn perf1(node_count: u32, message_count: u32, priorities: Vec<MessagePriority>)
         let mut node_gen = MessageNameGen::new();
         let mut name_gen = MessageNameGen::new();
 
-        let network_builder = can_config_rs::builder::NetworkBuilder::new();
+        let network_builder = canzero_config::builder::NetworkBuilder::new();
         for _ in 0..node_count {
             let node_name = node_gen.next();
             network_builder.create_node(&node_name);
@@ -1,17 +1,50 @@
 use std::{
+    path::PathBuf,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use canzero_appdata::AppData;
+use canzero_common::recording::RecordingHeader;
 use canzero_config::config::NetworkRef;
 use canzero_tcp::tcpcan::{ConnectionId, ConnectionIdHost, TcpCan};
 use canzero_udp::beacon::UdpNetworkBeacon;
 use color_print::cprintln;
 use tokio::{net::TcpListener, task::AbortHandle};
 
-use self::network::{node::NetworkNode, Network};
+use self::network::{
+    node::NetworkNode,
+    recorder::{Recorder, RecorderLimits},
+    Network,
+};
 
 pub mod network;
+pub mod simulation;
+
+pub struct ServerOptions {
+    /// directory to which all frames are recorded, None disables recording.
+    pub recording_dir: Option<PathBuf>,
+    /// recordings are rotated after reaching this size (in bytes).
+    pub max_recording_size: u64,
+    /// the oldest recordings are deleted, such that at most this many files are kept.
+    pub max_recording_count: usize,
+    /// window of frames that is kept in memory to sync newly connected clients.
+    pub sync_history_window: Duration,
+    /// connect the local SocketCAN interfaces (only with the socket-can feature).
+    pub connect_socketcan: bool,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            recording_dir: Some(AppData::dir().join("recordings")),
+            max_recording_size: 64 * 1024 * 1024,
+            max_recording_count: 16,
+            sync_history_window: Duration::from_secs(300),
+            connect_socketcan: true,
+        }
+    }
+}
 
 pub struct Server {
     network: Arc<Network>,
@@ -24,21 +57,42 @@ pub struct Server {
 }
 
 impl Server {
-    pub async fn create(config: NetworkRef) -> std::io::Result<Self> {
+    pub async fn create(config: NetworkRef, options: ServerOptions) -> std::io::Result<Self> {
         let timebase = Instant::now();
+        let timebase_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_micros() as u64;
 
         let id_host = ConnectionIdHost::new((config.nodes().len() + 16) as u8);
         for n in config.nodes() {
             id_host.alloc_specific_id(n.id());
         }
 
-        let network = Network::new();
+        let recorder = match &options.recording_dir {
+            Some(recording_dir) => Some(Recorder::start(
+                recording_dir,
+                RecorderLimits {
+                    max_file_size: options.max_recording_size,
+                    max_file_count: options.max_recording_count,
+                    ..Default::default()
+                },
+                RecordingHeader {
+                    config_hash: config.portable_hash(),
+                    timebase_us,
+                },
+            )?),
+            None => None,
+        };
+
+        let network = Network::new(options.sync_history_window, recorder);
 
         #[cfg(feature = "socket-can")]
-        {
+        if options.connect_socketcan {
             network
-                .start(NetworkNode::SocketCanNode(
-                    canzero_socketcan::socket_can::SocketCan::connect().await?,
+                .start(NetworkNode::new(
+                    "socketcan connection",
+                    Box::new(canzero_socketcan::socket_can::SocketCan::connect().await?),
                 ))
                 .await
         }
@@ -58,6 +112,16 @@ impl Server {
         })
     }
 
+    /// Frames of attached transports have to be timestamped relative to the timebase.
+    pub fn timebase(&self) -> Instant {
+        self.timebase
+    }
+
+    /// Attaches an additional participant (e.g. a Simulation) to the network.
+    pub async fn attach(&self, node: NetworkNode) {
+        self.network.start(node).await
+    }
+
     pub fn start(&self) {
         cprintln!("<green>Starting Server</green>");
         let mut task_lock = self
@@ -117,16 +181,26 @@ impl Server {
             loop {
                 let (stream, addr) = welcome.accept().await.unwrap();
                 println!("\u{1b}[32mConnection from {addr:?}\u{1b}[0m");
+                let tcpcan = match TcpCan::new(
+                    stream,
+                    ConnectionId::Host {
+                        id_host: id_host.clone(),
+                        sync_history: Some(network.sync_history().await),
+                    },
+                )
+                .await
+                {
+                    Ok(tcpcan) => tcpcan,
+                    // a single incompatible or misbehaving client shouldn't stop the server.
+                    Err(err) => {
+                        cprintln!("<yellow>Rejected connection from {addr:?} : {err}</yellow>");
+                        continue;
+                    }
+                };
                 network
-                    .start(NetworkNode::TcpCanNode(
-                        TcpCan::new(
-                            stream,
-                            ConnectionId::Host {
-                                id_host: id_host.clone(),
-                                sync_history: Some(network.sync_history().await),
-                            },
-                        )
-                        .await?,
+                    .start(NetworkNode::new(
+                        &format!("tcp connection {addr}"),
+                        Box::new(tcpcan),
                     ))
                     .await;
             }
@@ -1,49 +1,73 @@
-use std::sync::{atomic::AtomicU32, Arc};
+use std::{
+    collections::VecDeque,
+    sync::{atomic::AtomicU32, Arc},
+    time::Duration,
+};
 
-use canzero_common::TNetworkFrame;
+use canzero_common::{TNetworkError, TNetworkFrame};
 use color_print::cprintln;
 use tokio::sync::{Mutex, RwLock};
 
-use self::node::NetworkNode;
+use self::{node::NetworkNode, recorder::Recorder};
 
 pub mod node;
+pub mod recorder;
 
 pub struct Network {
     nodes: Arc<RwLock<Vec<(u32, Arc<NetworkNode>)>>>,
-    history: Arc<Mutex<Vec<TNetworkFrame>>>,
+    history: Arc<Mutex<History>>,
+    recorder: Option<Arc<Recorder>>,
     id_acc: AtomicU32,
 }
 
+pub type HistoryFrame = Result<TNetworkFrame, TNetworkError>;
+
+/// In-memory history that is used to sync newly connected clients,
+/// only holds the frames (and error frames) of the last `window`.
+struct History {
+    frames: VecDeque<HistoryFrame>,
+    window: Duration,
+}
+
+fn timestamp(frame: &HistoryFrame) -> Duration {
+    match frame {
+        Ok(frame) => frame.timestamp,
+        Err(error) => error.timestamp,
+    }
+}
+
+impl History {
+    fn push(&mut self, frame: HistoryFrame) {
+        let breakpoint = timestamp(&frame).saturating_sub(self.window);
+        self.frames.push_back(frame);
+        while let Some(front) = self.frames.front() {
+            if timestamp(front) >= breakpoint {
+                break;
+            }
+            self.frames.pop_front();
+        }
+    }
+}
+
 impl Network {
-    pub fn new() -> Self {
+    pub fn new(history_window: Duration, recorder: Option<Recorder>) -> Self {
         Self {
             nodes: Arc::new(RwLock::new(vec![])),
             id_acc: AtomicU32::new(0),
-            history: Arc::new(Mutex::new(vec![])),
+            history: Arc::new(Mutex::new(History {
+                frames: VecDeque::new(),
+                window: history_window,
+            })),
+            recorder: recorder.map(Arc::new),
         }
     }
 
-    pub async fn sync_history(&self) -> Vec<TNetworkFrame> {
-        return self.history.lock().await.clone() //hopefully fast enough
+    pub async fn sync_history(&self) -> Vec<HistoryFrame> {
+        return self.history.lock().await.frames.iter().cloned().collect() //hopefully fast enough
     }
 
     pub async fn start(&self, node: NetworkNode) {
-        match &node {
-            #[cfg(feature = "socket-can")]
-            NetworkNode::SocketCanNode(_) => {
-                cprintln!("<green>Establish socketcan connection</green>");
-            }
-            NetworkNode::TcpCanNode(tcpcan) => {
-                let addr = match tcpcan.addr().await {
-                    Ok(addr) => addr,
-                    Err(_) => {
-                        cprintln!("<red>Can't resolve connection addr [rejected connection]</red>");
-                        return;
-                    }
-                };
-                cprintln!("<green>Establish tcp connection {}</green>", addr);
-            }
-        }
+        cprintln!("<green>Establish {}</green>", node.name());
         let nodes = self.nodes.clone();
         let node_id = self
             .id_acc
@@ -51,6 +75,7 @@ impl Network {
         let node = Arc::new(node);
         nodes.write().await.push((node_id, node.clone()));
         let history = self.history.clone();
+        let recorder = self.recorder.clone();
         tokio::spawn(async move {
             loop {
                 let Some(frame) = node.recv().await else {
@@ -59,11 +84,19 @@ impl Network {
                 for (id, node) in nodes.read().await.iter() {
                     if *id != node_id {
                         // ignore loop back!
-                        if let Err(err) = node.send(&frame).await {
+                        let result = match &frame {
+                            Ok(frame) => node.send(frame).await,
+                            Err(error) => node.send_error(error).await,
+                        };
+                        if let Err(err) = result {
                             eprintln!("{err:?}");
                         };
                     }
                 }
+                // recordings only contain frames.
+                if let (Some(recorder), Ok(frame)) = (&recorder, &frame) {
+                    recorder.record(frame);
+                }
                 history.lock().await.push(frame);
             }
             // remove node
@@ -72,24 +105,7 @@ impl Network {
                 return;
             };
             nodes_lock.remove(node_pos);
-            match node.as_ref() {
-                #[cfg(feature = "socket-can")]
-                NetworkNode::SocketCanNode(_) => {
-                    cprintln!("<red>Shutdown socketcan connection</red>")
-                }
-                NetworkNode::TcpCanNode(tcp) => {
-                    let addr = match tcp.addr().await {
-                        Ok(addr) => addr,
-                        Err(_) => {
-                            cprintln!(
-                                "???"
-                            );
-                            return;
-                        }
-                    };
-                    cprintln!("<red>Shutdown tcp connection {}</red>", addr)
-                }
-            };
+            cprintln!("<red>Shutdown {}</red>", node.name());
         });
     }
 }
@@ -1,27 +1,33 @@
-use canzero_common::TNetworkFrame;
-use canzero_tcp::tcpcan::TcpCan;
+use canzero_common::{transport::CanTransport, TNetworkError, TNetworkFrame};
 
-#[derive(Debug)]
-pub enum NetworkNode {
-    #[cfg(feature = "socket-can")]
-    SocketCanNode(canzero_socketcan::socket_can::SocketCan),
-    TcpCanNode(TcpCan),
+/// A participant of the network, which is connected through any CanTransport
+/// (e.g. the local SocketCAN interfaces or a tcp client).
+pub struct NetworkNode {
+    name: String,
+    transport: Box<dyn CanTransport>,
 }
 
 impl NetworkNode {
-    pub async fn send(&self, frame: &TNetworkFrame) -> std::io::Result<()> {
-        match &self {
-            #[cfg(feature = "socket-can")]
-            NetworkNode::SocketCanNode(socketcan) => socketcan.send(frame).await,
-            NetworkNode::TcpCanNode(tcpcan) => tcpcan.send(frame).await,
+    pub fn new(name: &str, transport: Box<dyn CanTransport>) -> Self {
+        Self {
+            name: name.to_owned(),
+            transport,
         }
     }
 
-    pub async fn recv(&self) -> Option<TNetworkFrame> {
-        match &self {
-            #[cfg(feature = "socket-can")]
-            NetworkNode::SocketCanNode(socketcan) => socketcan.recv().await,
-            NetworkNode::TcpCanNode(tcpcan) => tcpcan.recv().await,
-        }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub async fn send(&self, frame: &TNetworkFrame) -> std::io::Result<()> {
+        self.transport.send(frame).await
+    }
+
+    pub async fn send_error(&self, error: &TNetworkError) -> std::io::Result<()> {
+        self.transport.send_error(error).await
+    }
+
+    pub async fn recv(&self) -> Option<Result<TNetworkFrame, TNetworkError>> {
+        self.transport.recv().await
     }
 }
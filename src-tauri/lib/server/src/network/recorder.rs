@@ -0,0 +1,178 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use canzero_common::{
+    recording::{
+        frame_size, recording_file_name, rotated_recordings, RecordingHeader, RecordingWriter,
+        FRAME_SIZE, HEADER_SIZE,
+    },
+    TNetworkFrame,
+};
+use color_print::cprintln;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// Smallest file size, which fits the header and a single frame.
+pub const MIN_FILE_SIZE: u64 = (HEADER_SIZE + FRAME_SIZE) as u64;
+
+/// Bounds the disk and memory usage of a Recorder.
+#[derive(Clone, Debug)]
+pub struct RecorderLimits {
+    /// files are rotated after reaching this size (in bytes), at least MIN_FILE_SIZE.
+    pub max_file_size: u64,
+    /// the oldest recordings of the directory (including older sessions)
+    /// are deleted, such that at most this many files are kept, at least 1.
+    pub max_file_count: usize,
+    /// frames are dropped, while this many frames are waiting to be written.
+    pub queue_capacity: usize,
+}
+
+impl Default for RecorderLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: 64 * 1024 * 1024,
+            max_file_count: 16,
+            queue_capacity: 64 * 1024,
+        }
+    }
+}
+
+/// Streams all frames of the network into a rotating, append-only log on disk.
+/// Writing is done on a blocking thread, such that slow disks never stall the network.
+pub struct Recorder {
+    tx: mpsc::Sender<TNetworkFrame>,
+    dropped_frames: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+
+impl Recorder {
+    pub fn start(
+        dir: &Path,
+        limits: RecorderLimits,
+        header: RecordingHeader,
+    ) -> std::io::Result<Self> {
+        if limits.max_file_size < MIN_FILE_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("the maximum recording size has to be at least {MIN_FILE_SIZE} bytes"),
+            ));
+        }
+        if limits.max_file_count == 0 || limits.queue_capacity == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "the recording file count and queue capacity have to be at least 1",
+            ));
+        }
+        std::fs::create_dir_all(dir)?;
+        let (tx, rx) = mpsc::channel(limits.queue_capacity);
+        let dir = dir.to_owned();
+        let task = tokio::task::spawn_blocking(move || {
+            if let Err(err) = recorder_task(rx, &dir, &limits, &header) {
+                cprintln!("<red>Recorder stopped : {err:?}</red>");
+            }
+        });
+        Ok(Self {
+            tx,
+            dropped_frames: Arc::new(AtomicU64::new(0)),
+            task,
+        })
+    }
+
+    /// Waits until all recorded frames are written to disk.
+    pub async fn finish(self) {
+        drop(self.tx);
+        // a panic of the recorder_task is already reported.
+        let _ = self.task.await;
+        let dropped_frames = self.dropped_frames.load(Ordering::Relaxed);
+        if dropped_frames != 0 {
+            cprintln!("<yellow>Recorder dropped {dropped_frames} frames</yellow>");
+        }
+    }
+
+    /// Never blocks, frames are dropped if the disk can't keep up with the network.
+    pub fn record(&self, frame: &TNetworkFrame) {
+        match self.tx.try_send(frame.clone()) {
+            Ok(()) => (),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                if self.dropped_frames.fetch_add(1, Ordering::Relaxed) == 0 {
+                    cprintln!("<yellow>Recorder can't keep up, dropping frames</yellow>");
+                }
+            }
+            // the recorder_task stopped, which has already been reported.
+            Err(mpsc::error::TrySendError::Closed(_)) => (),
+        }
+    }
+
+    /// Number of frames, which were dropped because the queue was full.
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames.load(Ordering::Relaxed)
+    }
+}
+
+fn recording_path(dir: &Path, header: &RecordingHeader, seq: u32) -> PathBuf {
+    dir.join(recording_file_name(header, seq))
+}
+
+fn open_recording(
+    dir: &Path,
+    header: &RecordingHeader,
+    seq: u32,
+) -> std::io::Result<RecordingWriter<BufWriter<File>>> {
+    let path = recording_path(dir, header, seq);
+    cprintln!("<green>Recording to {path:?}</green>");
+    let file = std::fs::OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .open(path)?;
+    RecordingWriter::create(BufWriter::new(file), header)
+}
+
+/// Deletes the oldest recordings of dir, until at most max_file_count are left.
+fn remove_old_recordings(dir: &Path, max_file_count: usize) -> std::io::Result<()> {
+    let recordings = rotated_recordings(dir)?;
+    let excess = recordings.len().saturating_sub(max_file_count);
+    for path in &recordings[..excess] {
+        cprintln!("<yellow>Removing old recording {path:?}</yellow>");
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn recorder_task(
+    mut rx: mpsc::Receiver<TNetworkFrame>,
+    dir: &Path,
+    limits: &RecorderLimits,
+    header: &RecordingHeader,
+) -> std::io::Result<()> {
+    let mut seq = 0;
+    let mut writer = open_recording(dir, header, seq)?;
+    remove_old_recordings(dir, limits.max_file_count)?;
+    let mut file_size = HEADER_SIZE as u64;
+    while let Some(frame) = rx.blocking_recv() {
+        let mut next = Some(frame);
+        // drain everything that is available and flush afterwards.
+        while let Some(frame) = next {
+            let size = frame_size(&frame) as u64;
+            // CAN FD frames may exceed the file size on their own,
+            // files are only rotated after they contain at least one frame.
+            if file_size + size > limits.max_file_size && file_size > HEADER_SIZE as u64 {
+                writer.flush()?;
+                seq += 1;
+                writer = open_recording(dir, header, seq)?;
+                remove_old_recordings(dir, limits.max_file_count)?;
+                file_size = HEADER_SIZE as u64;
+            }
+            writer.write_frame(&frame)?;
+            file_size += size;
+            next = rx.try_recv().ok();
+        }
+        writer.flush()?;
+    }
+    writer.flush()
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, time::Duration};
+
+use canzero_common::CanError;
+use canzero_config::config::NetworkRef;
+
+#[derive(Debug, Clone)]
+pub enum FaultKind {
+    /// The node doesn't send heartbeats.
+    MissHeartbeat { node_id: u8 },
+    /// The get responses of the node carry inverted toggle bits.
+    BadToggle { node_id: u8 },
+    /// An error frame is received on the bus.
+    ErrorFrame { bus_id: u32, can_error: CanError },
+}
+
+#[derive(Debug, Clone)]
+pub struct Fault {
+    /// relative to the start of the simulation.
+    pub at: Duration,
+    pub duration: Duration,
+    pub kind: FaultKind,
+}
+
+impl Fault {
+    fn is_active(&self, elapsed: Duration) -> bool {
+        elapsed >= self.at && elapsed < self.at + self.duration
+    }
+}
+
+#[derive(Debug)]
+pub struct FaultScriptError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for FaultScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {} : {}", self.line, self.reason)
+    }
+}
+
+/// Faults which are injected by the simulation, one fault per line:
+/// ```text
+/// # <at>  <fault>         <node or bus>  [duration or error type]
+/// 5s      miss-heartbeat  secu           2s
+/// 10s     bad-toggle      secu           500ms
+/// 12s     error-frame     can0           crc
+/// ```
+/// Error types are bit, stuff, form, ack and crc (default bit).
+#[derive(Debug, Clone, Default)]
+pub struct FaultScript {
+    faults: Vec<Fault>,
+}
+
+impl FaultScript {
+    pub fn parse(src: &str, config: &NetworkRef) -> Result<Self, FaultScriptError> {
+        let mut faults = vec![];
+        for (i, line) in src.lines().enumerate() {
+            let error = |reason: String| FaultScriptError { line: i + 1, reason };
+            let line = line.split('#').next().unwrap_or_default();
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let [at, fault, target, rest @ ..] = tokens.as_slice() else {
+                if tokens.is_empty() {
+                    continue;
+                }
+                return Err(error("expected <at> <fault> <node or bus>".to_owned()));
+            };
+            if rest.len() > 1 {
+                return Err(error(format!("unexpected {:?}", rest[1])));
+            }
+            let at = parse_duration(at).ok_or_else(|| error(format!("invalid time {at:?}")))?;
+            let node_id = || {
+                config
+                    .nodes()
+                    .iter()
+                    .find(|node| node.name() == *target)
+                    .map(|node| node.id())
+                    .ok_or_else(|| error(format!("node {target:?} is not defined")))
+            };
+            let duration = || match rest.first() {
+                Some(duration) => parse_duration(duration)
+                    .ok_or_else(|| error(format!("invalid duration {duration:?}"))),
+                None => Err(error(format!("{fault} requires a duration"))),
+            };
+            let (kind, duration) = match *fault {
+                "miss-heartbeat" => (FaultKind::MissHeartbeat { node_id: node_id()? }, duration()?),
+                "bad-toggle" => (FaultKind::BadToggle { node_id: node_id()? }, duration()?),
+                "error-frame" => {
+                    let bus_id = config
+                        .buses()
+                        .iter()
+                        .find(|bus| bus.name() == *target)
+                        .map(|bus| bus.id())
+                        .ok_or_else(|| error(format!("bus {target:?} is not defined")))?;
+                    // bit flags as reported by the trace.
+                    let erno = match rest.first().copied().unwrap_or("bit") {
+                        "bit" => 0x1,
+                        "stuff" => 0x2,
+                        "form" => 0x4,
+                        "ack" => 0x8,
+                        "crc" => 0x10,
+                        other => return Err(error(format!("unknown error type {other:?}"))),
+                    };
+                    let kind = FaultKind::ErrorFrame {
+                        bus_id,
+                        can_error: CanError(erno),
+                    };
+                    (kind, Duration::ZERO)
+                }
+                other => return Err(error(format!("unknown fault {other:?}"))),
+            };
+            faults.push(Fault { at, duration, kind });
+        }
+        faults.sort_by_key(|fault| fault.at);
+        Ok(Self { faults })
+    }
+
+    pub fn faults(&self) -> &Vec<Fault> {
+        &self.faults
+    }
+
+    pub fn misses_heartbeat(&self, node_id: u8, elapsed: Duration) -> bool {
+        self.faults.iter().any(|fault| {
+            matches!(fault.kind, FaultKind::MissHeartbeat { node_id: id } if id == node_id)
+                && fault.is_active(elapsed)
+        })
+    }
+
+    pub fn has_bad_toggle(&self, node_id: u8, elapsed: Duration) -> bool {
+        self.faults.iter().any(|fault| {
+            matches!(fault.kind, FaultKind::BadToggle { node_id: id } if id == node_id)
+                && fault.is_active(elapsed)
+        })
+    }
+}
+
+/// e.g. 500ms, 2s or 1.5s
+fn parse_duration(s: &str) -> Option<Duration> {
+    let (value, unit) = match s.strip_suffix("ms") {
+        Some(value) => (value, 1e-3),
+        None => (s.strip_suffix('s')?, 1.0),
+    };
+    let secs = value.parse::<f64>().ok()? * unit;
+    if !secs.is_finite() || secs < 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(secs))
+}
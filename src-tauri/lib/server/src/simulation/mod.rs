@@ -0,0 +1,191 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use canzero_common::{
+    transport::{BoxFuture, CanTransport, TransportBus},
+    NetworkError, NetworkFrame, TNetworkError, TNetworkFrame,
+};
+use canzero_config::config::{NetworkRef, NodeRef};
+use tokio::task::AbortHandle;
+
+use self::{
+    fault::{FaultKind, FaultScript},
+    node::SimulatedNode,
+    protocol::Protocol,
+};
+
+pub mod fault;
+mod node;
+mod protocol;
+
+const TICK_INTERVAL: Duration = Duration::from_millis(5);
+
+type SimulationFrame = Result<TNetworkFrame, TNetworkError>;
+
+struct SimulationState {
+    nodes: Vec<SimulatedNode>,
+    protocol: Protocol,
+    faults: FaultScript,
+    // index of the next fault, which is injected once (error frames).
+    next_fault: usize,
+    start: Instant,
+    timebase: Instant,
+    // None after the simulation was closed.
+    tx: Option<tokio::sync::mpsc::UnboundedSender<SimulationFrame>>,
+}
+
+impl SimulationState {
+    fn push(&self, frame: SimulationFrame) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(frame);
+        }
+    }
+
+    fn tick(&mut self) {
+        let elapsed = self.start.elapsed();
+        while let Some(fault) = self.faults.faults().get(self.next_fault) {
+            if fault.at > elapsed {
+                break;
+            }
+            if let FaultKind::ErrorFrame { bus_id, can_error } = &fault.kind {
+                self.push(Err(TNetworkError::now(
+                    self.timebase,
+                    NetworkError {
+                        bus_id: *bus_id,
+                        can_error: can_error.clone(),
+                    },
+                )));
+            }
+            self.next_fault += 1;
+        }
+        let mut frames = vec![];
+        for node in &mut self.nodes {
+            frames.extend(node.poll(elapsed, &self.protocol, &self.faults));
+        }
+        for frame in frames {
+            self.push(Ok(TNetworkFrame::now(self.timebase, frame)));
+        }
+    }
+
+    fn receive(&mut self, frame: &NetworkFrame) {
+        let elapsed = self.start.elapsed();
+        if let Some(req) = self.protocol.decode_get_req(frame) {
+            for node in &self.nodes {
+                let bad_toggle = self.faults.has_bad_toggle(node.id(), elapsed);
+                for resp in node.handle_get_req(&req, bad_toggle) {
+                    let frame = self.protocol.encode_get_resp(&resp);
+                    self.push(Ok(TNetworkFrame::now(self.timebase, frame)));
+                }
+            }
+        } else if let Some(req) = self.protocol.decode_set_req(frame) {
+            let mut resps = vec![];
+            for node in &mut self.nodes {
+                resps.extend(node.handle_set_req(&req));
+            }
+            for resp in resps {
+                let frame = self.protocol.encode_set_resp(&resp);
+                self.push(Ok(TNetworkFrame::now(self.timebase, frame)));
+            }
+        }
+    }
+}
+
+/// In-process network of simulated nodes, which can be attached to the server
+/// like a real network (see Server::attach), such that clients can't tell the difference.
+pub struct Simulation {
+    buses: Vec<TransportBus>,
+    state: Arc<Mutex<SimulationState>>,
+    rx: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<SimulationFrame>>,
+    task_handle: AbortHandle,
+}
+
+impl Simulation {
+    /// Starts simulating the nodes, frames are timestamped relative to timebase.
+    /// Has to be called from within a tokio runtime.
+    pub fn start(
+        config: &NetworkRef,
+        nodes: &[NodeRef],
+        faults: FaultScript,
+        timebase: Instant,
+    ) -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let state = Arc::new(Mutex::new(SimulationState {
+            nodes: nodes.iter().map(SimulatedNode::new).collect(),
+            protocol: Protocol::new(config),
+            faults,
+            next_fault: 0,
+            start: Instant::now(),
+            timebase,
+            tx: Some(tx),
+        }));
+        let task_state = state.clone();
+        let task_handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                task_state
+                    .lock()
+                    .expect("Failed to acquire simulation lock")
+                    .tick();
+            }
+        })
+        .abort_handle();
+        Self {
+            buses: config
+                .buses()
+                .iter()
+                .map(|bus| TransportBus::new(bus.id(), bus.name()))
+                .collect(),
+            state,
+            rx: tokio::sync::Mutex::new(rx),
+            task_handle,
+        }
+    }
+
+    pub fn node_names(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .expect("Failed to acquire simulation lock")
+            .nodes
+            .iter()
+            .map(|node| node.name().to_owned())
+            .collect()
+    }
+}
+
+impl CanTransport for Simulation {
+    fn buses(&self) -> Vec<TransportBus> {
+        self.buses.clone()
+    }
+
+    fn send<'a>(&'a self, frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>> {
+        self.state
+            .lock()
+            .expect("Failed to acquire simulation lock")
+            .receive(frame);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>> {
+        Box::pin(async move { self.rx.lock().await.recv().await })
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        self.task_handle.abort();
+        // frames which where already simulated can still be received.
+        self.state
+            .lock()
+            .expect("Failed to acquire simulation lock")
+            .tx
+            .take();
+        Box::pin(async {})
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
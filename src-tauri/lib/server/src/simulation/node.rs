@@ -0,0 +1,489 @@
+use std::{collections::HashMap, time::Duration};
+
+use canzero_common::NetworkFrame;
+use canzero_config::config::{stream::StreamRef, NodeRef, ObjectEntryAccess, ObjectEntryRef};
+
+use super::{
+    fault::FaultScript,
+    protocol::{self, Field, GetReq, GetResp, Protocol, SetReq, SetResp},
+};
+
+/// Same schedule as the generated nodes.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+const HEARTBEAT_TICK: Duration = Duration::from_millis(50);
+
+struct SimulatedObjectEntry {
+    config: ObjectEntryRef,
+    /// serialized like in get and set requests (bit after bit, starting at the lsb).
+    value: Vec<u32>,
+}
+
+struct SimulatedStream {
+    config: StreamRef,
+    /// flattened signals of each mapped object entry.
+    mappings: Vec<(usize, Vec<Field>)>,
+    /// transmitted at the latest (max_interval).
+    next: Duration,
+    last: Option<Duration>,
+    /// a mapped object entry was set since the last transmission.
+    changed: bool,
+}
+
+impl SimulatedStream {
+    /// Like the generated nodes, streams are transmitted every max_interval
+    /// and, if a mapped object entry changed, min_interval after the last transmission.
+    fn is_due(&self, elapsed: Duration) -> bool {
+        elapsed >= self.next
+            || (self.changed
+                && self
+                    .last
+                    .map_or(true, |last| elapsed >= last + *self.config.min_interval()))
+    }
+}
+
+/// A fragmented set request, which is not yet complete.
+struct SetFragments {
+    od_index: u32,
+    buffer: Vec<u32>,
+}
+
+/// Node of the network config, which answers get and set requests
+/// from its object dictionary and transmits its tx streams and heartbeats.
+pub struct SimulatedNode {
+    config: NodeRef,
+    object_entries: Vec<SimulatedObjectEntry>,
+    streams: Vec<SimulatedStream>,
+    // by client id.
+    set_fragments: HashMap<u8, SetFragments>,
+    next_heartbeat: Duration,
+}
+
+impl SimulatedNode {
+    pub fn new(config: &NodeRef) -> Self {
+        let object_entries: Vec<SimulatedObjectEntry> = config
+            .object_entries()
+            .iter()
+            .map(|oe| SimulatedObjectEntry {
+                config: oe.clone(),
+                value: vec![0; oe.ty().size().div_ceil(32) as usize],
+            })
+            .collect();
+        let streams = config
+            .tx_streams()
+            .iter()
+            .map(|stream| {
+                let Some(encoding) = stream.message().encoding() else {
+                    panic!("DETECTED INVALID CONFIG: stream messages are expected to define a encoding");
+                };
+                let mappings = stream
+                    .mapping()
+                    .iter()
+                    .zip(encoding.attributes())
+                    .filter_map(|(oe, attrib)| {
+                        let oe = oe.as_ref()?;
+                        let index = object_entries
+                            .iter()
+                            .position(|simulated| simulated.config.id() == oe.id())?;
+                        Some((index, protocol::flatten(attrib)))
+                    })
+                    .collect();
+                SimulatedStream {
+                    config: stream.clone(),
+                    mappings,
+                    next: Duration::ZERO,
+                    last: None,
+                    changed: false,
+                }
+            })
+            .collect();
+        Self {
+            config: config.clone(),
+            object_entries,
+            streams,
+            set_fragments: HashMap::new(),
+            next_heartbeat: Duration::ZERO,
+        }
+    }
+
+    pub fn id(&self) -> u8 {
+        self.config.id()
+    }
+
+    pub fn name(&self) -> &str {
+        self.config.name()
+    }
+
+    fn object_entry(&self, od_index: u32) -> Option<&SimulatedObjectEntry> {
+        self.object_entries
+            .iter()
+            .find(|oe| oe.config.id() == od_index)
+    }
+
+    /// Fragmented get response, empty if the request isn't addressed to this node.
+    pub fn handle_get_req(&self, req: &GetReq, bad_toggle: bool) -> Vec<GetResp> {
+        if req.server_id != self.id() {
+            return vec![];
+        }
+        let Some(oe) = self.object_entry(req.od_index) else {
+            return vec![];
+        };
+        let fragments = oe.value.len();
+        oe.value
+            .iter()
+            .enumerate()
+            .map(|(i, data)| GetResp {
+                sof: i == 0,
+                eof: i + 1 == fragments,
+                toggle: (i % 2 == 1) != bad_toggle,
+                od_index: req.od_index,
+                client_id: req.client_id,
+                server_id: req.server_id,
+                data: *data,
+            })
+            .collect()
+    }
+
+    /// Set response, once the last fragment was received.
+    pub fn handle_set_req(&mut self, req: &SetReq) -> Option<SetResp> {
+        if req.server_id != self.id() {
+            return None;
+        }
+        let resp = |success: bool| SetResp {
+            od_index: req.od_index,
+            client_id: req.client_id,
+            server_id: req.server_id,
+            success,
+        };
+        if req.sof {
+            self.set_fragments.insert(
+                req.client_id,
+                SetFragments {
+                    od_index: req.od_index,
+                    buffer: vec![],
+                },
+            );
+        }
+        let fragments = self.set_fragments.get_mut(&req.client_id)?;
+        let expected_toggle = fragments.buffer.len() % 2 == 1;
+        if fragments.od_index != req.od_index || expected_toggle != req.toggle {
+            self.set_fragments.remove(&req.client_id);
+            return Some(resp(false));
+        }
+        fragments.buffer.push(req.data);
+        if !req.eof {
+            return None;
+        }
+        let fragments = self.set_fragments.remove(&req.client_id)?;
+        let Some(index) = self
+            .object_entries
+            .iter()
+            .position(|oe| oe.config.id() == req.od_index)
+        else {
+            return Some(resp(false));
+        };
+        let oe = &mut self.object_entries[index];
+        if oe.config.access() == &ObjectEntryAccess::Const
+            || fragments.buffer.len() != oe.value.len()
+        {
+            return Some(resp(false));
+        }
+        oe.value = fragments.buffer;
+        for stream in &mut self.streams {
+            if stream.mappings.iter().any(|(mapped, _)| *mapped == index) {
+                stream.changed = true;
+            }
+        }
+        Some(resp(true))
+    }
+
+    /// Heartbeats and streams, which are due at elapsed.
+    pub fn poll(
+        &mut self,
+        elapsed: Duration,
+        protocol: &Protocol,
+        faults: &FaultScript,
+    ) -> Vec<NetworkFrame> {
+        let mut frames = vec![];
+        if elapsed >= self.next_heartbeat {
+            self.next_heartbeat = elapsed + HEARTBEAT_INTERVAL;
+            if !faults.misses_heartbeat(self.id(), elapsed) {
+                let ticks_next = self
+                    .config
+                    .heartbeat_timeout()
+                    .as_millis()
+                    .div_ceil(HEARTBEAT_TICK.as_millis())
+                    .min(0x7F) as u8;
+                frames.extend(protocol.encode_heartbeats(self.id(), ticks_next));
+            }
+        }
+        for stream in &mut self.streams {
+            if !stream.is_due(elapsed) {
+                continue;
+            }
+            let interval = (*stream.config.max_interval()).max(*stream.config.min_interval());
+            stream.next = elapsed + interval.max(Duration::from_millis(1));
+            stream.last = Some(elapsed);
+            stream.changed = false;
+            let message = stream.config.message();
+            let mut data = protocol::payload(message);
+            for (index, fields) in &stream.mappings {
+                let value = &self.object_entries[*index].value;
+                let mut bit_offset = 0;
+                for field in fields {
+                    field.write(&mut data, read_bits(value, bit_offset, field.size()));
+                    bit_offset += field.size() as usize;
+                }
+            }
+            frames.push(protocol::frame(message, &data));
+        }
+        frames
+    }
+}
+
+fn read_bits(words: &[u32], bit_offset: usize, size: u8) -> u64 {
+    let mut value = 0u64;
+    for i in 0..size as usize {
+        let bit = bit_offset + i;
+        let Some(word) = words.get(bit / 32) else {
+            break;
+        };
+        value |= (((word >> (bit % 32)) & 1) as u64) << i;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use canzero_config::{
+        builder::NetworkBuilder,
+        config::{NetworkRef, ObjectEntryAccess},
+    };
+
+    use super::*;
+
+    const CLIENT_ID: u8 = 7;
+
+    fn network() -> NetworkRef {
+        let network_builder = NetworkBuilder::new();
+        network_builder
+            .create_bus("can0", Some(1000000))
+            .data_baudrate(4000000);
+        let secu = network_builder.create_node("secu");
+        network_builder.create_node("master");
+        secu.create_object_entry("position", "u64");
+        secu.create_object_entry("velocity", "u32");
+        secu.create_object_entry("serial", "u32")
+            .set_access(ObjectEntryAccess::Const);
+        let stream = secu.create_stream("state");
+        stream.add_entry("position");
+        stream.add_entry("velocity");
+        stream.set_interval(Duration::from_millis(10), Duration::from_millis(100));
+        network_builder
+            .build()
+            .expect("failed to build the network")
+    }
+
+    fn secu(network: &NetworkRef) -> SimulatedNode {
+        SimulatedNode::new(
+            network
+                .nodes()
+                .iter()
+                .find(|node| node.name() == "secu")
+                .unwrap(),
+        )
+    }
+
+    fn od_index(node: &SimulatedNode, name: &str) -> u32 {
+        node.config
+            .object_entries()
+            .iter()
+            .find(|oe| oe.name() == name)
+            .unwrap()
+            .id()
+    }
+
+    fn set_req(node: &SimulatedNode, od_index: u32, fragments: &[(bool, u32)]) -> Vec<SetReq> {
+        fragments
+            .iter()
+            .enumerate()
+            .map(|(i, (toggle, data))| SetReq {
+                sof: i == 0,
+                eof: i + 1 == fragments.len(),
+                toggle: *toggle,
+                od_index,
+                client_id: CLIENT_ID,
+                server_id: node.id(),
+                data: *data,
+            })
+            .collect()
+    }
+
+    fn set(node: &mut SimulatedNode, reqs: &[SetReq]) -> Vec<Option<bool>> {
+        reqs.iter()
+            .map(|req| node.handle_set_req(req).map(|resp| resp.success))
+            .collect()
+    }
+
+    fn get(node: &SimulatedNode, od_index: u32, bad_toggle: bool) -> Vec<GetResp> {
+        let req = GetReq {
+            od_index,
+            client_id: CLIENT_ID,
+            server_id: node.id(),
+        };
+        node.handle_get_req(&req, bad_toggle)
+    }
+
+    #[test]
+    fn fragmented_set_and_get() {
+        let network = network();
+        let mut node = secu(&network);
+        let position = od_index(&node, "position");
+        let reqs = set_req(&node, position, &[(false, 0xDEADBEEF), (true, 0x12345678)]);
+        assert_eq!(set(&mut node, &reqs), vec![None, Some(true)]);
+
+        let resps = get(&node, position, false);
+        assert_eq!(resps.len(), 2);
+        let header: Vec<(bool, bool, bool)> = resps
+            .iter()
+            .map(|resp| (resp.sof, resp.eof, resp.toggle))
+            .collect();
+        assert_eq!(header, vec![(true, false, false), (false, true, true)]);
+        assert_eq!(
+            resps.iter().map(|resp| resp.data).collect::<Vec<_>>(),
+            vec![0xDEADBEEF, 0x12345678]
+        );
+        assert!(resps
+            .iter()
+            .all(|resp| resp.client_id == CLIENT_ID && resp.od_index == position));
+
+        let velocity = od_index(&node, "velocity");
+        let resps = get(&node, velocity, false);
+        assert_eq!(resps.len(), 1);
+        assert!(resps[0].sof && resps[0].eof && !resps[0].toggle);
+    }
+
+    #[test]
+    fn bad_toggle_inverts_the_toggle_bits() {
+        let network = network();
+        let node = secu(&network);
+        let position = od_index(&node, "position");
+        let toggles: Vec<bool> = get(&node, position, true)
+            .iter()
+            .map(|resp| resp.toggle)
+            .collect();
+        assert_eq!(toggles, vec![true, false]);
+    }
+
+    #[test]
+    fn requests_to_other_nodes_are_ignored() {
+        let network = network();
+        let mut node = secu(&network);
+        let position = od_index(&node, "position");
+        let get_req = GetReq {
+            od_index: position,
+            client_id: CLIENT_ID,
+            server_id: node.id() + 1,
+        };
+        assert!(node.handle_get_req(&get_req, false).is_empty());
+        let mut reqs = set_req(&node, position, &[(false, 1), (true, 2)]);
+        for req in &mut reqs {
+            req.server_id = node.id() + 1;
+        }
+        assert_eq!(set(&mut node, &reqs), vec![None, None]);
+    }
+
+    #[test]
+    fn set_with_a_wrong_toggle_bit_is_rejected() {
+        let network = network();
+        let mut node = secu(&network);
+        let position = od_index(&node, "position");
+        let reqs = set_req(&node, position, &[(false, 1), (false, 2)]);
+        assert_eq!(set(&mut node, &reqs), vec![None, Some(false)]);
+        let values: Vec<u32> = get(&node, position, false)
+            .iter()
+            .map(|resp| resp.data)
+            .collect();
+        assert_eq!(values, vec![0, 0]);
+        // fragments without a start of frame are dropped.
+        assert_eq!(set(&mut node, &reqs[1..]), vec![None]);
+    }
+
+    #[test]
+    fn set_of_const_or_with_the_wrong_length_is_rejected() {
+        let network = network();
+        let mut node = secu(&network);
+        let serial = od_index(&node, "serial");
+        let reqs = set_req(&node, serial, &[(false, 1)]);
+        assert_eq!(set(&mut node, &reqs), vec![Some(false)]);
+        let position = od_index(&node, "position");
+        let reqs = set_req(&node, position, &[(false, 1)]);
+        assert_eq!(set(&mut node, &reqs), vec![Some(false)]);
+    }
+
+    fn stream_frames(
+        node: &mut SimulatedNode,
+        elapsed: Duration,
+        protocol: &Protocol,
+    ) -> Vec<NetworkFrame> {
+        let message = node.streams[0].config.message().clone();
+        node.poll(elapsed, protocol, &FaultScript::default())
+            .into_iter()
+            .filter(|frame| protocol::matches(&message, frame))
+            .collect()
+    }
+
+    #[test]
+    fn fd_streams_carry_signals_beyond_64_bits() {
+        let network = network();
+        let protocol = Protocol::new(&network);
+        let mut node = secu(&network);
+        let position = od_index(&node, "position");
+        let velocity = od_index(&node, "velocity");
+        let reqs = set_req(&node, position, &[(false, 0xDEADBEEF), (true, 0x12345678)]);
+        assert_eq!(set(&mut node, &reqs), vec![None, Some(true)]);
+        let reqs = set_req(&node, velocity, &[(false, 0xCAFEBABE)]);
+        assert_eq!(set(&mut node, &reqs), vec![Some(true)]);
+
+        let frames = stream_frames(&mut node, Duration::ZERO, &protocol);
+        assert_eq!(frames.len(), 1);
+        let can_frame = &frames[0].can_frame;
+        assert!(can_frame.is_fd());
+        assert!(can_frame.get_brs_flag());
+        let data = can_frame.get_data();
+        assert_eq!(data.len(), 12);
+        assert_eq!(
+            u64::from_le_bytes(data[..8].try_into().unwrap()),
+            0x12345678_DEADBEEF
+        );
+        assert_eq!(
+            u32::from_le_bytes(data[8..12].try_into().unwrap()),
+            0xCAFEBABE
+        );
+    }
+
+    #[test]
+    fn streams_are_sent_every_max_interval_and_after_a_set() {
+        let network = network();
+        let protocol = Protocol::new(&network);
+        let mut node = secu(&network);
+        let at = Duration::from_millis;
+        assert_eq!(stream_frames(&mut node, at(0), &protocol).len(), 1);
+        assert!(stream_frames(&mut node, at(50), &protocol).is_empty());
+        assert_eq!(stream_frames(&mut node, at(100), &protocol).len(), 1);
+
+        let velocity = od_index(&node, "velocity");
+        let reqs = set_req(&node, velocity, &[(false, 42)]);
+        assert_eq!(set(&mut node, &reqs), vec![Some(true)]);
+        // not before min_interval.
+        assert!(stream_frames(&mut node, at(105), &protocol).is_empty());
+        assert_eq!(stream_frames(&mut node, at(110), &protocol).len(), 1);
+        assert!(stream_frames(&mut node, at(150), &protocol).is_empty());
+        assert_eq!(stream_frames(&mut node, at(210), &protocol).len(), 1);
+
+        // object entries which aren't mapped don't trigger the stream.
+        let serial = od_index(&node, "serial");
+        let reqs = set_req(&node, serial, &[(false, 1)]);
+        set(&mut node, &reqs);
+        assert!(stream_frames(&mut node, at(250), &protocol).is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use canzero_common::{CanFrame, NetworkFrame};
+use canzero_config::config::{MessageRef, NetworkRef, SignalRef, TypeSignalEncoding};
+
+/// Location of a primitive signal within the payload of a frame.
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    offset: u32,
+    size: u8,
+}
+
+impl Field {
+    pub fn new(signal: &SignalRef) -> Self {
+        Self {
+            // NOTE byte_offset is actually the bit offset.
+            offset: signal.byte_offset() as u32,
+            size: signal.size(),
+        }
+    }
+
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    /// Bits outside of data are read as zeros.
+    pub fn read(&self, data: &[u8]) -> u64 {
+        let mut value = 0u64;
+        for i in 0..self.size as usize {
+            let bit = self.offset as usize + i;
+            let Some(byte) = data.get(bit / 8) else {
+                break;
+            };
+            value |= (((byte >> (bit % 8)) & 1) as u64) << i;
+        }
+        value
+    }
+
+    /// Panics if the field exceeds data.
+    pub fn write(&self, data: &mut [u8], value: u64) {
+        for i in 0..self.size as usize {
+            let bit = self.offset as usize + i;
+            if (value >> i) & 1 != 0 {
+                data[bit / 8] |= 1 << (bit % 8);
+            } else {
+                data[bit / 8] &= !(1 << (bit % 8));
+            }
+        }
+    }
+}
+
+/// Flattens the signals of an encoded attribute, in the order
+/// in which the attribute type is serialized in get and set requests.
+pub fn flatten(encoding: &TypeSignalEncoding) -> Vec<Field> {
+    match encoding {
+        TypeSignalEncoding::Primitive(primitive) => vec![Field::new(primitive.signal())],
+        TypeSignalEncoding::Composite(composite) => {
+            composite.attributes().iter().flat_map(flatten).collect()
+        }
+    }
+}
+
+fn find_signal(attributes: &[TypeSignalEncoding], path: &[&str]) -> Option<SignalRef> {
+    let (name, rest) = path.split_first()?;
+    match attributes.iter().find(|attrib| attrib.name() == *name)? {
+        TypeSignalEncoding::Primitive(primitive) if rest.is_empty() => {
+            Some(primitive.signal().clone())
+        }
+        TypeSignalEncoding::Composite(composite) => find_signal(composite.attributes(), rest),
+        _ => None,
+    }
+}
+
+fn field(message: &MessageRef, path: &str) -> Field {
+    let Some(encoding) = message.encoding() else {
+        panic!(
+            "DETECTED INVALID CONFIG: {} is expected to define a encoding",
+            message.name()
+        );
+    };
+    let path: Vec<&str> = path.split('.').collect();
+    let Some(signal) = find_signal(encoding.attributes(), &path) else {
+        panic!(
+            "DETECTED INVALID CONFIG: invalid format of {} : {} missing",
+            message.name(),
+            path.join(".")
+        );
+    };
+    Field::new(&signal)
+}
+
+pub fn matches(message: &MessageRef, frame: &NetworkFrame) -> bool {
+    frame.bus_id == message.bus().id()
+        && frame.can_frame.get_id() == message.id().as_u32()
+        && frame.can_frame.get_ide_flag() == message.id().ide()
+}
+
+/// Zeroed payload of the message.
+pub fn payload(message: &MessageRef) -> Vec<u8> {
+    vec![0; message.dlc() as usize]
+}
+
+/// Messages with more than 8 bytes are transmitted as CAN FD frames,
+/// with bit rate switching if the bus defines a data baudrate.
+pub fn frame(message: &MessageRef, data: &[u8]) -> NetworkFrame {
+    let id = message.id().as_u32();
+    let ide = message.id().ide();
+    let can_frame = if message.dlc() > 8 {
+        let brs = message.bus().fd() && message.bus().data_baudrate().is_some();
+        CanFrame::new_fd(id, ide, brs, false, data)
+    } else {
+        let mut first = [0u8; 8];
+        first[..data.len()].copy_from_slice(data);
+        CanFrame::new(id, ide, false, message.dlc(), u64::from_le_bytes(first))
+    };
+    NetworkFrame {
+        bus_id: message.bus().id(),
+        can_frame,
+    }
+}
+
+pub struct GetReq {
+    pub od_index: u32,
+    pub client_id: u8,
+    pub server_id: u8,
+}
+
+pub struct GetResp {
+    pub sof: bool,
+    pub eof: bool,
+    pub toggle: bool,
+    pub od_index: u32,
+    pub client_id: u8,
+    pub server_id: u8,
+    pub data: u32,
+}
+
+pub struct SetReq {
+    pub sof: bool,
+    pub eof: bool,
+    pub toggle: bool,
+    pub od_index: u32,
+    pub client_id: u8,
+    pub server_id: u8,
+    pub data: u32,
+}
+
+pub struct SetResp {
+    pub od_index: u32,
+    pub client_id: u8,
+    pub server_id: u8,
+    pub success: bool,
+}
+
+struct FragmentHeader {
+    sof: Field,
+    eof: Field,
+    toggle: Field,
+    od_index: Field,
+    client_id: Field,
+    server_id: Field,
+    data: Field,
+}
+
+impl FragmentHeader {
+    fn new(message: &MessageRef) -> Self {
+        Self {
+            sof: field(message, "header.sof"),
+            eof: field(message, "header.eof"),
+            toggle: field(message, "header.toggle"),
+            od_index: field(message, "header.od_index"),
+            client_id: field(message, "header.client_id"),
+            server_id: field(message, "header.server_id"),
+            data: field(message, "data"),
+        }
+    }
+}
+
+/// Encoding of the get/set protocol and the heartbeats,
+/// resolved from the message encodings of the network config.
+pub struct Protocol {
+    get_req: (MessageRef, Field, Field, Field),
+    get_resp: (MessageRef, FragmentHeader),
+    set_req: (MessageRef, FragmentHeader),
+    set_resp: (MessageRef, Field, Field, Field, Field),
+    heartbeats: Vec<(MessageRef, Field, Field, Field)>,
+}
+
+impl Protocol {
+    pub fn new(config: &NetworkRef) -> Self {
+        let get_req = config.get_req_message();
+        let get_resp = config.get_resp_message();
+        let set_req = config.set_req_message();
+        let set_resp = config.set_resp_message();
+        Self {
+            get_req: (
+                get_req.clone(),
+                field(get_req, "header.od_index"),
+                field(get_req, "header.client_id"),
+                field(get_req, "header.server_id"),
+            ),
+            get_resp: (get_resp.clone(), FragmentHeader::new(get_resp)),
+            set_req: (set_req.clone(), FragmentHeader::new(set_req)),
+            set_resp: (
+                set_resp.clone(),
+                field(set_resp, "header.od_index"),
+                field(set_resp, "header.client_id"),
+                field(set_resp, "header.server_id"),
+                field(set_resp, "header.erno"),
+            ),
+            heartbeats: config
+                .heartbeat_messages()
+                .iter()
+                .map(|heartbeat| {
+                    (
+                        heartbeat.clone(),
+                        field(heartbeat, "node_id"),
+                        field(heartbeat, "unregister"),
+                        field(heartbeat, "ticks_next"),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn decode_get_req(&self, frame: &NetworkFrame) -> Option<GetReq> {
+        let (message, od_index, client_id, server_id) = &self.get_req;
+        if !matches(message, frame) {
+            return None;
+        }
+        let data = frame.can_frame.get_data();
+        Some(GetReq {
+            od_index: od_index.read(&data) as u32,
+            client_id: client_id.read(&data) as u8,
+            server_id: server_id.read(&data) as u8,
+        })
+    }
+
+    pub fn encode_get_resp(&self, resp: &GetResp) -> NetworkFrame {
+        let (message, header) = &self.get_resp;
+        let mut data = payload(message);
+        header.sof.write(&mut data, resp.sof as u64);
+        header.eof.write(&mut data, resp.eof as u64);
+        header.toggle.write(&mut data, resp.toggle as u64);
+        header.od_index.write(&mut data, resp.od_index as u64);
+        header.client_id.write(&mut data, resp.client_id as u64);
+        header.server_id.write(&mut data, resp.server_id as u64);
+        header.data.write(&mut data, resp.data as u64);
+        frame(message, &data)
+    }
+
+    pub fn decode_set_req(&self, frame: &NetworkFrame) -> Option<SetReq> {
+        let (message, header) = &self.set_req;
+        if !matches(message, frame) {
+            return None;
+        }
+        let data = frame.can_frame.get_data();
+        Some(SetReq {
+            sof: header.sof.read(&data) != 0,
+            eof: header.eof.read(&data) != 0,
+            toggle: header.toggle.read(&data) != 0,
+            od_index: header.od_index.read(&data) as u32,
+            client_id: header.client_id.read(&data) as u8,
+            server_id: header.server_id.read(&data) as u8,
+            data: header.data.read(&data) as u32,
+        })
+    }
+
+    pub fn encode_set_resp(&self, resp: &SetResp) -> NetworkFrame {
+        let (message, od_index, client_id, server_id, erno) = &self.set_resp;
+        let mut data = payload(message);
+        od_index.write(&mut data, resp.od_index as u64);
+        client_id.write(&mut data, resp.client_id as u64);
+        server_id.write(&mut data, resp.server_id as u64);
+        // set_resp_erno : Success = 0, Error = 1
+        erno.write(&mut data, if resp.success { 0 } else { 1 });
+        frame(message, &data)
+    }
+
+    /// One heartbeat per bus.
+    pub fn encode_heartbeats(&self, node_id: u8, ticks_next: u8) -> Vec<NetworkFrame> {
+        self.heartbeats
+            .iter()
+            .map(|(message, node_id_field, unregister, ticks_next_field)| {
+                let mut data = payload(message);
+                node_id_field.write(&mut data, node_id as u64);
+                unregister.write(&mut data, 0);
+                ticks_next_field.write(&mut data, ticks_next as u64);
+                frame(message, &data)
+            })
+            .collect()
+    }
+}
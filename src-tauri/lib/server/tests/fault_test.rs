@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use canzero_common::CanError;
+use canzero_config::{builder::NetworkBuilder, config::NetworkRef};
+use canzero_server::simulation::fault::{FaultKind, FaultScript};
+
+fn network() -> NetworkRef {
+    let network_builder = NetworkBuilder::new();
+    network_builder.create_bus("can0", Some(1000000));
+    network_builder.create_node("secu");
+    network_builder.create_node("master");
+    network_builder
+        .build()
+        .expect("failed to build the network")
+}
+
+fn node_id(network: &NetworkRef, name: &str) -> u8 {
+    network
+        .nodes()
+        .iter()
+        .find(|node| node.name() == name)
+        .unwrap()
+        .id()
+}
+
+#[test]
+fn faults_are_sorted_by_time() {
+    let network = network();
+    let script = FaultScript::parse(
+        "# <at>  <fault>  <node or bus>  [duration or error type]\n\
+         12s  error-frame     can0  crc\n\
+         \n\
+         5s   miss-heartbeat  secu  2s   # comment\n\
+         1.5s bad-toggle      secu  500ms\n\
+         20s  error-frame     can0\n",
+        &network,
+    )
+    .unwrap();
+    let secu = node_id(&network, "secu");
+    let faults = script.faults();
+    assert_eq!(faults.len(), 4);
+    assert_eq!(
+        faults.iter().map(|fault| fault.at).collect::<Vec<_>>(),
+        vec![
+            Duration::from_millis(1500),
+            Duration::from_secs(5),
+            Duration::from_secs(12),
+            Duration::from_secs(20),
+        ]
+    );
+    assert!(matches!(faults[0].kind, FaultKind::BadToggle { node_id } if node_id == secu));
+    assert_eq!(faults[0].duration, Duration::from_millis(500));
+    assert!(matches!(faults[1].kind, FaultKind::MissHeartbeat { node_id } if node_id == secu));
+    assert_eq!(faults[1].duration, Duration::from_secs(2));
+    let bus_id = network.buses()[0].id();
+    assert!(matches!(
+        faults[2].kind,
+        FaultKind::ErrorFrame { bus_id: id, can_error: CanError(0x10) } if id == bus_id
+    ));
+    // error frames default to bit errors.
+    assert!(matches!(
+        faults[3].kind,
+        FaultKind::ErrorFrame {
+            can_error: CanError(0x1),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn faults_are_active_for_their_duration() {
+    let network = network();
+    let script = FaultScript::parse(
+        "5s miss-heartbeat secu 2s\n10s bad-toggle secu 500ms",
+        &network,
+    )
+    .unwrap();
+    let secu = node_id(&network, "secu");
+    let master = node_id(&network, "master");
+    let at = Duration::from_millis;
+    assert!(!script.misses_heartbeat(secu, at(4999)));
+    assert!(script.misses_heartbeat(secu, at(5000)));
+    assert!(script.misses_heartbeat(secu, at(6999)));
+    assert!(!script.misses_heartbeat(secu, at(7000)));
+    assert!(!script.misses_heartbeat(master, at(6000)));
+
+    assert!(!script.has_bad_toggle(secu, at(6000)));
+    assert!(script.has_bad_toggle(secu, at(10250)));
+    assert!(!script.has_bad_toggle(secu, at(10500)));
+}
+
+#[test]
+fn errors_report_their_line() {
+    let network = network();
+    for (src, line, reason) in [
+        (
+            "5s miss-heartbeat",
+            1,
+            "expected <at> <fault> <node or bus>",
+        ),
+        (
+            "\n# comment\nfive miss-heartbeat secu 2s",
+            3,
+            "invalid time \"five\"",
+        ),
+        ("5s miss-heartbeat bms 2s", 1, "node \"bms\" is not defined"),
+        (
+            "5s miss-heartbeat secu",
+            1,
+            "miss-heartbeat requires a duration",
+        ),
+        ("5s bad-toggle secu -1s", 1, "invalid duration \"-1s\""),
+        ("5s error-frame can1", 1, "bus \"can1\" is not defined"),
+        (
+            "5s error-frame can0 parity",
+            1,
+            "unknown error type \"parity\"",
+        ),
+        ("5s reboot secu", 1, "unknown fault \"reboot\""),
+        ("5s miss-heartbeat secu 2s 3s", 1, "unexpected \"3s\""),
+    ] {
+        let err = FaultScript::parse(src, &network).expect_err(src);
+        assert_eq!((err.line, err.reason.as_str()), (line, reason), "{src:?}");
+    }
+}
@@ -0,0 +1,194 @@
+use std::{fs::File, io::BufReader, path::PathBuf, time::Duration};
+
+use canzero_common::{
+    recording::{recording_file_name, RecordingHeader, RecordingReader, FRAME_SIZE, HEADER_SIZE},
+    CanFrame, NetworkFrame, TNetworkFrame,
+};
+use canzero_server::network::recorder::{Recorder, RecorderLimits, MIN_FILE_SIZE};
+
+const HEADER: RecordingHeader = RecordingHeader {
+    config_hash: 0xDEAD_BEEF,
+    timebase_us: 1_700_000_000_000_000,
+};
+
+fn frame(timestamp_us: u64, can_frame: CanFrame) -> TNetworkFrame {
+    TNetworkFrame::new(
+        Duration::from_micros(timestamp_us),
+        NetworkFrame {
+            bus_id: 0,
+            can_frame,
+        },
+    )
+}
+
+fn recordings(dir: &tempfile::TempDir) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn limits(max_file_size: u64) -> RecorderLimits {
+    RecorderLimits {
+        max_file_size,
+        ..Default::default()
+    }
+}
+
+fn read(path: &PathBuf) -> Vec<TNetworkFrame> {
+    let reader = RecordingReader::open(BufReader::new(File::open(path).unwrap())).unwrap();
+    assert_eq!(reader.header(), &HEADER);
+    reader.map(Result::unwrap).collect()
+}
+
+#[tokio::test]
+async fn recordings_are_rotated_and_read_back() {
+    let dir = tempfile::tempdir().unwrap();
+    // two classical frames fit into a file.
+    let recorder = Recorder::start(
+        dir.path(),
+        limits((HEADER_SIZE + 2 * FRAME_SIZE) as u64),
+        HEADER.clone(),
+    )
+    .unwrap();
+    let frames: Vec<TNetworkFrame> = (0..5)
+        .map(|i| frame(i * 100, CanFrame::new(0x100 + i as u32, false, false, 8, i)))
+        .collect();
+    for f in &frames {
+        recorder.record(f);
+    }
+    recorder.finish().await;
+
+    let paths = recordings(&dir);
+    assert_eq!(paths.len(), 3, "{paths:?}");
+    let files: Vec<Vec<TNetworkFrame>> = paths.iter().map(read).collect();
+    assert_eq!(
+        files.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    let read: Vec<TNetworkFrame> = files.into_iter().flatten().collect();
+    for (a, b) in read.iter().zip(&frames) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert_eq!(a.can_frame.get_data_u64(), b.can_frame.get_data_u64());
+    }
+}
+
+#[tokio::test]
+async fn oversized_frames_never_produce_empty_recordings() {
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = Recorder::start(dir.path(), limits(MIN_FILE_SIZE), HEADER.clone()).unwrap();
+    let fd_frame = CanFrame::new_fd(0x123, false, true, false, &[0xAB; 64]);
+    recorder.record(&frame(0, fd_frame.clone()));
+    recorder.record(&frame(1, fd_frame));
+    recorder.record(&frame(2, CanFrame::new(0x124, false, false, 1, 1)));
+    recorder.finish().await;
+
+    let lens: Vec<usize> = recordings(&dir).iter().map(|p| read(p).len()).collect();
+    assert_eq!(lens, vec![1, 1, 1]);
+}
+
+#[tokio::test]
+async fn too_small_file_sizes_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = Recorder::start(dir.path(), limits(MIN_FILE_SIZE - 1), HEADER.clone())
+        .err()
+        .expect("a file size below a single frame was accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(recordings(&dir).is_empty());
+}
+
+#[tokio::test]
+async fn oldest_recordings_are_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    // recording of an older session and a file, which isn't a recording.
+    let older_session = RecordingHeader {
+        config_hash: HEADER.config_hash,
+        timebase_us: HEADER.timebase_us - 60_000_000,
+    };
+    let older_recording = dir.path().join(recording_file_name(&older_session, 0));
+    std::fs::write(&older_recording, b"").unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&notes, "keep me").unwrap();
+
+    let recorder = Recorder::start(
+        dir.path(),
+        RecorderLimits {
+            max_file_size: (HEADER_SIZE + 2 * FRAME_SIZE) as u64,
+            max_file_count: 2,
+            ..Default::default()
+        },
+        HEADER.clone(),
+    )
+    .unwrap();
+    let frames: Vec<TNetworkFrame> = (0..5)
+        .map(|i| frame(i * 100, CanFrame::new(0x100 + i as u32, false, false, 8, i)))
+        .collect();
+    for f in &frames {
+        recorder.record(f);
+    }
+    recorder.finish().await;
+
+    assert!(!older_recording.exists());
+    assert!(notes.exists());
+    let paths: Vec<PathBuf> = recordings(&dir)
+        .into_iter()
+        .filter(|path| path != &notes)
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            dir.path().join(recording_file_name(&HEADER, 1)),
+            dir.path().join(recording_file_name(&HEADER, 2)),
+        ]
+    );
+    let read: Vec<TNetworkFrame> = paths.iter().flat_map(read).collect();
+    assert_eq!(read.len(), 3);
+    for (a, b) in read.iter().zip(&frames[2..]) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+    }
+}
+
+#[tokio::test]
+async fn frames_are_dropped_if_the_queue_is_full() {
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = Recorder::start(
+        dir.path(),
+        RecorderLimits {
+            queue_capacity: 1,
+            ..Default::default()
+        },
+        HEADER.clone(),
+    )
+    .unwrap();
+    const FRAME_COUNT: u64 = 10_000;
+    // recording never blocks, such that the writer falls behind.
+    for i in 0..FRAME_COUNT {
+        recorder.record(&frame(i, CanFrame::new(0x100, false, false, 8, i)));
+    }
+    let dropped = recorder.dropped_frames();
+    recorder.finish().await;
+
+    assert!(dropped > 0);
+    let written: usize = recordings(&dir).iter().map(|p| read(p).len()).sum();
+    assert_eq!(written as u64 + dropped, FRAME_COUNT);
+}
+
+#[tokio::test]
+async fn zero_file_counts_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = Recorder::start(
+        dir.path(),
+        RecorderLimits {
+            max_file_count: 0,
+            ..Default::default()
+        },
+        HEADER.clone(),
+    )
+    .err()
+    .expect("a file count of zero was accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
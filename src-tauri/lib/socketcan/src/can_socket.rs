@@ -1,12 +1,32 @@
 
 use std::mem;
 
-use libc::{c_int, c_void, can_frame, read, sa_family_t, sockaddr_can, write};
+use libc::{c_int, c_void, can_frame, canfd_frame, read, sa_family_t, sockaddr_can, write};
 pub use libc::{
-    AF_CAN, CAN_EFF_FLAG, CAN_EFF_MASK, CAN_ERR_FLAG, CAN_RAW, CAN_RTR_FLAG, CAN_SFF_MASK, PF_CAN,
-    SOCK_RAW,
+    AF_CAN, CANFD_BRS, CANFD_ESI, CANFD_MTU, CAN_EFF_FLAG, CAN_EFF_MASK, CAN_ERR_FLAG,
+    CAN_ERR_MASK, CAN_MTU, CAN_RAW, CAN_RAW_ERR_FILTER, CAN_RAW_FD_FRAMES, CAN_RTR_FLAG,
+    CAN_SFF_MASK, PF_CAN, SOCK_RAW, SOL_CAN_RAW,
 };
 
+// error classes and details of error frames (see linux/can/error.h).
+const CAN_ERR_CRTL: u32 = 0x4;
+const CAN_ERR_PROT: u32 = 0x8;
+const CAN_ERR_ACK: u32 = 0x20;
+const CAN_ERR_BUSOFF: u32 = 0x40;
+const CAN_ERR_RESTARTED: u32 = 0x100;
+const CAN_ERR_CNT: u32 = 0x200;
+const CAN_ERR_CRTL_RX_WARNING: u8 = 0x04;
+const CAN_ERR_CRTL_TX_WARNING: u8 = 0x08;
+const CAN_ERR_CRTL_RX_PASSIVE: u8 = 0x10;
+const CAN_ERR_CRTL_TX_PASSIVE: u8 = 0x20;
+const CAN_ERR_PROT_BIT: u8 = 0x01;
+const CAN_ERR_PROT_FORM: u8 = 0x02;
+const CAN_ERR_PROT_STUFF: u8 = 0x04;
+const CAN_ERR_PROT_BIT0: u8 = 0x08;
+const CAN_ERR_PROT_BIT1: u8 = 0x10;
+const CAN_ERR_PROT_LOC_CRC_SEQ: u8 = 0x08;
+const CAN_ERR_PROT_LOC_CRC_DEL: u8 = 0x18;
+
 use canzero_common::{CanError, CanFrame};
 
 #[derive(Clone, Debug)]
@@ -33,10 +53,39 @@ impl CanSocket {
         if ret == -1 {
             let err = std::io::Error::last_os_error();
             unsafe { libc::close(fd) };
-            Err(err)
-        } else {
-            Ok(CanSocket { fd })
+            return Err(err);
+        }
+
+        // interfaces without CAN FD support (or old kernels) reject this option,
+        // in which case only classical frames are received.
+        let enable: c_int = 1;
+        unsafe {
+            libc::setsockopt(
+                fd,
+                SOL_CAN_RAW,
+                CAN_RAW_FD_FRAMES,
+                (&enable as *const c_int).cast(),
+                mem::size_of::<c_int>() as u32,
+            )
+        };
+        // error frames are only delivered for the error classes of this mask.
+        let err_mask: u32 = CAN_ERR_MASK;
+        let ret = unsafe {
+            libc::setsockopt(
+                fd,
+                SOL_CAN_RAW,
+                CAN_RAW_ERR_FILTER,
+                (&err_mask as *const u32).cast(),
+                mem::size_of::<u32>() as u32,
+            )
+        };
+        if ret == -1 {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(err);
         }
+
+        Ok(CanSocket { fd })
     }
     pub fn close(&mut self) {
         unsafe {
@@ -44,36 +93,51 @@ impl CanSocket {
         }
     }
     pub fn receive(&self) -> Result<Result<CanFrame, CanError>, std::io::Error> {
-        let mut frame: can_frame = unsafe { mem::zeroed() };
-        let n = mem::size_of::<can_frame>();
+        // can_frame and canfd_frame share the same layout up to the data,
+        // the size of the read tells them apart.
+        let mut frame: canfd_frame = unsafe { mem::zeroed() };
 
-        let rd = unsafe { read(self.fd, &mut frame as *mut _ as *mut c_void, n) };
+        let rd = unsafe {
+            read(
+                self.fd,
+                &mut frame as *mut _ as *mut c_void,
+                mem::size_of::<canfd_frame>(),
+            )
+        };
 
-        if rd as usize == n {
-            // parse can_frame into CanFrame
-            if frame.can_id & CAN_ERR_FLAG != 0 {
-                return Ok(Err(unsafe { std::mem::transmute(frame.data) }));
-            } else {
-                Ok(Ok(frame_from_socket_can_frame(&frame)))
+        if rd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        match rd as usize {
+            CAN_MTU => {
+                let frame: can_frame = unsafe { mem::transmute_copy(&frame) };
+                if frame.can_id & CAN_ERR_FLAG != 0 {
+                    Ok(Err(error_from_socket_can_frame(&frame)))
+                } else {
+                    Ok(Ok(frame_from_socket_can_frame(&frame)))
+                }
             }
-        } else {
-            Err(std::io::Error::last_os_error())
+            CANFD_MTU => Ok(Ok(frame_from_socket_canfd_frame(&frame))),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Incomplete CAN frame of {rd} bytes"),
+            )),
         }
     }
 
     pub fn transmit(&self, frame: &CanFrame) -> Result<(), std::io::Error> {
         let fd = self.fd;
-        let canframe = frame_to_socket_can_frame(frame);
-
-        let ret = unsafe {
-            write(
-                fd,
-                (&canframe as *const can_frame).cast(),
-                mem::size_of::<can_frame>(),
-            )
+        let ret = if frame.is_fd() {
+            let canframe = frame_to_socket_canfd_frame(frame);
+            let ret = unsafe { write(fd, (&canframe as *const canfd_frame).cast(), CANFD_MTU) };
+            (ret, CANFD_MTU)
+        } else {
+            let canframe = frame_to_socket_can_frame(frame);
+            let ret = unsafe { write(fd, (&canframe as *const can_frame).cast(), CAN_MTU) };
+            (ret, CAN_MTU)
         };
 
-        if ret as usize == mem::size_of::<can_frame>() {
+        if ret.0 >= 0 && ret.0 as usize == ret.1 {
             Ok(())
         } else {
             Err(std::io::Error::last_os_error())
@@ -81,31 +145,93 @@ impl CanSocket {
     }
 }
 
-pub fn frame_from_socket_can_frame(frame: &can_frame) -> CanFrame {
-    let ide = (frame.can_id & CAN_EFF_FLAG) != 0;
+fn split_can_id(can_id: u32) -> (u32, bool) {
+    let ide = (can_id & CAN_EFF_FLAG) != 0;
     let id = if ide {
-        frame.can_id & CAN_EFF_MASK
+        can_id & CAN_EFF_MASK
     } else {
-        frame.can_id & CAN_SFF_MASK
+        can_id & CAN_SFF_MASK
     };
+    (id, ide)
+}
+
+fn join_can_id(frame: &CanFrame) -> u32 {
+    if frame.get_ide_flag() {
+        CAN_EFF_FLAG | (frame.get_id() & CAN_EFF_MASK)
+    } else {
+        frame.get_id() & CAN_SFF_MASK
+    }
+}
+
+pub fn frame_from_socket_can_frame(frame: &can_frame) -> CanFrame {
+    let (id, ide) = split_can_id(frame.can_id);
     CanFrame::new(
         id,
+        ide,
         false,
         // (frame.can_id & CAN_RTR_FLAG) != 0,
-        ide,
         frame.can_dlc,
         u64::from_le_bytes(frame.data),
     )
 }
 
+/// Maps the error frame onto the controller independent CanError.
+pub fn error_from_socket_can_frame(frame: &can_frame) -> CanError {
+    let class = frame.can_id & CAN_ERR_MASK;
+    let mut flags = 0;
+    if class & CAN_ERR_CRTL != 0 {
+        let status = frame.data[1];
+        if status & (CAN_ERR_CRTL_RX_WARNING | CAN_ERR_CRTL_TX_WARNING) != 0 {
+            flags |= CanError::ERROR_WARNING;
+        }
+        if status & (CAN_ERR_CRTL_RX_PASSIVE | CAN_ERR_CRTL_TX_PASSIVE) != 0 {
+            flags |= CanError::ERROR_PASSIVE;
+        }
+    }
+    if class & CAN_ERR_PROT != 0 {
+        let kind = frame.data[2];
+        if kind & (CAN_ERR_PROT_BIT | CAN_ERR_PROT_BIT0 | CAN_ERR_PROT_BIT1) != 0 {
+            flags |= CanError::BIT_ERROR;
+        }
+        if kind & CAN_ERR_PROT_FORM != 0 {
+            flags |= CanError::FORM_ERROR;
+        }
+        if kind & CAN_ERR_PROT_STUFF != 0 {
+            flags |= CanError::STUFF_ERROR;
+        }
+        let location = frame.data[3];
+        if location == CAN_ERR_PROT_LOC_CRC_SEQ || location == CAN_ERR_PROT_LOC_CRC_DEL {
+            flags |= CanError::CRC_ERROR;
+        }
+    }
+    if class & CAN_ERR_ACK != 0 {
+        flags |= CanError::ACK_ERROR;
+    }
+    if class & CAN_ERR_BUSOFF != 0 {
+        flags |= CanError::BUS_OFF;
+    }
+    if class & CAN_ERR_RESTARTED != 0 {
+        flags |= CanError::RESTARTED;
+    }
+    let counters = (class & CAN_ERR_CNT != 0).then_some((frame.data[6], frame.data[7]));
+    CanError::new(flags, counters)
+}
+
+pub fn frame_from_socket_canfd_frame(frame: &canfd_frame) -> CanFrame {
+    let (id, ide) = split_can_id(frame.can_id);
+    let len = (frame.len as usize).min(frame.data.len());
+    CanFrame::new_fd(
+        id,
+        ide,
+        frame.flags as c_int & CANFD_BRS != 0,
+        frame.flags as c_int & CANFD_ESI != 0,
+        &frame.data[..len],
+    )
+}
+
 pub fn frame_to_socket_can_frame(frame: &CanFrame) -> can_frame {
     let mut canframe: can_frame = unsafe { std::mem::zeroed() };
-    if frame.get_ide_flag() {
-        canframe.can_id |= CAN_EFF_FLAG;
-        canframe.can_id |= frame.get_id() & CAN_SFF_MASK;
-    } else {
-        canframe.can_id |= frame.get_id() & CAN_EFF_MASK;
-    }
+    canframe.can_id = join_can_id(frame);
     // if frame.get_rtr_flag() {
     //     canframe.can_id |= CAN_RTR_FLAG;
     // }
@@ -113,3 +239,18 @@ pub fn frame_to_socket_can_frame(frame: &CanFrame) -> can_frame {
     canframe.data = frame.get_data_8u8();
     canframe
 }
+
+pub fn frame_to_socket_canfd_frame(frame: &CanFrame) -> canfd_frame {
+    let mut canframe: canfd_frame = unsafe { std::mem::zeroed() };
+    canframe.can_id = join_can_id(frame);
+    let data = frame.get_data();
+    canframe.len = data.len() as u8;
+    canframe.data[..data.len()].copy_from_slice(&data);
+    if frame.get_brs_flag() {
+        canframe.flags |= CANFD_BRS as u8;
+    }
+    if frame.get_esi_flag() {
+        canframe.flags |= CANFD_ESI as u8;
+    }
+    canframe
+}
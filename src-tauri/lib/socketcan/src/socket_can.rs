@@ -1,6 +1,9 @@
 use std::time::Instant;
 
-use canzero_common::{NetworkFrame, TNetworkFrame};
+use canzero_common::{
+    transport::{BoxFuture, CanTransport, TransportBus},
+    NetworkError, NetworkFrame, TNetworkError, TNetworkFrame,
+};
 use color_print::cprintln;
 use interfaces::Interface;
 use tokio::sync::mpsc;
@@ -9,13 +12,14 @@ use crate::can_socket::CanSocket;
 
 #[derive(Debug)]
 pub struct SocketCan {
-    sockets: Vec<CanSocket>,
-    rx: tokio::sync::Mutex<mpsc::Receiver<TNetworkFrame>>,
+    buses: Vec<TransportBus>,
+    sockets: Vec<(u32, CanSocket)>,
+    rx: tokio::sync::Mutex<mpsc::Receiver<Result<TNetworkFrame, TNetworkError>>>,
 }
 
 impl SocketCan {
+    /// Opens all can* interfaces, the n-th interface (sorted by name) becomes bus n.
     pub async fn connect() -> std::io::Result<Self> {
-        let mut sockets: Vec<CanSocket> = vec![];
         let mut ifnames: Vec<String> = vec![];
         for interface in Interface::get_all().unwrap() {
             let ifname = &interface.name;
@@ -24,31 +28,43 @@ impl SocketCan {
             }
         }
         ifnames.sort();
-        for ifname in ifnames {
-            sockets.push(CanSocket::open(&ifname)?);
+        let buses = ifnames
+            .iter()
+            .enumerate()
+            .map(|(bus_id, ifname)| TransportBus::new(bus_id as u32, ifname))
+            .collect();
+        Self::open(buses, Instant::now())
+    }
+
+    /// Opens one socket per bus, the bus name is used as interface name.
+    /// Received frames are timestamped relative to timebase.
+    pub fn open(buses: Vec<TransportBus>, timebase: Instant) -> std::io::Result<Self> {
+        let mut sockets: Vec<(u32, CanSocket)> = vec![];
+        for bus in &buses {
+            sockets.push((bus.id, CanSocket::open(&bus.name)?));
         }
 
         let (tx, rx) = mpsc::channel(16);
 
         let rx_sockets = sockets.clone();
 
-        let start_of_run = Instant::now();
-
-        for (bus_id, socket) in rx_sockets.into_iter().enumerate() {
+        for (bus_id, socket) in rx_sockets.into_iter() {
             let tx = tx.clone();
             tokio::task::spawn_blocking(move || loop {
                 match socket.receive() {
                     Ok(frame) => {
                         let tx = tx.clone();
                         tokio::spawn(async move {
-                            let frame = match frame {
-                                Ok(can_frame) => NetworkFrame {
-                                    bus_id: bus_id as u32,
-                                    can_frame,
-                                },
-                                Err(_) => todo!(),
+                            let tframe = match frame {
+                                Ok(can_frame) => Ok(TNetworkFrame::now(
+                                    timebase,
+                                    NetworkFrame { bus_id, can_frame },
+                                )),
+                                Err(can_error) => Err(TNetworkError::now(
+                                    timebase,
+                                    NetworkError { bus_id, can_error },
+                                )),
                             };
-                            let tframe = TNetworkFrame::now(start_of_run, frame);
 
                             if let Err(_) = tx.send(tframe).await {
                                 cprintln!(
@@ -66,14 +82,53 @@ impl SocketCan {
             });
         }
         Ok(Self {
+            buses,
             sockets,
             rx: tokio::sync::Mutex::new(rx),
         })
     }
     pub async fn send(&self, frame: &TNetworkFrame) -> std::io::Result<()> {
-        self.sockets[frame.value.bus_id as usize].transmit(&frame.value.can_frame)
+        let Some((_, socket)) = self
+            .sockets
+            .iter()
+            .find(|(bus_id, _)| *bus_id == frame.value.bus_id)
+        else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No SocketCAN interface for bus {}", frame.value.bus_id),
+            ));
+        };
+        socket.transmit(&frame.value.can_frame)
     }
+    /// Receives the next frame, error frames are skipped (see SocketCan::recv_any).
     pub async fn recv(&self) -> Option<TNetworkFrame> {
+        loop {
+            if let Ok(frame) = self.recv_any().await? {
+                return Some(frame);
+            }
+        }
+    }
+
+    /// Receives the next frame or error frame.
+    pub async fn recv_any(&self) -> Option<Result<TNetworkFrame, TNetworkError>> {
         self.rx.lock().await.recv().await
     }
 }
+
+impl CanTransport for SocketCan {
+    fn buses(&self) -> Vec<TransportBus> {
+        self.buses.clone()
+    }
+
+    fn send<'a>(&'a self, frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>> {
+        Box::pin(SocketCan::send(self, frame))
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>> {
+        Box::pin(SocketCan::recv_any(self))
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async move { self.rx.lock().await.close() })
+    }
+}
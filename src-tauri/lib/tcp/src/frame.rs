@@ -1,121 +1,490 @@
-use std::time::Duration;
+//! Wire format of the TCP connection between server and clients.
+//!
+//! A connection starts with a handshake, in which the client announces its
+//! protocol version and capabilities and the server accepts or rejects it.
+//! Afterwards both sides exchange frames, which consist of a 4 byte header
+//! (tag, reserved, payload length) followed by the payload.
+//! All multi byte fields are little endian.
+//!
+//! Frames with an unknown tag are skipped by the receiver, which allows
+//! adding new frame types without breaking older peers. Frames which
+//! require support from the peer must be guarded by a capability.
 
-use canzero_common::{CanFrame, NetworkFrame, TNetworkFrame};
-use color_print::cprintln;
+use std::{fmt::Display, time::Duration};
 
+use canzero_common::{
+    can_fd_dlc_to_len, CanError, CanFrame, NetworkError, NetworkFrame, TNetworkError, TNetworkFrame,
+};
 
-#[derive(Clone)]
-pub enum ConnectionHandshakeFrame {
-    ClientServer { request: bool, sync : bool },
-    ServerClient { success: bool, node_id: u8 },
+/// Incremented for every change of the wire format, which older peers can't handle.
+/// Peers with different versions reject each other during the handshake.
+/// Version 0 is the unversioned protocol, which is detected and rejected.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+#[derive(Debug)]
+pub enum FrameError {
+    InvalidTag(u8),
+    InvalidLength {
+        tag: u8,
+        len: usize,
+    },
+    /// Handshake of a peer, which predates protocol versioning.
+    LegacyHandshake,
 }
 
-impl ConnectionHandshakeFrame {
+impl Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::InvalidTag(tag) => write!(f, "invalid frame tag {tag:#x}"),
+            FrameError::InvalidLength { tag, len } => {
+                write!(f, "invalid payload length {len} of frame with tag {tag:#x}")
+            }
+            FrameError::LegacyHandshake => {
+                write!(f, "peer uses the unversioned protocol (version 0)")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Optional features of the protocol, a feature is only used if
+/// both peers announced it during the handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// CAN FD frames (see TcpFrame::NetworkFrame).
+    pub const CAN_FD: Capabilities = Capabilities(0x1);
+    /// error frames (see TcpFrame::NetworkError).
+    pub const ERROR_FRAMES: Capabilities = Capabilities(0x2);
 
-    pub fn bin_size() -> usize {
-        return 2;
+    pub const fn empty() -> Self {
+        Self(0)
     }
 
-    pub fn into_bin(&self, buf : &mut [u8;2]) {
+    /// All capabilities of this implementation.
+    pub const fn supported() -> Self {
+        Self(Self::CAN_FD.0 | Self::ERROR_FRAMES.0)
+    }
+
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Capabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Capabilities supported by both peers.
+    pub const fn intersection(&self, other: Capabilities) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+const HANDSHAKE_CLIENT_TAG: u8 = 0x2;
+const HANDSHAKE_SERVER_TAG: u8 = 0x3;
+// tags of the unversioned handshake.
+const LEGACY_CLIENT_TAG: u8 = 0x0;
+const LEGACY_SERVER_TAG: u8 = 0x1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionHandshakeFrame {
+    ClientServer {
+        version: u16,
+        capabilities: Capabilities,
+        request: bool,
+        sync: bool,
+    },
+    /// capabilities are the negotiated capabilities,
+    /// version is the version of the server (also if the client was rejected).
+    ServerClient {
+        version: u16,
+        capabilities: Capabilities,
+        success: bool,
+        node_id: u8,
+    },
+}
+
+impl ConnectionHandshakeFrame {
+    /// The first bytes of a handshake, which are sufficient to tell
+    /// a legacy handshake apart. Legacy handshakes consist only of the header.
+    pub const HEADER_SIZE: usize = 2;
+    pub const SIZE: usize = 8;
+
+    pub fn into_bin(&self) -> [u8; Self::SIZE] {
+        let mut buf = [0; Self::SIZE];
         match &self {
-            ConnectionHandshakeFrame::ClientServer { request, sync } => {
-                buf[0] = 0x0;
+            ConnectionHandshakeFrame::ClientServer {
+                version,
+                capabilities,
+                request,
+                sync,
+            } => {
+                buf[0] = HANDSHAKE_CLIENT_TAG;
                 if *request {
                     buf[0] |= 0x80;
                 }
                 if *sync {
                     buf[0] |= 0x40;
                 }
-                buf[1] = 0x0; //could be uninitalized
+                buf[2..4].copy_from_slice(&version.to_le_bytes());
+                buf[4..8].copy_from_slice(&capabilities.bits().to_le_bytes());
             }
-            ConnectionHandshakeFrame::ServerClient { success, node_id } => {
-                buf[0] = 0x1;
+            ConnectionHandshakeFrame::ServerClient {
+                version,
+                capabilities,
+                success,
+                node_id,
+            } => {
+                buf[0] = HANDSHAKE_SERVER_TAG;
                 if *success {
                     buf[0] |= 0x80;
                 }
                 buf[1] = *node_id;
+                buf[2..4].copy_from_slice(&version.to_le_bytes());
+                buf[4..8].copy_from_slice(&capabilities.bits().to_le_bytes());
             }
         }
+        buf
+    }
+
+    /// Checks the header before the rest of the handshake is read.
+    pub fn check_header(header: &[u8; Self::HEADER_SIZE]) -> Result<(), FrameError> {
+        match header[0] & 0xF {
+            HANDSHAKE_CLIENT_TAG | HANDSHAKE_SERVER_TAG => Ok(()),
+            LEGACY_CLIENT_TAG | LEGACY_SERVER_TAG => Err(FrameError::LegacyHandshake),
+            tag => Err(FrameError::InvalidTag(tag)),
+        }
     }
 
-    pub fn from_bin(buf : &[u8;2]) -> Result<Self,()> {
-        let tag = buf[0] & 0xF;
-        if tag == 0x0 {
-            let request = buf[0] & 0x80 != 0;
-            let sync = buf[0] & 0x40 != 0;
-            Ok(Self::ClientServer { request, sync})
-        }else if tag == 0x1 {
-            let success = buf[0] & 0x80 != 0;
-            let node_id = buf[1];
-            Ok(Self::ServerClient { success, node_id })
-        }else {
-            cprintln!("<red>Invalid ConnectionHandshake tag : {tag} </red>");
-            Err(())
+    pub fn from_bin(buf: &[u8; Self::SIZE]) -> Result<Self, FrameError> {
+        let version = u16::from_le_bytes([buf[2], buf[3]]);
+        let capabilities =
+            Capabilities::from_bits(u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]));
+        match buf[0] & 0xF {
+            HANDSHAKE_CLIENT_TAG => Ok(Self::ClientServer {
+                version,
+                capabilities,
+                request: buf[0] & 0x80 != 0,
+                sync: buf[0] & 0x40 != 0,
+            }),
+            HANDSHAKE_SERVER_TAG => Ok(Self::ServerClient {
+                version,
+                capabilities,
+                success: buf[0] & 0x80 != 0,
+                node_id: buf[1],
+            }),
+            LEGACY_CLIENT_TAG | LEGACY_SERVER_TAG => Err(FrameError::LegacyHandshake),
+            tag => Err(FrameError::InvalidTag(tag)),
         }
     }
+
+    /// Response to a legacy client, which it understands as a rejection.
+    pub fn legacy_rejection() -> [u8; Self::HEADER_SIZE] {
+        [LEGACY_SERVER_TAG, 0]
+    }
+}
+
+const KEEP_ALIVE_TAG: u8 = 0x0;
+const NETWORK_FRAME_TAG: u8 = 0x1;
+const SYNC_END_TAG: u8 = 0x2;
+const NETWORK_FD_FRAME_TAG: u8 = 0x3;
+const NETWORK_ERROR_TAG: u8 = 0x4;
+
+const NETWORK_FRAME_PAYLOAD_SIZE: usize = 24;
+// followed by the data bytes.
+const NETWORK_FD_FRAME_HEADER_SIZE: usize = 16;
+const FD_FLAG_BRS: u8 = 0x1;
+const FD_FLAG_ESI: u8 = 0x2;
+const NETWORK_ERROR_PAYLOAD_SIZE: usize = 24;
+
+/// Tag and payload length of a TcpFrame.
+#[derive(Debug, Clone, Copy)]
+pub struct TcpFrameHeader {
+    pub tag: u8,
+    pub len: u16,
 }
 
+impl TcpFrameHeader {
+    pub const SIZE: usize = 4;
+
+    pub fn from_bin(buf: &[u8; Self::SIZE]) -> Self {
+        Self {
+            tag: buf[0],
+            len: u16::from_le_bytes([buf[2], buf[3]]),
+        }
+    }
+}
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum TcpFrame {
+    /// CAN FD frames are only sent if both peers announced Capabilities::CAN_FD.
     NetworkFrame(TNetworkFrame),
+    /// only sent if both peers announced Capabilities::ERROR_FRAMES.
+    NetworkError(TNetworkError),
     SyncEnd,
     KeepAlive,
 }
 
 impl TcpFrame {
-
-    pub fn bin_size() -> usize {
-        return 24;
-    }
-
-    pub fn into_bin(&self, buf8 : &mut [u8;24]) {
-        match &self {
+    /// Header and payload.
+    pub fn into_bin(&self) -> Vec<u8> {
+        let (tag, payload) = match &self {
+            TcpFrame::NetworkFrame(timestamped) if timestamped.can_frame.is_fd() => {
+                let can_frame = &timestamped.can_frame;
+                let data = can_frame.get_data();
+                let mut payload = vec![0u8; NETWORK_FD_FRAME_HEADER_SIZE + data.len()];
+                payload[0] = timestamped.bus_id as u8;
+                payload[1] = can_frame.get_dlc();
+                if can_frame.get_brs_flag() {
+                    payload[2] |= FD_FLAG_BRS;
+                }
+                if can_frame.get_esi_flag() {
+                    payload[2] |= FD_FLAG_ESI;
+                }
+                payload[4..8].copy_from_slice(&can_frame.key().to_le_bytes());
+                payload[8..16]
+                    .copy_from_slice(&(timestamped.timestamp.as_micros() as u64).to_le_bytes());
+                payload[16..].copy_from_slice(&data);
+                (NETWORK_FD_FRAME_TAG, payload)
+            }
             TcpFrame::NetworkFrame(timestamped) => {
                 let frame = &timestamped.value;
-                let timestamp = timestamped.timestamp.as_micros() as u64;
-                let bus_id = &frame.bus_id;
-                let can_id = frame.can_frame.key();
-                let data = frame.can_frame.get_data_u64();
-                let dlc = frame.can_frame.get_dlc();
-                
-                buf8[0] = 0x1;
-                buf8[1] = *bus_id as u8;
-                buf8[2] = dlc;
-                let buf32 : &mut [u32;6] = unsafe {std::mem::transmute(buf8)};
-                buf32[1] = can_id;
-                let buf64 : &mut [u64;3] = unsafe {std::mem::transmute(buf32)};
-                buf64[1] = timestamp;
-                buf64[2] = data;
-
+                let mut payload = [0u8; NETWORK_FRAME_PAYLOAD_SIZE];
+                payload[0] = frame.bus_id as u8;
+                payload[1] = frame.can_frame.get_dlc();
+                payload[4..8].copy_from_slice(&frame.can_frame.key().to_le_bytes());
+                payload[8..16]
+                    .copy_from_slice(&(timestamped.timestamp.as_micros() as u64).to_le_bytes());
+                payload[16..24].copy_from_slice(&frame.can_frame.get_data_u64().to_le_bytes());
+                (NETWORK_FRAME_TAG, payload.to_vec())
+            }
+            TcpFrame::NetworkError(timestamped) => {
+                let mut payload = [0u8; NETWORK_ERROR_PAYLOAD_SIZE];
+                payload[0] = timestamped.bus_id as u8;
+                payload[8..16]
+                    .copy_from_slice(&(timestamped.timestamp.as_micros() as u64).to_le_bytes());
+                payload[16..24].copy_from_slice(&timestamped.can_error.erno().to_le_bytes());
+                (NETWORK_ERROR_TAG, payload.to_vec())
+            }
+            TcpFrame::KeepAlive => (KEEP_ALIVE_TAG, vec![]),
+            TcpFrame::SyncEnd => (SYNC_END_TAG, vec![]),
+        };
+        let mut buf = Vec::with_capacity(TcpFrameHeader::SIZE + payload.len());
+        buf.push(tag);
+        buf.push(0);
+        buf.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+        buf.extend_from_slice(&payload);
+        buf
+    }
 
+    /// Ok(None) for frames with an unknown tag, which should be skipped.
+    pub fn from_bin(header: &TcpFrameHeader, payload: &[u8]) -> Result<Option<Self>, FrameError> {
+        let expected_len = match header.tag {
+            KEEP_ALIVE_TAG | SYNC_END_TAG => 0,
+            NETWORK_FRAME_TAG => NETWORK_FRAME_PAYLOAD_SIZE,
+            NETWORK_FD_FRAME_TAG if payload.len() >= NETWORK_FD_FRAME_HEADER_SIZE => {
+                NETWORK_FD_FRAME_HEADER_SIZE + can_fd_dlc_to_len(payload[1])
             }
-            TcpFrame::KeepAlive => {
-                buf8[0] = 0x0;
+            NETWORK_FD_FRAME_TAG => NETWORK_FD_FRAME_HEADER_SIZE,
+            NETWORK_ERROR_TAG => NETWORK_ERROR_PAYLOAD_SIZE,
+            _ => return Ok(None),
+        };
+        if payload.len() != expected_len || header.len as usize != expected_len {
+            return Err(FrameError::InvalidLength {
+                tag: header.tag,
+                len: payload.len(),
+            });
+        }
+        match header.tag {
+            KEEP_ALIVE_TAG => Ok(Some(TcpFrame::KeepAlive)),
+            SYNC_END_TAG => Ok(Some(TcpFrame::SyncEnd)),
+            NETWORK_ERROR_TAG => {
+                let bus_id = payload[0] as u32;
+                let timestamp =
+                    Duration::from_micros(u64::from_le_bytes(payload[8..16].try_into().unwrap()));
+                let erno = u64::from_le_bytes(payload[16..24].try_into().unwrap());
+                Ok(Some(TcpFrame::NetworkError(TNetworkError::new(
+                    timestamp,
+                    NetworkError {
+                        bus_id,
+                        can_error: CanError(erno),
+                    },
+                ))))
+            }
+            NETWORK_FD_FRAME_TAG => {
+                let bus_id = payload[0] as u32;
+                let flags = payload[2];
+                let can_id = u32::from_le_bytes(payload[4..8].try_into().unwrap());
+                let timestamp =
+                    Duration::from_micros(u64::from_le_bytes(payload[8..16].try_into().unwrap()));
+                Ok(Some(TcpFrame::NetworkFrame(TNetworkFrame::new(
+                    timestamp,
+                    NetworkFrame {
+                        bus_id,
+                        can_frame: CanFrame::new_raw_fd(
+                            can_id,
+                            flags & FD_FLAG_BRS != 0,
+                            flags & FD_FLAG_ESI != 0,
+                            &payload[NETWORK_FD_FRAME_HEADER_SIZE..],
+                        ),
+                    },
+                ))))
+            }
+            _ => {
+                let bus_id = payload[0] as u32;
+                let dlc = payload[1];
+                let can_id = u32::from_le_bytes(payload[4..8].try_into().unwrap());
+                let timestamp =
+                    Duration::from_micros(u64::from_le_bytes(payload[8..16].try_into().unwrap()));
+                let data = u64::from_le_bytes(payload[16..24].try_into().unwrap());
+                Ok(Some(TcpFrame::NetworkFrame(TNetworkFrame::new(
+                    timestamp,
+                    NetworkFrame {
+                        bus_id,
+                        can_frame: CanFrame::new_raw(can_id, dlc, data),
+                    },
+                ))))
             }
-            TcpFrame::SyncEnd => {
-                buf8[0] = 0x2;
-            },
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bin: &[u8]) -> Result<Option<TcpFrame>, FrameError> {
+        let header = TcpFrameHeader::from_bin(bin[..TcpFrameHeader::SIZE].try_into().unwrap());
+        TcpFrame::from_bin(&header, &bin[TcpFrameHeader::SIZE..])
+    }
+
+    fn round_trip(frame: &TcpFrame) {
+        let bin = frame.into_bin();
+        let decoded = decode(&bin).unwrap().expect("known frame was skipped");
+        assert_eq!(decoded.into_bin(), bin);
+    }
+
+    #[test]
+    fn network_frame_is_little_endian() {
+        let frame = TcpFrame::NetworkFrame(TNetworkFrame::new(
+            Duration::from_micros(0x0102030405060708),
+            NetworkFrame {
+                bus_id: 3,
+                can_frame: CanFrame::new(0x1ABCDEF, true, false, 8, 0x1122334455667788),
+            },
+        ));
+        let bin = frame.into_bin();
+        assert_eq!(bin[..4], [NETWORK_FRAME_TAG, 0, 24, 0]);
+        assert_eq!(bin[4..6], [3, 8]);
+        assert_eq!(bin[12..20], 0x0102030405060708u64.to_le_bytes());
+        assert_eq!(bin[20..28], 0x1122334455667788u64.to_le_bytes());
+        round_trip(&frame);
+    }
+
+    #[test]
+    fn fd_frames_round_trip() {
+        let data: Vec<u8> = (0..64).collect();
+        let frame = TcpFrame::NetworkFrame(TNetworkFrame::new(
+            Duration::from_micros(42),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new_fd(0x123, false, true, false, &data),
+            },
+        ));
+        let bin = frame.into_bin();
+        assert_eq!(bin[..4], [NETWORK_FD_FRAME_TAG, 0, 80, 0]);
+        let TcpFrame::NetworkFrame(decoded) = decode(&bin).unwrap().unwrap() else {
+            panic!("expected a network frame");
+        };
+        assert!(decoded.can_frame.is_fd());
+        assert!(decoded.can_frame.get_brs_flag());
+        assert!(!decoded.can_frame.get_esi_flag());
+        assert_eq!(decoded.can_frame.get_data(), data);
+        round_trip(&frame);
+    }
+
+    #[test]
+    fn error_frames_round_trip() {
+        let frame = TcpFrame::NetworkError(TNetworkError::new(
+            Duration::from_micros(7),
+            NetworkError {
+                bus_id: 1,
+                can_error: CanError::new(
+                    CanError::ACK_ERROR | CanError::ERROR_PASSIVE,
+                    Some((128, 3)),
+                ),
+            },
+        ));
+        let bin = frame.into_bin();
+        assert_eq!(bin[..4], [NETWORK_ERROR_TAG, 0, 24, 0]);
+        let TcpFrame::NetworkError(decoded) = decode(&bin).unwrap().unwrap() else {
+            panic!("expected an error frame");
+        };
+        assert_eq!(decoded.bus_id, 1);
+        assert_eq!(decoded.timestamp, Duration::from_micros(7));
+        assert!(decoded.can_error.contains(CanError::ERROR_PASSIVE));
+        assert_eq!(decoded.can_error.tx_error_counter(), Some(128));
+        assert_eq!(decoded.can_error.rx_error_counter(), Some(3));
+        round_trip(&frame);
+    }
 
-    pub fn from_bin(buf : &[u8;24]) -> Result<Self, ()> {
-        let tag = buf[0];
-        if tag == 0x0 {
-            Ok(TcpFrame::KeepAlive)
-        }else if tag == 0x1 {
-            let bus_id = buf[1] as u32;
-            let dlc = buf[2];
-            let buf : &[u32;6] = unsafe {std::mem::transmute(buf)};
-            let can_id = buf[1];
-            let buf : &[u64;3] = unsafe {std::mem::transmute(buf)};
-            let timestamp = Duration::from_micros(buf[1]);
-            let data = buf[2];
-            Ok(TcpFrame::NetworkFrame(TNetworkFrame::new(timestamp, NetworkFrame { bus_id, can_frame: CanFrame::new_raw(can_id, dlc, data) })))
-        }else if tag == 0x2 {
-            Ok(TcpFrame::SyncEnd)
-        } else {
-            Err(())
+    #[test]
+    fn frames_round_trip() {
+        round_trip(&TcpFrame::KeepAlive);
+        round_trip(&TcpFrame::SyncEnd);
+    }
+
+    #[test]
+    fn unknown_frames_are_skipped() {
+        assert!(decode(&[0x7F, 0, 3, 0, 1, 2, 3]).unwrap().is_none());
+    }
+
+    #[test]
+    fn frames_with_invalid_length_are_rejected() {
+        assert!(matches!(
+            decode(&[NETWORK_FRAME_TAG, 0, 1, 0, 0]),
+            Err(FrameError::InvalidLength { .. })
+        ));
+    }
+
+    #[test]
+    fn handshake_round_trips() {
+        for handshake in [
+            ConnectionHandshakeFrame::ClientServer {
+                version: PROTOCOL_VERSION,
+                capabilities: Capabilities::from_bits(0x80000001),
+                request: true,
+                sync: false,
+            },
+            ConnectionHandshakeFrame::ServerClient {
+                version: 0x1234,
+                capabilities: Capabilities::empty(),
+                success: true,
+                node_id: 7,
+            },
+        ] {
+            let bin = handshake.into_bin();
+            ConnectionHandshakeFrame::check_header(bin[..2].try_into().unwrap()).unwrap();
+            assert_eq!(ConnectionHandshakeFrame::from_bin(&bin).unwrap(), handshake);
         }
     }
+
+    #[test]
+    fn legacy_handshake_is_detected() {
+        // request and sync of the unversioned protocol.
+        assert!(matches!(
+            ConnectionHandshakeFrame::check_header(&[0xC0, 0]),
+            Err(FrameError::LegacyHandshake)
+        ));
+        assert!(matches!(
+            ConnectionHandshakeFrame::check_header(&ConnectionHandshakeFrame::legacy_rejection()),
+            Err(FrameError::LegacyHandshake)
+        ));
+    }
 }
@@ -10,10 +10,16 @@ use tokio::{
     sync::{oneshot, Mutex},
 };
 
-use canzero_common::TNetworkFrame;
+use canzero_common::{
+    transport::{BoxFuture, CanTransport, TransportBus},
+    TNetworkError, TNetworkFrame,
+};
 
 use crate::{
-    frame::{ConnectionHandshakeFrame, TcpFrame},
+    frame::{
+        Capabilities, ConnectionHandshakeFrame, FrameError, TcpFrame, TcpFrameHeader,
+        PROTOCOL_VERSION,
+    },
     wdg::Watchdog,
 };
 
@@ -75,7 +81,7 @@ pub enum ConnectionId {
     },
     Host {
         id_host: Arc<ConnectionIdHost>,
-        sync_history: Option<Vec<TNetworkFrame>>,
+        sync_history: Option<Vec<Result<TNetworkFrame, TNetworkError>>>,
     },
 }
 
@@ -85,20 +91,50 @@ pub struct TcpCan {
     rx_stream: Mutex<OwnedReadHalf>,
     wdg: Watchdog,
     node_id: Option<u8>,
+    capabilities: Capabilities,
     id_host: Option<Arc<ConnectionIdHost>>,
     sync_complete: Mutex<Option<oneshot::Receiver<()>>>,
     sync_complete_signal: Mutex<Option<oneshot::Sender<()>>>,
 }
 
+
+fn invalid_data<E>(err: E) -> std::io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+}
+
+async fn read_handshake(rx: &mut OwnedReadHalf) -> std::io::Result<ConnectionHandshakeFrame> {
+    const HEADER_SIZE: usize = ConnectionHandshakeFrame::HEADER_SIZE;
+    let mut buf = [0; ConnectionHandshakeFrame::SIZE];
+    // legacy handshakes only consist of the header.
+    rx.read_exact(&mut buf[..HEADER_SIZE]).await?;
+    ConnectionHandshakeFrame::check_header(&[buf[0], buf[1]]).map_err(invalid_data)?;
+    rx.read_exact(&mut buf[HEADER_SIZE..]).await?;
+    ConnectionHandshakeFrame::from_bin(&buf).map_err(invalid_data)
+}
+
+/// Ok(None) for frames with an unknown tag.
+async fn read_frame(rx: &mut OwnedReadHalf) -> std::io::Result<Option<TcpFrame>> {
+    let mut header = [0; TcpFrameHeader::SIZE];
+    rx.read_exact(&mut header).await?;
+    let header = TcpFrameHeader::from_bin(&header);
+    let mut payload = vec![0; header.len as usize];
+    rx.read_exact(&mut payload).await?;
+    TcpFrame::from_bin(&header, &payload).map_err(invalid_data)
+}
+
 impl TcpCan {
     pub async fn connect(
         socketaddr: SocketAddr,
         connection_id: ConnectionId,
     ) -> std::io::Result<Self> {
         let tcp_stream = tokio::net::TcpStream::connect(socketaddr).await?;
-        Ok(Self::new(tcp_stream, connection_id).await?)
+        Self::new(tcp_stream, connection_id).await
     }
 
+    /// Performs the handshake, fails if the peer uses an incompatible protocol version.
     pub async fn new(tcp_stream: TcpStream, connection_id: ConnectionId) -> std::io::Result<Self> {
 
         tcp_stream.set_nodelay(true).unwrap();
@@ -106,127 +142,166 @@ impl TcpCan {
 
         let (sync_tx, sync_rx) = oneshot::channel();
 
-        let (node_id, sync_signal) = match &connection_id {
+        let (node_id, capabilities, sync_signal) = match &connection_id {
             ConnectionId::Client {
                 request_id,
                 sync_history,
             } => {
-                let mut request = [0; 2];
-                ConnectionHandshakeFrame::ClientServer {
+                let request = ConnectionHandshakeFrame::ClientServer {
+                    version: PROTOCOL_VERSION,
+                    capabilities: Capabilities::supported(),
                     request: *request_id,
                     sync: *sync_history,
-                }
-                .into_bin(&mut request);
-
-                tx.write_all(&request).await.unwrap();
-
-                if *request_id || *sync_history {
-                    let mut rx_buffer = [0; 2usize];
-                    match rx.read_exact(&mut rx_buffer).await {
-                        Ok(_) => {
-                            match ConnectionHandshakeFrame::from_bin(&rx_buffer) {
-                                Ok(handshake_frame) => match handshake_frame {
-                                    ConnectionHandshakeFrame::ServerClient { success, node_id } => {
-                                        if success {
-                                            if *sync_history {
-                                                (Some(node_id), Some(sync_tx))
-                                            } else {
-                                                sync_tx.send(()).unwrap();
-                                                (Some(node_id), None)
-                                            }
-                                        } else {
-                                            panic!("Server rejected connection")
-                                        }
-                                    }
-                                    ConnectionHandshakeFrame::ClientServer {
-                                        request: _,
-                                        sync: _,
-                                    } => {
-                                        panic!("Illegal request: This tcpcan doesn't act as a id server")
-                                    }
-                                },
-                                Err(_) => panic!("Received ill formed TCP frame"),
-                            }
-                        }
-                        Err(_) => {
-                            panic!("Failed to read ConnectionHandshake TCP frame from socket")
+                };
+                tx.write_all(&request.into_bin()).await?;
+
+                // NOTE: the server always responds, such that incompatible clients are rejected.
+                let response = match read_handshake(&mut rx).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        let legacy = err
+                            .get_ref()
+                            .and_then(|err| err.downcast_ref::<FrameError>())
+                            .is_some_and(|err| matches!(err, FrameError::LegacyHandshake));
+                        if legacy {
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::ConnectionRefused,
+                                format!("Server rejected connection: server uses an incompatible protocol version 0 (expected {PROTOCOL_VERSION})"),
+                            ));
                         }
+                        return Err(err);
                     }
-                }else {
-                    (None,None)
+                };
+                let ConnectionHandshakeFrame::ServerClient {
+                    version,
+                    capabilities,
+                    success,
+                    node_id,
+                } = response
+                else {
+                    return Err(invalid_data(
+                        "Illegal request: This tcpcan doesn't act as a id server",
+                    ));
+                };
+                if version != PROTOCOL_VERSION {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionRefused,
+                        format!("Server rejected connection: server uses an incompatible protocol version {version} (expected {PROTOCOL_VERSION})"),
+                    ));
+                }
+                if !success {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionRefused,
+                        "Server rejected connection",
+                    ));
+                }
+                let node_id = if *request_id { Some(node_id) } else { None };
+                if *sync_history {
+                    (node_id, capabilities, Some(sync_tx))
+                } else {
+                    sync_tx.send(()).unwrap();
+                    (node_id, capabilities, None)
                 }
             }
             ConnectionId::Host {
                 id_host,
                 sync_history,
             } => {
-                let mut rx_buffer = [0; 2];
-                match rx.read_exact(&mut rx_buffer).await {
-                    Ok(_) => match ConnectionHandshakeFrame::from_bin(&rx_buffer) {
-                        Ok(tcp_frame) => match tcp_frame {
-                            ConnectionHandshakeFrame::ServerClient {
-                                success: _,
-                                node_id: _,
-                            } => {
-                                panic!("Unexpected response: Host received connection id response during handshake");
-                            }
-                            ConnectionHandshakeFrame::ClientServer { request, sync } => {
-                                let node_id = if request {
-                                    match id_host.alloc_id() {
-                                        Some(node_id) => {
-                                            let mut response = [0; 2];
-                                            ConnectionHandshakeFrame::ServerClient {
-                                                success: true,
-                                                node_id,
-                                            }
-                                            .into_bin(&mut response);
-                                            tx.write_all(&response).await.unwrap();
-                                            Some(node_id)
-                                        }
-                                        None => {
-                                            let mut response = [0; 2];
-                                            ConnectionHandshakeFrame::ServerClient {
-                                                success: true,
-                                                node_id: 0,
-                                            }
-                                            .into_bin(&mut response);
-                                            tx.write_all(&response).await.unwrap();
-                                            None
-                                        }
-                                    }
-                                } else {
-                                    None
-                                };
+                let request = match read_handshake(&mut rx).await {
+                    Ok(request) => request,
+                    Err(err) => {
+                        let legacy = err
+                            .get_ref()
+                            .and_then(|err| err.downcast_ref::<FrameError>())
+                            .is_some_and(|err| matches!(err, FrameError::LegacyHandshake));
+                        if legacy {
+                            // legacy clients understand this as a rejection.
+                            let _ = tx
+                                .write_all(&ConnectionHandshakeFrame::legacy_rejection())
+                                .await;
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::ConnectionRefused,
+                                format!("Rejected client with incompatible protocol version 0 (expected {PROTOCOL_VERSION})"),
+                            ));
+                        }
+                        return Err(err);
+                    }
+                };
+                let ConnectionHandshakeFrame::ClientServer {
+                    version,
+                    capabilities,
+                    request,
+                    sync,
+                } = request
+                else {
+                    return Err(invalid_data(
+                        "Unexpected response: Host received connection id response during handshake",
+                    ));
+                };
+                if version != PROTOCOL_VERSION {
+                    let response = ConnectionHandshakeFrame::ServerClient {
+                        version: PROTOCOL_VERSION,
+                        capabilities: Capabilities::empty(),
+                        success: false,
+                        node_id: 0,
+                    };
+                    let _ = tx.write_all(&response.into_bin()).await;
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionRefused,
+                        format!("Rejected client with incompatible protocol version {version} (expected {PROTOCOL_VERSION})"),
+                    ));
+                }
+                let capabilities = capabilities.intersection(Capabilities::supported());
 
-                                if sync {
-                                    if let Some(sync_history) = sync_history {
-                                        for frame in sync_history {
-                                            let mut bytes = [0; 24];
-                                            TcpFrame::NetworkFrame(frame.clone())
-                                                .into_bin(&mut bytes);
-                                            if let Err(_) = tx.write_all(&bytes).await {
-                                                cprintln!("<yellow>Failed to transmit HistorySyncFrame.</yellow>")
-                                            };
-                                        }
-                                        let mut bytes = [0; 24];
-                                        TcpFrame::SyncEnd.into_bin(&mut bytes);
-                                        if let Err(_) = tx.write_all(&bytes).await {
-                                            cprintln!("<red>Failed to send SYNC_END frame.</red>");
-                                            return Err(std::io::Error::new(
-                                                std::io::ErrorKind::UnexpectedEof,
-                                                "Failed to send SYNC_END frame.".to_owned(),
-                                            ));
-                                        };
-                                    }
+                let node_id = if request { id_host.alloc_id() } else { None };
+                let response = ConnectionHandshakeFrame::ServerClient {
+                    version: PROTOCOL_VERSION,
+                    capabilities,
+                    success: true,
+                    node_id: node_id.unwrap_or(0),
+                };
+                if let Err(err) = tx.write_all(&response.into_bin()).await {
+                    if let Some(node_id) = node_id {
+                        id_host.free_id(node_id);
+                    }
+                    return Err(err);
+                }
+
+                if sync {
+                    if let Some(sync_history) = sync_history {
+                        for frame in sync_history {
+                            let tcp_frame = match frame {
+                                Ok(frame)
+                                    if frame.can_frame.is_fd()
+                                        && !capabilities.contains(Capabilities::CAN_FD) =>
+                                {
+                                    continue
+                                }
+                                Ok(frame) => TcpFrame::NetworkFrame(frame.clone()),
+                                Err(error) if capabilities.contains(Capabilities::ERROR_FRAMES) => {
+                                    TcpFrame::NetworkError(error.clone())
                                 }
-                                sync_tx.send(()).unwrap();
-                                (node_id, None)
+                                Err(_) => continue,
+                            };
+                            let bytes = tcp_frame.into_bin();
+                            if tx.write_all(&bytes).await.is_err() {
+                                cprintln!("<yellow>Failed to transmit HistorySyncFrame.</yellow>")
+                            };
+                        }
+                        if tx.write_all(&TcpFrame::SyncEnd.into_bin()).await.is_err() {
+                            cprintln!("<red>Failed to send SYNC_END frame.</red>");
+                            if let Some(node_id) = node_id {
+                                id_host.free_id(node_id);
                             }
-                        },
-                        Err(_) => panic!("Received ill formed TCP frame"),
-                    },
-                    Err(_) => panic!("Failed to receive ConnectionHandshake TCP frame from socket"),
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::UnexpectedEof,
+                                "Failed to send SYNC_END frame.".to_owned(),
+                            ));
+                        };
+                    }
                 }
+                sync_tx.send(()).unwrap();
+                (node_id, capabilities, None)
             }
         };
 
@@ -234,16 +309,16 @@ impl TcpCan {
 
         let keep_alive_sock = tx.clone();
         tokio::spawn(async move {
-            let mut keep_alive_frame = [0u8; 24];
-            TcpFrame::KeepAlive.into_bin(&mut keep_alive_frame);
+            let keep_alive_frame = TcpFrame::KeepAlive.into_bin();
             let mut interval = tokio::time::interval(Duration::from_millis(200));
             loop {
                 interval.tick().await;
-                if let Err(_) = keep_alive_sock
+                if keep_alive_sock
                     .lock()
                     .await
                     .write_all(&keep_alive_frame)
                     .await
+                    .is_err()
                 {
                     break; // Failed to send once -> stop sending keep alive completel! will
                            // eventually lead to none beeing returned from the recv() function!
@@ -261,6 +336,7 @@ impl TcpCan {
             rx_stream: Mutex::new(rx),
             wdg,
             node_id,
+            capabilities,
             id_host: match connection_id {
                 ConnectionId::Client {
                     request_id: _,
@@ -281,30 +357,57 @@ impl TcpCan {
         std::mem::swap(sync_complete, &mut sync_swap);
 
         drop(sync_complete_guard);
-        match sync_swap {
-            Some(sync_complete) => {
-                let _ = sync_complete.await;
-            }
-            None => (),
-        };
+        if let Some(sync_complete) = sync_swap {
+            let _ = sync_complete.await;
+        }
     }
 
+    /// CAN FD frames are dropped, if the peer doesn't support them.
     pub async fn send(&self, frame: &TNetworkFrame) -> std::io::Result<()> {
-        let mut bytes = [0; 24];
-        TcpFrame::NetworkFrame(frame.clone()).into_bin(&mut bytes);
+        if frame.can_frame.is_fd() && !self.capabilities.contains(Capabilities::CAN_FD) {
+            return Ok(());
+        }
+        let bytes = TcpFrame::NetworkFrame(frame.clone()).into_bin();
         self.tx_stream.lock().await.write_all(&bytes).await
     }
 
+    /// Error frames are dropped, if the peer doesn't support them.
+    pub async fn send_error(&self, error: &TNetworkError) -> std::io::Result<()> {
+        if !self.capabilities.contains(Capabilities::ERROR_FRAMES) {
+            return Ok(());
+        }
+        let bytes = TcpFrame::NetworkError(error.clone()).into_bin();
+        self.tx_stream.lock().await.write_all(&bytes).await
+    }
+
+    fn free_id(&self) {
+        if let Some(host) = &self.id_host {
+            if let Some(node_id) = &self.node_id {
+                host.free_id(*node_id);
+            }
+        }
+    }
+
+    /// Receives the next frame, error frames are skipped (see TcpCan::recv_any).
     pub async fn recv(&self) -> Option<TNetworkFrame> {
+        loop {
+            if let Ok(frame) = self.recv_any().await? {
+                return Some(frame);
+            }
+        }
+    }
+
+    /// Receives the next frame or error frame.
+    pub async fn recv_any(&self) -> Option<Result<TNetworkFrame, TNetworkError>> {
         let mut rx_lock = self.rx_stream.lock().await;
         let rx_stream = rx_lock.deref_mut();
-        let mut rx_buffer = [0; 24];
         loop {
             tokio::select! {
-                rx_res = rx_stream.read_exact(&mut rx_buffer) => {
+                rx_res = read_frame(rx_stream) => {
                     match rx_res {
-                        Ok(_) => match TcpFrame::from_bin(&rx_buffer).unwrap() {
-                            TcpFrame::NetworkFrame(network_frame) => return Some(network_frame),
+                        Ok(Some(tcp_frame)) => match tcp_frame {
+                            TcpFrame::NetworkFrame(network_frame) => return Some(Ok(network_frame)),
+                            TcpFrame::NetworkError(network_error) => return Some(Err(network_error)),
                             TcpFrame::KeepAlive => {
                                 self.wdg.reset().await;
                             },
@@ -315,7 +418,7 @@ impl TcpCan {
                                 let sync_complete_signal = sync_complete_signal_guard.deref_mut();
                                 std::mem::swap(sync_complete_signal, &mut sync_swap);
                                 if let Some(sync_signal) = sync_swap {
-                                    if let Err(_) = sync_signal.send(()) {
+                                    if sync_signal.send(()).is_err() {
                                         cprintln!("<yellow>Received unexpected SYNC_DONE frame.</yellow>");
                                     }
                                 };
@@ -325,24 +428,23 @@ impl TcpCan {
 
                             },
                         },
+                        // frame type of a newer protocol revision.
+                        Ok(None) => (),
+                        Err(err) if err.kind() == std::io::ErrorKind::InvalidData => {
+                            cprintln!("<red>Received ill formed TCP frame : {err}</red>");
+                            self.free_id();
+                            return None;
+                        }
                         Err(_) => {
                             cprintln!("<red>TCP connection closed</red>");
-                            if let Some(host) = &self.id_host{
-                                if let Some(node_id) = &self.node_id {
-                                    host.free_id(*node_id);
-                                }
-                            }
+                            self.free_id();
                             return None;
                         }
                     }
                 },
                 _wdg_timeout = self.wdg.timeout() =>  {
                     cprintln!("<red>TCP connection watchdog timed out</red>");
-                    if let Some(host) = &self.id_host{
-                        if let Some(node_id) = &self.node_id {
-                            host.free_id(*node_id);
-                        }
-                    }
+                    self.free_id();
                     return None;
                 },
             }
@@ -353,6 +455,234 @@ impl TcpCan {
     }
 
     pub fn connection_id(&self) -> Option<u8> {
-        return self.node_id;
+        self.node_id
+    }
+
+    /// Capabilities negotiated during the handshake.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
+
+impl CanTransport for TcpCan {
+    fn buses(&self) -> Vec<TransportBus> {
+        vec![]
+    }
+
+    fn send<'a>(&'a self, frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>> {
+        Box::pin(TcpCan::send(self, frame))
+    }
+
+    fn send_error<'a>(&'a self, error: &'a TNetworkError) -> BoxFuture<'a, std::io::Result<()>> {
+        Box::pin(TcpCan::send_error(self, error))
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>> {
+        Box::pin(TcpCan::recv_any(self))
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            let _ = self.tx_stream.lock().await.shutdown().await;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    async fn host(listener: &TcpListener) -> std::io::Result<TcpCan> {
+        let (stream, _) = listener.accept().await?;
+        TcpCan::new(
+            stream,
+            ConnectionId::Host {
+                id_host: Arc::new(ConnectionIdHost::new(8)),
+                sync_history: Some(vec![]),
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn handshake_assigns_connection_id() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (host, client) = tokio::join!(
+            host(&listener),
+            TcpCan::connect(
+                addr,
+                ConnectionId::Client {
+                    request_id: true,
+                    sync_history: false,
+                },
+            )
+        );
+        let (host, client) = (host.unwrap(), client.unwrap());
+        assert_eq!(host.connection_id(), Some(0));
+        assert_eq!(client.connection_id(), Some(0));
+        assert_eq!(client.capabilities(), Capabilities::supported());
+    }
+
+    #[tokio::test]
+    async fn host_rejects_legacy_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let legacy_client = async {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            // unversioned handshake with request and sync flags.
+            stream.write_all(&[0xC0, 0]).await.unwrap();
+            let mut response = [0; 2];
+            stream.read_exact(&mut response).await.unwrap();
+            response
+        };
+        let (host, response) = tokio::join!(host(&listener), legacy_client);
+        assert_eq!(
+            host.unwrap_err().kind(),
+            std::io::ErrorKind::ConnectionRefused
+        );
+        // legacy ServerClient frame with success unset.
+        assert_eq!(response, [0x1, 0]);
+    }
+
+    #[tokio::test]
+    async fn error_frames_are_synced_and_forwarded() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let error = |can_error: u64| {
+            TNetworkError::new(
+                Duration::ZERO,
+                canzero_common::NetworkError {
+                    bus_id: 0,
+                    can_error: canzero_common::CanError(can_error),
+                },
+            )
+        };
+        let host = async {
+            let (stream, _) = listener.accept().await.unwrap();
+            TcpCan::new(
+                stream,
+                ConnectionId::Host {
+                    id_host: Arc::new(ConnectionIdHost::new(8)),
+                    sync_history: Some(vec![Err(error(1))]),
+                },
+            )
+            .await
+        };
+        let (host, client) = tokio::join!(
+            host,
+            TcpCan::connect(
+                addr,
+                ConnectionId::Client {
+                    request_id: false,
+                    sync_history: true,
+                },
+            )
+        );
+        let (host, client) = (host.unwrap(), client.unwrap());
+        assert!(client.capabilities().contains(Capabilities::ERROR_FRAMES));
+        let received = client.recv_any().await.unwrap().unwrap_err();
+        assert_eq!(received.can_error.erno(), 1);
+
+        host.send_error(&error(2)).await.unwrap();
+        let received = client.recv_any().await.unwrap().unwrap_err();
+        assert_eq!(received.can_error.erno(), 2);
+    }
+
+    #[tokio::test]
+    async fn fd_frames_are_not_synced_to_clients_without_can_fd() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let frame = |can_frame: canzero_common::CanFrame| {
+            TNetworkFrame::new(
+                Duration::ZERO,
+                canzero_common::NetworkFrame {
+                    bus_id: 0,
+                    can_frame,
+                },
+            )
+        };
+        let host = async {
+            let (stream, _) = listener.accept().await.unwrap();
+            TcpCan::new(
+                stream,
+                ConnectionId::Host {
+                    id_host: Arc::new(ConnectionIdHost::new(8)),
+                    sync_history: Some(vec![
+                        Ok(frame(canzero_common::CanFrame::new_fd(
+                            0x10,
+                            false,
+                            true,
+                            false,
+                            &[0xAB; 16],
+                        ))),
+                        Ok(frame(canzero_common::CanFrame::new(
+                            0x20, false, false, 1, 0xCD,
+                        ))),
+                    ]),
+                },
+            )
+            .await
+        };
+        let client = async {
+            let (mut rx, mut tx) = TcpStream::connect(addr).await.unwrap().into_split();
+            let request = ConnectionHandshakeFrame::ClientServer {
+                version: PROTOCOL_VERSION,
+                capabilities: Capabilities::empty(),
+                request: false,
+                sync: true,
+            };
+            tx.write_all(&request.into_bin()).await.unwrap();
+            read_handshake(&mut rx).await.unwrap();
+            let mut synced = vec![];
+            loop {
+                match read_frame(&mut rx).await.unwrap() {
+                    Some(TcpFrame::NetworkFrame(frame)) => synced.push(frame),
+                    Some(TcpFrame::SyncEnd) => break,
+                    _ => (),
+                }
+            }
+            synced
+        };
+        let (host, synced) = tokio::join!(host, client);
+        assert!(!host.unwrap().capabilities().contains(Capabilities::CAN_FD));
+        assert_eq!(synced.len(), 1);
+        assert!(!synced[0].can_frame.is_fd());
+        assert_eq!(synced[0].can_frame.get_id(), 0x20);
+    }
+
+    #[tokio::test]
+    async fn client_rejects_incompatible_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = async {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; ConnectionHandshakeFrame::SIZE];
+            stream.read_exact(&mut request).await.unwrap();
+            let response = ConnectionHandshakeFrame::ServerClient {
+                version: PROTOCOL_VERSION + 1,
+                capabilities: Capabilities::empty(),
+                success: true,
+                node_id: 0,
+            };
+            stream.write_all(&response.into_bin()).await.unwrap();
+            stream
+        };
+        let (_stream, client) = tokio::join!(
+            server,
+            TcpCan::connect(
+                addr,
+                ConnectionId::Client {
+                    request_id: true,
+                    sync_history: false,
+                },
+            )
+        );
+        assert_eq!(
+            client.unwrap_err().kind(),
+            std::io::ErrorKind::ConnectionRefused
+        );
     }
 }
@@ -75,7 +75,7 @@ impl Watchdog {
     }
 
     pub async fn reset(&self) {
-        if let Err(_) = self.0.reset_tx.send(WatchdogSignal).await {
+        if self.0.reset_tx.send(WatchdogSignal).await.is_err() {
             unreachable!();
         }
     }
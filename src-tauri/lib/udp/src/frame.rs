@@ -35,6 +35,7 @@ pub struct NetworkDescription {
 }
 
 impl UdpFrame {
+    /// Multi byte fields are little endian.
     pub fn into_bin(&self, buf8: &mut [u8; 216]) {
         match &self {
             UdpFrame::Hello(hello) => {
@@ -70,11 +71,10 @@ impl UdpFrame {
                     buf8[152 + 1 + b] = server_name_bytes[b];
                 }
 
-                let buf16: &mut [u16; 108] = unsafe { std::mem::transmute(buf8) };
-                buf16[1] = ndf.service_port;
-                let buf64: &mut [u64; 27] = unsafe { std::mem::transmute(buf16) };
-                buf64[1] = ndf.config_hash;
-                buf64[2] = ndf.time_since_sor.as_micros() as u64;
+                buf8[2..4].copy_from_slice(&ndf.service_port.to_le_bytes());
+                buf8[8..16].copy_from_slice(&ndf.config_hash.to_le_bytes());
+                buf8[16..24]
+                    .copy_from_slice(&(ndf.time_since_sor.as_micros() as u64).to_le_bytes());
             }
         }
     }
@@ -94,11 +94,10 @@ impl UdpFrame {
             let server_name_len = buf8[152] as usize;
             let server_name =
                 String::from_utf8_lossy(&buf8[153..153 + server_name_len]).to_string();
-            let buf16: &[u16; 108] = unsafe { std::mem::transmute(buf8) };
-            let service_port = buf16[1];
-            let buf64: &[u64; 27] = unsafe { std::mem::transmute(buf16) };
-            let config_hash = buf64[1];
-            let time_since_sor = Duration::from_micros(buf64[2]);
+            let service_port = u16::from_le_bytes([buf8[2], buf8[3]]);
+            let config_hash = u64::from_le_bytes(buf8[8..16].try_into().unwrap());
+            let time_since_sor =
+                Duration::from_micros(u64::from_le_bytes(buf8[16..24].try_into().unwrap()));
             Ok(Self::NDF(NetworkDescriptionFrame {
                 service_name,
                 service_port,
@@ -0,0 +1,144 @@
+use std::{fmt::Display, path::PathBuf};
+
+/// Stable identifiers of all problems reported for a network configuration.
+/// The codes are part of the cli output and must not be renumbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCode {
+    /// the file is not valid yaml.
+    Syntax,
+    /// a included file could not be read.
+    Include,
+    ExpectedMap,
+    ExpectedString,
+    ExpectedList,
+    ExpectedInteger,
+    /// a time literal (e.g. "100ms" or "10ms-1s") could not be parsed.
+    InvalidInterval,
+    /// a value has the right type, but is not one of the accepted values.
+    InvalidValue,
+    MissingKey,
+    DuplicatedDefinition,
+    UndefinedType,
+    InvalidRange,
+    InvalidDatabase,
+    /// any other error reported while resolving the network.
+    Resolution,
+}
+
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::Syntax => "E001",
+            DiagnosticCode::Include => "E002",
+            DiagnosticCode::ExpectedMap => "E003",
+            DiagnosticCode::ExpectedString => "E004",
+            DiagnosticCode::ExpectedList => "E005",
+            DiagnosticCode::ExpectedInteger => "E006",
+            DiagnosticCode::InvalidInterval => "E007",
+            DiagnosticCode::InvalidValue => "E008",
+            DiagnosticCode::MissingKey => "E009",
+            DiagnosticCode::DuplicatedDefinition => "E010",
+            DiagnosticCode::UndefinedType => "E011",
+            DiagnosticCode::InvalidRange => "E012",
+            DiagnosticCode::InvalidDatabase => "E013",
+            DiagnosticCode::Resolution => "E014",
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Position within a configuration file, line and column start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub code: DiagnosticCode,
+    pub message: String,
+    /// None if the problem can't be associated with a position in the configuration.
+    pub location: Option<Location>,
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            location: None,
+            suggestion: None,
+        }
+    }
+
+    pub fn at(mut self, location: Option<Location>) -> Self {
+        self.location = location;
+        self
+    }
+
+    pub fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Formats the diagnostic as
+/// ```text
+/// error[E004]: description has to be a string
+///   --> network.yaml:12:18
+///   = help: quote the value
+/// ```
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error[{}]: {}", self.code, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, "\n  --> {location}")?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n  = help: {suggestion}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the candidate closest to name, if it is close enough to be a likely typo.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len().max(name.len()) / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
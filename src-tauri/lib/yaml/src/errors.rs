@@ -1,24 +1,25 @@
+use std::fmt::Display;
+
+use crate::diagnostics::Diagnostic;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
-    YamlScanError(yaml_rust::ScanError),
-    YamlInvalidFormat(String),
-    YamlInvalidType(String),
-    ConfigError(canzero_config::errors::ConfigError),
+    /// all problems found in the configuration, in the order they where found.
+    Diagnostics(Vec<Diagnostic>),
     Io(std::io::Error),
 }
 
-impl From<yaml_rust::ScanError> for Error {
-    fn from(value: yaml_rust::ScanError) -> Self {
-        Error::YamlScanError(value)
+impl From<Vec<Diagnostic>> for Error {
+    fn from(value: Vec<Diagnostic>) -> Self {
+        Error::Diagnostics(value)
     }
 }
 
-impl From<canzero_config::errors::ConfigError> for Error {
-    fn from(value: canzero_config::errors::ConfigError) -> Self {
-        Error::ConfigError(value)
+impl From<Diagnostic> for Error {
+    fn from(value: Diagnostic) -> Self {
+        Error::Diagnostics(vec![value])
     }
 }
 
@@ -27,3 +28,17 @@ impl From<std::io::Error> for Error {
         Error::Io(value)
     }
 }
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Diagnostics(diagnostics) => {
+                for diagnostic in diagnostics {
+                    writeln!(f, "{diagnostic}")?;
+                }
+                Ok(())
+            }
+            Error::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
@@ -1,12 +1,16 @@
 use std::{path::{Path, PathBuf}, str::FromStr};
 
-use canzero_config::{builder::NetworkBuilder, config::NetworkRef};
+use canzero_config::{builder::NetworkBuilder, config::NetworkRef, errors::ConfigError};
+use diagnostics::{did_you_mean, Diagnostic, DiagnosticCode};
 use errors::Result;
+use source::SourceFile;
 
 use crate::parser::parse_included_files;
 
+pub mod diagnostics;
 pub mod errors;
 mod parser;
+mod source;
 
 
 pub fn parse_yaml_config_from_file(path : &str) -> Result<NetworkRef> {
@@ -16,18 +20,121 @@ pub fn parse_yaml_config_from_file(path : &str) -> Result<NetworkRef> {
     Ok(network)
 }
 
+/// Parses and resolves the network, all problems are reported together
+/// as Error::Diagnostics.
 pub fn parse_yaml_config(src : &str, path : &Path) -> Result<NetworkRef> {
     let mut network_builder = NetworkBuilder::new();
 
-    let docs = yaml_rust::yaml::YamlLoader::load_from_str(src)?;
-    let doc = &docs[0];
-    //println!("{doc:?}");
-    parser::parse_top_level(doc, &mut network_builder, path)?;
+    let (doc, source) = SourceFile::parse(src, path)?;
+    let mut diagnostics = vec![];
+    let included_sources =
+        parser::parse_top_level(&doc, &source, &mut network_builder, &mut diagnostics);
+    if !diagnostics.is_empty() {
+        return Err(diagnostics.into());
+    }
 
-    let network = network_builder.build()?;
+    let type_names: Vec<String> = network_builder
+        .0
+        .borrow()
+        .types
+        .borrow()
+        .iter()
+        .map(|ty| ty.name())
+        .collect();
+    let mut sources = vec![&source];
+    sources.extend(included_sources.iter());
+    let network = network_builder
+        .build()
+        .map_err(|err| resolution_diagnostic(err, &sources, &type_names))?;
     Ok(network)
 }
 
+/// Errors of the network resolution don't know where they where caused,
+/// therefor they are located by the first occurrence of the offending name.
+pub(crate) fn resolution_diagnostic(err: ConfigError, sources: &[&SourceFile], type_names: &[String]) -> Diagnostic {
+    let locate = |name: &str| sources.iter().find_map(|source| source.find_scalar(name));
+    match err {
+        ConfigError::InvalidType(type_name) | ConfigError::UndefinedType(type_name) => {
+            let diagnostic = Diagnostic::new(
+                DiagnosticCode::UndefinedType,
+                format!("type {type_name:?} is not defined"),
+            )
+            .at(locate(&type_name));
+            match did_you_mean(&type_name, type_names.iter().map(String::as_str)) {
+                Some(candidate) => diagnostic.suggest(format!("did you mean {candidate:?}?")),
+                None => diagnostic.suggest(
+                    "define it in struct_types or enum_types or use a primitive type (e.g. u8, i16 or d16<0..10>)",
+                ),
+            }
+        }
+        ConfigError::InvalidRange(type_name) => Diagnostic::new(
+            DiagnosticCode::InvalidRange,
+            format!("the range of {type_name:?} is empty"),
+        )
+        .at(locate(&type_name))
+        .suggest("the minimum of a decimal range has to be less than the maximum (e.g. d16<0..10>)"),
+        ConfigError::InvalidArrayLength(type_name) => Diagnostic::new(
+            DiagnosticCode::InvalidValue,
+            format!("the length of the array type {type_name:?} is invalid"),
+        )
+        .at(locate(&type_name))
+        .suggest("arrays have to contain at least one element (e.g. u8[4])"),
+        ConfigError::NestedArray(type_name) => Diagnostic::new(
+            DiagnosticCode::InvalidValue,
+            format!("the array type {type_name:?} contains arrays"),
+        )
+        .at(locate(&type_name))
+        .suggest("arrays can't be nested, use an array of structs instead"),
+        ConfigError::NoBusAvaiable => Diagnostic::new(
+            DiagnosticCode::Resolution,
+            "no bus is available to transmit all messages",
+        )
+        .at(sources.iter().find_map(|source| source.find_key("buses")))
+        .suggest("add another bus or reduce the bus load"),
+        ConfigError::FailedToResolveId => Diagnostic::new(
+            DiagnosticCode::Resolution,
+            "failed to assign a unique id to every message",
+        )
+        .at(sources.iter().find_map(|source| source.find_key("buses")))
+        .suggest("reduce the number of fixed message ids or add another bus"),
+        ConfigError::DuplicatedSignal(name) => Diagnostic::new(
+            DiagnosticCode::DuplicatedDefinition,
+            format!("signal {name:?} is defined more than once in a message"),
+        )
+        .at(locate(&name))
+        .suggest("remove or rename one of the signals"),
+        ConfigError::DuplicatedEnumEntry(name) => Diagnostic::new(
+            DiagnosticCode::DuplicatedDefinition,
+            format!("enum variant {name:?} is defined more than once"),
+        )
+        .at(locate(&name))
+        .suggest("remove or rename one of the variants"),
+        ConfigError::DuplicatedStructAttribute(name) => Diagnostic::new(
+            DiagnosticCode::DuplicatedDefinition,
+            format!("struct attribute {name:?} is defined more than once"),
+        )
+        .at(locate(&name))
+        .suggest("remove or rename one of the attributes"),
+        ConfigError::InvalidDecimalDefinition(type_name) => Diagnostic::new(
+            DiagnosticCode::InvalidValue,
+            format!("the decimal type {type_name:?} is invalid"),
+        )
+        .at(locate(&type_name))
+        .suggest("decimals are defined by their size and range (e.g. d16<0..10>)"),
+        ConfigError::Io(err) => Diagnostic::new(
+            DiagnosticCode::Include,
+            format!("failed to read a file of the configuration : {err}"),
+        )
+        .at(sources.iter().find_map(|source| source.find_key("database"))),
+        ConfigError::CanDbc(err) => Diagnostic::new(
+            DiagnosticCode::InvalidDatabase,
+            format!("failed to parse a database : {err}"),
+        )
+        .at(sources.iter().find_map(|source| source.find_key("database")))
+        .suggest("databases have to be valid .dbc files"),
+    }
+}
+
 pub fn parse_yaml_config_files_from_file(path: &str) -> Result<Vec<PathBuf>> {
     let path = PathBuf::from_str(path).unwrap();
     let src = std::fs::read_to_string(&path)?;
@@ -35,8 +142,6 @@ pub fn parse_yaml_config_files_from_file(path: &str) -> Result<Vec<PathBuf>> {
 }
 
 pub fn parse_yaml_config_files(src :&str, path: &Path) -> Result<Vec<PathBuf>> {
-
-    let docs = yaml_rust::yaml::YamlLoader::load_from_str(src)?;
-    let doc = &docs[0];
-    parse_included_files(doc, path)
+    let (doc, source) = SourceFile::parse(src, path)?;
+    Ok(parse_included_files(&doc, &source)?)
 }
@@ -6,64 +6,239 @@ use std::{
 use canzero_config::{
     builder::{bus::BusBuilder, EnumBuilder, NetworkBuilder, NodeBuilder, StructBuilder},
     config::ObjectEntryAccess,
+    errors::ConfigError,
 };
+use yaml_rust::{yaml::Hash, Yaml};
 
-use crate::errors::{Error, Result};
+use crate::{
+    diagnostics::{did_you_mean, Diagnostic, DiagnosticCode},
+    resolution_diagnostic,
+    source::SourceFile,
+};
 
-pub fn parse_object_entry(
-    oe_name: &str,
-    oe_map: &yaml_rust::Yaml,
-    node_builder: &mut NodeBuilder,
-) -> Result<()> {
-    let yaml_rust::Yaml::Hash(map) = oe_map else {
-        return Err(Error::YamlInvalidType(format!(
-            "object_entries have to be defined by mapps"
-        )));
-    };
+type ParseResult<T = ()> = std::result::Result<T, Diagnostic>;
+
+/// The yaml node that is currently parsed, used to locate diagnostics.
+#[derive(Clone)]
+pub struct Context<'a> {
+    source: &'a SourceFile,
+    key_path: Vec<String>,
+}
 
-    let yaml_rust::Yaml::String(type_name) = &oe_map["type"] else {
-        return Err(Error::YamlInvalidType(format!(
-            "types have to be defined as strings by their name"
-        )));
+impl<'a> Context<'a> {
+    pub fn new(source: &'a SourceFile) -> Self {
+        Self {
+            source,
+            key_path: vec![],
+        }
+    }
+
+    fn at(&self, key: &str) -> Self {
+        let mut key_path = self.key_path.clone();
+        key_path.push(key.to_owned());
+        Self {
+            source: self.source,
+            key_path,
+        }
+    }
+
+    fn error(&self, code: DiagnosticCode, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(code, message).at(Some(self.source.location(&self.key_path)))
+    }
+}
+
+fn expect_map<'y>(ctx: &Context, yaml: &'y Yaml, what: &str) -> ParseResult<&'y Hash> {
+    match yaml {
+        Yaml::Hash(map) => Ok(map),
+        _ => Err(ctx
+            .error(DiagnosticCode::ExpectedMap, format!("{what} has to be a map"))
+            .suggest("use \"key: value\" pairs on separate, indented lines")),
+    }
+}
+
+fn expect_string<'y>(ctx: &Context, yaml: &'y Yaml, what: &str) -> ParseResult<&'y str> {
+    match yaml {
+        Yaml::String(string) => Ok(string),
+        _ => Err(ctx
+            .error(
+                DiagnosticCode::ExpectedString,
+                format!("{what} has to be a string"),
+            )
+            .suggest("quote the value, e.g. \"100ms\"")),
+    }
+}
+
+fn expect_list<'y>(ctx: &Context, yaml: &'y Yaml, what: &str) -> ParseResult<&'y Vec<Yaml>> {
+    match yaml {
+        Yaml::Array(list) => Ok(list),
+        _ => Err(ctx
+            .error(DiagnosticCode::ExpectedList, format!("{what} has to be a list"))
+            .suggest("write one \"- item\" per line")),
+    }
+}
+
+/// Keys of maps have to be strings.
+fn key_name<'y>(ctx: &Context, key: &'y Yaml, what: &str) -> ParseResult<&'y str> {
+    match key {
+        Yaml::String(name) => Ok(name),
+        _ => Err(ctx
+            .error(
+                DiagnosticCode::ExpectedString,
+                format!("the name of a {what} has to be a string"),
+            )
+            .suggest("names have to start with a letter")),
+    }
+}
+
+fn optional<'y>(map: &Hash, yaml: &'y Yaml, key: &str) -> Option<&'y Yaml> {
+    if map.contains_key(&Yaml::String(key.to_owned())) {
+        Some(&yaml[key])
+    } else {
+        None
+    }
+}
+
+/// Converts errors of the config builders, which are reported while parsing.
+/// They are described like errors of the resolution, but located at the offending key.
+fn config_error(ctx: &Context, err: ConfigError) -> Diagnostic {
+    resolution_diagnostic(err, &[ctx.source], &[]).at(Some(ctx.source.location(&ctx.key_path)))
+}
+
+fn parse_duration(ctx: &Context, value: &str, unit: &str) -> ParseResult<Duration> {
+    let Ok(value) = value.parse::<u64>() else {
+        return Err(ctx.error(
+            DiagnosticCode::InvalidInterval,
+            format!("{value}{unit} is out of range"),
+        ));
     };
+    Ok(match unit {
+        "ms" => Duration::from_millis(value),
+        _ => Duration::from_secs(value),
+    })
+}
+
+/// Parses time literals like "100ms" or "2s".
+fn parse_time_literal(ctx: &Context, literal: &str) -> ParseResult<Duration> {
+    let single = regex::Regex::new(r"^\s*(?<x>\d+)\s*(?<unit>(ms|s))\s*$").unwrap();
+    match single.captures(literal) {
+        Some(captures) => parse_duration(ctx, &captures["x"], &captures["unit"]),
+        None => Err(ctx
+            .error(
+                DiagnosticCode::InvalidInterval,
+                format!("{literal:?} is not a valid time literal"),
+            )
+            .suggest("time literals have the form \"<integer>ms\" or \"<integer>s\", e.g. \"100ms\"")),
+    }
+}
 
+/// Parses stream intervals, which are either a single time literal or a range
+/// of time literals (e.g. "10ms - 1s").
+fn parse_interval(ctx: &Context, literal: &str) -> ParseResult<(Duration, Duration)> {
+    let range = regex::Regex::new(
+        r"^\s*(?<min>\d+)\s*(?<min_unit>(ms|s))\s*-\s*(?<max>\d+)\s*(?<max_unit>(ms|s))\s*$",
+    )
+    .unwrap();
+    match range.captures(literal) {
+        Some(captures) => {
+            let min = parse_duration(ctx, &captures["min"], &captures["min_unit"])?;
+            let max = parse_duration(ctx, &captures["max"], &captures["max_unit"])?;
+            if min > max {
+                return Err(ctx
+                    .error(
+                        DiagnosticCode::InvalidRange,
+                        format!("the minimum of the interval {literal:?} is larger than the maximum"),
+                    )
+                    .suggest("intervals are given as \"<min> - <max>\""));
+            }
+            Ok((min, max))
+        }
+        None => {
+            let interval = parse_time_literal(ctx, literal).map_err(|diagnostic| {
+                diagnostic.suggest(
+                    "intervals are given as a time literal (e.g. \"100ms\") or as a range (e.g. \"10ms - 1s\")",
+                )
+            })?;
+            Ok((interval, interval))
+        }
+    }
+}
 
-    let oe_builder = node_builder.create_object_entry(oe_name, &type_name);
+/// Reads a file, which is included by a path relative to the including file.
+fn include(ctx: &Context, include_path: &str) -> ParseResult<(Yaml, SourceFile)> {
+    let path = ctx
+        .source
+        .path()
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(include_path);
+    SourceFile::load(&path).map_err(|diagnostic| match diagnostic.code {
+        // syntax errors are located in the included file.
+        DiagnosticCode::Syntax => diagnostic,
+        _ => ctx
+            .error(diagnostic.code, diagnostic.message)
+            .suggest("paths of included files are relative to the including file"),
+    })
+}
 
-    if map.contains_key(&yaml_rust::Yaml::String("description".to_owned())) {
-        let yaml_rust::Yaml::String(description) = &oe_map["description"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "descriptions have to be defined has strings"
-            )));
-        };
-        oe_builder.add_description(&description);
+pub fn parse_object_entry(
+    ctx: &Context,
+    oe_name: &str,
+    oe_map: &Yaml,
+    node_builder: &mut NodeBuilder,
+) -> ParseResult {
+    let map = expect_map(ctx, oe_map, &format!("object entry {oe_name}"))?;
+
+    let Some(type_yaml) = optional(map, oe_map, "type") else {
+        return Err(ctx
+            .error(
+                DiagnosticCode::MissingKey,
+                format!("object entry {oe_name} does not define a type"),
+            )
+            .suggest("add \"type: <type name>\", e.g. \"type: u8\""));
+    };
+    let type_name = expect_string(
+        &ctx.at("type"),
+        type_yaml,
+        &format!("the type of object entry {oe_name}"),
+    )?;
+
+    let oe_builder = node_builder.create_object_entry(oe_name, type_name);
+
+    if let Some(description) = optional(map, oe_map, "description") {
+        let description = expect_string(
+            &ctx.at("description"),
+            description,
+            &format!("the description of object entry {oe_name}"),
+        )?;
+        oe_builder.add_description(description);
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("friend".to_owned())) {
-        let yaml_rust::Yaml::String(friend) = &oe_map["friend"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "descriptions have to be defined has strings"
-            )));
-        };
-        oe_builder.friend(&friend);
+    if let Some(friend) = optional(map, oe_map, "friend") {
+        let friend = expect_string(
+            &ctx.at("friend"),
+            friend,
+            &format!("the friend of object entry {oe_name}"),
+        )?;
+        oe_builder.friend(friend);
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("unit".to_owned())) {
-        let yaml_rust::Yaml::String(unit) = &oe_map["unit"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "unit has to be defined as a string"
-            )));
-        };
-        oe_builder.add_unit(&unit);
+    if let Some(unit) = optional(map, oe_map, "unit") {
+        let unit = expect_string(
+            &ctx.at("unit"),
+            unit,
+            &format!("the unit of object entry {oe_name}"),
+        )?;
+        oe_builder.add_unit(unit);
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("access".to_owned())) {
-        let yaml_rust::Yaml::String(access) = &oe_map["access"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "access has to be defined as a string"
-            )));
-        };
-        let access = access.to_lowercase();
+    if let Some(access) = optional(map, oe_map, "access") {
+        let access_ctx = ctx.at("access");
+        let access = expect_string(
+            &access_ctx,
+            access,
+            &format!("the access of object entry {oe_name}"),
+        )?
+        .to_lowercase();
         if access == "const" {
             oe_builder.set_access(ObjectEntryAccess::Const);
         } else if access == "local" || access == "readonly" || access == "static" {
@@ -72,6 +247,13 @@ pub fn parse_object_entry(
             oe_builder.set_access(ObjectEntryAccess::Global);
         } else if access == "extern" || access == "external" {
             oe_builder.set_access(ObjectEntryAccess::Global);
+        } else {
+            return Err(access_ctx
+                .error(
+                    DiagnosticCode::InvalidValue,
+                    format!("{access:?} is not a valid access for object entry {oe_name}"),
+                )
+                .suggest("use one of const, local, readonly, static, global, readwrite or extern"));
         }
     }
 
@@ -79,174 +261,140 @@ pub fn parse_object_entry(
 }
 
 pub fn parse_tx_stream(
+    ctx: &Context,
     stream_name: &str,
-    stream_def: &yaml_rust::Yaml,
+    stream_def: &Yaml,
     node_builder: &mut NodeBuilder,
-) -> Result<()> {
-    let stream_builder = node_builder.create_stream(stream_name);
+    declared_object_entries: &[String],
+) -> ParseResult {
+    let map = expect_map(ctx, stream_def, &format!("stream {stream_name}"))?;
 
-    let yaml_rust::Yaml::Hash(map) = stream_def else {
-        return Err(Error::YamlInvalidType(format!(
-            "streams have to be defined has maps"
-        )));
-    };
-
-    if map.contains_key(&yaml_rust::Yaml::String("description".to_owned())) {
-        let yaml_rust::Yaml::String(description) = &stream_def["description"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "description has to be a string"
-            )));
-        };
-        stream_builder.add_description(&description);
-    }
+    let stream_builder = node_builder.create_stream(stream_name);
 
-    if map.contains_key(&yaml_rust::Yaml::String("mapping".to_owned())) {
-        let yaml_rust::Yaml::Array(array) = &stream_def["mapping"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "stream mappings have to be arrays"
-            )));
-        };
-        for oe_name in array {
-            let yaml_rust::Yaml::String(oe_name) = oe_name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "stream mappings entries have to be names of object entries"
-                )));
-            };
-            stream_builder.add_entry(oe_name);
-        }
+    if let Some(description) = optional(map, stream_def, "description") {
+        let description = expect_string(
+            &ctx.at("description"),
+            description,
+            &format!("the description of stream {stream_name}"),
+        )?;
+        stream_builder.add_description(description);
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("interval".to_owned())) {
-        let yaml_rust::Yaml::String(interval) = &stream_def["interval"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "stream intervals have to be defined has strings"
-            )));
-        };
-
-        let range_interval = regex::Regex::new(
-            r"(?<min>\d+)\s*(?<min_unit>(ms|s))\s*-\s*(?<max>\d+)\s*(?<max_unit>(ms|s))",
-        )
-        .unwrap();
-        match range_interval.captures(interval) {
-            Some(captures) => {
-                let min = &captures["min"];
-                let min_unit = &captures["min_unit"];
-                let max = &captures["max"];
-                let max_unit = &captures["max_unit"];
-
-                let min: u64 = min.parse().unwrap();
-                let min = if min_unit == "ms" {
-                    Duration::from_millis(min)
-                } else if min_unit == "s" {
-                    Duration::from_secs(min)
-                } else {
-                    panic!()
-                };
-                let max: u64 = max.parse().unwrap();
-                let max = if max_unit == "ms" {
-                    Duration::from_millis(max)
-                } else if max_unit == "s" {
-                    Duration::from_secs(max)
-                } else {
-                    panic!()
-                };
-                stream_builder.set_interval(min, max);
-            }
-            None => {
-                let single_interval = regex::Regex::new(r"(?<x>\d+)\s*(?<unit>(ms|s))").unwrap();
-                match single_interval.captures(interval) {
-                    Some(captures) => {
-                        let interval = &captures["x"];
-                        let unit = &captures["unit"];
-                        let interval: u64 = interval.parse().unwrap();
-                        let interval = if unit == "ms" {
-                            Duration::from_millis(interval)
-                        } else if unit == "s" {
-                            Duration::from_secs(interval)
-                        } else {
-                            panic!()
-                        };
-                        stream_builder.set_interval(interval, interval);
-                    }
-                    None => {
-                        panic!("intervals have to be defined as strings with \"\\d+(ms|s)\"");
-                    }
-                }
+    if let Some(mapping) = optional(map, stream_def, "mapping") {
+        let mapping_ctx = ctx.at("mapping");
+        let mapping = expect_list(
+            &mapping_ctx,
+            mapping,
+            &format!("the mapping of stream {stream_name}"),
+        )?;
+        for (i, oe_name) in mapping.iter().enumerate() {
+            let oe_name = expect_string(
+                &mapping_ctx.at(&i.to_string()),
+                oe_name,
+                "a stream mapping entry (the name of a object entry)",
+            )?;
+            let defined = node_builder
+                .0
+                .borrow()
+                .object_entries
+                .iter()
+                .any(|oe| oe.0.borrow().name == oe_name);
+            if defined {
+                stream_builder.add_entry(oe_name);
+            } else if !declared_object_entries.iter().any(|name| name == oe_name) {
+                let diagnostic = mapping_ctx.at(&i.to_string()).error(
+                    DiagnosticCode::InvalidValue,
+                    format!("stream {stream_name} maps the undefined object entry {oe_name}"),
+                );
+                return Err(
+                    match did_you_mean(oe_name, declared_object_entries.iter().map(String::as_str)) {
+                        Some(candidate) => diagnostic.suggest(format!("did you mean {candidate:?}?")),
+                        None => diagnostic
+                            .suggest("streams can only map object entries of their own node"),
+                    },
+                );
             }
+            // else the object entry is invalid, which was already reported.
         }
     }
 
-    // TODO parse interval.
+    if let Some(interval) = optional(map, stream_def, "interval") {
+        let interval_ctx = ctx.at("interval");
+        let interval = expect_string(
+            &interval_ctx,
+            interval,
+            &format!("the interval of stream {stream_name}"),
+        )?;
+        let (min, max) = parse_interval(&interval_ctx, interval)?;
+        stream_builder.set_interval(min, max);
+    }
 
     Ok(())
 }
 
 pub fn parse_tx_command(
+    ctx: &Context,
     command_name: &str,
-    command_map: &yaml_rust::Yaml,
+    command_map: &Yaml,
     node_builder: &mut NodeBuilder,
-) -> Result<()> {
-    let command_builder = node_builder.create_command(command_name, None);
+) -> ParseResult {
+    let map = expect_map(ctx, command_map, &format!("command {command_name}"))?;
 
-    let yaml_rust::Yaml::Hash(map) = command_map else {
-        return Err(Error::YamlInvalidType(format!(
-            "commands have to be defined has maps"
-        )));
-    };
+    let command_builder = node_builder.create_command(command_name, None);
 
-    if map.contains_key(&yaml_rust::Yaml::String("description".to_owned())) {
-        let yaml_rust::Yaml::String(description) = &command_map["description"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "description has to be a string"
-            )));
-        };
-        command_builder.add_description(&description);
+    if let Some(description) = optional(map, command_map, "description") {
+        let description = expect_string(
+            &ctx.at("description"),
+            description,
+            &format!("the description of command {command_name}"),
+        )?;
+        command_builder.add_description(description);
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("arguments".to_owned())) {
-        let yaml_rust::Yaml::Array(args) = &command_map["arguments"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "the argument list of a command has to be a array"
-            )));
-        };
-        for arg_map in args {
-            let yaml_rust::Yaml::Hash(arg_map) = arg_map else {
-                return Err(Error::YamlInvalidType(format!(
-                    "command args have to be defined as \" <name> : <type> \" "
-                )));
+    if let Some(args) = optional(map, command_map, "arguments") {
+        let args_ctx = ctx.at("arguments");
+        let args = expect_list(
+            &args_ctx,
+            args,
+            &format!("the argument list of command {command_name}"),
+        )?;
+        for (i, arg) in args.iter().enumerate() {
+            let arg_ctx = args_ctx.at(&i.to_string());
+            let invalid_argument = || {
+                arg_ctx
+                    .error(
+                        DiagnosticCode::InvalidValue,
+                        format!("invalid argument of command {command_name}"),
+                    )
+                    .suggest("arguments are defined as \"- <name> : <type>\"")
+            };
+            let Yaml::Hash(arg_map) = arg else {
+                return Err(invalid_argument());
             };
             if arg_map.len() != 1 {
-                return Err(Error::YamlInvalidType(format!(
-                    "command args have to be defined as \" <name> : <type> \" "
-                )));
+                return Err(invalid_argument());
             }
             let (name_yaml, type_yaml) = arg_map.iter().next().unwrap();
-            let yaml_rust::Yaml::String(name) = name_yaml else {
-                return Err(Error::YamlInvalidType(format!(
-                    "command args have to be defined as \" <name> : <type> \" "
-                )));
-            };
-            let yaml_rust::Yaml::String(ty) = type_yaml else {
-                return Err(Error::YamlInvalidType(format!(
-                    "command args have to be defined as \" <name> : <type> \" "
-                )));
+            let (Yaml::String(name), Yaml::String(ty)) = (name_yaml, type_yaml) else {
+                return Err(invalid_argument());
             };
             command_builder.add_argument(name, ty);
         }
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("callee".to_owned())) {
-        let yaml_rust::Yaml::Array(callees) = &command_map["callee"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "the argument list of a command has to be a array"
-            )));
-        };
-        for callee in callees {
-            let yaml_rust::Yaml::String(callee_name) = callee else {
-                return Err(Error::YamlInvalidType(format!(
-                    "callees have to refered to by name (String)"
-                )));
-            };
+    if let Some(callees) = optional(map, command_map, "callee") {
+        let callees_ctx = ctx.at("callee");
+        let callees = expect_list(
+            &callees_ctx,
+            callees,
+            &format!("the callees of command {command_name}"),
+        )?;
+        for (i, callee) in callees.iter().enumerate() {
+            let callee_name = expect_string(
+                &callees_ctx.at(&i.to_string()),
+                callee,
+                "a callee (the name of a node)",
+            )?;
             command_builder.add_callee(callee_name);
         }
     }
@@ -255,390 +403,439 @@ pub fn parse_tx_command(
 }
 
 pub fn parse_rx_stream(
+    ctx: &Context,
     node_name: &str,
     stream_name: &str,
-    stream_def: &yaml_rust::Yaml,
+    stream_def: &Yaml,
     node_builder: &mut NodeBuilder,
-) -> Result<()> {
+) -> ParseResult {
+    let map = expect_map(
+        ctx,
+        stream_def,
+        &format!("the received stream {node_name}.{stream_name}"),
+    )?;
     let rx_stream_builder = node_builder.receive_stream(node_name, stream_name);
 
-    //parse stream_def as oe mapping
-    let yaml_rust::Yaml::Hash(map) = stream_def else {
-        return Err(Error::YamlInvalidType(format!(
-            "rx_streams have to be defined as maps of oe entries"
-        )));
-    };
     for (tx_oe_name, rx_oe_name) in map {
-        let yaml_rust::Yaml::String(tx_oe_name) = tx_oe_name else {
-            return Err(Error::YamlInvalidType(format!(
-                "object entries have to be refered to by name in rx_stream definition"
-            )));
-        };
-        let yaml_rust::Yaml::String(rx_oe_name) = rx_oe_name else {
-            return Err(Error::YamlInvalidType(format!(
-                "object entries have to be refered to by name in rx_stream definition"
-            )));
-        };
+        let tx_oe_name = key_name(ctx, tx_oe_name, "transmitted object entry")?;
+        let rx_oe_name = expect_string(
+            &ctx.at(tx_oe_name),
+            rx_oe_name,
+            &format!("the object entry receiving {tx_oe_name}"),
+        )?;
         rx_stream_builder.map(tx_oe_name, rx_oe_name);
     }
     Ok(())
 }
 
+/// Parses all entries of a map, errors of one entry don't prevent
+/// the remaining entries from being parsed.
+fn parse_entries(
+    ctx: &Context,
+    map: &Hash,
+    what: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+    mut parse: impl FnMut(&Context, &str, &Yaml) -> ParseResult,
+) {
+    for (name, def) in map {
+        let result = key_name(ctx, name, what).and_then(|name| parse(&ctx.at(name), name, def));
+        if let Err(diagnostic) = result {
+            diagnostics.push(diagnostic);
+        }
+    }
+}
+
 pub fn parse_node(
+    ctx: &Context,
     node_name: &str,
-    node_map: &yaml_rust::Yaml,
+    node_map: &Yaml,
     network_builder: &mut NetworkBuilder,
-) -> Result<()> {
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ParseResult {
+    let map = expect_map(ctx, node_map, &format!("node {node_name}"))?;
     let mut node_builder = network_builder.create_node(node_name);
-    let yaml_rust::Yaml::Hash(map) = node_map else {
-        return Err(Error::YamlInvalidType(format!(
-            "nodes have to be defined as maps"
-        )));
-    };
 
-    if map.contains_key(&yaml_rust::Yaml::String("heartbeat_timeout".to_owned())) {
-        let yaml_rust::Yaml::String(heartbeat_timeout) = &node_map["heartbeat_timeout"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "heartbeat_timeout has to be a time literal [for example 100ms]"
-            )));
-        };
-        let single_interval = regex::Regex::new(r"(?<x>\d+)\s*(?<unit>(ms|s))").unwrap();
-        match single_interval.captures(heartbeat_timeout) {
-            Some(captures) => {
-                let interval = &captures["x"];
-                let unit = &captures["unit"];
-                let interval: u64 = interval.parse().unwrap();
-                let interval = if unit == "ms" {
-                    Duration::from_millis(interval)
-                } else if unit == "s" {
-                    Duration::from_secs(interval)
-                } else {
-                    panic!()
-                };
-                node_builder.heartbeat_timeout(interval);
-            }
-            None => {
-                panic!("intervals have to be defined as strings with \"\\d+(ms|s)\"");
-            }
-        };
+    if let Some(heartbeat_timeout) = optional(map, node_map, "heartbeat_timeout") {
+        let timeout_ctx = ctx.at("heartbeat_timeout");
+        let result = expect_string(
+            &timeout_ctx,
+            heartbeat_timeout,
+            &format!("the heartbeat_timeout of node {node_name}"),
+        )
+        .and_then(|timeout| parse_time_literal(&timeout_ctx, timeout));
+        match result {
+            Ok(timeout) => node_builder.heartbeat_timeout(timeout),
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("description".to_owned())) {
-        let yaml_rust::Yaml::String(description) = &node_map["description"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "description has to be a string"
-            )));
-        };
-        node_builder.add_description(&description);
-    }
-    if map.contains_key(&yaml_rust::Yaml::String("object_dictionary".to_owned())) {
-        let yaml_rust::Yaml::Hash(od_map) = &node_map["object_dictionary"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "object_dictionary has to be a map"
-            )));
-        };
-        for (name, oe_map) in od_map {
-            let yaml_rust::Yaml::String(name) = name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "the name of a object_entry has to be a string"
-                )));
-            };
-            parse_object_entry(name, oe_map, &mut node_builder)?;
+    if let Some(description) = optional(map, node_map, "description") {
+        match expect_string(
+            &ctx.at("description"),
+            description,
+            &format!("the description of node {node_name}"),
+        ) {
+            Ok(description) => node_builder.add_description(description),
+            Err(diagnostic) => diagnostics.push(diagnostic),
         }
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("tx_streams".to_owned())) {
-        let yaml_rust::Yaml::Hash(streams_map) = &node_map["tx_streams"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "tx_streams have to be defined as maps"
-            )));
-        };
-        for (stream_name, stream_def) in streams_map {
-            let yaml_rust::Yaml::String(stream_name) = stream_name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "the name of a stream has to be a string"
-                )));
-            };
-            parse_tx_stream(&stream_name, stream_def, &mut node_builder)?;
-        }
+    if let Some(od) = optional(map, node_map, "object_dictionary") {
+        let od_ctx = ctx.at("object_dictionary");
+        let od_map = expect_map(
+            &od_ctx,
+            od,
+            &format!("the object_dictionary of node {node_name}"),
+        )?;
+        parse_entries(&od_ctx, od_map, "object entry", diagnostics, |ctx, name, def| {
+            parse_object_entry(ctx, name, def, &mut node_builder)
+        });
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("rx_streams".to_owned())) {
-        let yaml_rust::Yaml::Hash(rx_node) = &node_map["rx_streams"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "rx_streams have to be defined as maps"
-            )));
-        };
-        for (node_name, tx_node_streams) in rx_node {
-            let yaml_rust::Yaml::String(node_name) = node_name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "rx_streams has to contains the names of the tx_nodes has strings"
-                )));
+    // names of all object entries, including the ones which failed to parse.
+    let declared_object_entries: Vec<String> = match optional(map, node_map, "object_dictionary") {
+        Some(Yaml::Hash(od_map)) => od_map
+            .keys()
+            .filter_map(|key| key.as_str().map(str::to_owned))
+            .collect(),
+        _ => vec![],
+    };
+
+    if let Some(tx_streams) = optional(map, node_map, "tx_streams") {
+        let streams_ctx = ctx.at("tx_streams");
+        let streams_map = expect_map(
+            &streams_ctx,
+            tx_streams,
+            &format!("the tx_streams of node {node_name}"),
+        )?;
+        parse_entries(&streams_ctx, streams_map, "stream", diagnostics, |ctx, name, def| {
+            parse_tx_stream(ctx, name, def, &mut node_builder, &declared_object_entries)
+        });
+    }
+
+    if let Some(rx_streams) = optional(map, node_map, "rx_streams") {
+        let rx_ctx = ctx.at("rx_streams");
+        let rx_node = expect_map(
+            &rx_ctx,
+            rx_streams,
+            &format!("the rx_streams of node {node_name}"),
+        )?;
+        for (tx_node_name, tx_node_streams) in rx_node {
+            let tx_node_name = match key_name(&rx_ctx, tx_node_name, "transmitting node") {
+                Ok(tx_node_name) => tx_node_name,
+                Err(diagnostic) => {
+                    diagnostics.push(diagnostic);
+                    continue;
+                }
             };
-            let yaml_rust::Yaml::Hash(tx_node_streams) = tx_node_streams else {
-                return Err(Error::YamlInvalidType(format!(
-                    "rx_streams has to be a map of names of tx_nodes, which has to be a map of the tx_streams that are received"
-                )));
+            let tx_node_ctx = rx_ctx.at(tx_node_name);
+            let tx_node_streams = match expect_map(
+                &tx_node_ctx,
+                tx_node_streams,
+                &format!("the streams received from {tx_node_name}"),
+            ) {
+                Ok(tx_node_streams) => tx_node_streams,
+                Err(diagnostic) => {
+                    diagnostics.push(diagnostic.suggest(
+                        "rx_streams map the names of transmitting nodes to the streams received from them",
+                    ));
+                    continue;
+                }
             };
-            for (stream_name, stream_def) in tx_node_streams {
-                let yaml_rust::Yaml::String(stream_name) = stream_name else {
-                    return Err(Error::YamlInvalidType(format!(
-                        "stream names have to be defined as strings"
-                    )));
-                };
-                parse_rx_stream(node_name, stream_name, stream_def, &mut node_builder)?;
-            }
+            parse_entries(&tx_node_ctx, tx_node_streams, "stream", diagnostics, |ctx, stream_name, stream_def| {
+                parse_rx_stream(ctx, tx_node_name, stream_name, stream_def, &mut node_builder)
+            });
         }
     }
 
-    if map.contains_key(&yaml_rust::Yaml::String("commands".to_owned())) {
-        let yaml_rust::Yaml::Hash(commands) = &node_map["commands"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "command lists have to be defined has maps"
-            )));
-        };
-        for (command_name, command_def) in commands {
-            let yaml_rust::Yaml::String(command_name) = command_name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "the name of a command has to be a string"
-                )));
-            };
-            parse_tx_command(command_name, command_def, &mut node_builder)?;
-        }
+    if let Some(commands) = optional(map, node_map, "commands") {
+        let commands_ctx = ctx.at("commands");
+        let commands = expect_map(
+            &commands_ctx,
+            commands,
+            &format!("the commands of node {node_name}"),
+        )?;
+        parse_entries(&commands_ctx, commands, "command", diagnostics, |ctx, name, def| {
+            parse_tx_command(ctx, name, def, &mut node_builder)
+        });
     }
     Ok(())
 }
 
-pub fn parse_enum_type(enum_map: &yaml_rust::Yaml, enum_builder: &mut EnumBuilder) -> Result<()> {
-    let yaml_rust::Yaml::Hash(enum_hash_map) = enum_map else {
-        return Err(Error::YamlInvalidType(format!(
-            "Enums have to be given as a map with variants"
-        )));
-    };
+pub fn parse_enum_type(ctx: &Context, enum_name: &str, enum_map: &Yaml, enum_builder: &mut EnumBuilder) -> ParseResult {
+    let enum_hash_map = expect_map(ctx, enum_map, &format!("enum {enum_name}"))
+        .map_err(|diagnostic| diagnostic.suggest("enums are given as a map of variants, e.g. \"Ok:\" or \"Ok: 0\""))?;
     for (variant_name, variant_value) in enum_hash_map {
-        let yaml_rust::Yaml::String(variant_name) = variant_name else {
-            return Err(Error::YamlInvalidType(format!(
-                "enum variants must be string"
-            )));
-        };
+        let variant_name = key_name(ctx, variant_name, "enum variant")?;
+        let variant_ctx = ctx.at(variant_name);
         let value = match variant_value {
-            yaml_rust::Yaml::Integer(value) => {
+            Yaml::Integer(value) => {
                 if *value < 0 {
-                    return Err(Error::YamlInvalidType(format!(
-                        "enum values must be positive"
-                    )));
+                    return Err(variant_ctx
+                        .error(
+                            DiagnosticCode::InvalidValue,
+                            format!("the value of enum variant {enum_name}::{variant_name} is negative"),
+                        )
+                        .suggest("enum values have to be positive"));
                 }
                 Some(*value as u64)
             }
-            yaml_rust::Yaml::Null => None,
+            Yaml::Null => None,
             _ => {
-                return Err(Error::YamlInvalidType(format!(
-                    "enum variants must be string"
-                )));
+                return Err(variant_ctx
+                    .error(
+                        DiagnosticCode::ExpectedInteger,
+                        format!("the value of enum variant {enum_name}::{variant_name} has to be an integer"),
+                    )
+                    .suggest("leave the value empty to assign the values implicitly"));
             }
         };
-        enum_builder.add_entry(variant_name, value)?;
+        enum_builder
+            .add_entry(variant_name, value)
+            .map_err(|err| config_error(&variant_ctx, err))?;
     }
     Ok(())
 }
 
 pub fn parse_struct_type(
-    struct_map: &yaml_rust::Yaml,
+    ctx: &Context,
+    struct_name: &str,
+    struct_map: &Yaml,
     struct_builder: &mut StructBuilder,
-) -> Result<()> {
-    let yaml_rust::Yaml::Hash(struct_hash_map) = struct_map else {
-        return Err(Error::YamlInvalidType(format!(
-            "structs have to be given as a map with of attributes"
-        )));
-    };
+) -> ParseResult {
+    let struct_hash_map = expect_map(ctx, struct_map, &format!("struct {struct_name}"))
+        .map_err(|diagnostic| diagnostic.suggest("structs are given as a map of attributes, e.g. \"x: d16<0..10>\""))?;
     for (attribute_name, attribute_type) in struct_hash_map {
-        let yaml_rust::Yaml::String(attribute_name) = attribute_name else {
-            return Err(Error::YamlInvalidType(format!(
-                "struct attributes have to be denoted by strings"
-            )));
-        };
-        let yaml_rust::Yaml::String(attribute_type) = attribute_type else {
-            return Err(Error::YamlInvalidType(format!(
-                "struct attributes need to have a string encoded type"
-            )));
-        };
-        struct_builder.add_attribute(attribute_name, attribute_type)?;
+        let attribute_name = key_name(ctx, attribute_name, "struct attribute")?;
+        let attribute_ctx = ctx.at(attribute_name);
+        let attribute_type = expect_string(
+            &attribute_ctx,
+            attribute_type,
+            &format!("the type of {struct_name}.{attribute_name}"),
+        )?;
+        struct_builder
+            .add_attribute(attribute_name, attribute_type)
+            .map_err(|err| config_error(&attribute_ctx, err))?;
     }
     Ok(())
 }
 
 pub fn parse_bus(
+    ctx: &Context,
+    bus_name: &str,
     network_builder: &NetworkBuilder,
-    bus_map: &yaml_rust::Yaml,
+    bus_map: &Yaml,
     bus_builder: &BusBuilder,
-) -> Result<()> {
-    let yaml_rust::Yaml::Hash(bus_hash_map) = bus_map else {
-        return Err(Error::YamlInvalidType(format!(
-            "bus must be described by a map"
-        )));
-    };
+) -> ParseResult {
+    let bus_hash_map = expect_map(ctx, bus_map, &format!("bus {bus_name}"))?;
+
+    if let Some(baudrate) = optional(bus_hash_map, bus_map, "baudrate") {
+        let Yaml::Integer(baudrate) = baudrate else {
+            return Err(ctx
+                .at("baudrate")
+                .error(
+                    DiagnosticCode::ExpectedInteger,
+                    format!("the baudrate of bus {bus_name} has to be an integer"),
+                )
+                .suggest("baudrates are given in bit/s, e.g. 1000000"));
+        };
+        bus_builder.baudrate(*baudrate as u32);
+    }
 
-    bus_hash_map
-        .get(&yaml_rust::yaml::Yaml::String("baudrate".to_owned()))
-        .map(|yaml| {
-            let yaml_rust::Yaml::Integer(baudrate) = yaml else {
-                panic!("baudrate must be integer value");
-            };
-            bus_builder.baudrate(*baudrate as u32);
-        });
+    if let Some(fd) = optional(bus_hash_map, bus_map, "fd") {
+        let Yaml::Boolean(fd) = fd else {
+            return Err(ctx
+                .at("fd")
+                .error(
+                    DiagnosticCode::InvalidValue,
+                    format!("fd of bus {bus_name} has to be either true or false"),
+                )
+                .suggest("fd: true declares a CAN FD bus"));
+        };
+        bus_builder.fd(*fd);
+    }
 
-    bus_hash_map
-        .get(&yaml_rust::yaml::Yaml::String("database".to_owned()))
-        .map(|yaml| {
-            let yaml_rust::Yaml::String(path) = yaml else {
-                panic!("database paths have to be strings ending in .dbc");
-            };
-            match network_builder.include_dbc(&bus_builder.0.borrow().name, path) {
-                Ok(_) => (),
-                Err(err) => panic!("Failed to include DBC \n{err:?}"),
-            }
-        });
+    if let Some(data_baudrate) = optional(bus_hash_map, bus_map, "data_baudrate") {
+        let Yaml::Integer(data_baudrate) = data_baudrate else {
+            return Err(ctx
+                .at("data_baudrate")
+                .error(
+                    DiagnosticCode::ExpectedInteger,
+                    format!("the data_baudrate of bus {bus_name} has to be an integer"),
+                )
+                .suggest("baudrates are given in bit/s, e.g. 5000000"));
+        };
+        if let Some(Yaml::Boolean(false)) = optional(bus_hash_map, bus_map, "fd") {
+            return Err(ctx
+                .at("data_baudrate")
+                .error(
+                    DiagnosticCode::InvalidValue,
+                    format!("bus {bus_name} defines a data_baudrate, but isn't a CAN FD bus"),
+                )
+                .suggest("remove fd: false or the data_baudrate"));
+        }
+        bus_builder.data_baudrate(*data_baudrate as u32);
+    }
+
+    if let Some(database) = optional(bus_hash_map, bus_map, "database") {
+        let database_ctx = ctx.at("database");
+        let path = expect_string(
+            &database_ctx,
+            database,
+            &format!("the database of bus {bus_name}"),
+        )?;
+        if let Err(err) = network_builder.include_dbc(bus_name, path) {
+            return Err(database_ctx
+                .error(
+                    DiagnosticCode::InvalidDatabase,
+                    format!("failed to include the database {path:?} : {err:?}"),
+                )
+                .suggest("databases have to be valid .dbc files"));
+        }
+    }
 
     Ok(())
 }
 
+/// Returns the map defined at key, which is either given inline or as the path
+/// of a included file. Included files are appended to sources.
+fn section(
+    ctx: &Context,
+    yaml: &Yaml,
+    key: &str,
+    sources: &mut Vec<SourceFile>,
+) -> ParseResult<Option<(Hash, Option<usize>)>> {
+    let section_ctx = ctx.at(key);
+    match &yaml[key] {
+        Yaml::Null | Yaml::BadValue => Ok(None),
+        Yaml::String(include_path) => {
+            let (doc, source) = include(&section_ctx, include_path)?;
+            let Yaml::Hash(map) = doc else {
+                return Err(Context::new(&source).error(
+                    DiagnosticCode::ExpectedMap,
+                    format!("the included file {include_path:?} has to contain a map of {key}"),
+                ));
+            };
+            sources.push(source);
+            Ok(Some((map, Some(sources.len() - 1))))
+        }
+        section => {
+            let map = expect_map(&section_ctx, section, key).map_err(|diagnostic| {
+                diagnostic.suggest(format!(
+                    "{key} are given as a map or as the path of a included yaml file"
+                ))
+            })?;
+            Ok(Some((map.clone(), None)))
+        }
+    }
+}
+
+/// Parses the top level network definition. All problems are collected in
+/// diagnostics, the returned sources are the included files.
 pub fn parse_top_level(
-    yaml: &yaml_rust::yaml::Yaml,
+    yaml: &Yaml,
+    source: &SourceFile,
     network_builder: &mut NetworkBuilder,
-    path: &Path,
-) -> Result<()> {
-    let yaml_rust::Yaml::Hash(_) = yaml else {
-        return Err(Error::YamlInvalidFormat(format!("")));
-    };
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<SourceFile> {
+    let ctx = Context::new(source);
+    let mut sources = vec![];
+
+    if let Err(diagnostic) = expect_map(&ctx, yaml, "the network definition") {
+        diagnostics.push(diagnostic);
+        return sources;
+    }
 
-    let nodes_map = if let yaml_rust::Yaml::String(include_path) = &yaml["nodes"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
-        buf.push(include_path);
-        let path = buf.as_path();
-        let yaml_str = std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-        let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-        let doc = &docs[0];
-        doc.as_hash().unwrap().clone()
-    } else {
-        let yaml_rust::Yaml::Hash(nodes_map) = &yaml["nodes"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "enums must be given as a map"
-            )));
-        };
-        nodes_map.clone()
+    let mut load_section = |key: &str, diagnostics: &mut Vec<Diagnostic>| {
+        section(&ctx, yaml, key, &mut sources).unwrap_or_else(|diagnostic| {
+            diagnostics.push(diagnostic);
+            None
+        })
     };
-
-    for (name, node_def) in &nodes_map {
-        let yaml_rust::Yaml::String(name) = name else {
-            return Err(Error::YamlInvalidType(format!(
-                "name of a node has to be a string"
-            )));
-        };
-
-        let node_def = if let yaml_rust::Yaml::String(include_path) = node_def {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            let yaml_str =
-                std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-            let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-            let doc = &docs[0];
-            doc.clone()
-        } else {
-            node_def.clone()
-        };
-        parse_node(name, &node_def, network_builder)?;
-    }
-
-    if !yaml["struct_types"].is_null() && !yaml["struct_types"].is_badvalue() {
-        let structs_map = if let yaml_rust::Yaml::String(include_path) = &yaml["struct_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            let yaml_str =
-                std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-            let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-            let doc = &docs[0];
-            doc.as_hash().unwrap().clone()
-        } else {
-            let yaml_rust::Yaml::Hash(structs_map) = &yaml["struct_types"] else {
-                return Err(Error::YamlInvalidType(format!(
-                    "enums must be given as a map"
-                )));
-            };
-            structs_map.clone()
-        };
-
-        for (name, struct_map) in &structs_map {
-            let yaml_rust::Yaml::String(struct_name) = name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "struct names must be primitive string keys"
-                )));
+    let nodes = load_section("nodes", diagnostics);
+    let structs = load_section("struct_types", diagnostics);
+    let enums = load_section("enum_types", diagnostics);
+    let buses = load_section("buses", diagnostics);
+
+    // node definitions may be included files themselves.
+    let mut node_sources: Vec<(String, Yaml, Option<SourceFile>)> = vec![];
+    match &nodes {
+        Some((nodes_map, nodes_source)) => {
+            let nodes_ctx = match nodes_source {
+                Some(i) => Context::new(&sources[*i]),
+                None => ctx.at("nodes"),
             };
-
-            parse_struct_type(struct_map, &mut network_builder.define_struct(struct_name))?;
+            for (name, node_def) in nodes_map {
+                let name = match key_name(&nodes_ctx, name, "node") {
+                    Ok(name) => name,
+                    Err(diagnostic) => {
+                        diagnostics.push(diagnostic);
+                        continue;
+                    }
+                };
+                if let Yaml::String(include_path) = node_def {
+                    match include(&nodes_ctx.at(name), include_path) {
+                        Ok((doc, source)) => node_sources.push((name.to_owned(), doc, Some(source))),
+                        Err(diagnostic) => diagnostics.push(diagnostic),
+                    }
+                } else {
+                    node_sources.push((name.to_owned(), node_def.clone(), None));
+                }
+            }
         }
+        None => diagnostics.push(
+            ctx.error(DiagnosticCode::MissingKey, "the network does not define any nodes")
+                .suggest("add a \"nodes\" map or the path of a yaml file defining the nodes"),
+        ),
     }
-
-    if !yaml["enum_types"].is_null() && !yaml["enum_types"].is_badvalue() {
-        let enums_map = if let yaml_rust::Yaml::String(include_path) = &yaml["enum_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            let yaml_str =
-                std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-            let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-            let doc = &docs[0];
-            doc.as_hash().unwrap().clone()
-        } else {
-            let yaml_rust::Yaml::Hash(enums_map) = &yaml["enum_types"] else {
-                return Err(Error::YamlInvalidType(format!(
-                    "enums must be given as a map"
-                )));
-            };
-            enums_map.clone()
+    let nodes_ctx = match &nodes {
+        Some((_, Some(i))) => Context::new(&sources[*i]),
+        _ => ctx.at("nodes"),
+    };
+    for (name, node_def, node_source) in &node_sources {
+        let node_ctx = match node_source {
+            Some(node_source) => Context::new(node_source),
+            None => nodes_ctx.at(name),
         };
-
-        for (name, enum_map) in &enums_map {
-            let yaml_rust::Yaml::String(enum_name) = name else {
-                return Err(Error::YamlInvalidType(format!(
-                    "struct names must be primitive string keys"
-                )));
-            };
-
-            parse_enum_type(enum_map, &mut network_builder.define_enum(enum_name))?;
+        if let Err(diagnostic) = parse_node(&node_ctx, name, node_def, network_builder, diagnostics) {
+            diagnostics.push(diagnostic);
         }
     }
 
-    let bus_map = if let yaml_rust::Yaml::String(include_path) = &yaml["buses"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
-        buf.push(include_path);
-        let path = buf.as_path();
-        let yaml_str = std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-        let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-        let doc = &docs[0];
-        doc.as_hash().unwrap().clone()
-    } else {
-        let yaml_rust::Yaml::Hash(buses) = &yaml["buses"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "enums must be given as a map"
-            )));
+    if let Some((structs_map, structs_source)) = &structs {
+        let structs_ctx = match structs_source {
+            Some(i) => Context::new(&sources[*i]),
+            None => ctx.at("struct_types"),
         };
-        buses.clone()
-    };
+        parse_entries(&structs_ctx, structs_map, "struct", diagnostics, |ctx, name, def| {
+            parse_struct_type(ctx, name, def, &mut network_builder.define_struct(name))
+        });
+    }
 
-    for (name, bus_map) in &bus_map {
-        let yaml_rust::Yaml::String(bus_name) = name else {
-            return Err(Error::YamlInvalidType(format!(
-                "bus names must be primitive string keys"
-            )));
+    if let Some((enums_map, enums_source)) = &enums {
+        let enums_ctx = match enums_source {
+            Some(i) => Context::new(&sources[*i]),
+            None => ctx.at("enum_types"),
         };
+        parse_entries(&enums_ctx, enums_map, "enum", diagnostics, |ctx, name, def| {
+            parse_enum_type(ctx, name, def, &mut network_builder.define_enum(name))
+        });
+    }
 
-        parse_bus(
-            &network_builder,
-            bus_map,
-            &mut network_builder.create_bus(bus_name, None),
-        )?;
+    match &buses {
+        Some((buses_map, buses_source)) => {
+            let buses_ctx = match buses_source {
+                Some(i) => Context::new(&sources[*i]),
+                None => ctx.at("buses"),
+            };
+            parse_entries(&buses_ctx, buses_map, "bus", diagnostics, |ctx, name, def| {
+                parse_bus(ctx, name, network_builder, def, &network_builder.create_bus(name, None))
+            });
+        }
+        None => diagnostics.push(
+            ctx.error(DiagnosticCode::MissingKey, "the network does not define any buses")
+                .suggest("add a \"buses\" map, e.g. \"buses: { can0: { baudrate: 1000000 } }\""),
+        ),
     }
 
     // all nodes are connected to all buses!!
@@ -650,67 +847,57 @@ pub fn parse_top_level(
         }
     }
 
-    Ok(())
+    sources.extend(node_sources.into_iter().filter_map(|(_, _, source)| source));
+    sources
 }
 
-pub fn parse_included_files(yaml: &yaml_rust::yaml::Yaml, path: &Path) -> Result<Vec<PathBuf>> {
+pub fn parse_included_files(yaml: &Yaml, source: &SourceFile) -> std::result::Result<Vec<PathBuf>, Vec<Diagnostic>> {
+    let ctx = Context::new(source);
     let mut paths = vec![];
+    let mut diagnostics = vec![];
 
-    let yaml_rust::Yaml::Hash(_) = yaml else {
-        return Err(Error::YamlInvalidFormat(format!("")));
+    if let Err(diagnostic) = expect_map(&ctx, yaml, "the network definition") {
+        return Err(vec![diagnostic]);
+    }
+    let include_path = |include_path: &str| -> PathBuf {
+        source
+            .path()
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(include_path)
     };
 
-    let nodes_map = if let yaml_rust::Yaml::String(include_path) = &yaml["nodes"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
-        buf.push(include_path);
-        let path = buf.as_path();
-        paths.push(path.to_path_buf());
-        let yaml_str = std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-        let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-        let doc = &docs[0];
-        doc.as_hash().unwrap().clone()
-    } else {
-        let yaml_rust::Yaml::Hash(nodes_map) = &yaml["nodes"] else {
-            return Err(Error::YamlInvalidType(format!(
-                "enums must be given as a map"
-            )));
-        };
-        nodes_map.clone()
+    let nodes_map = match &yaml["nodes"] {
+        Yaml::String(nodes_path) => {
+            paths.push(include_path(nodes_path));
+            match include(&ctx.at("nodes"), nodes_path) {
+                Ok((Yaml::Hash(map), _)) => Some(map),
+                Ok(_) => None,
+                Err(diagnostic) => {
+                    diagnostics.push(diagnostic);
+                    None
+                }
+            }
+        }
+        Yaml::Hash(map) => Some(map.clone()),
+        _ => None,
     };
 
-    for (_, node_def) in &nodes_map {
-        if let yaml_rust::Yaml::String(include_path) = node_def {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            paths.push(path.to_path_buf());
+    for (_, node_def) in nodes_map.iter().flatten() {
+        if let Yaml::String(node_path) = node_def {
+            paths.push(include_path(node_path));
         };
     }
 
-    if !yaml["struct_types"].is_null() && !yaml["struct_types"].is_badvalue() {
-        if let yaml_rust::Yaml::String(include_path) = &yaml["struct_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            paths.push(path.to_path_buf());
-        };
+    for key in ["struct_types", "enum_types", "buses"] {
+        if let Yaml::String(section_path) = &yaml[key] {
+            paths.push(include_path(section_path));
+        }
     }
 
-    if !yaml["enum_types"].is_null() && !yaml["enum_types"].is_badvalue() {
-        if let yaml_rust::Yaml::String(include_path) = &yaml["enum_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            paths.push(path.to_path_buf());
-        };
+    if diagnostics.is_empty() {
+        Ok(paths)
+    } else {
+        Err(diagnostics)
     }
-
-    if let yaml_rust::Yaml::String(include_path) = &yaml["buses"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
-        buf.push(include_path);
-        let path = buf.as_path();
-        paths.push(path.to_path_buf());
-    };
-
-    Ok(paths)
 }
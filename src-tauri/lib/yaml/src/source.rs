@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use yaml_rust::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::Marker,
+    Yaml, YamlLoader,
+};
+
+use crate::diagnostics::{Diagnostic, DiagnosticCode, Location};
+
+/// A parsed yaml file, which remembers where each node was defined.
+/// Nodes are identified by their key path (e.g. ["nodes", "secu", "object_dictionary"]),
+/// sequence elements by their index. Map values are located at their key.
+pub struct SourceFile {
+    path: PathBuf,
+    marks: HashMap<Vec<String>, Marker>,
+    scalars: Vec<(String, Marker)>,
+}
+
+impl SourceFile {
+    pub fn load(path: &Path) -> Result<(Yaml, SourceFile), Diagnostic> {
+        let src = std::fs::read_to_string(path).map_err(|err| {
+            Diagnostic::new(
+                DiagnosticCode::Include,
+                format!("failed to read {} : {err}", path.display()),
+            )
+        })?;
+        Self::parse(&src, path)
+    }
+
+    pub fn parse(src: &str, path: &Path) -> Result<(Yaml, SourceFile), Diagnostic> {
+        let syntax_error = |err: yaml_rust::ScanError| {
+            Diagnostic::new(DiagnosticCode::Syntax, format!("{err}")).at(Some(Location {
+                file: path.to_path_buf(),
+                line: err.marker().line(),
+                column: err.marker().col() + 1,
+            }))
+        };
+        let docs = YamlLoader::load_from_str(src).map_err(syntax_error)?;
+        let mut recorder = MarkRecorder::default();
+        Parser::new(src.chars())
+            .load(&mut recorder, false)
+            .map_err(syntax_error)?;
+        let doc = docs.into_iter().next().unwrap_or(Yaml::Null);
+        Ok((
+            doc,
+            SourceFile {
+                path: path.to_path_buf(),
+                marks: recorder.marks,
+                scalars: recorder.scalars,
+            },
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Location of the node at key_path, or of its closest defined parent.
+    pub fn location(&self, key_path: &[String]) -> Location {
+        let mark = (0..=key_path.len())
+            .rev()
+            .find_map(|n| self.marks.get(&key_path[..n]));
+        match mark {
+            Some(mark) => self.location_of(mark),
+            None => Location {
+                file: self.path.clone(),
+                line: 1,
+                column: 1,
+            },
+        }
+    }
+
+    /// Location of the first scalar with the given value.
+    pub fn find_scalar(&self, value: &str) -> Option<Location> {
+        self.scalars
+            .iter()
+            .find(|(scalar, _)| scalar == value)
+            .map(|(_, mark)| self.location_of(mark))
+    }
+
+    /// Location of the first mapping key with the given name (at any depth).
+    pub fn find_key(&self, key: &str) -> Option<Location> {
+        self.marks
+            .iter()
+            .filter(|(path, _)| path.last().is_some_and(|last| last == key))
+            .min_by_key(|(_, mark)| mark.index())
+            .map(|(_, mark)| self.location_of(mark))
+    }
+
+    fn location_of(&self, mark: &Marker) -> Location {
+        Location {
+            file: self.path.clone(),
+            line: mark.line(),
+            column: mark.col() + 1,
+        }
+    }
+}
+
+enum Container {
+    Map {
+        path: Vec<String>,
+        key: Option<String>,
+        // complex keys (maps or sequences used as keys) are not supported by the config.
+        in_key: bool,
+    },
+    Seq {
+        path: Vec<String>,
+        index: usize,
+    },
+}
+
+#[derive(Default)]
+struct MarkRecorder {
+    stack: Vec<Container>,
+    marks: HashMap<Vec<String>, Marker>,
+    scalars: Vec<(String, Marker)>,
+}
+
+impl MarkRecorder {
+    /// returns the path of the next value, or None if the next node is a mapping key.
+    fn next_path(&mut self) -> Option<Vec<String>> {
+        match self.stack.last_mut() {
+            None => Some(vec![]),
+            Some(Container::Map { key: None, .. }) => None,
+            Some(Container::Map {
+                path,
+                key: Some(key),
+                ..
+            }) => {
+                let mut path = path.clone();
+                path.push(key.clone());
+                Some(path)
+            }
+            Some(Container::Seq { path, index }) => {
+                let mut path = path.clone();
+                path.push(index.to_string());
+                Some(path)
+            }
+        }
+    }
+
+    /// called after a complete value was read.
+    fn value_done(&mut self) {
+        match self.stack.last_mut() {
+            Some(Container::Map { key, .. }) => *key = None,
+            Some(Container::Seq { index, .. }) => *index += 1,
+            None => (),
+        }
+    }
+
+    fn start(&mut self, mark: Marker, container: impl FnOnce(Vec<String>) -> Container) {
+        match self.next_path() {
+            Some(path) => {
+                self.marks.entry(path.clone()).or_insert(mark);
+                self.stack.push(container(path));
+            }
+            None => {
+                if let Some(Container::Map { in_key, .. }) = self.stack.last_mut() {
+                    *in_key = true;
+                }
+                self.stack.push(container(vec!["?".to_owned()]));
+            }
+        }
+    }
+
+    fn end(&mut self) {
+        self.stack.pop();
+        match self.stack.last_mut() {
+            Some(Container::Map { in_key, key, .. }) if *in_key => {
+                *in_key = false;
+                *key = Some("?".to_owned());
+            }
+            _ => self.value_done(),
+        }
+    }
+}
+
+impl MarkedEventReceiver for MarkRecorder {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::Scalar(value, ..) => {
+                self.scalars.push((value.clone(), mark));
+                match self.next_path() {
+                    Some(path) => {
+                        self.marks.entry(path).or_insert(mark);
+                        self.value_done();
+                    }
+                    None => {
+                        if let Some(Container::Map { path, key, .. }) = self.stack.last_mut() {
+                            let mut key_path = path.clone();
+                            key_path.push(value.clone());
+                            self.marks.insert(key_path, mark);
+                            *key = Some(value);
+                        }
+                    }
+                }
+            }
+            Event::Alias(_) => {
+                if let Some(path) = self.next_path() {
+                    self.marks.entry(path).or_insert(mark);
+                    self.value_done();
+                } else if let Some(Container::Map { key, .. }) = self.stack.last_mut() {
+                    *key = Some("?".to_owned());
+                }
+            }
+            Event::MappingStart(_) => self.start(mark, |path| Container::Map {
+                path,
+                key: None,
+                in_key: false,
+            }),
+            Event::SequenceStart(_) => {
+                self.start(mark, |path| Container::Seq { path, index: 0 })
+            }
+            Event::MappingEnd | Event::SequenceEnd => self.end(),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use canzero_yaml::{
+    diagnostics::{Diagnostic, DiagnosticCode},
+    errors::Error,
+};
+
+fn diagnostics(src: &str) -> Vec<Diagnostic> {
+    match canzero_yaml::parse_yaml_config(src, Path::new("network.yaml")) {
+        Ok(_) => panic!("expected the config to be rejected"),
+        Err(Error::Diagnostics(diagnostics)) => diagnostics,
+        Err(err) => panic!("expected diagnostics, got {err:?}"),
+    }
+}
+
+fn assert_located(diagnostic: &Diagnostic, file: &Path, line: usize, column: usize) {
+    let location = diagnostic
+        .location
+        .as_ref()
+        .unwrap_or_else(|| panic!("{diagnostic} is not located"));
+    assert_eq!(
+        (location.file.as_path(), location.line, location.column),
+        (file, line, column),
+        "{diagnostic} is reported at the wrong location"
+    );
+}
+
+#[test]
+fn invalid_intervals_are_located() {
+    let diagnostics = diagnostics(
+        "\
+buses:
+  can0:
+    baudrate: 1000000
+
+nodes:
+  secu:
+    object_dictionary:
+      position:
+        type: u32
+        access: global
+    tx_streams:
+      state:
+        mapping:
+          - position
+        interval: 100 ms - 1 fortnight
+",
+    );
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.code, DiagnosticCode::InvalidInterval);
+    assert_eq!(diagnostic.code.as_str(), "E007");
+    assert_located(diagnostic, Path::new("network.yaml"), 15, 9);
+    assert!(diagnostic.suggestion.is_some());
+}
+
+#[test]
+fn errors_in_included_files_are_located_in_the_included_file() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/configs/include");
+    let err = canzero_yaml::parse_yaml_config_from_file(dir.join("network.yaml").to_str().unwrap())
+        .expect_err("expected the config to be rejected");
+    let Error::Diagnostics(diagnostics) = err else {
+        panic!("expected diagnostics, got {err:?}");
+    };
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidInterval);
+    assert_located(&diagnostics[0], &dir.join("nodes.yaml"), 10, 7);
+}
+
+#[test]
+fn independent_errors_are_all_reported() {
+    let diagnostics = diagnostics(
+        "\
+buses:
+  can0:
+    baudrate: 1000000
+
+nodes:
+  secu:
+    object_dictionary:
+      position:
+        access: global
+      velocity:
+        type: [u32]
+        access: global
+    tx_streams:
+      state:
+        mapping:
+          - velocity
+        interval: soon
+",
+    );
+    let codes: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(
+        codes,
+        vec![
+            DiagnosticCode::MissingKey,
+            DiagnosticCode::ExpectedString,
+            DiagnosticCode::InvalidInterval,
+        ],
+        "{diagnostics:?}"
+    );
+    assert_located(&diagnostics[0], Path::new("network.yaml"), 8, 7);
+    assert_located(&diagnostics[1], Path::new("network.yaml"), 11, 9);
+    assert_located(&diagnostics[2], Path::new("network.yaml"), 17, 9);
+}
+
+#[test]
+fn misspelled_types_get_a_suggestion() {
+    let diagnostics = diagnostics(
+        "\
+buses:
+  can0:
+    baudrate: 1000000
+
+struct_types:
+  battery_state:
+    voltage: u16
+    current: i16
+
+nodes:
+  bms:
+    object_dictionary:
+      state:
+        type: batery_state
+        access: global
+",
+    );
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.code, DiagnosticCode::UndefinedType);
+    assert_eq!(
+        diagnostic.suggestion.as_deref(),
+        Some("did you mean \"battery_state\"?")
+    );
+    assert_located(diagnostic, Path::new("network.yaml"), 14, 15);
+}
+
+#[test]
+fn invalid_array_lengths_are_located() {
+    let diagnostics = diagnostics(
+        "\
+buses:
+  can0:
+    baudrate: 1000000
+
+nodes:
+  bms:
+    object_dictionary:
+      cell_voltages:
+        type: u16[0]
+        access: global
+",
+    );
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidValue);
+    assert_located(&diagnostics[0], Path::new("network.yaml"), 9, 15);
+}
+
+#[test]
+fn undefined_stream_mappings_are_reported() {
+    let diagnostics = diagnostics(
+        "\
+buses:
+  can0:
+    baudrate: 1000000
+
+nodes:
+  secu:
+    object_dictionary:
+      position:
+        type: u32
+        access: global
+    tx_streams:
+      state:
+        mapping:
+          - positon
+",
+    );
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidValue);
+    assert_eq!(
+        diagnostics[0].suggestion.as_deref(),
+        Some("did you mean \"position\"?")
+    );
+    assert_located(&diagnostics[0], Path::new("network.yaml"), 14, 13);
+}
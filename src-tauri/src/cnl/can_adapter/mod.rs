@@ -1,105 +1,196 @@
+use std::{net::SocketAddr, sync::Arc, time::Instant};
+
+use canzero_common::{
+    trace_format::TraceBus, transport::CanTransport, CanFrame, NetworkFrame, TCanError,
+    TCanFrame, TNetworkFrame, Timestamped,
+};
 use canzero_config::config::{bus::BusRef, NetworkRef};
+use canzero_tcp::tcpcan::{ConnectionId, TcpCan};
 use canzero_udp::frame::NetworkDescription;
+use color_print::cprintln;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::notification::notify_warning;
 
-use self::tcp::TcpCanAdapter;
-use canzero_common::{CanFrame, TCanFrame, TCanError};
+use self::replay::{ReplayController, ReplayTransport};
 
-#[cfg(feature = "socket-can")]
-mod socket_can;
+pub mod replay;
 
-mod tcp;
+// None signals that the transport was closed.
+type AdapterFrame = Option<Result<TCanFrame, TCanError>>;
 
+/// A single bus of a CanTransport, all adapters of a network share the same transport.
 pub struct CanAdapter {
     bus: BusRef,
-    imp: CanAdapterImpl,
-}
-
-pub enum CanAdapterImpl {
-    TcpCanAdapter(TcpCanAdapter),
-    #[cfg(feature = "socket-can")]
-    SocketCanAdapter(socket_can::SocketCanAdapter),
+    transport: Arc<dyn CanTransport>,
+    rx: Mutex<mpsc::Receiver<AdapterFrame>>,
+    loopback_tx: mpsc::Sender<AdapterFrame>,
+    timebase: Instant,
 }
 
 impl CanAdapter {
+    /// Creates one adapter per bus of the network, the frames received by the transport
+    /// are distributed to the adapter of their bus.
+    /// Frames sent through the adapters are timestamped relative to timebase.
+    pub fn create_adapters(
+        network_config: &NetworkRef,
+        app_handle: &tauri::AppHandle,
+        transport: Arc<dyn CanTransport>,
+        timebase: Instant,
+    ) -> Vec<Self> {
+        let mut adapters = vec![];
+        let mut bus_txs = vec![];
+        for bus in network_config.buses() {
+            let (tx, rx) = mpsc::channel(16);
+            bus_txs.push((bus.id(), tx.clone()));
+            adapters.push(Self {
+                bus: bus.clone(),
+                transport: transport.clone(),
+                rx: Mutex::new(rx),
+                loopback_tx: tx,
+                timebase,
+            });
+        }
+
+        let app_handle = app_handle.clone();
+        tokio::spawn(async move {
+            while let Some(frame) = transport.recv().await {
+                let (bus_id, frame) = match frame {
+                    Ok(frame) => (
+                        frame.bus_id,
+                        Ok(Timestamped::new(frame.timestamp, frame.value.can_frame)),
+                    ),
+                    Err(error) => (
+                        error.bus_id,
+                        Err(Timestamped::new(error.timestamp, error.value.can_error)),
+                    ),
+                };
+                let Some((_, tx)) = bus_txs.iter().find(|(id, _)| *id == bus_id) else {
+                    eprintln!("Received NetworkFrame with invalid bus id (dropped frame)");
+                    continue;
+                };
+                if tx.send(Some(frame)).await.is_err() {
+                    notify_warning(
+                        &app_handle,
+                        "Failed to Forward Frame",
+                        "Error during forwarding the frames received from the network",
+                        chrono::offset::Local::now(),
+                    );
+                    return;
+                }
+            }
+            for (_, tx) in bus_txs {
+                let _ = tx.send(None).await;
+            }
+        });
+        adapters
+    }
+
     pub async fn create_tcp_adapters(
         network_config: &NetworkRef,
         app_handle: &tauri::AppHandle,
         network_description: &NetworkDescription,
-    ) -> std::io::Result<(Vec<Self>,u8, tokio::sync::oneshot::Receiver<()>)> {
-        let channels: Vec<(
-            tokio::sync::mpsc::Sender<Option<Result<TCanFrame, TCanError>>>,
-            tokio::sync::mpsc::Receiver<Option<Result<TCanFrame, TCanError>>>,
-        )> = network_config
-            .buses()
-            .iter()
-            .map(|_| { tokio::sync::mpsc::channel(16) })
-            .collect();
-        let tx_channels: Vec<tokio::sync::mpsc::Sender<Option<Result<TCanFrame, TCanError>>>> = channels
-            .iter()
-            .map(|(tx, _rx)| tx.clone())
-            .collect();
-        let tcp_client = std::sync::Arc::new(
-            self::tcp::client::TcpClient::create(network_description, app_handle, tx_channels).await?,
+    ) -> std::io::Result<(Vec<Self>, u8, tokio::sync::oneshot::Receiver<()>)> {
+        let address = SocketAddr::new(
+            network_description.server_addr,
+            network_description.service_port,
+        );
+        let tcpcan = Arc::new(
+            TcpCan::connect(
+                address,
+                ConnectionId::Client {
+                    request_id: true,
+                    sync_history: true,
+                },
+            )
+            .await?,
         );
-        let tcp_sync = tcp_client.clone();
+        let node_id = tcpcan
+            .connection_id()
+            .expect("Expected a connection_requested during handshake");
+
+        let tcp_sync = tcpcan.clone();
         let (tx_sync_complete, sync_complete) = tokio::sync::oneshot::channel::<()>();
         tokio::spawn(async move {
             tcp_sync.sync_complete().await;
             tx_sync_complete.send(()).expect("Failed to send tx_sync");
         });
 
-        let mut adapters = vec![];
-        for (bus, (tx, rx)) in
-            network_config.buses().iter().zip(channels.into_iter())
-        {
-            adapters.push(Self {
-                bus: bus.clone(),
-                imp: CanAdapterImpl::TcpCanAdapter(TcpCanAdapter::create(
-                    tcp_client.clone(),
-                    bus.id(),
-                    rx,
-                    tx,
-                    network_description.timebase,
-                )),
-            });
-        }
-        Ok((adapters, tcp_client.node_id(), sync_complete))
+        let adapters = Self::create_adapters(
+            network_config,
+            app_handle,
+            tcpcan,
+            network_description.timebase,
+        );
+        Ok((adapters, node_id, sync_complete))
     }
 
     #[cfg(feature = "socket-can")]
     pub fn create_socketcan_adapters(
         network_config: &NetworkRef,
         app_handle: &tauri::AppHandle,
-    ) -> std::io::Result<Vec<Self>> {
-        let start_of_run = std::time::Instant::now();
-        let mut adapters = vec![];
-        for bus in network_config.buses() {
-            adapters.push(Self {
-                bus: bus.clone(),
-                imp: CanAdapterImpl::SocketCanAdapter(socket_can::SocketCanAdapter::create(
-                    bus,
-                    start_of_run,
-                    app_handle,
-                )?),
-            });
-        }
-        Ok(adapters)
+    ) -> std::io::Result<(Vec<Self>, Instant)> {
+        let timebase = Instant::now();
+        let buses = network_config
+            .buses()
+            .iter()
+            .map(|bus| canzero_common::transport::TransportBus::new(bus.id(), bus.name()))
+            .collect();
+        let socketcan = canzero_socketcan::socket_can::SocketCan::open(buses, timebase)?;
+        Ok((
+            Self::create_adapters(network_config, app_handle, Arc::new(socketcan), timebase),
+            timebase,
+        ))
+    }
+
+    pub fn create_replay_adapters(
+        network_config: &NetworkRef,
+        app_handle: &tauri::AppHandle,
+        path: &std::path::Path,
+    ) -> std::io::Result<(Vec<Self>, ReplayController, Instant)> {
+        let timebase = Instant::now();
+        let buses: Vec<TraceBus> = network_config
+            .buses()
+            .iter()
+            .map(|bus| TraceBus::new(bus.id(), bus.name()))
+            .collect();
+        let (replay, controller) = ReplayTransport::create(path, &buses, timebase)?;
+        Ok((
+            Self::create_adapters(network_config, app_handle, Arc::new(replay), timebase),
+            controller,
+            timebase,
+        ))
     }
 
     pub async fn receive(&self) -> std::io::Result<Result<TCanFrame, TCanError>> {
-        match &self.imp {
-            CanAdapterImpl::TcpCanAdapter(adapter) => adapter.receive().await,
-            #[cfg(feature = "socket-can")]
-            CanAdapterImpl::SocketCanAdapter(adapter) => adapter.receive().await,
+        match self.rx.lock().await.recv().await {
+            Some(Some(frame)) => Ok(frame),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionAborted,
+                "CAN transport closed",
+            )),
         }
     }
 
-    pub async fn send(&self, frame: CanFrame, loopback:  bool) -> std::io::Result<()> {
-        match &self.imp {
-            CanAdapterImpl::TcpCanAdapter(adapter) => adapter.send(frame, loopback).await,
-            #[cfg(feature = "socket-can")]
-            CanAdapterImpl::SocketCanAdapter(adapter) => adapter.send(frame).await,
+    pub async fn send(&self, frame: CanFrame, loopback: bool) -> std::io::Result<()> {
+        if loopback {
+            if let Err(_) = self
+                .loopback_tx
+                .send(Some(Ok(TCanFrame::now(self.timebase, frame.clone()))))
+                .await
+            {
+                cprintln!("<red> Failed to loopback frame</red>");
+            }
         }
+        self.transport
+            .send(&TNetworkFrame::now(
+                self.timebase,
+                NetworkFrame {
+                    bus_id: self.bus.id(),
+                    can_frame: frame,
+                },
+            ))
+            .await
     }
 
     pub fn bus(&self) -> &BusRef {
@@ -0,0 +1,340 @@
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use canzero_common::{
+    recording::{recording_files, RecordingHeader, RecordingReader},
+    trace_format::{TraceBus, TraceFormat, TraceReader},
+    transport::{BoxFuture, CanTransport, TransportBus},
+    TNetworkError, TNetworkFrame,
+};
+use serde::Serialize;
+use tokio::sync::{mpsc, watch, Mutex};
+
+/// Playback state shared between the ReplayController and the replay task.
+#[derive(Debug, Clone)]
+struct ReplayControl {
+    speed: f64,
+    paused: bool,
+    // incremented on every seek, to detect seeks to the same position.
+    seek_seq: u64,
+    seek_to: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayStatus {
+    speed: f64,
+    paused: bool,
+    // position within the recording in ms
+    position: u64,
+    // length of the recording in ms
+    duration: u64,
+    finished: bool,
+}
+
+/// Controls the playback of a recording that feeds the ReplayTransport.
+pub struct ReplayController {
+    control: watch::Sender<ReplayControl>,
+    position: Arc<std::sync::Mutex<(Duration, bool)>>,
+    duration: Duration,
+    header: Option<RecordingHeader>,
+}
+
+impl ReplayController {
+    pub fn set_speed(&self, speed: f64) {
+        if !(speed.is_finite() && speed > 0.0) {
+            return;
+        }
+        self.control.send_modify(|ctrl| ctrl.speed = speed);
+    }
+    pub fn pause(&self) {
+        self.control.send_modify(|ctrl| ctrl.paused = true);
+    }
+    pub fn resume(&self) {
+        self.control.send_modify(|ctrl| ctrl.paused = false);
+    }
+    pub fn seek(&self, position: Duration) {
+        self.control.send_modify(|ctrl| {
+            ctrl.seek_seq += 1;
+            ctrl.seek_to = position;
+        });
+    }
+    /// None if the replay is a candump or ASC trace.
+    pub fn header(&self) -> Option<&RecordingHeader> {
+        self.header.as_ref()
+    }
+    pub fn status(&self) -> ReplayStatus {
+        let control = self.control.borrow().clone();
+        let (position, finished) = *self
+            .position
+            .lock()
+            .expect("Failed to acquire replay position lock");
+        ReplayStatus {
+            speed: control.speed,
+            paused: control.paused,
+            position: position.as_millis() as u64,
+            duration: self.duration.as_millis() as u64,
+            finished,
+        }
+    }
+}
+
+pub struct ReplayTransport {
+    buses: Vec<TransportBus>,
+    rx: Mutex<mpsc::Receiver<TNetworkFrame>>,
+}
+
+impl ReplayTransport {
+    /// Reads the recording (or candump / ASC trace) at path and replays the frames of the given buses,
+    /// the files of a rotated recording are replayed as a whole.
+    /// The recorded frames are replayed with their original timing (scaled by the speed factor),
+    /// but are timestamped relative to timebase at the moment they are replayed,
+    /// such that the rest of the CNL behaves exactly as with a live connection.
+    pub fn create(
+        path: &Path,
+        buses: &[TraceBus],
+        timebase: Instant,
+    ) -> std::io::Result<(Self, ReplayController)> {
+        let (header, mut frames) = match TraceFormat::from_path(path) {
+            Some(format) => {
+                let file = std::io::BufReader::new(std::fs::File::open(path)?);
+                let reader = TraceReader::new(file, format, buses.to_vec());
+                (None, reader.collect::<std::io::Result<Vec<TNetworkFrame>>>()?)
+            }
+            None => {
+                let (header, frames) = read_rotated_recording(path)?;
+                (Some(header), frames)
+            }
+        };
+        // frames from different connections are not necessarily recorded in order.
+        frames.sort_by_key(|frame| frame.timestamp);
+        let duration = frames
+            .last()
+            .map(|frame| frame.timestamp)
+            .unwrap_or_default();
+
+        let (tx, rx) = mpsc::channel(16);
+
+        let (control_tx, control_rx) = watch::channel(ReplayControl {
+            speed: 1.0,
+            paused: false,
+            seek_seq: 0,
+            seek_to: Duration::ZERO,
+        });
+        let position = Arc::new(std::sync::Mutex::new((Duration::ZERO, false)));
+
+        tokio::spawn(replay_task(
+            frames,
+            buses.iter().map(|bus| bus.id).collect(),
+            tx,
+            control_rx,
+            position.clone(),
+            timebase,
+        ));
+
+        Ok((
+            Self {
+                buses: buses
+                    .iter()
+                    .map(|bus| TransportBus::new(bus.id, &bus.name))
+                    .collect(),
+                rx: Mutex::new(rx),
+            },
+            ReplayController {
+                control: control_tx,
+                position,
+                duration,
+                header,
+            },
+        ))
+    }
+
+}
+
+/// Reads all files of the rotated recording, which path is part of.
+fn read_rotated_recording(path: &Path) -> std::io::Result<(RecordingHeader, Vec<TNetworkFrame>)> {
+    let mut header: Option<RecordingHeader> = None;
+    let mut frames = vec![];
+    for file in recording_files(path)? {
+        let reader = RecordingReader::open(std::io::BufReader::new(std::fs::File::open(&file)?))?;
+        match &header {
+            Some(header) if header != reader.header() => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{file:?} belongs to a different recording than {path:?}"),
+                ));
+            }
+            Some(_) => (),
+            None => header = Some(reader.header().clone()),
+        }
+        for frame in reader {
+            frames.push(frame?);
+        }
+    }
+    let header = header.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Recording {path:?} does not exist"),
+        )
+    })?;
+    Ok((header, frames))
+}
+
+impl CanTransport for ReplayTransport {
+    fn buses(&self) -> Vec<TransportBus> {
+        self.buses.clone()
+    }
+
+    /// There is no network to send to, frames are only looped back by the CanAdapters.
+    fn send<'a>(&'a self, _frame: &'a TNetworkFrame) -> BoxFuture<'a, std::io::Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Option<Result<TNetworkFrame, TNetworkError>>> {
+        Box::pin(async move { self.rx.lock().await.recv().await.map(Ok) })
+    }
+
+    fn close(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async move { self.rx.lock().await.close() })
+    }
+}
+
+async fn replay_task(
+    frames: Vec<TNetworkFrame>,
+    bus_ids: Vec<u32>,
+    tx: mpsc::Sender<TNetworkFrame>,
+    mut control_rx: watch::Receiver<ReplayControl>,
+    position: Arc<std::sync::Mutex<(Duration, bool)>>,
+    timebase: Instant,
+) {
+    let set_position = |pos: Duration, finished: bool| {
+        *position
+            .lock()
+            .expect("Failed to acquire replay position lock") = (pos, finished);
+    };
+
+    let mut index = 0;
+    let mut seek_seq = 0;
+    // (wall clock, recording time) at which the playback was (re)started
+    let mut anchor = (tokio::time::Instant::now(), Duration::ZERO);
+    loop {
+        let control = control_rx.borrow_and_update().clone();
+        if control.seek_seq != seek_seq {
+            seek_seq = control.seek_seq;
+            index = frames.partition_point(|frame| frame.timestamp < control.seek_to);
+            anchor = (tokio::time::Instant::now(), control.seek_to);
+            set_position(control.seek_to, index >= frames.len());
+        }
+
+        if control.paused || index >= frames.len() {
+            set_position(anchor.1, index >= frames.len());
+            if control_rx.changed().await.is_err() {
+                return;
+            }
+            // resume from where we stopped.
+            anchor = (tokio::time::Instant::now(), anchor.1);
+            continue;
+        }
+
+        let frame = &frames[index];
+        let delta = frame.timestamp.saturating_sub(anchor.1);
+        let deadline = anchor.0 + delta.div_f64(control.speed);
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => {
+                index += 1;
+                // re-anchor every frame, such that speed changes apply from the current position.
+                anchor = (deadline, frame.timestamp);
+                set_position(frame.timestamp, false);
+                if !bus_ids.contains(&frame.bus_id) {
+                    continue;
+                }
+                if tx
+                    .send(TNetworkFrame::now(timebase, frame.value.clone()))
+                    .await
+                    .is_err()
+                {
+                    // the CNL was dropped.
+                    return;
+                }
+            }
+            changed = control_rx.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                // keep the progress made while waiting for the next frame.
+                let now = tokio::time::Instant::now();
+                anchor = (now, anchor.1 + (now - anchor.0).mul_f64(control.speed));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use canzero_common::{
+        recording::{recording_file_name, RecordingWriter},
+        CanFrame, NetworkFrame,
+    };
+
+    use super::*;
+
+    fn write_recording(dir: &Path, header: &RecordingHeader, seq: u32, timestamps: &[u64]) {
+        let file = std::fs::File::create(dir.join(recording_file_name(header, seq))).unwrap();
+        let mut writer = RecordingWriter::create(file, header).unwrap();
+        for timestamp in timestamps {
+            writer
+                .write_frame(&TNetworkFrame::new(
+                    Duration::from_millis(*timestamp),
+                    NetworkFrame {
+                        bus_id: 0,
+                        can_frame: CanFrame::new(0x10, false, false, 8, *timestamp),
+                    },
+                ))
+                .unwrap();
+        }
+        writer.flush().unwrap();
+    }
+
+    #[test]
+    fn rotated_recordings_are_read_as_a_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let header = RecordingHeader {
+            config_hash: 1,
+            timebase_us: 1_700_000_000_000_000,
+        };
+        write_recording(dir.path(), &header, 0, &[0, 1, 2]);
+        write_recording(dir.path(), &header, 1, &[3, 4]);
+        write_recording(dir.path(), &header, 2, &[5]);
+
+        let (read_header, frames) =
+            read_rotated_recording(&dir.path().join(recording_file_name(&header, 1))).unwrap();
+        assert_eq!(read_header, header);
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| frame.can_frame.get_data_u64())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn files_of_different_recordings_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let header = RecordingHeader {
+            config_hash: 1,
+            timebase_us: 1_700_000_000_000_000,
+        };
+        write_recording(dir.path(), &header, 0, &[0]);
+        let other = RecordingHeader {
+            config_hash: 2,
+            ..header.clone()
+        };
+        write_recording(dir.path(), &other, 1, &[1]);
+        let err =
+            read_rotated_recording(&dir.path().join(recording_file_name(&header, 0))).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
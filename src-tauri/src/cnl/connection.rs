@@ -12,6 +12,10 @@ pub enum ConnectionStatus {
         bus_id: u32,
     },
     SyncDone,
+    /// a controller reported bus off, until it restarts.
+    BusOff {
+        bus_id: u32,
+    },
     NetworkDisconnected,
     FrontendWdgTimeout,
     DeadlockWdgTimeout,
@@ -25,6 +29,7 @@ impl Serialize for ConnectionStatus {
         match &self {
             ConnectionStatus::NetworkConnected => serializer.serialize_str("network-connected"),
             ConnectionStatus::HeartbeatMiss{node_id : _, bus_id : _} => serializer.serialize_str("heartbeat-miss"),
+            ConnectionStatus::BusOff { bus_id: _ } => serializer.serialize_str("bus-off"),
             ConnectionStatus::NetworkDisconnected => serializer.serialize_str("network-disconnected"),
             ConnectionStatus::FrontendWdgTimeout => serializer.serialize_str("frontend-wdg-timeout"),
             ConnectionStatus::DeadlockWdgTimeout => serializer.serialize_str("deadlock-wdg-timeout"),
@@ -65,6 +70,7 @@ impl ConnectionObject {
 
         match connection_status {
             ConnectionStatus::HeartbeatMiss { node_id: _, bus_id : _ }
+            | ConnectionStatus::BusOff { bus_id: _ }
             | ConnectionStatus::NetworkDisconnected
             | ConnectionStatus::FrontendWdgTimeout
             | ConnectionStatus::DeadlockWdgTimeout => {
@@ -7,6 +7,7 @@ use super::{signal_deserializer::SignalDeserializer, AttributeDeserializer};
 pub enum EncodedDeserializer {
     PrimitiveDeserializer(SignalDeserializer),
     CompositeDeserializer(Vec<AttributeDeserializer>),
+    ArrayDeserializer(Vec<EncodedDeserializer>),
     EnumDeserializer(EnumDeserializer),
 }
 
@@ -28,9 +29,18 @@ impl EncodedDeserializer {
                         Self::EnumDeserializer(EnumDeserializer { entries : entries.clone(), signal_deserializer: SignalDeserializer::new(prim.signal()) })
                     }
                     Type::Struct { name : _, description : _, attribs :_ , visibility : _ } =>  panic!("DETECTED INVALID CONFIG : primitive encoding associated with a structured type!"),
-                    Type::Array { len : _, ty : _ } => todo!("FUCK ARRAYS"),
+                    Type::Array { len : _, ty : _ } => panic!("DETECTED INVALID CONFIG : primitive encoding associated with a array type!"),
                 }
             }
+            // NOTE: arrays are encoded as composites with the element index as attribute name.
+            TypeSignalEncoding::Composite(comp) if matches!(comp.ty() as &Type, Type::Array { .. }) => {
+                Self::ArrayDeserializer(
+                    comp.attributes()
+                        .iter()
+                        .map(EncodedDeserializer::new)
+                        .collect(),
+                )
+            }
             TypeSignalEncoding::Composite(comp) => Self::CompositeDeserializer(
                 comp.attributes()
                     .iter()
@@ -55,7 +65,7 @@ impl EncodedDeserializer {
         Self::PrimitiveDeserializer(SignalDeserializer::new(signal))
     }
 
-    pub fn deserializer(&self, data: u64) -> Value {
+    pub fn deserializer(&self, data: &[u8]) -> Value {
         match &self {
             Self::PrimitiveDeserializer(signal_deserializer) => {
                 signal_deserializer.deserialize(data)
@@ -66,6 +76,12 @@ impl EncodedDeserializer {
                     .map(|attribute_deserializer| attribute_deserializer.deserialize(data))
                     .collect(),
             ),
+            Self::ArrayDeserializer(element_deserializers) => Value::ArrayValue(
+                element_deserializers
+                    .iter()
+                    .map(|element_deserializer| element_deserializer.deserializer(data))
+                    .collect(),
+            ),
             Self::EnumDeserializer(EnumDeserializer {
                 entries,
                 signal_deserializer,
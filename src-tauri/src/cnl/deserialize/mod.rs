@@ -8,6 +8,9 @@ mod encoding_deserializer;
 mod signal_deserializer;
 pub mod type_deserializer;
 
+#[cfg(test)]
+mod tests;
+
 pub struct FrameDeserializer {
     message_config: MessageRef,
     value_deserializer: FrameValueDeserializer,
@@ -20,10 +23,12 @@ impl FrameDeserializer {
             value_deserializer: FrameValueDeserializer::new(message_config),
         }
     }
-    pub fn deserialize(&self, data: u64) -> Frame {
+    /// The data is the whole payload of the frame (see CanFrame::get_data),
+    /// which may exceed 8 bytes for CAN FD frames.
+    pub fn deserialize(&self, data: &[u8]) -> Frame {
         Frame::new(
             self.message_config.clone(),
-            data,
+            data.to_vec(),
             self.value_deserializer.deserialize(data),
         )
     }
@@ -45,7 +50,7 @@ impl AttributeDeserializer {
             encoded_deserializer,
         }
     }
-    pub fn deserialize(&self, data: u64) -> Attribute {
+    pub fn deserialize(&self, data: &[u8]) -> Attribute {
         Attribute::new(
             &self.attribute_name,
             self.encoded_deserializer.deserializer(data),
@@ -86,7 +91,7 @@ impl FrameValueDeserializer {
         }
     }
 
-    pub fn deserialize(&self, data: u64) -> FrameValue {
+    pub fn deserialize(&self, data: &[u8]) -> FrameValue {
         FrameValue::new(
             self.attribute_deserializer
                 .iter()
@@ -3,8 +3,7 @@ use canzero_config::config::{self, SignalRef};
 use crate::cnl::frame::Value;
 
 pub struct SignalDeserializer {
-    bit_mask: u64,
-    bit_offset: u32,
+    bit_offset: usize,
     bit_size : u8,
     type_info: SignalDeserializerTypeInfo,
 }
@@ -17,12 +16,10 @@ enum SignalDeserializerTypeInfo {
 
 impl SignalDeserializer {
     pub fn new(signal: &SignalRef) -> Self {
-        let bit_offset = signal.byte_offset() as u32; // NOTE stupid naming =^)
-        let bit_size = signal.size() as u32;
+        let bit_offset = signal.byte_offset(); // NOTE stupid naming =^)
         Self {
             bit_offset,
-            bit_mask: (u64::MAX >> (u64::BITS - bit_size)),
-            bit_size : bit_size as u8,
+            bit_size : signal.size(),
             type_info: match signal.ty() {
                 config::SignalType::UnsignedInt { size: _ } => {
                     SignalDeserializerTypeInfo::UnsignedSignalDeserializer
@@ -42,8 +39,8 @@ impl SignalDeserializer {
         }
     }
 
-    pub fn deserialize(&self, data: u64) -> Value {
-        let unsigned_bits = data >> (self.bit_offset) & self.bit_mask;
+    pub fn deserialize(&self, data: &[u8]) -> Value {
+        let unsigned_bits = read_bits(data, self.bit_offset, self.bit_size as usize);
         match &self.type_info {
             SignalDeserializerTypeInfo::DecimalSignalDeserializer { offset, scale } => {
                 Value::RealValue(unsigned_bits as f64 * scale + offset)
@@ -67,3 +64,19 @@ impl SignalDeserializer {
         }
     }
 }
+
+/// Reads bit_size bits starting at bit_offset, the first byte of the data holds the
+/// least significant bits. Bits after the end of the data are read as zeros.
+fn read_bits(data: &[u8], bit_offset: usize, bit_size: usize) -> u64 {
+    let mut value = 0u64;
+    for i in 0..bit_size {
+        let bit = bit_offset + i;
+        let Some(byte) = data.get(bit / 8) else {
+            break;
+        };
+        if (byte >> (bit % 8)) & 0x1 != 0 {
+            value |= 1 << i;
+        }
+    }
+    value
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use bitvec::view::AsBits;
+use canzero_config::config::NetworkRef;
+
+use crate::cnl::{
+    frame::{Attribute, Value},
+    serialize::FrameSerializer,
+};
+
+use super::{type_deserializer::TypeDeserializer, FrameDeserializer};
+
+/// Stream of 152 bits on a CAN FD bus, the last two attributes
+/// are placed after the first 8 bytes of the payload.
+const FD_CONFIG: &str = "
+buses:
+  can0:
+    fd: true
+
+nodes:
+  secu:
+    object_dictionary:
+      position:
+        type: u64
+        access: global
+      velocity:
+        type: i64
+        access: global
+      acceleration:
+        type: i24
+        access: global
+    tx_streams:
+      state:
+        mapping:
+          - position
+          - velocity
+          - acceleration
+";
+
+/// Array of 5 elements with 12 bits each, such that the elements
+/// cross byte (and for get responses word) boundaries.
+const ARRAY_CONFIG: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+
+nodes:
+  secu:
+    object_dictionary:
+      cell_voltages:
+        type: u12[5]
+        access: global
+    tx_streams:
+      cells:
+        mapping:
+          - cell_voltages
+";
+
+fn fd_network() -> NetworkRef {
+    canzero_yaml::parse_yaml_config(FD_CONFIG, Path::new("fd.yaml"))
+        .unwrap_or_else(|err| panic!("Failed to parse the fd config : {err:?}"))
+}
+
+fn array_network() -> NetworkRef {
+    canzero_yaml::parse_yaml_config(ARRAY_CONFIG, Path::new("array.yaml"))
+        .unwrap_or_else(|err| panic!("Failed to parse the array config : {err:?}"))
+}
+
+fn cell_voltages() -> Value {
+    Value::ArrayValue(
+        [0x123, 0xFFF, 0x000, 0xA5A, 0x801]
+            .into_iter()
+            .map(Value::UnsignedValue)
+            .collect(),
+    )
+}
+
+/// Writes the value at the bit offset, the first byte holds the least significant bits.
+fn write_bits(data: &mut [u8], bit_offset: usize, bit_size: usize, value: u64) {
+    for i in 0..bit_size {
+        let bit = bit_offset + i;
+        if (value >> i) & 0x1 != 0 {
+            data[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+}
+
+#[test]
+fn signals_after_the_first_8_bytes_are_decoded() {
+    let network = fd_network();
+    let node = network.nodes().iter().find(|n| n.name() == "secu").unwrap();
+    let stream = node.tx_streams().iter().find(|s| s.name() == "state").unwrap();
+    let message = stream.message();
+    assert!(message.dlc() > 16, "the stream is expected to be a CAN FD message");
+
+    let mut data = vec![0u8; message.dlc() as usize];
+    write_bits(&mut data, 0, 64, 0x0123_4567_89AB_CDEF);
+    write_bits(&mut data, 64, 64, (-1234567890123i64) as u64);
+    write_bits(&mut data, 128, 24, (-4242i64) as u64 & 0xFF_FFFF);
+
+    let frame = FrameDeserializer::new(message).deserialize(&data);
+    assert_eq!(
+        frame.attribute("position"),
+        Some(&Value::UnsignedValue(0x0123_4567_89AB_CDEF))
+    );
+    assert_eq!(
+        frame.attribute("velocity"),
+        Some(&Value::SignedValue(-1234567890123))
+    );
+    assert_eq!(
+        frame.attribute("acceleration"),
+        Some(&Value::SignedValue(-4242))
+    );
+    assert_eq!(frame.data(), data.as_slice());
+}
+
+#[test]
+fn bits_after_the_end_of_the_payload_are_zero() {
+    let network = fd_network();
+    let node = network.nodes().iter().find(|n| n.name() == "secu").unwrap();
+    let stream = node.tx_streams().iter().find(|s| s.name() == "state").unwrap();
+
+    let frame = FrameDeserializer::new(stream.message()).deserialize(&[0xFF; 8]);
+    assert_eq!(frame.attribute("position"), Some(&Value::UnsignedValue(u64::MAX)));
+    assert_eq!(frame.attribute("velocity"), Some(&Value::SignedValue(0)));
+    assert_eq!(frame.attribute("acceleration"), Some(&Value::SignedValue(0)));
+}
+
+#[test]
+fn array_attributes_of_streams_round_trip() {
+    let network = array_network();
+    let node = network.nodes().iter().find(|n| n.name() == "secu").unwrap();
+    let stream = node.tx_streams().iter().find(|s| s.name() == "cells").unwrap();
+    let message = stream.message();
+
+    let data = FrameSerializer::new(message)
+        .serialize(&[Attribute::new("cell_voltages", cell_voltages())]);
+    let data = &data.to_le_bytes()[..message.dlc() as usize];
+
+    let frame = FrameDeserializer::new(message).deserialize(data);
+    assert_eq!(frame.attribute("cell_voltages"), Some(&cell_voltages()));
+}
+
+#[test]
+fn array_object_entries_round_trip() {
+    let network = array_network();
+    let node = network.nodes().iter().find(|n| n.name() == "secu").unwrap();
+    let object_entry = node
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == "cell_voltages")
+        .unwrap();
+
+    // encoded like the data of set requests and get responses.
+    let (words, _) = cell_voltages().get_as_bin::<u32>(object_entry.ty());
+    assert_eq!(words.len(), 2);
+
+    let value = TypeDeserializer::new(object_entry.ty()).deserialize(words.as_slice().as_bits());
+    assert_eq!(value, cell_voltages());
+}
@@ -1,5 +1,3 @@
-use std::io::Read;
-
 use bitvec::slice::BitSlice;
 use canzero_config::config::{SignalType, Type};
 
@@ -14,6 +12,7 @@ enum TypeDeserilaizeInfo {
     PrimitiveInfo(SignalType),
     StructInfo { attributes: Vec<StructAttribute> },
     EnumInfo { entries: Vec<(String, u64)> },
+    ArrayInfo { element_size: usize, elements: Vec<TypeDeserializer> },
 }
 
 struct StructAttribute {
@@ -59,27 +58,27 @@ impl TypeDeserializer {
                 } => TypeDeserilaizeInfo::EnumInfo {
                     entries: entries.clone(),
                 },
-                Type::Array { len : _, ty : _ } => todo!("FUCK YOU ARRAYS"),
+                Type::Array { len, ty } => TypeDeserilaizeInfo::ArrayInfo {
+                    element_size: ty.size() as usize,
+                    elements: (0..*len).map(|_| TypeDeserializer::new(ty)).collect(),
+                },
             },
         }
     }
 
+    /// NOTE the first bit of the slice is the least significant bit of the value,
+    /// values may span multiple words.
+    fn unsigned_value(&self, bitslice: &BitSlice<u32>) -> u64 {
+        bitslice[0..self.bit_size]
+            .iter()
+            .enumerate()
+            .fold(0u64, |value, (i, bit)| if *bit { value | (1 << i) } else { value })
+    }
 
     pub fn deserialize(&self, bitslice: &BitSlice<u32>) -> Value {
         match &self.type_info {
             TypeDeserilaizeInfo::PrimitiveInfo(ty) => {
-                // NOTE get unsigned value from bitslice.
-                let mut bitvec = bitslice[0..self.bit_size].to_bitvec();
-                let byte_padding = 8 * ((bitvec.len() + 7) / 8) - bitvec.len();
-                for _ in 0..byte_padding {
-                    bitvec.push(false);
-                }
-                let mut u8_vector = vec![];
-                bitvec.read_to_end(&mut u8_vector).expect("failed to read from bitvector");
-                // append / remove most significant bits!
-                u8_vector.resize(8usize, 0u8);
-                // NOTE cursed bullshit! C is so easy!
-                let unsigned_value = u64::from_le_bytes(u8_vector.try_into().unwrap());
+                let unsigned_value = self.unsigned_value(bitslice);
                 match ty {
                     SignalType::UnsignedInt { size: _ } => Value::UnsignedValue(unsigned_value),
                     SignalType::SignedInt { size } => {
@@ -103,21 +102,7 @@ impl TypeDeserializer {
                 }
             }
             TypeDeserilaizeInfo::EnumInfo { entries } => {
-
-                // NOTE get unsigned value from bitslice.
-                let mut bitvec = bitslice[0..self.bit_size].to_bitvec();
-                // NOTE pad bits for alignment
-                let byte_padding = 8 * ((bitvec.len() + 7) / 8) - bitvec.len();
-                for _ in 0..byte_padding {
-                    bitvec.push(false);
-                }
-                let mut u8_vector = vec![];
-                bitvec.read_to_end(&mut u8_vector).expect("failed to read from bitvector");
-                // align for convertion to u64
-                u8_vector.resize(8usize, 0u8);
-                // NOTE cursed bullshit! C is so easy!
-                let unsigned_value = u64::from_le_bytes(u8_vector.try_into().unwrap());
-                println!("enum unsigned_value = {unsigned_value}");
+                let unsigned_value = self.unsigned_value(bitslice);
                 Value::EnumValue(
                     entries
                         .iter()
@@ -142,6 +127,18 @@ impl TypeDeserializer {
                     })
                     .collect(),
             ),
+            TypeDeserilaizeInfo::ArrayInfo {
+                element_size,
+                elements,
+            } => Value::ArrayValue(
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element_deserializer)| {
+                        element_deserializer.deserialize(&bitslice[i * element_size..])
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -1,5 +1,32 @@
+use canzero_common::fragmentation::GetRespFragmentError;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Error code of a set response, mirrors the set_resp_erno enum of the network config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetRespErno {
+    Success,
+    Error,
+}
+
+impl SetRespErno {
+    /// Decodes the name of a set_resp_erno enum entry.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Success" => Some(SetRespErno::Success),
+            "Error" => Some(SetRespErno::Error),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            SetRespErno::Success => "The node accepted the set request",
+            SetRespErno::Error => "The node rejected the set request",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     InvalidGetResponseSofFlag,
@@ -7,7 +34,16 @@ pub enum Error {
     InvalidGetResponseToggleFlag,
     InvalidGetResponseServerOrObjectEntryNotFound,
     InvalidSetResponseServerOrObjectEntryNotFound,
-    InvalidHeartbeatNodeId
+    InvalidHeartbeatNodeId,
+    InvalidCommandResponseCommandNotFound,
+    MissingCommandArgument,
+    /// an older invocation of the command didn't receive a response or timeout yet.
+    CommandInProgress,
+    CommandReturnedError,
+    /// the node responded to a set request with a erno other than Success.
+    SetRequestRejected(SetRespErno),
+    SetRequestTimeout,
+    GetRequestTimeout,
 }
 
 impl Error {
@@ -19,6 +55,13 @@ impl Error {
             Error::InvalidGetResponseServerOrObjectEntryNotFound => "invalid get response: server or object entry not found",
             Error::InvalidSetResponseServerOrObjectEntryNotFound => "invalid set response: server or object entry not found",
             Error::InvalidHeartbeatNodeId => "invalid heartbeat message: node id does not exists",
+            Error::InvalidCommandResponseCommandNotFound => "invalid command response: command not found",
+            Error::MissingCommandArgument => "invalid command invocation: missing argument",
+            Error::CommandInProgress => "Command In Progress",
+            Error::CommandReturnedError => "command returned with an error",
+            Error::SetRequestRejected(_) => "Set Request Rejected",
+            Error::SetRequestTimeout => "Set Request Timeout",
+            Error::GetRequestTimeout => "Get Request Timeout",
         }
     }
 
@@ -30,6 +73,22 @@ impl Error {
             Error::InvalidGetResponseServerOrObjectEntryNotFound => "invalid get response: server not found",
             Error::InvalidSetResponseServerOrObjectEntryNotFound => "No node with given id and object entry index could be found",
             Error::InvalidHeartbeatNodeId => "invalid heartbeat message: node id does not exists",
+            Error::InvalidCommandResponseCommandNotFound => "No command is associated with the received command response",
+            Error::MissingCommandArgument => "Not all arguments of the command were given",
+            Error::CommandInProgress => "An older invocation of the command is still in progress",
+            Error::CommandReturnedError => "The node responded to the command invocation with an error",
+            Error::SetRequestRejected(erno) => erno.description(),
+            Error::SetRequestTimeout => "The node did not respond to the set request",
+            Error::GetRequestTimeout => "The node did not respond to the get request",
+        }
+    }
+}
+
+impl From<GetRespFragmentError> for Error {
+    fn from(value: GetRespFragmentError) -> Self {
+        match value {
+            GetRespFragmentError::InvalidToggleFlag => Error::InvalidGetResponseToggleFlag,
+            GetRespFragmentError::InvalidEofFlag => Error::InvalidGetResponseEofFlag,
         }
     }
 }
@@ -0,0 +1,296 @@
+//! Drives the get/set fragmentation protocol with generated types, values
+//! and manipulated frame sequences (reordered, duplicated, truncated, interleaved).
+
+use canzero_common::CanFrame;
+use canzero_config::{
+    builder::NetworkBuilder,
+    config::{make_config_ref, NetworkRef, SignalType, Type, Visibility},
+};
+
+use crate::cnl::{
+    deserialize::FrameDeserializer,
+    errors::Error,
+    frame::{Attribute, Value},
+    serialize::FrameSerializer,
+};
+
+use super::{GetRespFrame, GetRespRouter};
+
+/// Network with a single node without object entries, which only defines
+/// the protocol messages (a network without nodes can't be built).
+pub fn test_network() -> NetworkRef {
+    let network_builder = NetworkBuilder::new();
+    network_builder.create_node("server");
+    network_builder
+        .build()
+        .expect("Failed to build test network")
+}
+
+/// Deterministic xorshift generator, such that failures are reproducible.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// uniform in from..=to
+    pub fn range(&mut self, from: u64, to: u64) -> u64 {
+        from + self.next_u64() % (to - from + 1)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
+fn mask(size: u8) -> u64 {
+    u64::MAX >> (64 - size as u32)
+}
+
+/// Random type, which nests structs and arrays up to depth levels.
+pub fn random_type(rng: &mut Rng, depth: u32) -> Type {
+    let kinds = if depth == 0 { 4 } else { 6 };
+    match rng.range(0, kinds - 1) {
+        0 => Type::Primitive(SignalType::UnsignedInt {
+            size: rng.range(1, 64) as u8,
+        }),
+        1 => Type::Primitive(SignalType::SignedInt {
+            size: rng.range(2, 64) as u8,
+        }),
+        // decimals are limited to 32 bit, such that the f64 conversion is exact.
+        2 => Type::Primitive(SignalType::Decimal {
+            size: rng.range(1, 32) as u8,
+            offset: -(rng.range(0, 100) as f64),
+            scale: [0.5, 0.1, 0.01, 2.0][rng.range(0, 3) as usize],
+        }),
+        3 => {
+            let size = rng.range(1, 8) as u8;
+            let entries = (0..rng.range(1, 4).min(mask(size) + 1))
+                .map(|i| (format!("variant{i}"), i))
+                .collect();
+            Type::Enum {
+                name: format!("enum{}", rng.next_u64()),
+                description: None,
+                size,
+                entries,
+                visibility: Visibility::Global,
+            }
+        }
+        4 => Type::Struct {
+            name: format!("struct{}", rng.next_u64()),
+            description: None,
+            attribs: (0..rng.range(1, 4))
+                .map(|i| {
+                    (
+                        format!("attrib{i}"),
+                        make_config_ref(random_type(rng, depth - 1)),
+                    )
+                })
+                .collect(),
+            visibility: Visibility::Global,
+        },
+        _ => Type::Array {
+            len: rng.range(1, 4) as usize,
+            ty: make_config_ref(random_type(rng, depth - 1)),
+        },
+    }
+}
+
+/// Random value of the type, which is representable without loss.
+pub fn random_value(rng: &mut Rng, ty: &Type) -> Value {
+    match ty {
+        Type::Primitive(SignalType::UnsignedInt { size }) => {
+            Value::UnsignedValue(rng.next_u64() & mask(*size))
+        }
+        Type::Primitive(SignalType::SignedInt { size }) => {
+            let bits = rng.next_u64() & mask(*size);
+            let sign_extended = if bits >> (size - 1) & 1 == 1 {
+                bits | !mask(*size)
+            } else {
+                bits
+            };
+            Value::SignedValue(sign_extended as i64)
+        }
+        Type::Primitive(SignalType::Decimal {
+            size,
+            offset,
+            scale,
+        }) => {
+            let bits = rng.next_u64() & mask(*size);
+            // same expression as the deserializers.
+            Value::RealValue(bits as f64 * scale + offset)
+        }
+        Type::Enum { entries, .. } => {
+            let (name, _) = &entries[rng.range(0, entries.len() as u64 - 1) as usize];
+            Value::EnumValue(name.clone())
+        }
+        Type::Struct { attribs, .. } => Value::StructValue(
+            attribs
+                .iter()
+                .map(|(name, attrib_ty)| Attribute::new(name, random_value(rng, attrib_ty)))
+                .collect(),
+        ),
+        Type::Array { len, ty } => {
+            Value::ArrayValue((0..*len).map(|_| random_value(rng, ty)).collect())
+        }
+    }
+}
+
+/// Header and data of a single get response frame, before encoding.
+#[derive(Debug, Clone)]
+pub struct Fragment {
+    pub sof: bool,
+    pub eof: bool,
+    pub toggle: bool,
+    pub server_id: u8,
+    pub client_id: u8,
+    pub od_index: u16,
+    pub data: u32,
+}
+
+/// The fragments of a get response, as sent by a conforming node.
+pub fn get_resp_fragments(server_id: u8, client_id: u8, od_index: u16, words: &[u32]) -> Vec<Fragment> {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, data)| Fragment {
+            sof: i == 0,
+            eof: i + 1 == words.len(),
+            toggle: i % 2 == 1,
+            server_id,
+            client_id,
+            od_index,
+            data: *data,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Mutation {
+    /// swaps the fragment with its successor.
+    Swap(usize),
+    /// sends the fragment twice.
+    Duplicate(usize),
+    /// drops the fragment and all following fragments.
+    Truncate(usize),
+    FlipToggle(usize),
+    FlipEof(usize),
+}
+
+pub fn mutate(fragments: &[Fragment], mutation: Mutation) -> Vec<Fragment> {
+    let mut fragments = fragments.to_vec();
+    match mutation {
+        Mutation::Swap(i) => fragments.swap(i, i + 1),
+        Mutation::Duplicate(i) => fragments.insert(i + 1, fragments[i].clone()),
+        Mutation::Truncate(i) => fragments.truncate(i),
+        Mutation::FlipToggle(i) => fragments[i].toggle = !fragments[i].toggle,
+        Mutation::FlipEof(i) => fragments[i].eof = !fragments[i].eof,
+    }
+    fragments
+}
+
+/// Random merge of both sequences, which preserves the order within each sequence.
+pub fn interleave(rng: &mut Rng, a: &[Fragment], b: &[Fragment]) -> Vec<Fragment> {
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    let mut merged = vec![];
+    loop {
+        let next = match (a.peek().is_some(), b.peek().is_some()) {
+            (true, true) => {
+                if rng.bool() {
+                    a.next()
+                } else {
+                    b.next()
+                }
+            }
+            (true, false) => a.next(),
+            (false, true) => b.next(),
+            (false, false) => return merged,
+        };
+        merged.extend(next.cloned());
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Completion {
+    pub od_index: u16,
+    pub value: Value,
+    pub unsolicited: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Outcome {
+    pub values: Vec<Completion>,
+    pub errors: Vec<Error>,
+}
+
+/// Feeds encoded get response frames through the same decoding and routing
+/// as the GetRespFrameHandler, without the object entry objects.
+pub struct GetRespDriver {
+    serializer: FrameSerializer,
+    deserializer: FrameDeserializer,
+    message_id: (u32, bool, u8),
+    router: GetRespRouter<u16>,
+}
+
+impl GetRespDriver {
+    /// object_entries are (server_id, od_index, type).
+    pub fn new(network: &NetworkRef, client_id: u8, object_entries: &[(u8, u16, &Type)]) -> Self {
+        let message = network.get_resp_message();
+        let mut router = GetRespRouter::new(client_id);
+        for (server_id, od_index, ty) in object_entries {
+            router.insert(*server_id, *od_index, ty, *od_index);
+        }
+        Self {
+            serializer: FrameSerializer::new(message),
+            deserializer: FrameDeserializer::new(message),
+            message_id: (message.id().as_u32(), message.id().ide(), message.dlc()),
+            router,
+        }
+    }
+
+    pub fn encode(&self, fragment: &Fragment) -> CanFrame {
+        let flag = |b: bool| Value::UnsignedValue(b as u64);
+        let data = self.serializer.serialize(&[
+            Attribute::new(
+                "header",
+                Value::StructValue(vec![
+                    Attribute::new("sof", flag(fragment.sof)),
+                    Attribute::new("eof", flag(fragment.eof)),
+                    Attribute::new("toggle", flag(fragment.toggle)),
+                    Attribute::new("od_index", Value::UnsignedValue(fragment.od_index as u64)),
+                    Attribute::new("client_id", Value::UnsignedValue(fragment.client_id as u64)),
+                    Attribute::new("server_id", Value::UnsignedValue(fragment.server_id as u64)),
+                ]),
+            ),
+            Attribute::new("data", Value::UnsignedValue(fragment.data as u64)),
+        ]);
+        let (id, ide, dlc) = self.message_id;
+        CanFrame::new(id, ide, false, dlc, data)
+    }
+
+    pub fn drive(&self, fragments: &[Fragment]) -> Outcome {
+        let mut outcome = Outcome::default();
+        for fragment in fragments {
+            let can_frame = self.encode(fragment);
+            let frame = self.deserializer.deserialize(&can_frame.get_data());
+            match self.router.receive(&GetRespFrame::new(&frame)) {
+                Ok(Some(resp)) => outcome.values.push(Completion {
+                    od_index: *resp.target,
+                    value: resp.value,
+                    unsolicited: resp.unsolicited,
+                }),
+                Ok(None) => (),
+                Err(err) => outcome.errors.push(err),
+            }
+        }
+        outcome
+    }
+}
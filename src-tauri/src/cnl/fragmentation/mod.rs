@@ -0,0 +1,214 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use bitvec::view::AsBits;
+use canzero_common::{
+    fragmentation::{GetRespAssembler, GetRespFragment},
+    CanFrame,
+};
+use canzero_config::config::{self, Type};
+
+use super::{
+    deserialize::type_deserializer::TypeDeserializer,
+    errors::{Error, Result},
+    frame::{Frame, Value},
+};
+
+#[cfg(test)]
+pub mod harness;
+#[cfg(test)]
+mod tests;
+
+/// Client id of get responses, which where not requested by any client.
+pub const UNSOLICITED_ID: u8 = 0xff;
+
+pub struct GetRespFrame {
+    pub sof: bool,
+    pub eof: bool,
+    pub toggle: bool,
+    pub server_id: u8,
+    pub client_id: u8,
+    pub object_entry_id: u16,
+    pub data: u32,
+}
+
+impl GetRespFrame {
+    pub fn new(frame: &Frame) -> Self {
+        let Some(header) = frame.attribute("header") else {
+            panic!("DETECTED INVALID CONFIG: invalid format of get_resp_frame : header missing");
+        };
+        let Some(Value::UnsignedValue(sof)) = header.attribute("sof") else {
+            panic!(
+                "DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.sof missing"
+            );
+        };
+        let Some(Value::UnsignedValue(eof)) = header.attribute("eof") else {
+            panic!(
+                "DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.eof missing"
+            );
+        };
+        let Some(Value::UnsignedValue(toggle)) = header.attribute("toggle") else {
+            panic!(
+                "DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.toggle missing"
+            );
+        };
+        let Some(Value::UnsignedValue(object_entry_id)) = header.attribute("od_index") else {
+            panic!("DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.od_index missing");
+        };
+        let Some(Value::UnsignedValue(server_id)) = header.attribute("server_id") else {
+            panic!("DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.server_id missing");
+        };
+        let Some(Value::UnsignedValue(client_id)) = header.attribute("client_id") else {
+            panic!("DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.client_id missing");
+        };
+        let Some(Value::UnsignedValue(data)) = frame.attribute("data") else {
+            panic!(
+                "DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.data missing"
+            );
+        };
+        Self {
+            sof: *sof != 0,
+            eof: *eof != 0,
+            toggle: *toggle != 0,
+            server_id: *server_id as u8,
+            client_id: *client_id as u8,
+            object_entry_id: *object_entry_id as u16,
+            data: *data as u32,
+        }
+    }
+
+    pub fn fragment(&self) -> GetRespFragment {
+        GetRespFragment {
+            sof: self.sof,
+            eof: self.eof,
+            toggle: self.toggle,
+            data: self.data,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct GetRespIdentifier {
+    server_id: u8,
+    object_entry_id: u16,
+    unsolicited: bool,
+}
+
+struct GetRespEntry<T> {
+    target: T,
+    assembler: Mutex<GetRespAssembler>,
+    type_deserializer: TypeDeserializer,
+}
+
+/// A complete get response.
+pub struct GetRespValue<'a, T> {
+    pub target: &'a T,
+    pub value: Value,
+    pub unsolicited: bool,
+}
+
+/// Routes the get responses addressed to the client (or unsolicited responses)
+/// to the assembler of their object entry, solicited and unsolicited
+/// responses are reassembled separately.
+pub struct GetRespRouter<T> {
+    client_id: u8,
+    entries: HashMap<GetRespIdentifier, GetRespEntry<T>>,
+}
+
+impl<T: Clone> GetRespRouter<T> {
+    pub fn new(client_id: u8) -> Self {
+        Self {
+            client_id,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, server_id: u8, object_entry_id: u16, ty: &Type, target: T) {
+        for unsolicited in [false, true] {
+            self.entries.insert(
+                GetRespIdentifier {
+                    server_id,
+                    object_entry_id,
+                    unsolicited,
+                },
+                GetRespEntry {
+                    target: target.clone(),
+                    assembler: Mutex::new(GetRespAssembler::new(ty.size())),
+                    type_deserializer: TypeDeserializer::new(ty),
+                },
+            );
+        }
+    }
+
+    /// Ok(None) if the response is addressed to another client or isn't complete yet.
+    pub fn receive(&self, frame: &GetRespFrame) -> Result<Option<GetRespValue<'_, T>>> {
+        if frame.client_id != self.client_id && frame.client_id != UNSOLICITED_ID {
+            return Ok(None);
+        }
+        let identifier = GetRespIdentifier {
+            server_id: frame.server_id,
+            object_entry_id: frame.object_entry_id,
+            unsolicited: frame.client_id == UNSOLICITED_ID,
+        };
+        let Some(entry) = self.entries.get(&identifier) else {
+            return Err(Error::InvalidGetResponseServerOrObjectEntryNotFound);
+        };
+        let data = entry
+            .assembler
+            .lock()
+            .expect("Failed to acquire get response lock")
+            .receive(&frame.fragment())?;
+        Ok(data.map(|data| GetRespValue {
+            target: &entry.target,
+            value: entry.type_deserializer.deserialize(data.as_slice().as_bits()),
+            unsolicited: identifier.unsolicited,
+        }))
+    }
+}
+
+/// Fragments of a set request, the data of a fragment is a single word
+/// of the serialized value (see Value::get_as_bin), last_fill is the number
+/// of bytes used in the last word.
+pub fn set_req_frames(
+    set_req_message: &config::MessageRef,
+    server_id: u8,
+    client_id: u8,
+    oe_id: u32,
+    val: &[u32],
+    last_fill: u8,
+) -> Vec<CanFrame> {
+    let (set_request_id, ide) = match set_req_message.id() {
+        config::MessageId::StandardId(id) => (*id, false),
+        config::MessageId::ExtendedId(id) => (*id, true),
+    };
+    let mut frame_data: Vec<CanFrame> = vec![];
+    let frames_to_send = val.len();
+
+    for i in 0..frames_to_send {
+        // SOF
+        let mut data_curr = if i == 0 { 1u64 } else { 0u64 };
+        // EOF
+        data_curr |= if i == frames_to_send - 1 {
+            1u64 << 1
+        } else {
+            0u64
+        };
+        // toggle
+        data_curr |= ((i % 2) as u64) << 2;
+        // oe-id
+        data_curr |= (oe_id as u64) << 3;
+        // client-id
+        data_curr |= (client_id as u64) << 16;
+        // server-id
+        data_curr |= (server_id as u64) << 24;
+        // data
+        data_curr |= (val[i] as u64) << 32;
+
+        let dlc = if i == (frames_to_send - 1) {
+            4 + last_fill
+        } else {
+            8
+        };
+        frame_data.push(CanFrame::new(set_request_id, ide, false, dlc, data_curr));
+    }
+    frame_data
+}
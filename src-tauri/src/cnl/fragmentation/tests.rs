@@ -0,0 +1,283 @@
+use bitvec::view::AsBits;
+use canzero_config::config::{SignalType, Type};
+
+use crate::cnl::{
+    deserialize::{type_deserializer::TypeDeserializer, FrameDeserializer},
+    errors::Error,
+    frame::Value,
+};
+
+use super::{
+    harness::{
+        get_resp_fragments, interleave, mutate, random_type, random_value, test_network,
+        Completion, GetRespDriver, Mutation, Rng,
+    },
+    set_req_frames, UNSOLICITED_ID,
+};
+
+const CASES: u64 = 256;
+const CLIENT_ID: u8 = 3;
+const SERVER_ID: u8 = 1;
+const OD_INDEX: u16 = 42;
+
+/// Random type and value, serialized like a set request.
+fn random_case(rng: &mut Rng) -> (Type, Value, Vec<u32>) {
+    let ty = random_type(rng, 3);
+    let value = random_value(rng, &ty);
+    let (words, _) = value.get_as_bin::<u32>(&ty);
+    (ty, value, words)
+}
+
+fn completion(value: &Value) -> Completion {
+    Completion {
+        od_index: OD_INDEX,
+        value: value.clone(),
+        unsolicited: false,
+    }
+}
+
+#[test]
+fn get_as_bin_round_trips_through_type_deserializer() {
+    let mut rng = Rng::new(1);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        assert_eq!(words.len(), ty.size().div_ceil(32) as usize, "{ty:?}");
+        let deserialized = TypeDeserializer::new(&ty).deserialize(words.as_slice().as_bits());
+        assert_eq!(deserialized, value, "{ty:?}");
+    }
+}
+
+#[test]
+fn get_resp_in_order_is_reassembled() {
+    let network = test_network();
+    let mut rng = Rng::new(2);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+        let outcome = driver.drive(&get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words));
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(outcome.values, vec![completion(&value)]);
+    }
+}
+
+#[test]
+fn get_resp_tolerates_swapped_fragments() {
+    let network = test_network();
+    let mut rng = Rng::new(3);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+        let fragments = get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words);
+        for i in 0..fragments.len().saturating_sub(1) {
+            let outcome = driver.drive(&mutate(&fragments, Mutation::Swap(i)));
+            assert!(outcome.errors.is_empty(), "swap {i} : {:?}", outcome.errors);
+            assert_eq!(outcome.values, vec![completion(&value)], "swap {i}");
+        }
+    }
+}
+
+#[test]
+fn get_resp_truncated_sequence_yields_no_value() {
+    let network = test_network();
+    let mut rng = Rng::new(4);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        let fragments = get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words);
+        for i in 0..fragments.len() {
+            let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+            let outcome = driver.drive(&mutate(&fragments, Mutation::Truncate(i)));
+            assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+            assert!(outcome.values.is_empty());
+            // the next response starts over.
+            let outcome = driver.drive(&fragments);
+            assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+            assert_eq!(outcome.values, vec![completion(&value)]);
+        }
+    }
+}
+
+#[test]
+fn get_resp_rejects_invalid_sof_fragment() {
+    let network = test_network();
+    let mut rng = Rng::new(5);
+    for _ in 0..CASES {
+        let (ty, _, words) = random_case(&mut rng);
+        let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+        let fragments = get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words);
+
+        let outcome = driver.drive(&mutate(&fragments, Mutation::FlipToggle(0)));
+        assert!(outcome.values.is_empty());
+        assert!(matches!(
+            outcome.errors.first(),
+            Some(Error::InvalidGetResponseToggleFlag)
+        ));
+
+        let outcome = driver.drive(&mutate(&fragments, Mutation::FlipEof(0)));
+        assert!(outcome.values.is_empty());
+        assert!(matches!(
+            outcome.errors.first(),
+            Some(Error::InvalidGetResponseEofFlag)
+        ));
+    }
+}
+
+#[test]
+fn get_resp_with_bad_toggle_never_completes() {
+    let network = test_network();
+    let mut rng = Rng::new(6);
+    for _ in 0..CASES {
+        let (ty, _, words) = random_case(&mut rng);
+        let fragments = get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words);
+        for i in 0..fragments.len() {
+            let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+            let outcome = driver.drive(&mutate(&fragments, Mutation::FlipToggle(i)));
+            assert!(outcome.values.is_empty(), "toggle {i} : {:?}", outcome.values);
+            if i + 1 == fragments.len() {
+                assert!(matches!(
+                    outcome.errors.last(),
+                    Some(Error::InvalidGetResponseToggleFlag)
+                ));
+            }
+        }
+    }
+}
+
+#[test]
+fn get_resp_tolerates_duplicated_sof_and_eof() {
+    let network = test_network();
+    let mut rng = Rng::new(7);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+        let fragments = get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words);
+        let n = fragments.len();
+        if n >= 2 {
+            let outcome = driver.drive(&mutate(&fragments, Mutation::Duplicate(0)));
+            assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+            assert_eq!(outcome.values, vec![completion(&value)]);
+        }
+        if n >= 3 {
+            // must not leak into the next response.
+            let mut sequence = mutate(&fragments, Mutation::Duplicate(n - 1));
+            sequence.extend(fragments.clone());
+            let outcome = driver.drive(&sequence);
+            assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+            assert_eq!(outcome.values, vec![completion(&value), completion(&value)]);
+        }
+    }
+}
+
+#[test]
+fn get_resp_ignores_other_clients() {
+    let network = test_network();
+    let mut rng = Rng::new(8);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        let other_words = random_value(&mut rng, &ty).get_as_bin::<u32>(&ty).0;
+        let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+        let sequence = interleave(
+            &mut rng,
+            &get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words),
+            &get_resp_fragments(SERVER_ID, CLIENT_ID + 1, OD_INDEX, &other_words),
+        );
+        let outcome = driver.drive(&sequence);
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(outcome.values, vec![completion(&value)]);
+    }
+}
+
+#[test]
+fn get_resp_reassembles_interleaved_responses_separately() {
+    let network = test_network();
+    let mut rng = Rng::new(9);
+    for _ in 0..CASES {
+        let (ty, value, words) = random_case(&mut rng);
+        let (other_ty, other_value, other_words) = random_case(&mut rng);
+        let unsolicited_value = random_value(&mut rng, &ty);
+        let unsolicited_words = unsolicited_value.get_as_bin::<u32>(&ty).0;
+        let driver = GetRespDriver::new(
+            &network,
+            CLIENT_ID,
+            &[(SERVER_ID, OD_INDEX, &ty), (SERVER_ID + 1, OD_INDEX + 1, &other_ty)],
+        );
+        let sequence = interleave(
+            &mut rng,
+            &get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX, &words),
+            &get_resp_fragments(SERVER_ID + 1, CLIENT_ID, OD_INDEX + 1, &other_words),
+        );
+        let sequence = interleave(
+            &mut rng,
+            &sequence,
+            &get_resp_fragments(SERVER_ID, UNSOLICITED_ID, OD_INDEX, &unsolicited_words),
+        );
+        let mut outcome = driver.drive(&sequence);
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        outcome
+            .values
+            .sort_by_key(|completion| (completion.od_index, completion.unsolicited));
+        assert_eq!(
+            outcome.values,
+            vec![
+                completion(&value),
+                Completion {
+                    od_index: OD_INDEX,
+                    value: unsolicited_value,
+                    unsolicited: true,
+                },
+                Completion {
+                    od_index: OD_INDEX + 1,
+                    value: other_value,
+                    unsolicited: false,
+                },
+            ]
+        );
+    }
+}
+
+#[test]
+fn get_resp_of_unknown_object_entry_is_rejected() {
+    let network = test_network();
+    let ty = Type::Primitive(SignalType::UnsignedInt { size: 8 });
+    let driver = GetRespDriver::new(&network, CLIENT_ID, &[(SERVER_ID, OD_INDEX, &ty)]);
+    let outcome = driver.drive(&get_resp_fragments(SERVER_ID, CLIENT_ID, OD_INDEX + 1, &[0]));
+    assert!(matches!(
+        outcome.errors.as_slice(),
+        [Error::InvalidGetResponseServerOrObjectEntryNotFound]
+    ));
+}
+
+#[test]
+fn set_req_frames_conform_to_the_set_req_encoding() {
+    let network = test_network();
+    let message = network.set_req_message();
+    let deserializer = FrameDeserializer::new(message);
+    let mut rng = Rng::new(10);
+    for _ in 0..CASES {
+        let (ty, _, words) = random_case(&mut rng);
+        let (_, last_fill) = random_value(&mut rng, &ty).get_as_bin::<u32>(&ty);
+        let oe_id = rng.range(0, 0x1FFF) as u32;
+        let frames = set_req_frames(message, SERVER_ID, CLIENT_ID, oe_id, &words, last_fill);
+        assert_eq!(frames.len(), words.len());
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(frame.get_id(), message.id().as_u32());
+            assert_eq!(frame.get_ide_flag(), message.id().ide());
+            let expected_dlc = if i + 1 == words.len() { 4 + last_fill } else { 8 };
+            assert_eq!(frame.get_dlc(), expected_dlc);
+
+            let decoded = deserializer.deserialize(&frame.get_data());
+            let header = decoded.attribute("header").expect("set_req without header");
+            let field = |name: &str| header.attribute(name).cloned();
+            let flag = |b: bool| Some(Value::UnsignedValue(b as u64));
+            assert_eq!(field("sof"), flag(i == 0));
+            assert_eq!(field("eof"), flag(i + 1 == words.len()));
+            assert_eq!(field("toggle"), flag(i % 2 == 1));
+            assert_eq!(field("od_index"), Some(Value::UnsignedValue(oe_id as u64)));
+            assert_eq!(field("client_id"), Some(Value::UnsignedValue(CLIENT_ID as u64)));
+            assert_eq!(field("server_id"), Some(Value::UnsignedValue(SERVER_ID as u64)));
+            assert_eq!(
+                decoded.attribute("data").cloned(),
+                Some(Value::UnsignedValue(words[i] as u64))
+            );
+        }
+    }
+}
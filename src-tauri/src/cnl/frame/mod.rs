@@ -1,5 +1,8 @@
 use canzero_config::config::{MessageId, MessageRef, SignalType, Type};
-use serde::{ser::SerializeMap, Serialize};
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize,
+};
 
 use canzero_common::Timestamped;
 
@@ -12,19 +15,20 @@ use bitvec::{
 #[derive(Clone, Debug)]
 pub struct Frame {
     config: MessageRef,
-    data: u64,
+    data: Vec<u8>,
     value: FrameValue,
 }
 
 pub type TFrame = Timestamped<Frame>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     UnsignedValue(u64),
     SignedValue(i64),
     RealValue(f64),
     StructValue(Vec<Attribute>),
     EnumValue(String),
+    ArrayValue(Vec<Value>),
 }
 
 impl Value {
@@ -53,7 +57,7 @@ impl FrameValue {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Attribute {
     name: String,
     value: Value,
@@ -75,7 +79,7 @@ impl Attribute {
 }
 
 impl Frame {
-    pub fn new(config: MessageRef, data: u64, value: FrameValue) -> Self {
+    pub fn new(config: MessageRef, data: Vec<u8>, value: FrameValue) -> Self {
         Self {
             config,
             data,
@@ -89,8 +93,9 @@ impl Frame {
     pub fn ide(&self) -> bool {
         self.config.id().ide()
     }
-    pub fn data(&self) -> u64 {
-        self.data
+    /// whole payload of the frame.
+    pub fn data(&self) -> &[u8] {
+        &self.data
     }
     pub fn rtr(&self) -> bool {
         // TODO am i even required still
@@ -194,6 +199,11 @@ impl Value {
                         panic!("enum variant name not known!");
                     };
                 }
+                (Value::ArrayValue(elements), Type::Array { len: _, ty: element_ty }) => {
+                    for element in elements {
+                        continue_get_as_bin(element, element_ty, bit_vec);
+                    }
+                }
                 _ => panic!("TypeValue and config::Type did not match!"),
             };
         }
@@ -251,6 +261,13 @@ impl Serialize for Value {
                 map.end()
             }
             Value::EnumValue(value) => serializer.serialize_str(value),
+            Value::ArrayValue(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
         }
     }
 }
@@ -0,0 +1,27 @@
+use canzero_config::config::MessageRef;
+use canzero_common::TCanFrame;
+
+use crate::cnl::frame::TFrame;
+use crate::cnl::deserialize::FrameDeserializer;
+use crate::cnl::errors::Result;
+
+
+pub struct CommandReqFrameHandler {
+    frame_deserializer: FrameDeserializer,
+}
+
+impl CommandReqFrameHandler {
+    pub fn create(
+        command_req_message : &MessageRef,
+    ) -> Self {
+        Self {
+            frame_deserializer: FrameDeserializer::new(command_req_message),
+        }
+    }
+    pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
+        let frame = self
+            .frame_deserializer
+            .deserialize(&can_frame.get_data());
+        Ok(can_frame.new_value(frame))
+    }
+}
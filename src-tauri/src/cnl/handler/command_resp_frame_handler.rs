@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use canzero_common::TCanFrame;
+use canzero_config::config::{CommandRef, MessageRef};
+
+use crate::cnl::{
+    deserialize::FrameDeserializer,
+    errors::{Error, Result},
+    frame::{Frame, TFrame, Value},
+    network::{command_object::CommandObject, NetworkObject},
+};
+
+struct CommandRespFrame {
+    result: Result<()>,
+}
+
+impl CommandRespFrame {
+    fn create(frame: &Frame) -> Self {
+        let Some(Value::EnumValue(erno)) = frame.attribute("erno") else {
+            panic!("DETECTED INVALID CONFIG: invalid format of command_resp : erno missing");
+        };
+        let result = if erno == "Success" {
+            Ok(())
+        } else {
+            Err(Error::CommandReturnedError)
+        };
+        Self { result }
+    }
+}
+
+pub struct CommandRespFrameHandler {
+    frame_deserializer: FrameDeserializer,
+    command_object: Option<Arc<CommandObject>>,
+}
+
+impl CommandRespFrameHandler {
+    pub fn create(
+        command_resp_message: &MessageRef,
+        command: &CommandRef,
+        network_object: &Arc<NetworkObject>,
+    ) -> Self {
+        let command_object = network_object
+            .nodes()
+            .iter()
+            .flat_map(|node| node.commands())
+            .find(|command_object| Arc::ptr_eq(command_object.config(), command))
+            .cloned();
+        Self {
+            frame_deserializer: FrameDeserializer::new(command_resp_message),
+            command_object,
+        }
+    }
+    pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
+        let frame = self
+            .frame_deserializer
+            .deserialize(&can_frame.get_data());
+        let command_resp = CommandRespFrame::create(&frame);
+        let Some(command_object) = &self.command_object else {
+            return Err(Error::InvalidCommandResponseCommandNotFound);
+        };
+        command_object.push_command_resp(command_resp.result).await;
+        Ok(can_frame.new_value(frame))
+    }
+}
@@ -53,7 +53,7 @@ impl HeartbeatFrameHandler {
     pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(&can_frame.get_data());
         let heartbeat_frame = HeartbeatFrame::create(&frame);
         let Some(node_object) = self
             .node_objects
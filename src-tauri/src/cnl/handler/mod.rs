@@ -1,3 +1,5 @@
+use self::command_req_frame_handler::CommandReqFrameHandler;
+use self::command_resp_frame_handler::CommandRespFrameHandler;
 use self::get_req_frame_handler::GetReqFrameHandler;
 use self::heartbeat_frame_handler::HeartbeatFrameHandler;
 use self::set_req_frame_handler::SetReqFrameHandler;
@@ -17,6 +19,8 @@ pub mod stream_frame_handler;
 pub mod get_req_frame_handler;
 pub mod set_req_frame_handler;
 pub mod heartbeat_frame_handler;
+pub mod command_req_frame_handler;
+pub mod command_resp_frame_handler;
 
 pub enum MessageHandler {
     GetRespFrameHandler(GetRespFrameHandler),
@@ -25,6 +29,8 @@ pub enum MessageHandler {
     SetReqFrameHandler(SetReqFrameHandler),
     StreamFrameHandler(StreamFrameHandler),
     HeartbeatFrameHandler(HeartbeatFrameHandler),
+    CommandReqFrameHandler(CommandReqFrameHandler),
+    CommandRespFrameHandler(CommandRespFrameHandler),
 }
 
 impl MessageHandler {
@@ -36,6 +42,8 @@ impl MessageHandler {
             MessageHandler::GetReqFrameHandler(handler) => handler.handle(frame).await,
             MessageHandler::SetReqFrameHandler(handler) => handler.handle(frame).await,
             MessageHandler::HeartbeatFrameHandler(handler) => handler.handle(frame).await,
+            MessageHandler::CommandReqFrameHandler(handler) => handler.handle(frame).await,
+            MessageHandler::CommandRespFrameHandler(handler) => handler.handle(frame).await,
         }
     }
 }
@@ -1,11 +1,11 @@
-use std::{collections::HashMap, sync::Arc, time::{SystemTime, UNIX_EPOCH}};
+use std::{collections::HashMap, sync::Arc};
 
 use canzero_config::config::MessageRef;
 
 use crate::cnl::{
     self,
     deserialize::FrameDeserializer,
-    errors::{Error, Result},
+    errors::{Error, Result, SetRespErno},
     frame::{Frame, TFrame, Value},
     network::{object_entry_object::ObjectEntryObject, NetworkObject},
 };
@@ -38,12 +38,12 @@ impl SetResponseFrame {
                 "DETECTED INVALID CONFIG: invalid format of set_resp_frame : header.erno missing"
             );
         };
-        // TODO: properly use erno
-        let result = if erno == "Success" {
-            Ok(())
-        } else {
-            println!("setter returned with an error");
-            Ok(())
+        let result = match SetRespErno::from_name(erno) {
+            Some(SetRespErno::Success) => Ok(()),
+            Some(erno) => Err(Error::SetRequestRejected(erno)),
+            None => panic!(
+                "DETECTED INVALID CONFIG: invalid format of set_resp_frame : unknown erno {erno}"
+            ),
         };
         Self {
             server_id: *server_id as u8,
@@ -94,7 +94,7 @@ impl SetRespFrameHandler {
     pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(&can_frame.get_data());
         let set_resp = SetResponseFrame::create(&frame);
 
         if set_resp.client_id == self.node_id {
@@ -103,11 +103,6 @@ impl SetRespFrameHandler {
                 oe_index: set_resp.oe_index,
             };
             if let Some(oe_object) = self.set_resp_lookup.get(&set_resp_id) {
-                let start = SystemTime::now();
-                let since_the_epoch = start
-                    .duration_since(UNIX_EPOCH)
-                    .expect("Time went backwards");
-                println!("Received set request: {:?}", since_the_epoch);
                 oe_object.push_set_response(set_resp.result).await;
             } else {
                 return Err(Error::InvalidSetResponseServerOrObjectEntryNotFound);
@@ -6,7 +6,7 @@ use crate::cnl::{
     deserialize::FrameDeserializer,
     errors::Result,
     frame::TFrame,
-    network::object_entry_object::ObjectEntryObject,
+    network::{object_entry_object::ObjectEntryObject, stream_object::StreamObject},
 };
 
 use canzero_common::TCanFrame;
@@ -14,22 +14,26 @@ use canzero_common::TCanFrame;
 pub struct StreamFrameHandler {
     frame_deserializer: FrameDeserializer,
     object_entries: Vec<Arc<ObjectEntryObject>>,
+    stream_object: Arc<StreamObject>,
 }
 
 impl StreamFrameHandler {
     pub fn create(
         stream: &config::stream::StreamRef,
         stream_object_entry_objects: &Vec<Arc<ObjectEntryObject>>,
+        stream_object: Arc<StreamObject>,
     ) -> Self {
         Self {
             frame_deserializer: FrameDeserializer::new(stream.message()),
             object_entries: stream_object_entry_objects.clone(),
+            stream_object,
         }
     }
     pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(&can_frame.get_data());
+        self.stream_object.push_frame(&can_frame.timestamp).await;
         for (attrib, oeo) in frame.attributes().iter().zip(&self.object_entries) {
             oeo.push_value(attrib.value().clone(), &can_frame.timestamp).await
         }
@@ -0,0 +1,191 @@
+//! Runs the generated c code of a node on the host. The node is generated for
+//! Platform::Host with the in-memory can hooks of the code generator and compiled
+//! with the system c compiler ($CC, defaults to cc), the harness main of the
+//! generated code is driven over stdin and stdout of the child process.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use canzero_codegen::options::{Options, Platform};
+use canzero_common::{CanFrame, NetworkFrame};
+use canzero_config::config::NetworkRef;
+use tempfile::TempDir;
+
+mod tests;
+
+/// same bits as {NAMESPACE}_FRAME_IDE_BIT and {NAMESPACE}_FRAME_RTR_BIT of the generated code.
+const C_FRAME_IDE_BIT: u32 = 0x40000000;
+const C_FRAME_RTR_BIT: u32 = 0x80000000;
+
+pub struct HostNode {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    time: u32,
+    next: u32,
+    // removed after the child was killed, also if the build panics.
+    _dir: TempDir,
+}
+
+impl HostNode {
+    /// Generates and compiles the node and calls {namespace}_init at time 0.
+    pub fn build(network: &NetworkRef, node_name: &str) -> Self {
+        // unique per build, such that tests building the same node can run in parallel.
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("canzero-host-{node_name}-"))
+            .tempdir()
+            .expect("Failed to create build directory");
+        let source = dir.path().join("canzero.c");
+        let header = dir.path().join("canzero.h");
+        let binary = dir.path().join("node");
+
+        let mut options = Options::default();
+        options.set_platform(Platform::Host);
+        options.set_host_harness(true);
+        options.set_source_file_path(source.to_str().unwrap());
+        options.set_header_file_path(header.to_str().unwrap());
+        canzero_codegen::generate(node_name, network.clone(), options)
+            .unwrap_or_else(|err| panic!("Failed to generate {node_name} : {err:?}"));
+
+        let cc = std::env::var("CC").unwrap_or("cc".to_owned());
+        let output = Command::new(&cc)
+            .arg("-std=c99")
+            .arg("-DCANZERO_HARNESS_MAIN")
+            .arg("-o")
+            .arg(&binary)
+            .arg(&source)
+            .output()
+            .unwrap_or_else(|err| panic!("Failed to run the c compiler {cc} : {err}"));
+        assert!(
+            output.status.success(),
+            "Failed to compile the generated code of {node_name}:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let mut child = Command::new(&binary)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|err| panic!("Failed to start {binary:?} : {err}"));
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut node = Self {
+            child,
+            stdin,
+            stdout,
+            time: 0,
+            next: 0,
+            _dir: dir,
+        };
+        node.command("init");
+        node
+    }
+
+    pub fn time(&self) -> u32 {
+        self.time
+    }
+
+    /// Passes the frame to the node, false if the acceptance filters of the bus rejected it.
+    /// The frame is handled with the next update.
+    pub fn receive(&mut self, frame: &NetworkFrame) -> bool {
+        let can_frame = &frame.can_frame;
+        let mut id = can_frame.get_id();
+        if can_frame.get_ide_flag() {
+            id |= C_FRAME_IDE_BIT;
+        }
+        if can_frame.get_rtr_flag() {
+            id |= C_FRAME_RTR_BIT;
+        }
+        let data = can_frame.get_data();
+        let mut line = format!("rx {} {id:X} {}", frame.bus_id, data.len());
+        for byte in data {
+            line.push_str(&format!(" {byte:02X}"));
+        }
+        self.command(&line);
+        match self.read_line().as_str() {
+            "accepted" => true,
+            "rejected" => false,
+            line => panic!("unexpected harness output {line:?}"),
+        }
+    }
+
+    /// Updates the node at the time and returns the frames it sent.
+    pub fn update(&mut self, time: u32) -> Vec<NetworkFrame> {
+        self.time = time;
+        self.command(&format!("time {time}"));
+        self.command("update");
+        let mut frames = vec![];
+        loop {
+            let line = self.read_line();
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("tx") => frames.push(parse_frame(tokens)),
+                Some("next") => {
+                    self.next = tokens.next().and_then(|t| t.parse().ok()).unwrap();
+                    return frames;
+                }
+                _ => panic!("unexpected harness output {line:?}"),
+            }
+        }
+    }
+
+    /// Updates the node whenever it has a job due, until the time,
+    /// returns the sent frames with the time they were sent at.
+    pub fn run_until(&mut self, time: u32) -> Vec<(u32, NetworkFrame)> {
+        let mut frames = vec![];
+        while self.time < time {
+            let now = self.next.clamp(self.time + 1, time);
+            frames.extend(self.update(now).into_iter().map(|frame| (now, frame)));
+        }
+        frames
+    }
+
+    fn command(&mut self, line: &str) {
+        writeln!(self.stdin, "{line}").expect("Failed to write to the harness");
+        self.stdin.flush().expect("Failed to write to the harness");
+    }
+
+    fn read_line(&mut self) -> String {
+        let mut line = String::new();
+        let n = self
+            .stdout
+            .read_line(&mut line)
+            .expect("Failed to read from the harness");
+        assert!(n != 0, "the harness exited unexpectedly");
+        line.trim().to_owned()
+    }
+}
+
+impl Drop for HostNode {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// "<bus_id> <id> <dlc> <b0> ..." with the id and the bytes in hex.
+fn parse_frame<'a>(mut tokens: impl Iterator<Item = &'a str>) -> NetworkFrame {
+    let bus_id: u32 = tokens.next().and_then(|t| t.parse().ok()).unwrap();
+    let id = tokens
+        .next()
+        .and_then(|t| u32::from_str_radix(t, 16).ok())
+        .unwrap();
+    let dlc: u8 = tokens.next().and_then(|t| t.parse().ok()).unwrap();
+    let data: Vec<u8> = tokens
+        .map(|t| u8::from_str_radix(t, 16).unwrap())
+        .collect();
+    assert_eq!(data.len(), dlc as usize);
+    let ide = id & C_FRAME_IDE_BIT != 0;
+    let rtr = id & C_FRAME_RTR_BIT != 0;
+    let id = id & !(C_FRAME_IDE_BIT | C_FRAME_RTR_BIT);
+    let can_frame = if dlc > 8 {
+        CanFrame::new_fd(id, ide, true, false, &data)
+    } else {
+        let mut bytes = [0u8; 8];
+        bytes[..data.len()].copy_from_slice(&data);
+        CanFrame::new(id, ide, rtr, dlc, u64::from_le_bytes(bytes))
+    };
+    NetworkFrame { bus_id, can_frame }
+}
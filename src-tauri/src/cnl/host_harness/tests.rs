@@ -0,0 +1,388 @@
+use canzero_common::{CanFrame, NetworkFrame};
+use canzero_config::config::{
+    MessageRef, NetworkRef, NodeRef, ObjectEntryRef, SignalType, Type,
+};
+
+use crate::cnl::{
+    deserialize::FrameDeserializer,
+    fragmentation::{
+        harness::{random_value, Rng},
+        set_req_frames, GetRespFrame, GetRespRouter,
+    },
+    frame::{Attribute, Frame, Value},
+    serialize::FrameSerializer,
+};
+
+use super::HostNode;
+
+/// relative to the manifest directory.
+const EXAMPLE_CONFIGS: [&str; 3] = [
+    "lib/codegen/examples/simple.yaml",
+    "lib/yaml/stream_example.yaml",
+    "lib/yaml/test.yaml",
+];
+
+/// Client id of the requests, must not be the id of a node in the example configs.
+const CLIENT_ID: u8 = 0xFE;
+
+fn example_networks() -> Vec<NetworkRef> {
+    EXAMPLE_CONFIGS
+        .iter()
+        .map(|path| {
+            let path = format!("{}/{path}", env!("CARGO_MANIFEST_DIR"));
+            canzero_yaml::parse_yaml_config_from_file(&path)
+                .unwrap_or_else(|err| panic!("Failed to parse {path} : {err:?}"))
+        })
+        .collect()
+}
+
+/// Every node of every example config.
+fn example_nodes() -> Vec<(NetworkRef, NodeRef)> {
+    example_networks()
+        .into_iter()
+        .flat_map(|network| {
+            network
+                .nodes()
+                .iter()
+                .map(|node| (network.clone(), node.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn message_frame(message: &MessageRef, data: u64) -> NetworkFrame {
+    NetworkFrame {
+        bus_id: message.bus().id(),
+        can_frame: CanFrame::new(
+            message.id().as_u32(),
+            message.id().ide(),
+            false,
+            message.dlc(),
+            data,
+        ),
+    }
+}
+
+/// None if the frame is not an instance of the message.
+fn decode(message: &MessageRef, frame: &NetworkFrame) -> Option<Frame> {
+    if frame.bus_id != message.bus().id()
+        || frame.can_frame.get_id() != message.id().as_u32()
+        || frame.can_frame.get_ide_flag() != message.id().ide()
+    {
+        return None;
+    }
+    Some(FrameDeserializer::new(message).deserialize(&frame.can_frame.get_data()))
+}
+
+/// Decimals are compared with a tolerance of a single step,
+/// because the generated code stores them as floating point values.
+fn assert_close(actual: &Value, expected: &Value, ty: &Type, context: &str) {
+    match (actual, expected, ty) {
+        (
+            Value::RealValue(actual),
+            Value::RealValue(expected),
+            Type::Primitive(SignalType::Decimal { scale, .. }),
+        ) => {
+            let tolerance = scale.max(expected.abs() * 1e-6) * 1.001;
+            assert!(
+                (actual - expected).abs() <= tolerance,
+                "{context} : {actual} != {expected}"
+            );
+        }
+        (Value::StructValue(actual), Value::StructValue(expected), Type::Struct { attribs, .. }) => {
+            for ((actual, expected), (name, ty)) in actual.iter().zip(expected).zip(attribs) {
+                assert_close(actual.value(), expected.value(), ty, &format!("{context}.{name}"));
+            }
+        }
+        (Value::ArrayValue(actual), Value::ArrayValue(expected), Type::Array { ty, .. }) => {
+            for (i, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+                assert_close(actual, expected, ty, &format!("{context}[{i}]"));
+            }
+        }
+        _ => assert_eq!(actual, expected, "{context}"),
+    }
+}
+
+/// Sets the object entry with a (fragmented) set request and expects a successful set response,
+/// returns all frames sent by the update handling the request.
+fn set(
+    host: &mut HostNode,
+    network: &NetworkRef,
+    node: &NodeRef,
+    object_entry: &ObjectEntryRef,
+    value: &Value,
+) -> Vec<NetworkFrame> {
+    let set_req = network.set_req_message();
+    let (words, last_fill) = value.get_as_bin::<u32>(object_entry.ty());
+    for can_frame in set_req_frames(set_req, node.id(), CLIENT_ID, object_entry.id(), &words, last_fill) {
+        let frame = NetworkFrame {
+            bus_id: set_req.bus().id(),
+            can_frame,
+        };
+        assert!(host.receive(&frame), "{} rejected a set request", node.name());
+    }
+    let frames = host.update(host.time() + 1);
+    let Some(resp) = frames
+        .iter()
+        .find_map(|frame| decode(network.set_resp_message(), frame))
+    else {
+        panic!("{} didn't respond to the set request of {}", node.name(), object_entry.name());
+    };
+    let header = resp.attribute("header").unwrap();
+    assert_eq!(
+        header.attribute("od_index"),
+        Some(&Value::UnsignedValue(object_entry.id() as u64))
+    );
+    assert_eq!(
+        header.attribute("client_id"),
+        Some(&Value::UnsignedValue(CLIENT_ID as u64))
+    );
+    assert_eq!(
+        header.attribute("erno"),
+        Some(&Value::EnumValue("Success".to_owned()))
+    );
+    frames
+}
+
+/// Requests the object entry and reassembles the fragments of the get response.
+fn get(
+    host: &mut HostNode,
+    network: &NetworkRef,
+    node: &NodeRef,
+    object_entry: &ObjectEntryRef,
+) -> Value {
+    let get_req = network.get_req_message();
+    let data = FrameSerializer::new(get_req).serialize(&[Attribute::new(
+        "header",
+        Value::StructValue(vec![
+            Attribute::new("od_index", Value::UnsignedValue(object_entry.id() as u64)),
+            Attribute::new("client_id", Value::UnsignedValue(CLIENT_ID as u64)),
+            Attribute::new("server_id", Value::UnsignedValue(node.id() as u64)),
+        ]),
+    )]);
+    assert!(
+        host.receive(&message_frame(get_req, data)),
+        "{} rejected a get request",
+        node.name()
+    );
+
+    let mut router = GetRespRouter::new(CLIENT_ID);
+    router.insert(node.id(), object_entry.id() as u16, object_entry.ty(), ());
+    let deadline = host.time() + 1000;
+    // the first fragment is sent immediately, the others are scheduled.
+    let mut frames = host.update(host.time() + 1);
+    loop {
+        for frame in &frames {
+            let Some(resp) = decode(network.get_resp_message(), frame) else {
+                continue;
+            };
+            match router.receive(&GetRespFrame::new(&resp)) {
+                Ok(Some(resp)) => return resp.value,
+                Ok(None) => (),
+                Err(err) => panic!("invalid get response of {} : {err:?}", object_entry.name()),
+            }
+        }
+        assert!(
+            host.time() < deadline,
+            "{} didn't respond to the get request of {}",
+            node.name(),
+            object_entry.name()
+        );
+        frames = host
+            .run_until(host.time() + 10)
+            .into_iter()
+            .map(|(_, frame)| frame)
+            .collect();
+    }
+}
+
+#[test]
+fn heartbeats_are_sent_on_every_bus() {
+    for (network, node) in example_nodes() {
+        let mut host = HostNode::build(&network, node.name());
+        let frames = host.run_until(1000);
+        for heartbeat in network.heartbeat_messages() {
+            let heartbeats: Vec<Frame> = frames
+                .iter()
+                .filter_map(|(_, frame)| decode(heartbeat, frame))
+                .collect();
+            // every 100ms
+            assert!(
+                heartbeats.len() >= 9,
+                "{} sent {} heartbeats on {}",
+                node.name(),
+                heartbeats.len(),
+                heartbeat.bus().name()
+            );
+            for frame in heartbeats {
+                assert_eq!(
+                    frame.attribute("node_id"),
+                    Some(&Value::UnsignedValue(node.id() as u64))
+                );
+                assert_eq!(frame.attribute("unregister"), Some(&Value::UnsignedValue(0)));
+            }
+        }
+    }
+}
+
+#[test]
+fn acceptance_filters_accept_all_rx_messages() {
+    for (network, node) in example_nodes() {
+        let mut host = HostNode::build(&network, node.name());
+        for message in node.rx_messages() {
+            assert!(
+                host.receive(&message_frame(message, 0)),
+                "{} rejected {}",
+                node.name(),
+                message.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn set_and_get_requests_round_trip() {
+    let mut rng = Rng::new(1);
+    for (network, node) in example_nodes() {
+        let mut host = HostNode::build(&network, node.name());
+        for object_entry in node.object_entries() {
+            for _ in 0..4 {
+                let value = random_value(&mut rng, object_entry.ty());
+                set(&mut host, &network, &node, object_entry, &value);
+                let actual = get(&mut host, &network, &node, object_entry);
+                let context = format!("{}.{}", node.name(), object_entry.name());
+                assert_close(&actual, &value, object_entry.ty(), &context);
+            }
+        }
+    }
+}
+
+#[test]
+fn streams_carry_the_object_entries() {
+    let mut rng = Rng::new(2);
+    for (network, node) in example_nodes() {
+        let mut host = HostNode::build(&network, node.name());
+        for stream in node.tx_streams() {
+            let encoding = stream
+                .message()
+                .encoding()
+                .expect("stream messages are expected to define a encoding");
+            let mut expected = vec![];
+            for (mapping, attribute) in stream.mapping().iter().zip(encoding.attributes()) {
+                let Some(object_entry) = mapping else {
+                    continue;
+                };
+                let value = random_value(&mut rng, object_entry.ty());
+                set(&mut host, &network, &node, object_entry, &value);
+                expected.push((attribute.name().to_owned(), object_entry.clone(), value));
+            }
+
+            let min_interval = stream.min_interval().as_millis() as u32;
+            let max_interval = stream.max_interval().as_millis() as u32;
+            let start = host.time();
+            let sent: Vec<(u32, Frame)> = host
+                .run_until(start + 4 * max_interval + 1)
+                .into_iter()
+                .filter_map(|(time, frame)| decode(stream.message(), &frame).map(|f| (time, f)))
+                .collect();
+            assert!(sent.len() >= 2, "{} didn't send {}", node.name(), stream.name());
+            for pair in sent.windows(2) {
+                let interval = pair[1].0 - pair[0].0;
+                assert!(
+                    interval <= max_interval,
+                    "{} exceeded the max interval of {}",
+                    node.name(),
+                    stream.name()
+                );
+                assert!(
+                    interval >= min_interval,
+                    "{} undercut the min interval of {}",
+                    node.name(),
+                    stream.name()
+                );
+            }
+            let (_, last) = sent.last().unwrap();
+            for (attribute, object_entry, value) in &expected {
+                let context = format!("{}.{}.{attribute}", node.name(), stream.name());
+                assert_close(
+                    last.attribute(attribute).unwrap(),
+                    value,
+                    object_entry.ty(),
+                    &context,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn streams_are_sent_on_change_after_their_min_interval() {
+    let mut rng = Rng::new(3);
+    for (network, node) in example_nodes() {
+        let mut host = HostNode::build(&network, node.name());
+        for stream in node.tx_streams() {
+            let min_interval = stream.min_interval().as_millis() as u32;
+            let max_interval = stream.max_interval().as_millis() as u32;
+            if min_interval == max_interval {
+                // periodic stream
+                continue;
+            }
+            let encoding = stream
+                .message()
+                .encoding()
+                .expect("stream messages are expected to define a encoding");
+            let (Some(object_entry), attribute) = (&stream.mapping()[0], &encoding.attributes()[0])
+            else {
+                continue;
+            };
+            let current = get(&mut host, &network, &node, object_entry);
+            let value = loop {
+                let value = random_value(&mut rng, object_entry.ty());
+                if value.get_as_bin::<u32>(object_entry.ty())
+                    != current.get_as_bin::<u32>(object_entry.ty())
+                {
+                    break value;
+                }
+            };
+
+            // step until the stream is sent, such that the change happens right after a transmission.
+            let deadline = host.time() + max_interval + 1;
+            while !host
+                .update(host.time() + 1)
+                .iter()
+                .any(|frame| decode(stream.message(), frame).is_some())
+            {
+                assert!(host.time() < deadline, "{} didn't send {}", node.name(), stream.name());
+            }
+            let last = host.time();
+
+            let changed_at = host.time() + 1;
+            let mut sent: Vec<(u32, NetworkFrame)> =
+                set(&mut host, &network, &node, object_entry, &value)
+                    .into_iter()
+                    .map(|frame| (changed_at, frame))
+                    .collect();
+            sent.extend(host.run_until(last + min_interval + 1));
+            let Some((sent_at, frame)) = sent
+                .iter()
+                .find_map(|(time, frame)| decode(stream.message(), frame).map(|f| (*time, f)))
+            else {
+                panic!("{} didn't send {} after a change", node.name(), stream.name());
+            };
+            assert!(
+                sent_at >= last + min_interval && sent_at < last + max_interval,
+                "{} sent {} {}ms after the last transmission",
+                node.name(),
+                stream.name(),
+                sent_at - last
+            );
+            let context = format!("{}.{}.{}", node.name(), stream.name(), attribute.name());
+            assert_close(
+                frame.attribute(attribute.name()).unwrap(),
+                &value,
+                object_entry.ty(),
+                &context,
+            );
+        }
+    }
+}
@@ -1,11 +1,16 @@
 pub mod connection;
 mod deserialize;
 pub mod errors;
+mod fragmentation;
 pub mod frame;
 mod gamepad;
 mod handler;
+#[cfg(test)]
+mod host_harness;
 pub mod network;
+pub mod request;
 mod rx;
+mod serialize;
 pub mod trace;
 mod tx;
 pub mod watchdog;
@@ -22,6 +27,7 @@ use self::{
     connection::{ConnectionObject, ConnectionStatus},
     gamepad::Gamepad,
     network::{node_object::NodeObject, NetworkObject},
+    request::RequestManager,
     rx::RxCom,
     trace::TraceObject,
     tx::TxCom,
@@ -45,6 +51,7 @@ pub struct CNL {
     #[allow(dead_code)]
     tx: Arc<TxCom>,
     network: Arc<NetworkObject>,
+    requests: RequestManager,
     connection_object: Arc<ConnectionObject>,
 
     _watchdog_overlord: WatchdogOverlord,
@@ -69,7 +76,7 @@ impl CNL {
 
         let node_id = node_id.unwrap_or(network_config.nodes().len() as u8);
 
-        let trace = Arc::new(TraceObject::create(app_handle));
+        let trace = Arc::new(TraceObject::create(app_handle, network_config, timebase));
 
         let tx = Arc::new(TxCom::create(
             &network_config,
@@ -80,12 +87,17 @@ impl CNL {
             node_id,
         ));
 
-        let (deadlock_lvl, frontend_lvl) = match AppData::read() {
+        let (deadlock_lvl, frontend_lvl, history_limit) = match AppData::read() {
             Ok(app_data) => (
                 app_data.get_deadlock_wdg_lvl(),
                 app_data.get_frontend_wdg_lvl(),
+                app_data.get_history_limit(),
+            ),
+            Err(_) => (
+                WdgLevel::Active,
+                WdgLevel::Active,
+                AppData::default().get_history_limit(),
             ),
-            Err(_) => (WdgLevel::Active, WdgLevel::Active),
         };
         let watchdog_overlord = WatchdogOverlord::new(
             network_config,
@@ -96,13 +108,16 @@ impl CNL {
             &app_handle,
         );
         let gamepad = Gamepad::create(&tx, network_config);
+        let requests = RequestManager::new(tx.clone());
 
         let network = Arc::new(NetworkObject::create(
             network_config,
             app_handle,
             tx.clone(),
+            &requests,
             timebase,
             &watchdog_overlord,
+            history_limit,
         ));
 
         let rx = RxCom::create(
@@ -128,6 +143,7 @@ impl CNL {
             let network_dead = network.clone();
             let trace_dead = trace.clone();
             let connection_object_dead = connection_object.clone();
+            let requests_dead = requests.clone();
             tokio::spawn(async move {
                 let mut deadlock_interval = tokio::time::interval(Duration::from_millis(500));
                 loop {
@@ -135,6 +151,7 @@ impl CNL {
                     network_dead.deadlock_watchdog().await;
                     trace_dead.deadlock_watchdog().await;
                     connection_object_dead.deadlock_watchdog().await;
+                    requests_dead.deadlock_watchdog().await;
                     deadlock_watchdog.reset(false, None).await;
                 }
             });
@@ -157,6 +174,7 @@ impl CNL {
             tx,
             trace,
             network,
+            requests,
             connection_object,
             _watchdog_overlord: watchdog_overlord,
             external_watchdog,
@@ -171,6 +189,11 @@ impl CNL {
         self.network.nodes()
     }
 
+    /// Awaitable get and set requests, independent of the frontend.
+    pub fn requests(&self) -> &RequestManager {
+        &self.requests
+    }
+
     pub fn connection_object(&self) -> &Arc<ConnectionObject> {
         &self.connection_object
     }
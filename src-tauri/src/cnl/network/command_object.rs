@@ -1,13 +1,47 @@
+use std::{sync::Arc, time::Duration};
+
 use canzero_config::config;
+use serde::Serialize;
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+use crate::{
+    cnl::{
+        errors::{Error, Result},
+        frame::{Attribute, Value},
+        serialize::FrameSerializer,
+        tx::TxCom,
+    },
+    notification::{notify_error, notify_info},
+};
 
 pub struct CommandObject {
     command_ref: config::CommandRef,
+    node_name: String,
+    event_name: String,
+    app_handle: tauri::AppHandle,
+    tx_com: Arc<TxCom>,
+    req_serializer: FrameSerializer,
+    invocations: Arc<Invocations>,
+    timeout: Duration,
 }
 
 impl CommandObject {
-    pub fn create(command_config: &config::CommandRef, _app_handle: &tauri::AppHandle) -> Self {
+    pub fn create(
+        node_config: &config::NodeRef,
+        command_config: &config::CommandRef,
+        app_handle: &tauri::AppHandle,
+        tx_com: Arc<TxCom>,
+    ) -> Self {
         Self {
             command_ref: command_config.clone(),
+            node_name: node_config.name().to_owned(),
+            event_name: format!("{}_{}_command", node_config.name(), command_config.name()),
+            app_handle: app_handle.clone(),
+            tx_com,
+            req_serializer: FrameSerializer::new(command_config.tx_message()),
+            invocations: Arc::new(Invocations::new()),
+            timeout: *command_config.expected_interval(),
         }
     }
     pub fn name(&self) -> &str {
@@ -16,31 +50,348 @@ impl CommandObject {
     pub fn description(&self) -> Option<&String> {
         self.command_ref.description()
     }
+    pub fn event_name(&self) -> &str {
+        &self.event_name
+    }
+    pub fn config(&self) -> &config::CommandRef {
+        &self.command_ref
+    }
+    pub fn arguments(&self) -> Vec<(&str, &config::TypeRef)> {
+        match self.command_ref.tx_message().encoding() {
+            Some(encoding) => encoding
+                .attributes()
+                .iter()
+                .map(|arg| (arg.name(), arg.ty()))
+                .collect(),
+            None => vec![],
+        }
+    }
 
-    #[allow(unused)] //FIXME
     pub fn build_invoke(&self) -> InvokeCommandBuilder {
         InvokeCommandBuilder::new(self)
     }
 
+    async fn invoke(&self, arguments: Vec<Attribute>) -> Result<()> {
+        let my_req_num = self.invocations.open().await?;
+
+        let payload = self.req_serializer.serialize_payload(&arguments);
+        self.emit(CommandEvent::Req { req_num: my_req_num });
+        self.tx_com
+            .send_command_req(self.command_ref.tx_message(), &payload)
+            .await;
+
+        tokio::spawn({
+            let timeout = self.timeout;
+            let command_name = self.name().to_owned();
+            let node_name = self.node_name.clone();
+            let invocations = self.invocations.clone();
+            let app_handle = self.app_handle.clone();
+            let event_name = self.event_name.clone();
+
+            async move {
+                if invocations.expire_after(my_req_num, timeout).await {
+                    app_handle
+                        .emit_all(&event_name, CommandEvent::Timeout { req_num: my_req_num })
+                        .expect("failed to emit command event");
+                    notify_error(
+                        &app_handle,
+                        "Command Timeout",
+                        &format!("Invocation of {node_name}::{command_name} timed out"),
+                        chrono::Local::now(),
+                    );
+                }
+            }
+        });
+        Ok(())
+    }
+
+    pub async fn push_command_resp(&self, result: Result<()>) {
+        let Some(my_req_num) = self.invocations.complete().await else {
+            notify_info(
+                &self.app_handle,
+                "Ignoring Command Response",
+                &format!(
+                    "Response of {}::{} came in after timeout or was not requested by us",
+                    self.node_name,
+                    self.name()
+                ),
+                chrono::Local::now(),
+            );
+            return;
+        };
+        self.emit(CommandEvent::Resp {
+            req_num: my_req_num,
+            success: result.is_ok(),
+        });
+        match result {
+            Ok(_) => notify_info(
+                &self.app_handle,
+                "Command Successfull",
+                &format!("{}::{} was invoked successfully", self.node_name, self.name()),
+                chrono::Local::now(),
+            ),
+            Err(err) => notify_error(
+                &self.app_handle,
+                err.reason(),
+                &format!("{}::{} : {}", self.node_name, self.name(), err.description()),
+                chrono::Local::now(),
+            ),
+        }
+    }
+
+    fn emit(&self, event: CommandEvent) {
+        self.app_handle
+            .emit_all(&self.event_name, event)
+            .expect("failed to emit command event");
+    }
+
     pub async fn deadlock_watchdog(&self) {
-        // pass
+        self.invocations.deadlock_watchdog().await;
     }
 }
 
-pub struct InvokeCommandBuilder {
+/// Tracks the invocations of a command, of which at most one is open at a time.
+/// Odd request numbers belong to an open invocation.
+struct Invocations {
+    req_num: Mutex<u64>,
 }
 
-impl InvokeCommandBuilder {
-    pub fn new(_command_object: &CommandObject) -> Self {
+impl Invocations {
+    fn new() -> Self {
+        Self {
+            req_num: Mutex::new(0),
+        }
+    }
+
+    /// Opens a new invocation and returns its request number.
+    async fn open(&self) -> Result<u64> {
+        let mut req_num = self.req_num.lock().await;
+        if *req_num % 2 == 1 {
+            return Err(Error::CommandInProgress);
+        }
+        *req_num += 1;
+        Ok(*req_num)
+    }
+
+    /// Completes the open invocation on a response and returns its request number.
+    /// None if no invocation is open (e.g. the response came in after the timeout).
+    async fn complete(&self) -> Option<u64> {
+        let mut req_num = self.req_num.lock().await;
+        if *req_num % 2 == 0 {
+            return None;
+        }
+        *req_num += 1;
+        Some(*req_num - 1)
+    }
+
+    /// Waits for the timeout and completes the invocation, if it is still open.
+    /// Returns true if the invocation timed out.
+    async fn expire_after(&self, req_num: u64, timeout: Duration) -> bool {
+        tokio::time::sleep(timeout).await;
+        let mut curr_req_num = self.req_num.lock().await;
+        if *curr_req_num != req_num {
+            return false;
+        }
+        *curr_req_num += 1;
+        true
+    }
+
+    async fn deadlock_watchdog(&self) {
+        drop(self.req_num.lock().await);
+    }
+}
+
+pub struct InvokeCommandBuilder<'a> {
+    command_object: &'a CommandObject,
+    arguments: Vec<Attribute>,
+}
+
+impl<'a> InvokeCommandBuilder<'a> {
+    pub fn new(command_object: &'a CommandObject) -> Self {
         InvokeCommandBuilder {
+            command_object,
+            arguments: vec![],
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: Value) -> Self {
+        self.arguments.push(Attribute::new(name, value));
+        self
+    }
+
+    pub async fn invoke(self) -> Result<()> {
+        let Some(encoding) = self.command_object.command_ref.tx_message().encoding() else {
+            panic!("DETECTED INVALID CONFIG: command request messages require a type encoding");
+        };
+        let all_given = encoding.attributes().iter().all(|arg| {
+            self.arguments
+                .iter()
+                .any(|attrib| attrib.name() == arg.name())
+        });
+        if !all_given {
+            return Err(Error::MissingCommandArgument);
         }
+        self.command_object.invoke(self.arguments).await
     }
 }
 
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum CommandEvent {
+    Req { req_num: u64 },
+    Resp { req_num: u64, success: bool },
+    Timeout { req_num: u64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc, time::Duration};
+
+    use canzero_config::config::CommandRef;
+
+    use super::Invocations;
+    use crate::cnl::{
+        deserialize::FrameDeserializer,
+        errors::Error,
+        frame::{Attribute, Value},
+        serialize::FrameSerializer,
+        tx::message_frame,
+    };
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    const COMMAND_CONFIG: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+
+nodes:
+  secu:
+    commands:
+      brake:
+        arguments:
+          - force: u8
+          - duration: u16
+";
+
+    /// Command with 20 bytes of arguments, such that the request is a CAN FD frame.
+    const FD_COMMAND_CONFIG: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+    fd: true
+    data_baudrate: 4000000
+
+nodes:
+  secu:
+    commands:
+      brake:
+        arguments:
+          - target_position: u64
+          - target_velocity: u64
+          - timeout: u32
+";
+
+    fn brake_command(config: &str) -> CommandRef {
+        let network = canzero_yaml::parse_yaml_config(config, Path::new("command.yaml"))
+            .unwrap_or_else(|err| panic!("Failed to parse the command config : {err:?}"));
+        let node = network.nodes().iter().find(|n| n.name() == "secu").unwrap();
+        node.commands()
+            .iter()
+            .find(|c| c.name() == "brake")
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn command_req_arguments_round_trip() {
+        let command = brake_command(COMMAND_CONFIG);
+        let message = command.tx_message();
+
+        let payload = FrameSerializer::new(message).serialize_payload(&[
+            Attribute::new("duration", Value::UnsignedValue(0x3456)),
+            Attribute::new("force", Value::UnsignedValue(0x12)),
+        ]);
+        let can_frame = message_frame(message, &payload);
+        assert!(!can_frame.is_fd());
+        assert_eq!(can_frame.get_dlc(), message.dlc());
+
+        let frame = FrameDeserializer::new(message).deserialize(&can_frame.get_data());
+        assert_eq!(frame.attribute("force"), Some(&Value::UnsignedValue(0x12)));
+        assert_eq!(
+            frame.attribute("duration"),
+            Some(&Value::UnsignedValue(0x3456))
+        );
+    }
+
+    #[test]
+    fn command_req_arguments_after_byte_8_are_sent_as_can_fd() {
+        let command = brake_command(FD_COMMAND_CONFIG);
+        let message = command.tx_message();
+        assert!(message.dlc() > 8, "the request is expected to be a CAN FD message");
+
+        let payload = FrameSerializer::new(message).serialize_payload(&[
+            Attribute::new("target_position", Value::UnsignedValue(0x0123_4567_89AB_CDEF)),
+            Attribute::new("target_velocity", Value::UnsignedValue(0xFEDC_BA98_7654_3210)),
+            Attribute::new("timeout", Value::UnsignedValue(0xDEAD_BEEF)),
+        ]);
+        assert_eq!(payload.len(), message.dlc() as usize);
+        let can_frame = message_frame(message, &payload);
+        assert!(can_frame.is_fd());
+        assert!(can_frame.get_brs_flag());
+        assert_eq!(can_frame.get_data(), payload);
+
+        let frame = FrameDeserializer::new(message).deserialize(&can_frame.get_data());
+        assert_eq!(
+            frame.attribute("target_position"),
+            Some(&Value::UnsignedValue(0x0123_4567_89AB_CDEF))
+        );
+        assert_eq!(
+            frame.attribute("target_velocity"),
+            Some(&Value::UnsignedValue(0xFEDC_BA98_7654_3210))
+        );
+        assert_eq!(
+            frame.attribute("timeout"),
+            Some(&Value::UnsignedValue(0xDEAD_BEEF))
+        );
+    }
+
+    #[tokio::test]
+    async fn only_one_invocation_is_open() {
+        let invocations = Invocations::new();
+        invocations.open().await.unwrap();
+        assert!(matches!(
+            invocations.open().await,
+            Err(Error::CommandInProgress)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn response_completes_the_invocation() {
+        let invocations = Arc::new(Invocations::new());
+        let req_num = invocations.open().await.unwrap();
+        let timeout = tokio::spawn({
+            let invocations = invocations.clone();
+            async move { invocations.expire_after(req_num, TIMEOUT).await }
+        });
 
-#[allow(unused)] //FIXME
-enum CommandEvent {
-    Resp,
-    Req(u32),
-    Timeout(u32),
+        assert_eq!(invocations.complete().await, Some(req_num));
+        // the timeout of a completed invocation is ignored.
+        assert!(!timeout.await.unwrap());
+        // the response of a completed invocation is ignored.
+        assert_eq!(invocations.complete().await, None);
+        assert!(invocations.open().await.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timeout_completes_the_invocation() {
+        let invocations = Invocations::new();
+        let req_num = invocations.open().await.unwrap();
+
+        assert!(invocations.expire_after(req_num, TIMEOUT).await);
+        // responses after the timeout are ignored.
+        assert_eq!(invocations.complete().await, None);
+        let next_req_num = invocations.open().await.unwrap();
+        assert_ne!(next_req_num, req_num);
+    }
 }
@@ -1,16 +1,22 @@
 use std::{sync::Arc, time::Instant};
 
-use self::node_object::NodeObject;
+use self::{node_object::NodeObject, object_entry_object::database::HistoryConfig};
+use canzero_appdata::AppData;
 use canzero_config::config;
+use color_print::cprintln;
+use tempfile::TempDir;
 
-use super::{tx::TxCom, watchdog::WatchdogOverlord};
+use super::{request::RequestManager, tx::TxCom, watchdog::WatchdogOverlord};
 
 pub mod command_object;
 pub mod node_object;
 pub mod object_entry_object;
+pub mod stream_object;
 
 pub struct NetworkObject {
     nodes: Vec<Arc<NodeObject>>,
+    // evicted history of all object entries, removed when the network is dropped.
+    _history_dir: Option<TempDir>,
 }
 
 impl NetworkObject {
@@ -18,9 +24,19 @@ impl NetworkObject {
         network_config: &config::NetworkRef,
         app_handle: &tauri::AppHandle,
         tx_com: Arc<TxCom>,
+        requests: &RequestManager,
         timebase: Instant,
         watchdog_overloard: &WatchdogOverlord,
+        history_limit: usize,
     ) -> Self {
+        let history_dir = match create_history_dir() {
+            Ok(dir) => Some(dir),
+            Err(err) => {
+                cprintln!("<red>Failed to create history directory</red> : {err:?}");
+                None
+            }
+        };
+        let history = HistoryConfig::new(history_limit, history_dir.as_ref().map(TempDir::path));
         Self {
             nodes: network_config
                 .nodes()
@@ -31,11 +47,14 @@ impl NetworkObject {
                         node_config,
                         app_handle,
                         tx_com.clone(),
+                        requests,
                         timebase,
                         watchdog_overloard,
+                        &history,
                     ))
                 })
                 .collect(),
+            _history_dir: history_dir,
         }
     }
     pub fn nodes(&self) -> &Vec<Arc<NodeObject>> {
@@ -47,3 +66,9 @@ impl NetworkObject {
         }
     }
 }
+
+fn create_history_dir() -> std::io::Result<TempDir> {
+    let dir = AppData::dir().join("history");
+    std::fs::create_dir_all(&dir)?;
+    tempfile::Builder::new().prefix("session-").tempdir_in(dir)
+}
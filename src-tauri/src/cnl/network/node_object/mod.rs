@@ -7,6 +7,7 @@ use canzero_config::config::{self, bus::BusRef};
 
 use crate::{
     cnl::{
+        request::RequestManager,
         tx::TxCom,
         watchdog::{Watchdog, WatchdogOverlord, WdgStatus, WdgTag},
     },
@@ -15,7 +16,11 @@ use crate::{
 
 use self::{heartbeat_observable::HeartbeatObservable, latest::NodeLatestObservable};
 
-use super::{command_object::CommandObject, object_entry_object::ObjectEntryObject};
+use super::{
+    command_object::CommandObject,
+    object_entry_object::{database::HistoryConfig, ObjectEntryObject},
+    stream_object::StreamObject,
+};
 
 pub mod heartbeat_observable;
 pub mod latest;
@@ -24,6 +29,7 @@ pub struct NodeObject {
     node_ref: config::NodeRef,
     object_entries: Vec<Arc<ObjectEntryObject>>,
     commands: Vec<Arc<CommandObject>>,
+    streams: Vec<Arc<StreamObject>>,
     latest_observable: NodeLatestObservable,
     heartbeat_wdgs: Vec<Watchdog>,
     heartbeat_observables: Vec<HeartbeatObservable>,
@@ -36,8 +42,10 @@ impl NodeObject {
         node_config: &config::NodeRef,
         app_handle: &tauri::AppHandle,
         tx_com: Arc<TxCom>,
+        requests: &RequestManager,
         timebase: Instant,
         watchdog_overloard: &WatchdogOverlord,
+        history: &HistoryConfig,
     ) -> Self {
         let object_entries = node_config
             .object_entries()
@@ -48,8 +56,9 @@ impl NodeObject {
                     node_config,
                     object_entry,
                     app_handle,
-                    tx_com.clone(),
+                    requests,
                     timebase,
+                    history,
                 ))
             })
             .collect();
@@ -83,7 +92,26 @@ impl NodeObject {
             commands: node_config
                 .commands()
                 .iter()
-                .map(|command| Arc::new(CommandObject::create(command, app_handle)))
+                .map(|command| {
+                    Arc::new(CommandObject::create(
+                        node_config,
+                        command,
+                        app_handle,
+                        tx_com.clone(),
+                    ))
+                })
+                .collect(),
+            streams: node_config
+                .tx_streams()
+                .iter()
+                .map(|stream| {
+                    Arc::new(StreamObject::create(
+                        node_config,
+                        stream,
+                        app_handle,
+                        timebase,
+                    ))
+                })
                 .collect(),
             node_ref: node_config.clone(),
             heartbeat_wdgs,
@@ -106,6 +134,9 @@ impl NodeObject {
     pub fn commands(&self) -> &Vec<Arc<CommandObject>> {
         &self.commands
     }
+    pub fn streams(&self) -> &Vec<Arc<StreamObject>> {
+        &self.streams
+    }
     pub fn buses(&self) -> &Vec<BusRef> {
         self.node_ref.buses()
     }
@@ -120,9 +151,9 @@ impl NodeObject {
         for oe in &self.object_entries {
             oe.deadlock_watchdog().await;
         }
-        // for c in &self.commands {
-        //     c.deadlock_watchdog().await;
-        // }
+        for c in &self.commands {
+            c.deadlock_watchdog().await;
+        }
         // let _ = self.latest_observable.deadlock_watchdog().await;
     }
 
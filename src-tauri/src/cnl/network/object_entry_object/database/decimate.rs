@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use crate::cnl::frame::{Attribute, Value};
+
+/// Summary of all values of an object entry within [start, end).
+#[derive(Debug, Clone)]
+pub struct DecimatedBucket {
+    pub start: Duration,
+    pub end: Duration,
+    pub min: Value,
+    pub max: Value,
+    pub mean: Value,
+    pub count: usize,
+}
+
+/// Accumulates values of the same shape, numeric values are reduced elementwise,
+/// for enums the last value is kept.
+enum Accumulator {
+    Unsigned { min: u64, max: u64, sum: f64 },
+    Signed { min: i64, max: i64, sum: f64 },
+    Real { min: f64, max: f64, sum: f64 },
+    Enum(String),
+    Struct(Vec<(String, Accumulator)>),
+    Array(Vec<Accumulator>),
+}
+
+impl Accumulator {
+    fn new(value: &Value) -> Self {
+        match value {
+            Value::UnsignedValue(v) => Accumulator::Unsigned {
+                min: *v,
+                max: *v,
+                sum: *v as f64,
+            },
+            Value::SignedValue(v) => Accumulator::Signed {
+                min: *v,
+                max: *v,
+                sum: *v as f64,
+            },
+            Value::RealValue(v) => Accumulator::Real {
+                min: *v,
+                max: *v,
+                sum: *v,
+            },
+            Value::EnumValue(v) => Accumulator::Enum(v.clone()),
+            Value::StructValue(attribs) => Accumulator::Struct(
+                attribs
+                    .iter()
+                    .map(|attrib| (attrib.name().to_owned(), Accumulator::new(attrib.value())))
+                    .collect(),
+            ),
+            Value::ArrayValue(elements) => {
+                Accumulator::Array(elements.iter().map(Accumulator::new).collect())
+            }
+        }
+    }
+
+    fn add(&mut self, value: &Value) {
+        match (self, value) {
+            (Accumulator::Unsigned { min, max, sum }, Value::UnsignedValue(v)) => {
+                *min = (*min).min(*v);
+                *max = (*max).max(*v);
+                *sum += *v as f64;
+            }
+            (Accumulator::Signed { min, max, sum }, Value::SignedValue(v)) => {
+                *min = (*min).min(*v);
+                *max = (*max).max(*v);
+                *sum += *v as f64;
+            }
+            (Accumulator::Real { min, max, sum }, Value::RealValue(v)) => {
+                *min = min.min(*v);
+                *max = max.max(*v);
+                *sum += *v;
+            }
+            (Accumulator::Enum(last), Value::EnumValue(v)) => *last = v.clone(),
+            (Accumulator::Struct(attribs), Value::StructValue(values)) => {
+                for ((_, acc), attrib) in attribs.iter_mut().zip(values) {
+                    acc.add(attrib.value());
+                }
+            }
+            (Accumulator::Array(elements), Value::ArrayValue(values)) => {
+                for (acc, value) in elements.iter_mut().zip(values) {
+                    acc.add(value);
+                }
+            }
+            // the type of an object entry never changes.
+            _ => (),
+        }
+    }
+
+    fn min(&self) -> Value {
+        match self {
+            Accumulator::Unsigned { min, .. } => Value::UnsignedValue(*min),
+            Accumulator::Signed { min, .. } => Value::SignedValue(*min),
+            Accumulator::Real { min, .. } => Value::RealValue(*min),
+            Accumulator::Enum(last) => Value::EnumValue(last.clone()),
+            Accumulator::Struct(attribs) => Value::StructValue(
+                attribs
+                    .iter()
+                    .map(|(name, acc)| Attribute::new(name, acc.min()))
+                    .collect(),
+            ),
+            Accumulator::Array(elements) => {
+                Value::ArrayValue(elements.iter().map(|acc| acc.min()).collect())
+            }
+        }
+    }
+
+    fn max(&self) -> Value {
+        match self {
+            Accumulator::Unsigned { max, .. } => Value::UnsignedValue(*max),
+            Accumulator::Signed { max, .. } => Value::SignedValue(*max),
+            Accumulator::Real { max, .. } => Value::RealValue(*max),
+            Accumulator::Enum(last) => Value::EnumValue(last.clone()),
+            Accumulator::Struct(attribs) => Value::StructValue(
+                attribs
+                    .iter()
+                    .map(|(name, acc)| Attribute::new(name, acc.max()))
+                    .collect(),
+            ),
+            Accumulator::Array(elements) => {
+                Value::ArrayValue(elements.iter().map(|acc| acc.max()).collect())
+            }
+        }
+    }
+
+    /// integer means are rounded, such that the value keeps the type of the object entry.
+    fn mean(&self, count: usize) -> Value {
+        let count = count as f64;
+        match self {
+            Accumulator::Unsigned { sum, .. } => Value::UnsignedValue((sum / count).round() as u64),
+            Accumulator::Signed { sum, .. } => Value::SignedValue((sum / count).round() as i64),
+            Accumulator::Real { sum, .. } => Value::RealValue(sum / count),
+            Accumulator::Enum(last) => Value::EnumValue(last.clone()),
+            Accumulator::Struct(attribs) => Value::StructValue(
+                attribs
+                    .iter()
+                    .map(|(name, acc)| Attribute::new(name, acc.mean(count as usize)))
+                    .collect(),
+            ),
+            Accumulator::Array(elements) => {
+                Value::ArrayValue(elements.iter().map(|acc| acc.mean(count as usize)).collect())
+            }
+        }
+    }
+}
+
+/// Reduces a stream of values into buckets of a fixed width.
+pub struct Decimator {
+    bucket_width: Duration,
+    open: Option<(Duration, Accumulator, usize)>,
+}
+
+impl Decimator {
+    pub fn new(bucket_width: Duration) -> Self {
+        Self {
+            bucket_width,
+            open: None,
+        }
+    }
+
+    /// returns the previous bucket if the value does not belong into it.
+    pub fn push(&mut self, value: &Value, timestamp: Duration) -> Option<DecimatedBucket> {
+        if let Some((start, acc, count)) = &mut self.open {
+            if timestamp < *start + self.bucket_width {
+                acc.add(value);
+                *count += 1;
+                return None;
+            }
+        }
+        let closed = self.close();
+        let width = self.bucket_width.as_micros().max(1);
+        let start = Duration::from_micros((timestamp.as_micros() / width * width) as u64);
+        self.open = Some((start, Accumulator::new(value), 1));
+        closed
+    }
+
+    /// closes the currently open bucket.
+    pub fn close(&mut self) -> Option<DecimatedBucket> {
+        let (start, acc, count) = self.open.take()?;
+        Some(DecimatedBucket {
+            start,
+            end: start + self.bucket_width,
+            min: acc.min(),
+            max: acc.max(),
+            mean: acc.mean(count),
+            count,
+        })
+    }
+
+    /// the currently open bucket, without closing it.
+    pub fn peek(&self) -> Option<DecimatedBucket> {
+        let (start, acc, count) = self.open.as_ref()?;
+        Some(DecimatedBucket {
+            start: *start,
+            end: *start + self.bucket_width,
+            min: acc.min(),
+            max: acc.max(),
+            mean: acc.mean(*count),
+            count: *count,
+        })
+    }
+}
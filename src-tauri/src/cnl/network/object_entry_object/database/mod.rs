@@ -1,24 +1,359 @@
-use self::value::ObjectEntryValue;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use color_print::cprintln;
+
+use self::{
+    decimate::{DecimatedBucket, Decimator},
+    spill::SpillFile,
+    value::ObjectEntryValue,
+};
+
+pub mod decimate;
+mod spill;
 pub mod value;
 
+#[derive(Clone)]
+pub struct HistoryConfig {
+    /// values younger than this are kept at full resolution.
+    pub recent_window: Duration,
+    /// hard limit for the amount of full resolution values (high-rate streams).
+    pub max_recent: usize,
+    /// width of the min/max/mean buckets of older values.
+    pub bucket_width: Duration,
+    /// the oldest buckets are dropped after this limit is reached.
+    pub max_buckets: usize,
+    /// if set, evicted values are written to this file at full resolution
+    /// (a directory for configs created with HistoryConfig::new).
+    pub spill_path: Option<PathBuf>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            recent_window: Duration::from_secs(60),
+            max_recent: 100_000,
+            bucket_width: Duration::from_secs(1),
+            max_buckets: 24 * 60 * 60,
+            spill_path: None,
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// keeps up to max_recent values at full resolution in memory and spills
+    /// evicted values into the directory spill_dir.
+    pub fn new(max_recent: usize, spill_dir: Option<&Path>) -> Self {
+        Self {
+            max_recent,
+            spill_path: spill_dir.map(Path::to_owned),
+            ..Default::default()
+        }
+    }
+
+    /// the config of a single object entry, which spills into its own file
+    /// {spill_dir}/{node}/{object_entry}.bin (names may contain underscores,
+    /// such that joining them into a single file name is ambiguous).
+    pub fn for_object_entry(&self, node: &str, object_entry: &str) -> Self {
+        Self {
+            spill_path: self
+                .spill_path
+                .as_ref()
+                .map(|dir| dir.join(node).join(format!("{object_entry}.bin"))),
+            ..self.clone()
+        }
+    }
+}
+
+/// Tiered history of an object entry:
+/// the recent window at full resolution, older values as decimated buckets and
+/// optionally all evicted values at full resolution on disk.
 pub struct ObjectEntryDatabase {
-    history: Vec<ObjectEntryValue>,
+    config: HistoryConfig,
+    recent: Vec<ObjectEntryValue>,
+    // number of values that were evicted from recent,
+    // such that sequence numbers stay valid after eviction.
+    evicted: usize,
+    decimator: Decimator,
+    buckets: Vec<DecimatedBucket>,
+    spill: Option<SpillFile>,
 }
 
 impl ObjectEntryDatabase {
-    pub fn new() -> Self {
-        Self { history: vec![] }
+    pub fn new(config: HistoryConfig) -> Self {
+        let spill = match &config.spill_path {
+            Some(path) => match SpillFile::create(path) {
+                Ok(spill) => Some(spill),
+                Err(err) => {
+                    cprintln!("<red>Failed to create history spill file {path:?}</red> : {err:?}");
+                    None
+                }
+            },
+            None => None,
+        };
+        Self {
+            decimator: Decimator::new(config.bucket_width),
+            config,
+            recent: vec![],
+            evicted: 0,
+            buckets: vec![],
+            spill,
+        }
     }
 
-    pub fn history(&self) -> &Vec<ObjectEntryValue> {
-        &self.history
+    /// total number of values that were ever pushed,
+    /// which is the sequence number of the next value.
+    pub fn len(&self) -> usize {
+        self.evicted + self.recent.len()
+    }
+
+    /// full resolution values with a sequence number >= seq,
+    /// values that were already evicted are skipped.
+    pub fn values_since(&self, seq: usize) -> &[ObjectEntryValue] {
+        &self.recent[seq.saturating_sub(self.evicted).min(self.recent.len())..]
     }
 
     pub fn latest_value(&self) -> Option<&ObjectEntryValue> {
-        self.history.last()
+        self.recent.last()
+    }
+
+    #[allow(unused)]
+    pub fn decimated(&self) -> &[DecimatedBucket] {
+        &self.buckets
     }
+
     pub fn push_value(&mut self, value: ObjectEntryValue) {
-        self.history.push(value);
+        let breakpoint = value.timestamp.saturating_sub(self.config.recent_window);
+        self.recent.push(value);
+
+        let expired = self.recent.partition_point(|v| v.timestamp < breakpoint);
+        let overflow = self.recent.len().saturating_sub(self.config.max_recent);
+        // evict in batches, such that pushing stays amortized O(1).
+        let evict = expired.max(overflow);
+        if evict > 0 && (evict >= self.recent.len() / 4 || overflow > self.config.max_recent / 4) {
+            self.evict(evict);
+        }
+    }
+
+    fn evict(&mut self, count: usize) {
+        for value in self.recent.drain(..count) {
+            if let Some(bucket) = self.decimator.push(&value.value, value.timestamp) {
+                self.buckets.push(bucket);
+            }
+            if let Some(spill) = &mut self.spill {
+                if let Err(err) = spill.append(&value) {
+                    cprintln!("<red>Failed to spill history to disk</red> : {err:?}");
+                    self.spill = None;
+                }
+            }
+        }
+        self.evicted += count;
+        if self.buckets.len() > self.config.max_buckets {
+            let drop = self.buckets.len() - self.config.max_buckets;
+            self.buckets.drain(..drop);
+        }
+    }
+
+    /// all values with timestamp >= since (and the last value before since, such that
+    /// plots start at the edge of the frame), evicted values are represented by the
+    /// mean of their bucket.
+    pub fn history_since(&self, since: Duration) -> Vec<ObjectEntryValue> {
+        let mut history: Vec<ObjectEntryValue> = self
+            .buckets
+            .iter()
+            .cloned()
+            .chain(self.decimator.peek())
+            .filter(|bucket| bucket.end > since)
+            .map(|bucket| {
+                ObjectEntryValue::new(bucket.mean, bucket.start, self.config.bucket_width)
+            })
+            .collect();
+        let start = self
+            .recent
+            .partition_point(|v| v.timestamp < since)
+            .saturating_sub(1);
+        history.extend_from_slice(&self.recent[start..]);
+        history
+    }
+
+    /// the complete history, at full resolution if spilling to disk is enabled.
+    pub fn complete_history(&mut self) -> Vec<ObjectEntryValue> {
+        if let Some(spill) = &mut self.spill {
+            match spill.read_all() {
+                Ok(mut history) => {
+                    history.extend_from_slice(&self.recent);
+                    return history;
+                }
+                Err(err) => cprintln!("<red>Failed to read spilled history</red> : {err:?}"),
+            }
+        }
+        self.history_since(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnl::frame::{Attribute, Value};
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// values 0, 1, 2, ... every 10ms.
+    fn fill(database: &mut ObjectEntryDatabase, count: u64) {
+        for i in 0..count {
+            database.push_value(ObjectEntryValue::new(
+                Value::RealValue(i as f64),
+                ms(i * 10),
+                ms(10),
+            ));
+        }
+    }
+
+    fn config(recent_window: Duration, max_recent: usize) -> HistoryConfig {
+        HistoryConfig {
+            recent_window,
+            max_recent,
+            bucket_width: ms(100),
+            max_buckets: 1000,
+            spill_path: None,
+        }
+    }
+
+    #[test]
+    fn values_older_than_the_recent_window_are_evicted() {
+        let mut database = ObjectEntryDatabase::new(config(Duration::from_secs(1), 100_000));
+        fill(&mut database, 500);
+        assert_eq!(database.len(), 500);
+        let recent = database.values_since(0);
+        assert!(recent.len() < 500);
+        // eviction is batched, at most a quarter of the window is kept additionally.
+        assert!(recent[0].timestamp >= ms(4990 - 1250), "{:?}", recent[0]);
+        assert_eq!(recent.last().unwrap().timestamp, ms(4990));
+        assert_eq!(
+            database.latest_value().unwrap().value,
+            Value::RealValue(499.0)
+        );
+        // sequence numbers stay valid after eviction.
+        assert_eq!(database.values_since(499).len(), 1);
+        assert_eq!(database.values_since(500).len(), 0);
+        assert_eq!(database.values_since(0).len(), recent.len());
+    }
+
+    #[test]
+    fn max_recent_limits_the_full_resolution_values() {
+        let mut database = ObjectEntryDatabase::new(config(Duration::from_secs(3600), 100));
+        fill(&mut database, 1000);
+        assert_eq!(database.len(), 1000);
+        let recent = database.values_since(0);
+        assert!(recent.len() <= 125, "{}", recent.len());
+        assert_eq!(recent.last().unwrap().value, Value::RealValue(999.0));
+    }
+
+    #[test]
+    fn evicted_values_are_decimated_into_buckets() {
+        let mut database = ObjectEntryDatabase::new(config(ms(100), 100_000));
+        fill(&mut database, 1000);
+        let buckets = database.decimated();
+        assert!(!buckets.is_empty());
+        for (i, bucket) in buckets.iter().enumerate() {
+            // 10 values per 100ms bucket.
+            let first = (i * 10) as f64;
+            assert_eq!(bucket.start, ms(i as u64 * 100));
+            assert_eq!(bucket.end, ms(i as u64 * 100 + 100));
+            assert_eq!(bucket.count, 10);
+            assert_eq!(bucket.min, Value::RealValue(first));
+            assert_eq!(bucket.max, Value::RealValue(first + 9.0));
+            assert_eq!(bucket.mean, Value::RealValue(first + 4.5));
+        }
+
+        // the history represents evicted values by the mean of their bucket.
+        let history = database.history_since(Duration::ZERO);
+        assert_eq!(history[0].value, Value::RealValue(4.5));
+        assert_eq!(history.last().unwrap().value, Value::RealValue(999.0));
+        assert!(history.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    }
+
+    #[test]
+    fn the_oldest_buckets_are_dropped() {
+        let mut config = config(ms(100), 100_000);
+        config.max_buckets = 5;
+        let mut database = ObjectEntryDatabase::new(config);
+        fill(&mut database, 1000);
+        let buckets = database.decimated();
+        assert_eq!(buckets.len(), 5);
+        assert!(buckets[0].start > Duration::ZERO);
+        assert!(buckets.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[test]
+    fn spilled_values_are_reloaded_at_full_resolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = HistoryConfig {
+            spill_path: Some(dir.path().to_owned()),
+            ..config(ms(100), 100_000)
+        }
+        .for_object_entry("secu", "position");
+        let path = dir.path().join("secu").join("position.bin");
+        assert_eq!(history.spill_path.as_ref(), Some(&path));
+
+        let mut database = ObjectEntryDatabase::new(history);
+        fill(&mut database, 1000);
+        assert!(database.values_since(0).len() < 1000);
+        let complete = database.complete_history();
+        assert_eq!(complete.len(), 1000);
+        for (i, value) in complete.iter().enumerate() {
+            assert_eq!(value.value, Value::RealValue(i as f64));
+            assert_eq!(value.timestamp, ms(i as u64 * 10));
+            assert_eq!(value.delta_time, ms(10));
+        }
+        // values pushed after a reload are spilled as well.
+        database.push_value(ObjectEntryValue::new(
+            Value::RealValue(1000.0),
+            ms(10000),
+            ms(10),
+        ));
+        assert_eq!(database.complete_history().len(), 1001);
+
+        assert!(path.exists());
+        drop(database);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn spill_files_of_object_entries_are_unique() {
+        let history = HistoryConfig::new(100, Some(Path::new("spill")));
+        assert_ne!(
+            history.for_object_entry("a_b", "c").spill_path,
+            history.for_object_entry("a", "b_c").spill_path
+        );
+    }
+
+    #[test]
+    fn structured_values_survive_the_spill_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut database = ObjectEntryDatabase::new(HistoryConfig {
+            spill_path: Some(dir.path().join("cells.bin")),
+            ..config(Duration::ZERO, 100_000)
+        });
+        let value = |i: u64| {
+            Value::ArrayValue(vec![Value::StructValue(vec![
+                Attribute::new("voltage", Value::RealValue(i as f64 * 0.5)),
+                Attribute::new("temperature", Value::SignedValue(-(i as i64))),
+                Attribute::new("mode", Value::EnumValue("idle".to_owned())),
+                Attribute::new("count", Value::UnsignedValue(i)),
+            ])])
+        };
+        for i in 0..100 {
+            database.push_value(ObjectEntryValue::new(value(i), ms(i * 10), ms(10)));
+        }
+        let complete = database.complete_history();
+        assert_eq!(complete.len(), 100);
+        for (i, v) in complete.iter().enumerate() {
+            assert_eq!(v.value, value(i as u64));
+        }
     }
 }
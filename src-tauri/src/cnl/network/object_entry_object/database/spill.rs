@@ -0,0 +1,160 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::cnl::frame::{Attribute, Value};
+
+use super::value::ObjectEntryValue;
+
+/// Append only file of full resolution values that were evicted from memory.
+pub struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl SpillFile {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Ok(Self {
+            path: path.to_owned(),
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+
+    pub fn append(&mut self, value: &ObjectEntryValue) -> std::io::Result<()> {
+        self.writer
+            .write_all(&(value.timestamp.as_micros() as u64).to_le_bytes())?;
+        self.writer
+            .write_all(&(value.delta_time.as_micros() as u64).to_le_bytes())?;
+        write_value(&mut self.writer, &value.value)
+    }
+
+    pub fn read_all(&mut self) -> std::io::Result<Vec<ObjectEntryValue>> {
+        self.writer.flush()?;
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut values = vec![];
+        loop {
+            let mut buf = [0u8; 8];
+            match reader.read_exact(&mut buf) {
+                Ok(()) => (),
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            }
+            let timestamp = Duration::from_micros(u64::from_le_bytes(buf));
+            reader.read_exact(&mut buf)?;
+            let delta_time = Duration::from_micros(u64::from_le_bytes(buf));
+            let value = read_value(&mut reader)?;
+            values.push(ObjectEntryValue::new(value, timestamp, delta_time));
+        }
+        Ok(values)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn write_str<W: Write>(writer: &mut W, s: &str) -> std::io::Result<()> {
+    writer.write_all(&(s.len() as u16).to_le_bytes())?;
+    writer.write_all(s.as_bytes())
+}
+
+fn read_str<R: Read>(reader: &mut R) -> std::io::Result<String> {
+    let mut len = [0u8; 2];
+    reader.read_exact(&mut len)?;
+    let mut buf = vec![0u8; u16::from_le_bytes(len) as usize];
+    reader.read_exact(&mut buf)?;
+    String::from_utf8(buf)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+fn write_value<W: Write>(writer: &mut W, value: &Value) -> std::io::Result<()> {
+    match value {
+        Value::UnsignedValue(v) => {
+            writer.write_all(&[0])?;
+            writer.write_all(&v.to_le_bytes())
+        }
+        Value::SignedValue(v) => {
+            writer.write_all(&[1])?;
+            writer.write_all(&v.to_le_bytes())
+        }
+        Value::RealValue(v) => {
+            writer.write_all(&[2])?;
+            writer.write_all(&v.to_le_bytes())
+        }
+        Value::EnumValue(v) => {
+            writer.write_all(&[3])?;
+            write_str(writer, v)
+        }
+        Value::StructValue(attribs) => {
+            writer.write_all(&[4])?;
+            writer.write_all(&(attribs.len() as u16).to_le_bytes())?;
+            for attrib in attribs {
+                write_str(writer, attrib.name())?;
+                write_value(writer, attrib.value())?;
+            }
+            Ok(())
+        }
+        Value::ArrayValue(elements) => {
+            writer.write_all(&[5])?;
+            writer.write_all(&(elements.len() as u16).to_le_bytes())?;
+            for element in elements {
+                write_value(writer, element)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn read_value<R: Read>(reader: &mut R) -> std::io::Result<Value> {
+    let mut tag = [0u8; 1];
+    reader.read_exact(&mut tag)?;
+    let mut buf = [0u8; 8];
+    Ok(match tag[0] {
+        0 => {
+            reader.read_exact(&mut buf)?;
+            Value::UnsignedValue(u64::from_le_bytes(buf))
+        }
+        1 => {
+            reader.read_exact(&mut buf)?;
+            Value::SignedValue(i64::from_le_bytes(buf))
+        }
+        2 => {
+            reader.read_exact(&mut buf)?;
+            Value::RealValue(f64::from_le_bytes(buf))
+        }
+        3 => Value::EnumValue(read_str(reader)?),
+        4 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            let mut attribs = vec![];
+            for _ in 0..u16::from_le_bytes(len) {
+                let name = read_str(reader)?;
+                attribs.push(Attribute::new(&name, read_value(reader)?));
+            }
+            Value::StructValue(attribs)
+        }
+        5 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            let mut elements = vec![];
+            for _ in 0..u16::from_le_bytes(len) {
+                elements.push(read_value(reader)?);
+            }
+            Value::ArrayValue(elements)
+        }
+        tag => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid value tag {tag}"),
+            ))
+        }
+    })
+}
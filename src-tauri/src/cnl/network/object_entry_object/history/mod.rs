@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::VecDeque, sync::Arc, time::Duration};
 
 use color_print::cprintln;
 use tauri::Manager;
@@ -24,7 +24,8 @@ pub struct ObjectEntryHistroyObservable {
     tx: mpsc::Sender<ObserverCommand>,
     rx: Arc<Mutex<mpsc::Receiver<ObserverCommand>>>,
 
-    start_index: usize,
+    // timestamps of the values that the listener already received.
+    sent_timestamps: Mutex<Option<VecDeque<Duration>>>,
     start_time: std::time::Instant,
 }
 
@@ -34,7 +35,7 @@ impl ObjectEntryHistroyObservable {
         min_interval: Duration,
         frame_size: Duration,
         app_handle: &tauri::AppHandle,
-        start_index: usize,
+        sent_timestamps: VecDeque<Duration>,
         start_time: std::time::Instant,
     ) -> Self {
         let (tx, rx) = mpsc::channel(10);
@@ -45,7 +46,7 @@ impl ObjectEntryHistroyObservable {
             app_handle: app_handle.clone(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
-            start_index,
+            sent_timestamps: Mutex::new(Some(sent_timestamps)),
             start_time,
         }
     }
@@ -58,6 +59,12 @@ impl ObjectEntryHistroyObservable {
     }
 
     pub async fn start_notify_task(&self, store: &Arc<Mutex<ObjectEntryDatabase>>) {
+        let sent_timestamps = self
+            .sent_timestamps
+            .lock()
+            .await
+            .take()
+            .expect("notify task of object entry history observable started twice");
         tokio::spawn(Self::notify_task(
             self.event_name.clone(),
             self.min_interval,
@@ -65,8 +72,8 @@ impl ObjectEntryHistroyObservable {
             self.app_handle.clone(),
             self.rx.clone(),
             store.clone(),
-            store.lock().await.history().len(),
-            self.start_index,
+            store.lock().await.len(),
+            sent_timestamps,
             self.start_time,
         ));
     }
@@ -78,6 +85,44 @@ impl ObjectEntryHistroyObservable {
             .expect("Failed to send poison to object entry history notify task");
     }
 
+    /// sends all values that were pushed since latest_seq and tells the listener
+    /// how many of the values it holds left the frame.
+    /// Returns false if there was nothing to send.
+    fn emit_batch(
+        event_name: &str,
+        frame_size: Duration,
+        app_handle: &tauri::AppHandle,
+        store: &ObjectEntryDatabase,
+        latest_seq: &mut usize,
+        sent_timestamps: &mut VecDeque<Duration>,
+        start_time: std::time::Instant,
+    ) -> bool {
+        if store.len() <= *latest_seq {
+            return false;
+        }
+        let now = std::time::Instant::now().duration_since(start_time);
+        let breakpoint = now.saturating_sub(frame_size);
+        let mut deprecated_count = 0;
+        while let Some(timestamp) = sent_timestamps.front() {
+            if *timestamp >= breakpoint {
+                break;
+            }
+            sent_timestamps.pop_front();
+            deprecated_count += 1;
+        }
+
+        let payload = store.values_since(*latest_seq);
+        sent_timestamps.extend(payload.iter().map(|value| value.timestamp));
+        *latest_seq = store.len();
+        app_handle
+            .emit_all(
+                event_name,
+                ObjectEntryHistoryEvent::new(payload, deprecated_count),
+            )
+            .unwrap();
+        true
+    }
+
     async fn notify_task(
         event_name: String,
         min_interval: Duration,
@@ -85,13 +130,12 @@ impl ObjectEntryHistroyObservable {
         app_handle: tauri::AppHandle,
         rx: Arc<Mutex<mpsc::Receiver<ObserverCommand>>>,
         store: Arc<Mutex<ObjectEntryDatabase>>,
-        latest_index: usize,
-        start_index: usize,
+        latest_seq: usize,
+        sent_timestamps: VecDeque<Duration>,
         start_time: std::time::Instant,
     ) {
-        // println!("start notify task {event_name}");
-        let mut start_index = start_index;
-        let mut latest_index = latest_index;
+        let mut latest_seq = latest_seq;
+        let mut sent_timestamps = sent_timestamps;
         let mut rx = rx.lock().await;
         let mut next_batch_time = tokio::time::Instant::now();
         let mut timeout = tokio::time::Instant::now() + Duration::from_secs(0xFFFF);
@@ -99,67 +143,25 @@ impl ObjectEntryHistroyObservable {
             match tokio::time::timeout_at(timeout, rx.recv()).await {
                 Ok(opt) => {
                     match opt {
-                        Some(ObserverCommand::Poison) => {
-                            let store_lock = store.lock().await;
-                            if store_lock.history().len() < latest_index {
-                                let mut deprecated_count = 0;
-
-                                let now = std::time::Instant::now().duration_since(start_time);
-                                let breakpoint = now.saturating_sub(frame_size);
-                                for event in store_lock.history()[start_index..].iter() {
-                                    if event.timestamp >= breakpoint {
-                                        break;
-                                    }
-                                    deprecated_count += 1;
-                                }
-
-                                let payload = &store_lock.history()[latest_index..];
-                                app_handle
-                                    .emit_all(
-                                        &event_name,
-                                        ObjectEntryHistoryEvent::new(payload, deprecated_count),
-                                    )
-                                    .unwrap();
-                            }
-                            break;
-                        }
+                        Some(ObserverCommand::Poison) => break,
                         Some(ObserverCommand::Value) => {
                             // only send batch if the last interval is min_interval in the past!
-
                             if next_batch_time <= tokio::time::Instant::now() {
                                 let store_lock = store.lock().await;
-                                // should never be false because the store should be updated before
-                                // the notify (otherwise notify should not be called)
-                                if store_lock.history().len() > latest_index {
-                                    // go up from start_index and count how many messages are
-                                    // deprecated afterwards update start_index
-
-                                    let mut deprecated_count = 0;
-
-                                    let now = std::time::Instant::now().duration_since(start_time);
-                                    let breakpoint = now.saturating_sub(frame_size);
-                                    for event in store_lock.history()[start_index..].iter() {
-                                        if event.timestamp >= breakpoint {
-                                            break;
-                                        }
-                                        deprecated_count += 1;
-                                    }
-                                    start_index += deprecated_count;
-
-                                    let payload = &store_lock.history()[latest_index..];
-                                    latest_index = store_lock.history().len();
-                                    app_handle
-                                        .emit_all(
-                                            &event_name,
-                                            ObjectEntryHistoryEvent::new(payload, deprecated_count),
-                                        )
-                                        .unwrap();
+                                if Self::emit_batch(
+                                    &event_name,
+                                    frame_size,
+                                    &app_handle,
+                                    &store_lock,
+                                    &mut latest_seq,
+                                    &mut sent_timestamps,
+                                    start_time,
+                                ) {
                                     next_batch_time = tokio::time::Instant::now() + min_interval;
-                                    timeout = next_batch_time;
-                                } else {
-                                    //panic!("I assumed this should not be called, just checking here!");
                                 }
                             }
+                            // send the values that arrive within min_interval afterwards.
+                            timeout = next_batch_time;
                         }
                         None => {
                             panic!("rx_receiver closed early");
@@ -168,34 +170,21 @@ impl ObjectEntryHistroyObservable {
                 }
                 Err(_elapsed) => {
                     let store_lock = store.lock().await;
-                    if store_lock.history().len() < latest_index {
-                        let mut deprecated_count = 0;
-
-                        let now = std::time::Instant::now().duration_since(start_time);
-                        let breakpoint = now.saturating_sub(frame_size);
-                        for event in store_lock.history()[start_index..].iter() {
-                            if event.timestamp >= breakpoint {
-                                break;
-                            }
-                            deprecated_count += 1;
-                        }
-                        start_index += deprecated_count;
-
-                        let payload = &store_lock.history()[latest_index..];
-                        latest_index = store_lock.history().len();
-                        app_handle
-                            .emit_all(
-                                &event_name,
-                                ObjectEntryHistoryEvent::new(payload, deprecated_count),
-                            )
-                            .unwrap();
+                    if Self::emit_batch(
+                        &event_name,
+                        frame_size,
+                        &app_handle,
+                        &store_lock,
+                        &mut latest_seq,
+                        &mut sent_timestamps,
+                        start_time,
+                    ) {
                         next_batch_time = tokio::time::Instant::now() + min_interval;
                     }
                     timeout += Duration::from_secs(0xFFFF); //wait for ever!
                 }
             }
         }
-        // println!("start notify task {event_name}");
     }
 
     pub fn event_name(&self) -> &str {
@@ -30,6 +30,12 @@ pub struct ObjectEntryStructType {
     attributes: ObjectEntryStructAttributeMap,
 }
 
+#[derive(Clone, Serialize)]
+pub struct ObjectEntryArrayType {
+    len: usize,
+    ty: Box<ObjectEntryType>,
+}
+
 #[derive(Clone)]
 pub struct ObjectEntryStructAttributeMap(Vec<(String, ObjectEntryType)>);
 
@@ -40,6 +46,7 @@ pub enum ObjectEntryType {
     Real(ObjectEntryRealType),
     Enum(ObjectEntryEnumType),
     Struct(ObjectEntryStructType),
+    Array(ObjectEntryArrayType),
 }
 
 impl ObjectEntryType {
@@ -96,7 +103,10 @@ impl ObjectEntryType {
                 name: name.clone(),
                 variants: entries.iter().map(|(variant, _)| variant.clone()).collect(),
             }),
-            config::Type::Array { len : _, ty : _ } => todo!("FUCK YOU"),
+            config::Type::Array { len, ty } => ObjectEntryType::Array(ObjectEntryArrayType {
+                len: *len,
+                ty: Box::new(ObjectEntryType::new(ty)),
+            }),
         }
     }
 }
@@ -128,6 +138,10 @@ impl Serialize for ObjectEntryType {
                 map.serialize_entry("id", "struct")?;
                 map.serialize_entry("info", info)?;
             }
+            ObjectEntryType::Array(info) => {
+                map.serialize_entry("id", "array")?;
+                map.serialize_entry("info", info)?;
+            }
         }
         map.end()
     }
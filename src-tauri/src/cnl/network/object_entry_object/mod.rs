@@ -1,7 +1,7 @@
 use std::{
     ops::Deref,
     sync::{atomic::AtomicU64, Arc, OnceLock},
-    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant},
 };
 
 use canzero_config::config;
@@ -10,7 +10,8 @@ use tokio::sync::{Mutex, OnceCell};
 
 use crate::{
     cnl::{
-        self, frame::Value, network::object_entry_object::database::ObjectEntryDatabase, tx::TxCom,
+        self, errors::Error, frame::Value, network::object_entry_object::database::{HistoryConfig, ObjectEntryDatabase},
+        request::{RequestKey, RequestManager, RetryPolicy},
     },
     notification::{notify_error, notify_info, notify_warning},
 };
@@ -22,11 +23,15 @@ use self::{
     latest::{event::OwnedObjectEntryEvent, ObjectEntryLatestObservable},
 };
 
-mod database;
+pub mod database;
 pub mod history;
 pub mod info;
 pub mod latest;
 
+// get requests don't have side effects and can always be retransmitted.
+const GET_REQUEST_RETRIES: u32 = 2;
+const SET_REQUEST_RETRIES: u32 = 0;
+
 pub struct ObjectEntryObject {
     object_entry_ref: config::ObjectEntryRef,
     store: Arc<Mutex<ObjectEntryDatabase>>,
@@ -37,11 +42,9 @@ pub struct ObjectEntryObject {
     history_observables_id_acc: AtomicU64,
     history_event_name_prefix: String,
     app_handle: tauri::AppHandle,
-    tx_com: Arc<TxCom>,
-    open_set_request: Arc<Mutex<(u64, Option<Value>)>>,
-    open_get_request: Arc<Mutex<u64>>,
-    set_request_timeout: Duration,
-    get_request_timeout: Duration,
+    requests: RequestManager,
+    set_retry_policy: RetryPolicy,
+    get_retry_policy: RetryPolicy,
     plottable: bool,
 }
 
@@ -51,8 +54,9 @@ impl ObjectEntryObject {
         node_config: &config::NodeRef,
         object_entry_config: &config::ObjectEntryRef,
         app_handle: &tauri::AppHandle,
-        tx_com: Arc<TxCom>,
+        requests: &RequestManager,
         timebase: Instant,
+        history: &HistoryConfig,
     ) -> Self {
         let latest_event_name = format!(
             "{}_{}_latest",
@@ -76,7 +80,9 @@ impl ObjectEntryObject {
 
         Self {
             object_entry_ref: object_entry_config.clone(),
-            store: Arc::new(Mutex::new(ObjectEntryDatabase::new())),
+            store: Arc::new(Mutex::new(ObjectEntryDatabase::new(
+                history.for_object_entry(node_config.name(), object_entry_config.name()),
+            ))),
             timebase,
             latest_observable: ObjectEntryLatestObservable::new(
                 &latest_event_name,
@@ -88,11 +94,15 @@ impl ObjectEntryObject {
             history_observables_id_acc: AtomicU64::new(0),
             history_event_name_prefix: history_event_name,
             app_handle: app_handle.clone(),
-            tx_com,
-            open_set_request: Arc::new(Mutex::new((0, None))),
-            open_get_request: Arc::new(Mutex::new(0)),
-            set_request_timeout: Duration::from_millis(1000 + get_req_num_frames * 200),
-            get_request_timeout: Duration::from_millis(1000 + get_req_num_frames * 200),
+            requests: requests.clone(),
+            set_retry_policy: RetryPolicy::new(
+                Duration::from_millis(1000 + get_req_num_frames * 200),
+                SET_REQUEST_RETRIES,
+            ),
+            get_retry_policy: RetryPolicy::new(
+                Duration::from_millis(1000 + get_req_num_frames * 200),
+                GET_REQUEST_RETRIES,
+            ),
             plottable,
         }
     }
@@ -114,145 +124,72 @@ impl ObjectEntryObject {
         self.object_entry_ref.node().id() as u8
     }
 
-    pub async fn request_current_value(&self) {
-        let mut get_req_num = match self.open_get_request.try_lock() {
-            Ok(n) => {
-                if *n % 2 == 0 {
-                    n
-                } else {
-                    notify_warning(
-                        &self.app_handle,
-                        "Ignoring Get Request",
-                        &format!(
-                            "Older get request for {}::{} still in progress",
-                            self.object_entry_ref.node().name(),
-                            self.name()
-                        ),
-                        chrono::Local::now(),
-                    );
-                    return;
-                }
-            }
-            Err(_) => {
-                notify_warning(
-                    &self.app_handle,
-                    "Ignoring Get Request",
-                    &format!(
-                        "Older get request for {}::{} still in progress",
-                        self.object_entry_ref.node().name(),
-                        self.name()
-                    ),
-                    chrono::Local::now(),
-                );
-                return;
-            }
-        };
-        *get_req_num += 1;
-        let my_req_num = *get_req_num;
-        drop(get_req_num);
-        self.tx_com
-            .send_get_req(self.node_id(), self.id() as u16)
-            .await;
-        tokio::spawn({
-            let timeout = self.get_request_timeout.clone();
-            let oe_name = self.name().to_owned();
-            let node_name = self.object_entry_ref.node().name().to_owned();
-            let get_requests = self.open_get_request.clone();
-            let app_handle = self.app_handle.clone();
+    fn request_key(&self) -> RequestKey {
+        self.requests.key(self.node_id(), self.id())
+    }
 
-            async move {
-                tokio::time::sleep(timeout).await;
-                let mut new_req_num = get_requests.lock().await;
-                if *new_req_num == my_req_num {
-                    *new_req_num += 1;
-                    drop(new_req_num);
-                    notify_error(
-                        &app_handle,
-                        "Get Request Timeout",
-                        &format!("Get request for {node_name}::{oe_name} timed out",),
-                        chrono::Local::now(),
-                    );
-                }
-            }
-        });
+    pub async fn get_request(&self) -> cnl::errors::Result<Value> {
+        self.get_request_with(self.get_retry_policy).await
     }
 
-    pub async fn set_request(&self, value: Value) {
-        let start = SystemTime::now();
-        let since_the_epoch = start
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards");
-        println!("Sending set request: {:?}", since_the_epoch);
-        let mut set_req_data = match self.open_set_request.try_lock() {
-            Ok(set_req_data) => {
-                if set_req_data.0 % 2 == 0 {
-                    set_req_data
-                } else {
-                    drop(set_req_data);
-                    notify_warning(
-                        &self.app_handle,
-                        "Ignoring Set Request",
-                        &format!(
-                            "Older set request for {}::{} still in progress",
-                            self.object_entry_ref.node().name(),
-                            self.name()
-                        ),
-                        chrono::Local::now(),
-                    );
-                    return;
-                }
-            }
-            Err(_) => {
-                notify_warning(
+    /// Resolves to the value of the get response, concurrent get requests
+    /// share a single request on the bus.
+    pub async fn get_request_with(&self, policy: RetryPolicy) -> cnl::errors::Result<Value> {
+        let result = self.requests.get(self.request_key(), policy).await;
+        if let Err(err) = &result {
+            self.notify_request_error(err);
+        }
+        result
+    }
+
+    pub async fn set_request(&self, value: Value) -> cnl::errors::Result<()> {
+        self.set_request_with(value, self.set_retry_policy).await
+    }
+
+    /// Sends a set request and resolves once the node accepted or rejected
+    /// the new value, or the request timed out.
+    /// Concurrent set requests are transmitted in order.
+    pub async fn set_request_with(
+        &self,
+        value: Value,
+        policy: RetryPolicy,
+    ) -> cnl::errors::Result<()> {
+        let (bit_value, last_fill) = value.get_as_bin(self.ty());
+        let result = self
+            .requests
+            .set(self.request_key(), bit_value, last_fill, policy)
+            .await;
+        match &result {
+            Ok(_) => {
+                self.push_value(value, &self.now()).await;
+                notify_info(
                     &self.app_handle,
-                    "Ignoring Set Request",
+                    "Set Request Successfull",
                     &format!(
-                        "Older set request for {}::{} still in progress",
+                        "{}::{} was set successfully",
                         self.object_entry_ref.node().name(),
-                        self.name()
+                        self.name(),
                     ),
                     chrono::Local::now(),
                 );
-                return;
             }
-        };
-        set_req_data.0 += 1;
-        let my_req_num = set_req_data.0;
-        let (bit_value, last_fill) = value.get_as_bin(self.ty());
-        set_req_data.1 = Some(value);
-        drop(set_req_data);
-
-        let server_id = self.node_id();
-        let oe_id = self.id();
-
-        let tx = self.tx().clone();
-        tokio::spawn(async move {
-            tx.send_set_request(server_id, oe_id, bit_value, last_fill)
-                .await;
-        });
-
-        tokio::task::spawn({
-            let timeout = self.set_request_timeout.clone();
-            let oe_name = self.name().to_owned();
-            let node_name = self.object_entry_ref.node().name().to_owned();
-            let app_handle = self.app_handle.clone();
-            let set_req_data = self.open_set_request.clone();
+            Err(err) => self.notify_request_error(err),
+        }
+        result
+    }
 
-            async move {
-                tokio::time::sleep(timeout).await;
-                let mut curr_req = set_req_data.lock().await;
-                if curr_req.0 == my_req_num {
-                    *curr_req = (curr_req.0 + 1, None);
-                    drop(curr_req);
-                    notify_error(
-                        &app_handle,
-                        "Set Request Timeout",
-                        &format!("Set request for {node_name}::{oe_name} timed out",),
-                        chrono::Local::now(),
-                    );
-                }
-            }
-        });
+    fn notify_request_error(&self, err: &Error) {
+        notify_error(
+            &self.app_handle,
+            err.reason(),
+            &format!(
+                "{}::{} : {}",
+                self.object_entry_ref.node().name(),
+                self.name(),
+                err.description()
+            ),
+            chrono::Local::now(),
+        );
     }
 
     pub async fn push_value(&self, value: Value, timestamp: &Duration) {
@@ -274,9 +211,7 @@ impl ObjectEntryObject {
     }
 
     pub async fn push_get_response(&self, value: Value, timestamp: &Duration) {
-        let mut get_req_num = self.open_get_request.lock().await;
-        if *get_req_num % 2 == 0 {
-            drop(get_req_num);
+        if !self.requests.complete_get(self.request_key(), &value).await {
             notify_warning(
                 &self.app_handle,
                 "Ignoring Get Response",
@@ -289,8 +224,6 @@ impl ObjectEntryObject {
             );
             return;
         }
-        *get_req_num += 1;
-        drop(get_req_num);
         self.push_value(value, timestamp).await;
         notify_info(
             &self.app_handle,
@@ -319,9 +252,7 @@ impl ObjectEntryObject {
     }
 
     pub async fn push_set_response(&self, result: cnl::errors::Result<()>) {
-        let mut set_req_data = self.open_set_request.lock().await;
-        if set_req_data.0 % 2 == 0 {
-            drop(set_req_data);
+        if !self.requests.complete_set(self.request_key(), result).await {
             notify_info(
                 &self.app_handle,
                 "Ignoring Set Response",
@@ -332,37 +263,6 @@ impl ObjectEntryObject {
                 ),
                 chrono::Local::now(),
             );
-            return;
-        };
-        set_req_data.0 += 1;
-        match result {
-            Ok(_) => {
-                if let Some(value) = set_req_data.1.as_ref() {
-                    self.push_value(value.clone(), &self.now()).await;
-                    *set_req_data = (set_req_data.0, None);
-                };
-                drop(set_req_data);
-                notify_info(
-                    &self.app_handle,
-                    "Set Request Successfull",
-                    &format!(
-                        "{}::{} was set successfully",
-                        self.object_entry_ref.node().name(),
-                        self.name(),
-                    ),
-                    chrono::Local::now(),
-                );
-            }
-            Err(err) => {
-                *set_req_data = (set_req_data.0, None);
-                drop(set_req_data);
-                notify_error(
-                    &self.app_handle,
-                    err.reason(),
-                    err.description(),
-                    chrono::Local::now(),
-                );
-            }
         }
     }
 
@@ -370,10 +270,6 @@ impl ObjectEntryObject {
         &self.object_entry_ref.ty()
     }
 
-    fn tx(&self) -> Arc<TxCom> {
-        self.tx_com.clone()
-    }
-
     pub fn listen_to_latest(&self) {
         self.latest_observable.listen(&self.store);
     }
@@ -395,23 +291,15 @@ impl ObjectEntryObject {
         min_interval: Duration,
     ) -> (String, Vec<OwnedObjectEntryEvent>) {
         let store_lock = self.store.lock().await;
-        let history = store_lock.history();
         let now = std::time::Instant::now().duration_since(self.timebase);
         let breakpoint = now.saturating_sub(frame_size);
-        let mut breakpoint_index = None;
-        for (i, event) in history.iter().enumerate().rev() {
-            if event.timestamp <= breakpoint {
-                breakpoint_index = Some(i);
-                break;
-            }
-        }
-        let start_index = breakpoint_index.unwrap_or(0);
-        let history_of = history[start_index..]
-            .iter()
-            .map(ObjectEntryValue::clone)
+        let history = store_lock.history_since(breakpoint);
+        drop(store_lock);
+        let sent_timestamps = history.iter().map(|value| value.timestamp).collect();
+        let history_of = history
+            .into_iter()
             .map(OwnedObjectEntryEvent::new)
             .collect();
-        drop(store_lock);
 
         // create a custom event_name for the
         let event_name = format!(
@@ -425,7 +313,7 @@ impl ObjectEntryObject {
             min_interval,
             frame_size,
             &self.app_handle,
-            start_index,
+            sent_timestamps,
             self.timebase,
         );
         new_history_observable.start_notify_task(&self.store).await;
@@ -438,11 +326,11 @@ impl ObjectEntryObject {
     }
 
     pub async fn complete_history(&self) -> Vec<OwnedObjectEntryEvent> {
-        let store_lock = self.store.lock().await;
-        let history = store_lock.history();
-        history
-            .iter()
-            .map(ObjectEntryValue::clone)
+        self.store
+            .lock()
+            .await
+            .complete_history()
+            .into_iter()
             .map(OwnedObjectEntryEvent::new)
             .collect()
     }
@@ -482,7 +370,5 @@ impl ObjectEntryObject {
     pub async fn deadlock_watchdog(&self) {
         drop(self.store.lock().await);
         drop(self.history_observables.lock().await);
-        drop(self.open_set_request.lock().await);
-        drop(self.open_get_request.lock().await);
     }
 }
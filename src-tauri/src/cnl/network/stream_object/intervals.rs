@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Jitter of the timestamps, which is tolerated before an interval
+/// violates the configured bounds of a stream.
+const INTERVAL_TOLERANCE: Duration = Duration::from_millis(5);
+
+/// Number of max intervals without a frame, after which a stream is inactive.
+const INACTIVE_INTERVALS: u32 = 10;
+
+// In typescript represented as types/StreamStatus
+#[derive(Copy, Clone, Serialize, PartialEq, Debug)]
+pub enum StreamStatus {
+    Good,
+    TooFast,
+    TooSlow,
+    InActive,
+}
+
+impl StreamStatus {
+    pub fn is_violation(&self) -> bool {
+        matches!(self, StreamStatus::TooFast | StreamStatus::TooSlow)
+    }
+}
+
+/// Observed intervals between the received frames of a stream,
+/// compared to the configured (min, max) interval of the stream.
+#[derive(Clone)]
+pub struct StreamIntervals {
+    bounds: (Duration, Duration),
+    last_timestamp: Option<Duration>,
+    last: Option<Duration>,
+    min: Option<Duration>,
+    max: Option<Duration>,
+    frame_status: StreamStatus,
+}
+
+impl StreamIntervals {
+    pub fn new(bounds: &(Duration, Duration)) -> Self {
+        Self {
+            bounds: *bounds,
+            last_timestamp: None,
+            last: None,
+            min: None,
+            max: None,
+            frame_status: StreamStatus::InActive,
+        }
+    }
+
+    /// Records a frame of the stream and returns the status of the interval to the previous frame.
+    pub fn push(&mut self, timestamp: &Duration) -> StreamStatus {
+        let last_timestamp = self.last_timestamp.replace(*timestamp);
+        let interval = match last_timestamp {
+            // NOTE timestamps only go backwards if a replay seeks, which doesn't say
+            // anything about the interval of the stream.
+            Some(last_timestamp) if last_timestamp <= *timestamp => *timestamp - last_timestamp,
+            _ => {
+                self.frame_status = StreamStatus::Good;
+                return self.frame_status;
+            }
+        };
+        self.last = Some(interval);
+        self.min = Some(self.min.map_or(interval, |min| min.min(interval)));
+        self.max = Some(self.max.map_or(interval, |max| max.max(interval)));
+        self.frame_status = if interval + INTERVAL_TOLERANCE < self.bounds.0 {
+            StreamStatus::TooFast
+        } else if interval > self.bounds.1 + INTERVAL_TOLERANCE {
+            StreamStatus::TooSlow
+        } else {
+            StreamStatus::Good
+        };
+        self.frame_status
+    }
+
+    /// Status of the interval between the two latest frames.
+    pub fn frame_status(&self) -> StreamStatus {
+        self.frame_status
+    }
+    /// Status of the stream at now, which also accounts for the time since the latest frame,
+    /// such that streams, which stopped sending, become TooSlow and eventually InActive.
+    pub fn status(&self, now: &Duration) -> StreamStatus {
+        let Some(last_timestamp) = self.last_timestamp else {
+            return StreamStatus::InActive;
+        };
+        let silence = now.saturating_sub(last_timestamp);
+        let max_interval = self.bounds.1 + INTERVAL_TOLERANCE;
+        if silence > max_interval * INACTIVE_INTERVALS {
+            StreamStatus::InActive
+        } else if silence > max_interval {
+            StreamStatus::TooSlow
+        } else {
+            self.frame_status
+        }
+    }
+    pub fn min_interval(&self) -> &Duration {
+        &self.bounds.0
+    }
+    pub fn max_interval(&self) -> &Duration {
+        &self.bounds.1
+    }
+    /// Interval between the two latest frames.
+    pub fn last(&self) -> Option<&Duration> {
+        self.last.as_ref()
+    }
+    /// Smallest observed interval.
+    pub fn min(&self) -> Option<&Duration> {
+        self.min.as_ref()
+    }
+    /// Largest observed interval.
+    pub fn max(&self) -> Option<&Duration> {
+        self.max.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{StreamIntervals, StreamStatus};
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn intervals() -> StreamIntervals {
+        StreamIntervals::new(&(ms(50), ms(500)))
+    }
+
+    #[test]
+    fn first_frame_activates_the_stream() {
+        let mut intervals = intervals();
+        assert_eq!(intervals.status(&ms(0)), StreamStatus::InActive);
+        assert_eq!(intervals.push(&ms(1000)), StreamStatus::Good);
+        assert_eq!(intervals.last(), None);
+    }
+
+    #[test]
+    fn intervals_within_the_bounds_are_good() {
+        let mut intervals = intervals();
+        for timestamp in [0, 50, 550, 700, 1200] {
+            assert_eq!(intervals.push(&ms(timestamp)), StreamStatus::Good);
+        }
+        assert_eq!(intervals.last(), Some(&ms(500)));
+        assert_eq!(intervals.min(), Some(&ms(50)));
+        assert_eq!(intervals.max(), Some(&ms(500)));
+    }
+
+    #[test]
+    fn jitter_is_tolerated() {
+        let mut intervals = intervals();
+        intervals.push(&ms(0));
+        assert_eq!(intervals.push(&ms(47)), StreamStatus::Good);
+        assert_eq!(intervals.push(&ms(550)), StreamStatus::Good);
+    }
+
+    #[test]
+    fn intervals_outside_the_bounds_are_violations() {
+        let mut intervals = intervals();
+        intervals.push(&ms(0));
+        assert_eq!(intervals.push(&ms(10)), StreamStatus::TooFast);
+        assert!(intervals.frame_status().is_violation());
+        assert_eq!(intervals.push(&ms(1010)), StreamStatus::TooSlow);
+        assert_eq!(intervals.push(&ms(1110)), StreamStatus::Good);
+        assert_eq!(intervals.min(), Some(&ms(10)));
+        assert_eq!(intervals.max(), Some(&ms(1000)));
+    }
+
+    #[test]
+    fn timestamps_going_backwards_are_not_violations() {
+        let mut intervals = intervals();
+        intervals.push(&ms(1000));
+        assert_eq!(intervals.push(&ms(10)), StreamStatus::Good);
+        assert_eq!(intervals.last(), None);
+        assert_eq!(intervals.push(&ms(110)), StreamStatus::Good);
+    }
+
+    #[test]
+    fn streams_without_frames_become_too_slow_and_inactive() {
+        let mut intervals = intervals();
+        intervals.push(&ms(0));
+        intervals.push(&ms(100));
+        assert_eq!(intervals.status(&ms(600)), StreamStatus::Good);
+        assert_eq!(intervals.status(&ms(700)), StreamStatus::TooSlow);
+        assert_eq!(intervals.status(&ms(5100)), StreamStatus::TooSlow);
+        assert_eq!(intervals.status(&ms(5200)), StreamStatus::InActive);
+        // the observed intervals only change with received frames.
+        assert_eq!(intervals.frame_status(), StreamStatus::Good);
+        assert_eq!(intervals.last(), Some(&ms(100)));
+
+        assert_eq!(intervals.push(&ms(5300)), StreamStatus::TooSlow);
+        assert_eq!(intervals.push(&ms(5400)), StreamStatus::Good);
+        assert_eq!(intervals.status(&ms(5400)), StreamStatus::Good);
+    }
+}
@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use canzero_config::config;
+use tokio::sync::Mutex;
+
+use crate::notification::notify_warning;
+
+use self::intervals::{StreamIntervals, StreamStatus};
+
+pub mod intervals;
+
+pub struct StreamObject {
+    stream_ref: config::stream::StreamRef,
+    node_name: String,
+    app_handle: tauri::AppHandle,
+    timebase: Instant,
+    intervals: Mutex<StreamIntervals>,
+}
+
+impl StreamObject {
+    pub fn create(
+        node_config: &config::NodeRef,
+        stream_config: &config::stream::StreamRef,
+        app_handle: &tauri::AppHandle,
+        timebase: Instant,
+    ) -> Self {
+        Self {
+            stream_ref: stream_config.clone(),
+            node_name: node_config.name().to_owned(),
+            app_handle: app_handle.clone(),
+            timebase,
+            intervals: Mutex::new(StreamIntervals::new(stream_config.interval())),
+        }
+    }
+    pub fn name(&self) -> &str {
+        self.stream_ref.name()
+    }
+    pub fn description(&self) -> Option<&str> {
+        self.stream_ref.description()
+    }
+    pub async fn intervals(&self) -> StreamIntervals {
+        self.intervals.lock().await.clone()
+    }
+    /// Status of the stream, which accounts for the time since the latest frame.
+    pub async fn status(&self) -> StreamStatus {
+        let now = self.timebase.elapsed();
+        self.intervals.lock().await.status(&now)
+    }
+
+    /// Tracks the interval to the previous frame of the stream,
+    /// notifies once whenever the stream starts violating its configured interval.
+    pub async fn push_frame(&self, timestamp: &Duration) {
+        let mut intervals = self.intervals.lock().await;
+        let was_violation = intervals.frame_status().is_violation();
+        let status = intervals.push(timestamp);
+        if !status.is_violation() || was_violation {
+            return;
+        }
+        let interval = intervals.last().copied().unwrap_or_default();
+        let min_interval = *intervals.min_interval();
+        let max_interval = *intervals.max_interval();
+        drop(intervals);
+        notify_warning(
+            &self.app_handle,
+            "Stream Interval Violation",
+            &format!(
+                "{}::{} was received after {}ms, expected an interval between {}ms and {}ms",
+                self.node_name,
+                self.name(),
+                interval.as_millis(),
+                min_interval.as_millis(),
+                max_interval.as_millis(),
+            ),
+            chrono::Local::now(),
+        );
+    }
+}
@@ -0,0 +1,560 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use canzero_common::transport::BoxFuture;
+use tokio::sync::{oneshot, Mutex};
+
+use super::{
+    errors::{Error, Result},
+    frame::Value,
+    tx::TxCom,
+};
+
+/// Get and set responses only identify the request by the server,
+/// the object entry and the client, therefor at most one request per key
+/// can be in flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestKey {
+    pub server_id: u8,
+    pub oe_id: u32,
+    pub client_id: u8,
+}
+
+/// Transmission of get and set requests (implemented by TxCom).
+pub trait RequestTx: Send + Sync {
+    fn node_id(&self) -> u8;
+
+    fn send_get_req(&self, server_id: u8, oe_id: u16) -> BoxFuture<'_, ()>;
+
+    fn send_set_req(
+        &self,
+        server_id: u8,
+        oe_id: u32,
+        data: Vec<u32>,
+        last_fill: u8,
+    ) -> BoxFuture<'_, ()>;
+}
+
+impl RequestTx for TxCom {
+    fn node_id(&self) -> u8 {
+        TxCom::node_id(self)
+    }
+
+    fn send_get_req(&self, server_id: u8, oe_id: u16) -> BoxFuture<'_, ()> {
+        Box::pin(TxCom::send_get_req(self, server_id, oe_id))
+    }
+
+    fn send_set_req(
+        &self,
+        server_id: u8,
+        oe_id: u32,
+        data: Vec<u32>,
+        last_fill: u8,
+    ) -> BoxFuture<'_, ()> {
+        Box::pin(self.send_set_request(server_id, oe_id, data, last_fill))
+    }
+}
+
+/// A request is retransmitted up to `retries` times, if no response
+/// was received within `timeout`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub timeout: Duration,
+    pub retries: u32,
+}
+
+impl RetryPolicy {
+    pub fn new(timeout: Duration, retries: u32) -> Self {
+        Self { timeout, retries }
+    }
+}
+
+struct PendingGet {
+    // identifies the timeout of the latest transmission.
+    attempt: u64,
+    retries_left: u32,
+    policy: RetryPolicy,
+    waiters: Vec<oneshot::Sender<Result<Value>>>,
+}
+
+struct PendingSet {
+    attempt: u64,
+    retries_left: u32,
+    policy: RetryPolicy,
+    data: Vec<u32>,
+    last_fill: u8,
+    response: oneshot::Sender<Result<()>>,
+}
+
+impl PendingSet {
+    fn retransmitted(&self) -> bool {
+        self.retries_left < self.policy.retries
+    }
+}
+
+/// Set requests of a key, only one of them is transmitted at a time.
+#[derive(Default)]
+struct SetQueue {
+    // the front is in flight, unless the queue is draining.
+    pending: VecDeque<PendingSet>,
+    /// Set responses don't identify the transmission they answer. After a set request
+    /// timed out or was retransmitted, late responses are dropped for one timeout,
+    /// before the next set request is transmitted.
+    draining: bool,
+}
+
+#[derive(Default)]
+struct Requests {
+    gets: HashMap<RequestKey, PendingGet>,
+    sets: HashMap<RequestKey, SetQueue>,
+    attempt_acc: u64,
+}
+
+impl Requests {
+    fn next_attempt(&mut self) -> u64 {
+        self.attempt_acc += 1;
+        self.attempt_acc
+    }
+}
+
+/// Keeps track of all get and set requests in flight.
+/// Concurrent get requests of the same object entry are coalesced into a single
+/// request, concurrent set requests are queued and transmitted one after another.
+#[derive(Clone)]
+pub struct RequestManager {
+    tx_com: Arc<dyn RequestTx>,
+    requests: Arc<Mutex<Requests>>,
+}
+
+impl RequestManager {
+    pub fn new(tx_com: Arc<dyn RequestTx>) -> Self {
+        Self {
+            tx_com,
+            requests: Arc::new(Mutex::new(Requests::default())),
+        }
+    }
+
+    pub fn key(&self, server_id: u8, oe_id: u32) -> RequestKey {
+        RequestKey {
+            server_id,
+            oe_id,
+            client_id: self.tx_com.node_id(),
+        }
+    }
+
+    /// Resolves to the value of the get response, or Error::GetRequestTimeout
+    /// if all retries timed out.
+    pub async fn get(&self, key: RequestKey, policy: RetryPolicy) -> Result<Value> {
+        let (tx, rx) = oneshot::channel();
+        let mut requests = self.requests.lock().await;
+        let attempt = requests.next_attempt();
+        match requests.gets.get_mut(&key) {
+            Some(pending) => {
+                pending.waiters.push(tx);
+                drop(requests);
+            }
+            None => {
+                requests.gets.insert(
+                    key,
+                    PendingGet {
+                        attempt,
+                        retries_left: policy.retries,
+                        policy,
+                        waiters: vec![tx],
+                    },
+                );
+                drop(requests);
+                self.transmit_get(key, attempt);
+            }
+        }
+        rx.await.unwrap_or(Err(Error::GetRequestTimeout))
+    }
+
+    /// Resolves once the node accepted or rejected the set request,
+    /// or all retries timed out.
+    pub async fn set(
+        &self,
+        key: RequestKey,
+        data: Vec<u32>,
+        last_fill: u8,
+        policy: RetryPolicy,
+    ) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        let mut requests = self.requests.lock().await;
+        let attempt = requests.next_attempt();
+        let queue = requests.sets.entry(key).or_default();
+        let idle = queue.pending.is_empty() && !queue.draining;
+        queue.pending.push_back(PendingSet {
+            attempt,
+            retries_left: policy.retries,
+            policy,
+            data,
+            last_fill,
+            response: tx,
+        });
+        drop(requests);
+        if idle {
+            self.transmit_set(key, attempt);
+        }
+        rx.await.unwrap_or(Err(Error::SetRequestTimeout))
+    }
+
+    /// Returns false if no get request was pending for the key.
+    pub async fn complete_get(&self, key: RequestKey, value: &Value) -> bool {
+        let Some(pending) = self.requests.lock().await.gets.remove(&key) else {
+            return false;
+        };
+        for waiter in pending.waiters {
+            let _ = waiter.send(Ok(value.clone()));
+        }
+        true
+    }
+
+    /// Returns false if no set request was in flight for the key,
+    /// e.g. the response came in after the request timed out.
+    pub async fn complete_set(&self, key: RequestKey, result: Result<()>) -> bool {
+        let mut requests = self.requests.lock().await;
+        let Some(queue) = requests.sets.get_mut(&key) else {
+            return false;
+        };
+        if queue.draining {
+            return false;
+        }
+        let Some(pending) = queue.pending.pop_front() else {
+            return false;
+        };
+        queue.draining = true;
+        drop(requests);
+        // the response might answer the first transmission, the others can still be answered.
+        let drain = pending.retransmitted().then_some(pending.policy.timeout);
+        let _ = pending.response.send(result);
+        self.transmit_next_set(key, drain);
+        true
+    }
+
+    fn transmit_get(&self, key: RequestKey, attempt: u64) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let Some(timeout) = this
+                .requests
+                .lock()
+                .await
+                .gets
+                .get(&key)
+                .filter(|pending| pending.attempt == attempt)
+                .map(|pending| pending.policy.timeout)
+            else {
+                return;
+            };
+            this.tx_com
+                .send_get_req(key.server_id, key.oe_id as u16)
+                .await;
+            tokio::time::sleep(timeout).await;
+
+            let mut requests = this.requests.lock().await;
+            let retry = requests.next_attempt();
+            let Some(pending) = requests.gets.get_mut(&key) else {
+                return;
+            };
+            if pending.attempt != attempt {
+                return;
+            }
+            if pending.retries_left > 0 {
+                pending.retries_left -= 1;
+                pending.attempt = retry;
+                drop(requests);
+                this.transmit_get(key, retry);
+            } else if let Some(pending) = requests.gets.remove(&key) {
+                drop(requests);
+                for waiter in pending.waiters {
+                    let _ = waiter.send(Err(Error::GetRequestTimeout));
+                }
+            }
+        });
+    }
+
+    fn transmit_set(&self, key: RequestKey, attempt: u64) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let Some((data, last_fill, timeout)) = this
+                .requests
+                .lock()
+                .await
+                .sets
+                .get(&key)
+                .and_then(|queue| queue.pending.front())
+                .filter(|pending| pending.attempt == attempt)
+                .map(|pending| (pending.data.clone(), pending.last_fill, pending.policy.timeout))
+            else {
+                return;
+            };
+            this.tx_com
+                .send_set_req(key.server_id, key.oe_id, data, last_fill)
+                .await;
+            tokio::time::sleep(timeout).await;
+
+            let mut requests = this.requests.lock().await;
+            let retry = requests.next_attempt();
+            let Some(queue) = requests.sets.get_mut(&key) else {
+                return;
+            };
+            if queue.draining {
+                return;
+            }
+            let Some(pending) = queue
+                .pending
+                .front_mut()
+                .filter(|pending| pending.attempt == attempt)
+            else {
+                return;
+            };
+            if pending.retries_left > 0 {
+                pending.retries_left -= 1;
+                pending.attempt = retry;
+                drop(requests);
+                this.transmit_set(key, retry);
+                return;
+            }
+            let timed_out = queue.pending.pop_front().expect("front was checked above");
+            queue.draining = true;
+            drop(requests);
+            let _ = timed_out.response.send(Err(Error::SetRequestTimeout));
+            this.transmit_next_set(key, Some(timeout));
+        });
+    }
+
+    /// Transmits the next queued set request of the key, once the responses
+    /// to the previous request were drained for drain.
+    fn transmit_next_set(&self, key: RequestKey, drain: Option<Duration>) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            if let Some(drain) = drain {
+                tokio::time::sleep(drain).await;
+            }
+            let mut requests = this.requests.lock().await;
+            let Some(queue) = requests.sets.get_mut(&key) else {
+                return;
+            };
+            queue.draining = false;
+            match queue.pending.front().map(|next| next.attempt) {
+                Some(next) => {
+                    drop(requests);
+                    this.transmit_set(key, next);
+                }
+                None => {
+                    requests.sets.remove(&key);
+                }
+            }
+        });
+    }
+
+    pub async fn deadlock_watchdog(&self) {
+        drop(self.requests.lock().await);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use canzero_common::transport::BoxFuture;
+    use tokio::task::JoinHandle;
+
+    use super::{RequestKey, RequestManager, RequestTx, RetryPolicy};
+    use crate::cnl::{
+        errors::{Error, Result, SetRespErno},
+        frame::Value,
+    };
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Sent {
+        Get(u16),
+        Set(Vec<u32>),
+    }
+
+    /// Records the transmitted requests instead of sending them.
+    #[derive(Default)]
+    struct RecordingTx {
+        sent: std::sync::Mutex<Vec<Sent>>,
+    }
+
+    impl RecordingTx {
+        fn sent(&self) -> Vec<Sent> {
+            self.sent.lock().unwrap().clone()
+        }
+    }
+
+    impl RequestTx for RecordingTx {
+        fn node_id(&self) -> u8 {
+            1
+        }
+
+        fn send_get_req(&self, _server_id: u8, oe_id: u16) -> BoxFuture<'_, ()> {
+            self.sent.lock().unwrap().push(Sent::Get(oe_id));
+            Box::pin(async {})
+        }
+
+        fn send_set_req(
+            &self,
+            _server_id: u8,
+            _oe_id: u32,
+            data: Vec<u32>,
+            _last_fill: u8,
+        ) -> BoxFuture<'_, ()> {
+            self.sent.lock().unwrap().push(Sent::Set(data));
+            Box::pin(async {})
+        }
+    }
+
+    fn manager() -> (RequestManager, Arc<RecordingTx>, RequestKey) {
+        let tx = Arc::new(RecordingTx::default());
+        let manager = RequestManager::new(tx.clone());
+        let key = manager.key(2, 7);
+        (manager, tx, key)
+    }
+
+    fn get(manager: &RequestManager, key: RequestKey, retries: u32) -> JoinHandle<Result<Value>> {
+        let manager = manager.clone();
+        tokio::spawn(async move { manager.get(key, RetryPolicy::new(TIMEOUT, retries)).await })
+    }
+
+    fn set(
+        manager: &RequestManager,
+        key: RequestKey,
+        value: u32,
+        retries: u32,
+    ) -> JoinHandle<Result<()>> {
+        let manager = manager.clone();
+        tokio::spawn(async move {
+            manager
+                .set(key, vec![value], 0, RetryPolicy::new(TIMEOUT, retries))
+                .await
+        })
+    }
+
+    /// Lets the spawned tasks run, without reaching a timeout.
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_gets_are_coalesced() {
+        let (manager, tx, key) = manager();
+        let first = get(&manager, key, 0);
+        let second = get(&manager, key, 0);
+        settle().await;
+        assert_eq!(tx.sent(), vec![Sent::Get(7)]);
+
+        assert!(manager.complete_get(key, &Value::UnsignedValue(42)).await);
+        for waiter in [first, second] {
+            assert!(matches!(
+                waiter.await.unwrap(),
+                Ok(Value::UnsignedValue(42))
+            ));
+        }
+        // no get request is pending anymore.
+        assert!(!manager.complete_get(key, &Value::UnsignedValue(42)).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gets_are_retried_until_they_time_out() {
+        let (manager, tx, key) = manager();
+        let result = get(&manager, key, 2).await.unwrap();
+        assert!(matches!(result, Err(Error::GetRequestTimeout)));
+        assert_eq!(tx.sent(), vec![Sent::Get(7); 3]);
+        // late responses are reported as unsolicited.
+        assert!(!manager.complete_get(key, &Value::UnsignedValue(42)).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retried_get_completes_with_the_response() {
+        let (manager, tx, key) = manager();
+        let waiter = get(&manager, key, 1);
+        tokio::time::sleep(TIMEOUT + Duration::from_millis(10)).await;
+        assert_eq!(tx.sent(), vec![Sent::Get(7); 2]);
+        assert!(manager.complete_get(key, &Value::SignedValue(-1)).await);
+        assert!(matches!(waiter.await.unwrap(), Ok(Value::SignedValue(-1))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sets_are_queued() {
+        let (manager, tx, key) = manager();
+        let first = set(&manager, key, 1, 0);
+        settle().await;
+        let second = set(&manager, key, 2, 0);
+        settle().await;
+        assert_eq!(tx.sent(), vec![Sent::Set(vec![1])]);
+
+        assert!(
+            manager
+                .complete_set(key, Err(Error::SetRequestRejected(SetRespErno::Error)))
+                .await
+        );
+        assert!(matches!(
+            first.await.unwrap(),
+            Err(Error::SetRequestRejected(SetRespErno::Error))
+        ));
+        settle().await;
+        assert_eq!(tx.sent(), vec![Sent::Set(vec![1]), Sent::Set(vec![2])]);
+
+        assert!(manager.complete_set(key, Ok(())).await);
+        assert!(second.await.unwrap().is_ok());
+        assert!(!manager.complete_set(key, Ok(())).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sets_are_retried_until_they_time_out() {
+        let (manager, tx, key) = manager();
+        let result = set(&manager, key, 1, 1).await.unwrap();
+        assert!(matches!(result, Err(Error::SetRequestTimeout)));
+        assert_eq!(tx.sent(), vec![Sent::Set(vec![1]); 2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_set_response_does_not_complete_the_next_set() {
+        let (manager, tx, key) = manager();
+        let first = set(&manager, key, 1, 0);
+        settle().await;
+        let second = set(&manager, key, 2, 0);
+        assert!(matches!(
+            first.await.unwrap(),
+            Err(Error::SetRequestTimeout)
+        ));
+
+        // the response to the first set request.
+        assert!(!manager.complete_set(key, Ok(())).await);
+        assert_eq!(tx.sent(), vec![Sent::Set(vec![1])]);
+
+        // the second set request is transmitted after draining.
+        tokio::time::sleep(TIMEOUT + Duration::from_millis(10)).await;
+        assert_eq!(tx.sent(), vec![Sent::Set(vec![1]), Sent::Set(vec![2])]);
+        assert!(manager.complete_set(key, Ok(())).await);
+        assert!(second.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn responses_to_retransmitted_sets_are_drained() {
+        let (manager, tx, key) = manager();
+        let first = set(&manager, key, 1, 1);
+        settle().await;
+        let second = set(&manager, key, 2, 0);
+        tokio::time::sleep(TIMEOUT + Duration::from_millis(10)).await;
+        assert_eq!(tx.sent(), vec![Sent::Set(vec![1]); 2]);
+
+        // both transmissions are answered.
+        assert!(manager.complete_set(key, Ok(())).await);
+        assert!(first.await.unwrap().is_ok());
+        assert!(!manager.complete_set(key, Ok(())).await);
+        assert_eq!(tx.sent().len(), 2);
+
+        tokio::time::sleep(TIMEOUT + Duration::from_millis(10)).await;
+        assert_eq!(tx.sent().last(), Some(&Sent::Set(vec![2])));
+        assert!(manager.complete_set(key, Ok(())).await);
+        assert!(second.await.unwrap().is_ok());
+    }
+}
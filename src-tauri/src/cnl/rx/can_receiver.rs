@@ -13,7 +13,7 @@ use crate::{
     notification::notify_error,
 };
 
-use canzero_common::{TCanError, TCanFrame};
+use canzero_common::{CanError, TCanError, TCanFrame};
 
 use crate::cnl::errors::Result;
 
@@ -101,6 +101,16 @@ impl CanReceiver {
                     }
                 };
 
+                if let Err(error) = &frame {
+                    if error.contains(CanError::BUS_OFF) {
+                        connection_object.set_status(ConnectionStatus::BusOff { bus_id });
+                    } else if error.contains(CanError::RESTARTED)
+                        && connection_object.get_status() == (ConnectionStatus::BusOff { bus_id })
+                    {
+                        connection_object.set_status(ConnectionStatus::NetworkConnected);
+                    }
+                }
+
                 let receiver_data = receiver_data.clone();
                 let bus_name = bus_name.to_owned();
                 pcall(frame, receiver_data, bus_name, bus_id).await;
@@ -4,6 +4,8 @@ use canzero_config::config::{message, MessageId, MessageRef};
 
 use crate::cnl::{
     handler::{
+        command_req_frame_handler::CommandReqFrameHandler,
+        command_resp_frame_handler::CommandRespFrameHandler,
         get_req_frame_handler::GetReqFrameHandler, get_resp_frame_handler::GetRespFrameHandler,
         heartbeat_frame_handler::HeartbeatFrameHandler, set_req_frame_handler::SetReqFrameHandler,
         set_resp_frame_handler::SetRespFrameHandler, stream_frame_handler::StreamFrameHandler,
@@ -28,32 +30,65 @@ impl HandlerLookup {
                     MessageId::ExtendedId(id) => *id | 0x80000000, // ide flag
                 };
                 match msg.usage() {
-                    message::MessageUsage::Stream(stream) => Some((
-                        key,
-                        MessageHandler::StreamFrameHandler(StreamFrameHandler::create(
-                            stream,
-                            &stream
-                                .mapping()
+                    message::MessageUsage::Stream(stream) => {
+                        let object_entries: Vec<_> = stream
+                            .mapping()
+                            .iter()
+                            .map(|opt| opt.as_ref().expect("should be set for tx stream"))
+                            .collect();
+                        let find_node_object = |node_name: &str| {
+                            network_object
+                                .nodes()
                                 .iter()
-                                .map(|opt| opt.as_ref().expect("should be set for tx stream"))
-                                .map(|oe| {
-                                    let node_object = network_object
-                                        .nodes()
-                                        .iter()
-                                        .find(|no| no.name() == oe.node().name())
-                                        .expect("invalid node");
-                                    node_object
-                                        .object_entries()
-                                        .iter()
-                                        .find(|oeo| oeo.name() == oe.name())
-                                        .expect("oe not found")
-                                        .clone()
-                                })
-                                .collect(),
+                                .find(|no| no.name() == node_name)
+                                .expect("invalid node")
+                        };
+                        // NOTE all object entries of a tx stream belong to the node sending it.
+                        let stream_object = find_node_object(
+                            object_entries
+                                .first()
+                                .expect("tx streams are expected to map at least one object entry")
+                                .node()
+                                .name(),
+                        )
+                        .streams()
+                        .iter()
+                        .find(|so| so.name() == stream.name())
+                        .expect("stream not found")
+                        .clone();
+                        Some((
+                            key,
+                            MessageHandler::StreamFrameHandler(StreamFrameHandler::create(
+                                stream,
+                                &object_entries
+                                    .iter()
+                                    .map(|oe| {
+                                        find_node_object(oe.node().name())
+                                            .object_entries()
+                                            .iter()
+                                            .find(|oeo| oeo.name() == oe.name())
+                                            .expect("oe not found")
+                                            .clone()
+                                    })
+                                    .collect(),
+                                stream_object,
+                            )),
+                        ))
+                    }
+                    message::MessageUsage::CommandReq(_) => Some((
+                        key,
+                        MessageHandler::CommandReqFrameHandler(CommandReqFrameHandler::create(
+                            msg,
+                        )),
+                    )),
+                    message::MessageUsage::CommandResp(command) => Some((
+                        key,
+                        MessageHandler::CommandRespFrameHandler(CommandRespFrameHandler::create(
+                            msg,
+                            command,
+                            network_object,
                         )),
                     )),
-                    message::MessageUsage::CommandReq(_) => None, //TODO
-                    message::MessageUsage::CommandResp(_) => None, //TODO
                     message::MessageUsage::GetResp => Some((
                         key,
                         MessageHandler::GetRespFrameHandler(GetRespFrameHandler::create(
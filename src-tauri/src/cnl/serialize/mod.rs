@@ -0,0 +1,139 @@
+use canzero_config::config::{MessageRef, SignalRef, SignalType, Type, TypeSignalEncoding};
+
+use super::frame::{Attribute, Value};
+
+/// Inverse of the FrameDeserializer, packs attribute values
+/// into the data field of a can frame based on the message encoding.
+pub struct FrameSerializer {
+    message_config: MessageRef,
+}
+
+impl FrameSerializer {
+    pub fn new(message_config: &MessageRef) -> Self {
+        Self {
+            message_config: message_config.clone(),
+        }
+    }
+
+    /// Data of a message with at most 8 bytes, see serialize_payload.
+    pub fn serialize(&self, attributes: &[Attribute]) -> u64 {
+        let payload = self.serialize_payload(attributes);
+        let mut first = [0u8; 8];
+        let n = payload.len().min(8);
+        first[..n].copy_from_slice(&payload[..n]);
+        u64::from_le_bytes(first)
+    }
+
+    /// Payload of dlc bytes (the length in bytes of the message), which may exceed
+    /// 8 bytes for messages on CAN FD buses.
+    /// NOTE attributes are matched by name, missing attributes
+    /// are left as zero.
+    pub fn serialize_payload(&self, attributes: &[Attribute]) -> Vec<u8> {
+        let mut data = vec![0u8; self.message_config.dlc() as usize];
+        match self.message_config.encoding() {
+            Some(message_encoding) => {
+                for type_encoding in message_encoding.attributes() {
+                    if let Some(attrib) = attributes
+                        .iter()
+                        .find(|attrib| attrib.name() == type_encoding.name())
+                    {
+                        serialize_encoded(&mut data, type_encoding, attrib.value());
+                    }
+                }
+            }
+            None => {
+                for signal in self.message_config.signals() {
+                    if let Some(attrib) = attributes
+                        .iter()
+                        .find(|attrib| attrib.name() == signal.name())
+                    {
+                        serialize_signal(&mut data, signal, attrib.value());
+                    }
+                }
+            }
+        }
+        data
+    }
+}
+
+fn serialize_encoded(data: &mut [u8], encoding: &TypeSignalEncoding, value: &Value) {
+    match encoding {
+        TypeSignalEncoding::Primitive(prim) => match (prim.ty() as &Type, value) {
+            (Type::Primitive(_), _) => serialize_signal(data, prim.signal(), value),
+            (
+                Type::Enum {
+                    name: _,
+                    description: _,
+                    size: _,
+                    entries,
+                    visibility: _,
+                },
+                Value::EnumValue(variant_name),
+            ) => {
+                let Some((_, variant_value)) =
+                    entries.iter().find(|(name, _)| name == variant_name)
+                else {
+                    panic!("enum variant name not known!");
+                };
+                serialize_signal(data, prim.signal(), &Value::UnsignedValue(*variant_value))
+            }
+            (Type::Array { len: _, ty: _ }, _) => {
+                panic!("DETECTED INVALID CONFIG : primitive encoding associated with a array type!")
+            }
+            _ => panic!("Value and config::Type did not match!"),
+        },
+        // NOTE: arrays are encoded as composites with one attribute per element.
+        TypeSignalEncoding::Composite(comp) if matches!(comp.ty() as &Type, Type::Array { .. }) => {
+            let Value::ArrayValue(elements) = value else {
+                panic!("Value and config::Type did not match!");
+            };
+            for (element_encoding, element) in comp.attributes().iter().zip(elements) {
+                serialize_encoded(data, element_encoding, element);
+            }
+        }
+        TypeSignalEncoding::Composite(comp) => {
+            for attribute_encoding in comp.attributes() {
+                if let Some(attrib_value) = value.attribute(attribute_encoding.name()) {
+                    serialize_encoded(data, attribute_encoding, attrib_value);
+                }
+            }
+        }
+    }
+}
+
+/// Writes the value at the bit offset of the signal, the first byte holds the least significant bits.
+fn serialize_signal(data: &mut [u8], signal: &SignalRef, value: &Value) {
+    let bit_offset = signal.byte_offset() as u32; // NOTE stupid naming =^)
+    let bit_mask = u64::MAX >> (u64::BITS - signal.size() as u32);
+    let unsigned_bits = match (signal.ty(), value) {
+        (SignalType::UnsignedInt { size: _ }, Value::UnsignedValue(val)) => *val,
+        (SignalType::SignedInt { size: _ }, Value::SignedValue(val)) => *val as u64,
+        (
+            SignalType::Decimal {
+                size: _,
+                offset,
+                scale,
+            },
+            Value::RealValue(val),
+        ) => {
+            let base_float = ((val - offset) / scale).round();
+            if base_float <= 0.0 {
+                0
+            } else {
+                std::cmp::min(base_float as u64, bit_mask)
+            }
+        }
+        _ => panic!("Value and signal type did not match!"),
+    };
+    let bits = unsigned_bits & bit_mask;
+    for i in 0..signal.size() as u32 {
+        if (bits >> i) & 0x1 == 0 {
+            continue;
+        }
+        let bit = (bit_offset + i) as usize;
+        let Some(byte) = data.get_mut(bit / 8) else {
+            panic!("DETECTED INVALID CONFIG : signal exceeds the length of the message");
+        };
+        *byte |= 1 << (bit % 8);
+    }
+}
@@ -185,7 +185,7 @@ impl TraceDatabase {
                             format!("0x{:X}", can_frame.get_id())
                         }
                     }
-                    TraceFrame::Error(err) => err.name().to_owned(),
+                    TraceFrame::Error(err, _) => err.name().to_owned(),
                     TraceFrame::Frame(frame) => match frame.id() {
                         config::MessageId::StandardId(id) => format!("0x{id:X}"),
                         config::MessageId::ExtendedId(id) => format!("0x{id:X}x"),
@@ -3,7 +3,7 @@ use serde::{ser::SerializeMap, Serialize};
 
 use crate::cnl::frame::{Attribute, Frame, Value};
 
-use canzero_common::CanFrame;
+use canzero_common::{CanError, CanFrame};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CanErrorType {
@@ -12,9 +12,45 @@ pub enum CanErrorType {
     FormError,
     AckError,
     CrcError,
+    ErrorWarning,
+    ErrorPassive,
+    BusOff,
+    Restarted,
 }
 
 impl CanErrorType {
+    pub const ALL: [CanErrorType; 9] = [
+        CanErrorType::BitError,
+        CanErrorType::BitStuffing,
+        CanErrorType::FormError,
+        CanErrorType::AckError,
+        CanErrorType::CrcError,
+        CanErrorType::ErrorWarning,
+        CanErrorType::ErrorPassive,
+        CanErrorType::BusOff,
+        CanErrorType::Restarted,
+    ];
+
+    /// flag of the CanError, which reports this error.
+    pub fn flag(&self) -> u64 {
+        match &self {
+            CanErrorType::BitError => CanError::BIT_ERROR,
+            CanErrorType::BitStuffing => CanError::STUFF_ERROR,
+            CanErrorType::FormError => CanError::FORM_ERROR,
+            CanErrorType::AckError => CanError::ACK_ERROR,
+            CanErrorType::CrcError => CanError::CRC_ERROR,
+            CanErrorType::ErrorWarning => CanError::ERROR_WARNING,
+            CanErrorType::ErrorPassive => CanError::ERROR_PASSIVE,
+            CanErrorType::BusOff => CanError::BUS_OFF,
+            CanErrorType::Restarted => CanError::RESTARTED,
+        }
+    }
+
+    /// id under which the error is listed in the trace.
+    pub fn id(&self) -> u32 {
+        0x20000000 | self.flag() as u32
+    }
+
     pub fn name(&self) -> &'static str {
         match &self {
             CanErrorType::BitError => "Bit Error",
@@ -22,6 +58,10 @@ impl CanErrorType {
             CanErrorType::FormError => "Form Error",
             CanErrorType::AckError => "ACK Error",
             CanErrorType::CrcError => "CRC Error",
+            CanErrorType::ErrorWarning => "Error Warning",
+            CanErrorType::ErrorPassive => "Error Passive",
+            CanErrorType::BusOff => "Bus Off",
+            CanErrorType::Restarted => "Bus Restarted",
         }
     }
     pub fn description(&self) -> &'static str {
@@ -31,6 +71,10 @@ impl CanErrorType {
                 CanErrorType::FormError => "Node detected a bit of an invalid logic level in the SOF/EOF field or ACK/CRC delimiter.",
                 CanErrorType::AckError => "Node transmits a CAN message, but the ACK slot is not made dominant by receiver(s).",
                 CanErrorType::CrcError => "Node calculated a CAN message CRC that differs from the transmitted CRC field value.",
+                CanErrorType::ErrorWarning => "An error counter of the controller reached the warning level (96).",
+                CanErrorType::ErrorPassive => "An error counter of the controller exceeded 127, the controller only sends passive error flags.",
+                CanErrorType::BusOff => "The transmit error counter of the controller exceeded 255, the controller disconnected from the bus.",
+                CanErrorType::Restarted => "The controller recovered from bus off.",
             }
     }
 }
@@ -38,7 +82,8 @@ impl CanErrorType {
 #[derive(Clone)]
 pub enum TraceFrame {
     Undefined(CanFrame),
-    Error(CanErrorType),
+    /// with the tx and rx error counters of the controller, if reported.
+    Error(CanErrorType, Option<(u8, u8)>),
     Frame(Frame),
 }
 
@@ -46,13 +91,7 @@ impl TraceFrame {
     pub fn id(&self) -> u32 {
         match &self {
             TraceFrame::Undefined(can_frame) => can_frame.key(),
-            TraceFrame::Error(err) => match err {
-                CanErrorType::BitError => 0x20000001,
-                CanErrorType::BitStuffing => 0x20000002,
-                CanErrorType::FormError => 0x20000004,
-                CanErrorType::AckError => 0x20000008,
-                CanErrorType::CrcError => 0x20000016,
-            },
+            TraceFrame::Error(err, _) => err.id(),
             TraceFrame::Frame(frame) => match frame.id() {
                 MessageId::StandardId(id) => *id,
                 MessageId::ExtendedId(id) => *id,
@@ -62,7 +101,7 @@ impl TraceFrame {
     pub fn ide(&self) -> bool {
         match &self {
             TraceFrame::Undefined(can_frame) => can_frame.get_ide_flag(),
-            TraceFrame::Error(_) => false,
+            TraceFrame::Error(..) => false,
             TraceFrame::Frame(frame) => match frame.id() {
                 MessageId::StandardId(_) => false,
                 MessageId::ExtendedId(_) => true,
@@ -72,7 +111,7 @@ impl TraceFrame {
     pub fn dlc(&self) -> u8 {
         match &self {
             TraceFrame::Undefined(can_frame) => can_frame.get_dlc(),
-            TraceFrame::Error(_) => 0,
+            TraceFrame::Error(..) => 0,
             TraceFrame::Frame(frame) => frame.dlc(),
         }
     }
@@ -85,7 +124,7 @@ impl TraceFrame {
                     "Remote-Frame"
                 }
             }
-            TraceFrame::Error(err) => err.name(),
+            TraceFrame::Error(err, _) => err.name(),
             TraceFrame::Frame(frame) => frame.name(),
         }
     }
@@ -93,7 +132,7 @@ impl TraceFrame {
     pub fn key(&self) -> TraceFrameKey {
         match &self {
             TraceFrame::Undefined(can_frame) => TraceFrameKey::Undefined(can_frame.key()),
-            TraceFrame::Error(err) => TraceFrameKey::Error(err.clone()),
+            TraceFrame::Error(err, _) => TraceFrameKey::Error(*err),
             TraceFrame::Frame(frame) => TraceFrameKey::Frame(frame.id().clone()),
         }
     }
@@ -101,13 +140,7 @@ impl TraceFrame {
     pub fn key_u32(&self) -> u32 {
         match &self {
             TraceFrame::Undefined(can_frame) => can_frame.key(),
-            TraceFrame::Error(err) => match err {
-                CanErrorType::BitError => 0x20000001,
-                CanErrorType::BitStuffing => 0x20000002,
-                CanErrorType::FormError => 0x20000004,
-                CanErrorType::AckError => 0x20000008,
-                CanErrorType::CrcError => 0x20000016,
-            },
+            TraceFrame::Error(err, _) => err.id(),
             TraceFrame::Frame(frame) => match frame.id() {
                 MessageId::StandardId(id) => *id,
                 MessageId::ExtendedId(id) => *id | 0x80000000,
@@ -137,9 +170,15 @@ impl Serialize for TraceFrame {
                 map.serialize_entry("ty", "undefined")?;
                 map.serialize_entry("detail", &can_frame.get_data_u64())?;
             }
-            TraceFrame::Error(err) => {
+            TraceFrame::Error(err, counters) => {
                 map.serialize_entry("ty", "error")?;
-                map.serialize_entry("detail", err.description())?;
+                match counters {
+                    Some((tx_errors, rx_errors)) => map.serialize_entry(
+                        "detail",
+                        &format!("{} (TEC = {tx_errors}, REC = {rx_errors})", err.description()),
+                    )?,
+                    None => map.serialize_entry("detail", err.description())?,
+                }
             }
             TraceFrame::Frame(frame) => {
                 map.serialize_entry("ty", "normal")?;
@@ -166,6 +205,18 @@ impl Serialize for TraceFrame {
                                 );
                             }
                         }
+                        Value::ArrayValue(elements) => {
+                            for (i, element) in elements.iter().enumerate() {
+                                build_flat_attributes(
+                                    attributes,
+                                    &Attribute::new(
+                                        &format!("{}[{i}]", current.name()),
+                                        element.clone(),
+                                    ),
+                                    prefix,
+                                );
+                            }
+                        }
                     }
                 }
                 for attrib in frame.attributes() {
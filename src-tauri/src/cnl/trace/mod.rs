@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use self::{
     database::{SortCriteria, SortOrder, TraceDatabase},
@@ -8,7 +14,13 @@ use self::{
 
 use super::frame::TFrame;
 
-use canzero_common::{TCanFrame, TCanError};
+use canzero_common::{
+    trace_format::{TraceBus, TraceFormat, TraceWriter},
+    CanFrame, NetworkFrame, TCanError, TCanFrame, TNetworkFrame,
+};
+use canzero_config::config;
+use color_print::cprintln;
+use tokio::sync::Mutex;
 
 pub mod database;
 pub mod event;
@@ -20,14 +32,70 @@ const TRACE_EVENT_NAME: &'static str = "trace";
 pub struct TraceObject {
     database: Arc<TraceDatabase>,
     observable: TraceObservable,
+    buses: Vec<TraceBus>,
+    // start of run in us since the unix epoch
+    timebase_us: u64,
+    export: Mutex<Option<TraceWriter<BufWriter<File>>>>,
 }
 
 impl TraceObject {
-    pub fn create(app_handle: &tauri::AppHandle) -> Self {
+    pub fn create(
+        app_handle: &tauri::AppHandle,
+        network_config: &config::NetworkRef,
+        timebase: Instant,
+    ) -> Self {
         let database = Arc::new(TraceDatabase::new());
+        let timebase_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .saturating_sub(timebase.elapsed())
+            .as_micros() as u64;
         Self {
             observable: TraceObservable::new(app_handle, TRACE_EVENT_NAME, &database),
             database,
+            buses: network_config
+                .buses()
+                .iter()
+                .map(|bus| TraceBus::new(bus.id(), bus.name()))
+                .collect(),
+            timebase_us,
+            export: Mutex::new(None),
+        }
+    }
+
+    /// Writes all frames that arrive from now on to path,
+    /// until stop_export is called. Replaces a running export.
+    pub async fn start_export(&self, path: &Path, format: TraceFormat) -> std::io::Result<()> {
+        let date = chrono::Local::now().format("%a %b %d %I:%M:%S%.3f %P %Y");
+        let writer = TraceWriter::create(
+            BufWriter::new(File::create(path)?),
+            format,
+            self.buses.clone(),
+            self.timebase_us,
+            &date.to_string(),
+        )?;
+        if let Some(previous) = self.export.lock().await.replace(writer) {
+            previous.finish()?;
+        }
+        Ok(())
+    }
+
+    pub async fn stop_export(&self) -> std::io::Result<()> {
+        match self.export.lock().await.take() {
+            Some(writer) => writer.finish().map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    async fn export_frame(&self, timestamp: std::time::Duration, can_frame: CanFrame, bus_id: u32) {
+        let mut export = self.export.lock().await;
+        let Some(writer) = export.as_mut() else {
+            return;
+        };
+        let frame = TNetworkFrame::new(timestamp, NetworkFrame { bus_id, can_frame });
+        if let Err(err) = writer.write_frame(&frame) {
+            cprintln!("<red>Failed to export trace, stopping export</red> : {err:?}");
+            *export = None;
         }
     }
 
@@ -38,6 +106,7 @@ impl TraceObject {
         bus_id: u32,
     ) {
         let (arrive, can_frame) = undefined_frame.destruct();
+        self.export_frame(arrive, can_frame.clone(), bus_id).await;
         self.database
             .update(TraceFrame::Undefined(can_frame), arrive, bus_name, bus_id)
             .await;
@@ -46,29 +115,33 @@ impl TraceObject {
     pub async fn push_error_frame(&self, error_frame: TCanError, bus_name: &str, bus_id: u32) {
         let (arrive_instant, can_error) = error_frame.destruct();
 
-        let mut errors = vec![];
-        let can_error_flag = can_error.0;
-        if can_error_flag & 0x1 != 0 {
-            errors.push(CanErrorType::BitError);
-        } else if can_error_flag & 0x2 != 0 {
-            errors.push(CanErrorType::BitStuffing);
-        } else if can_error_flag & 0x4 != 0 {
-            errors.push(CanErrorType::FormError);
-        } else if can_error_flag & 0x8 != 0 {
-            errors.push(CanErrorType::AckError);
-        } else if can_error_flag & 0x8 != 0 {
-            errors.push(CanErrorType::CrcError);
-        }
-
-        for err in errors {
+        let counters = can_error.tx_error_counter().zip(can_error.rx_error_counter());
+        for err in CanErrorType::ALL {
+            if !can_error.contains(err.flag()) {
+                continue;
+            }
             self.database
-                .update(TraceFrame::Error(err), arrive_instant, bus_name, bus_id)
+                .update(TraceFrame::Error(err, counters), arrive_instant, bus_name, bus_id)
                 .await;
         }
     }
 
     pub async fn push_normal_frame(&self, frame: TFrame, bus_name: &str, bus_id: u32) {
         let (arrive_instant, frame) = frame.destruct();
+        let can_frame = if frame.data().len() > 8 {
+            CanFrame::new_fd(frame.id().as_u32(), frame.ide(), false, false, frame.data())
+        } else {
+            let mut data = [0u8; 8];
+            data[..frame.data().len()].copy_from_slice(frame.data());
+            CanFrame::new(
+                frame.id().as_u32(),
+                frame.ide(),
+                frame.rtr(),
+                frame.dlc(),
+                u64::from_le_bytes(data),
+            )
+        };
+        self.export_frame(arrive_instant, can_frame, bus_id).await;
         let trace_frame = TraceFrame::Frame(frame);
         self.database
             .update(trace_frame, arrive_instant, bus_name, bus_id)
@@ -92,5 +165,6 @@ impl TraceObject {
     }
 
     pub async fn deadlock_watchdog(&self) {
+        drop(self.export.lock().await);
     }
 }
@@ -10,8 +10,8 @@ use tokio::time;
 use crate::cnl::connection::ConnectionStatus;
 
 use super::{
-    connection::ConnectionObject, deserialize::FrameDeserializer, frame::Frame, trace::TraceObject,
-    CanAdapter,
+    connection::ConnectionObject, deserialize::FrameDeserializer, fragmentation::set_req_frames,
+    frame::Frame, trace::TraceObject, CanAdapter,
 };
 
 use canzero_common::CanFrame;
@@ -64,41 +64,19 @@ impl TxCom {
         }
     }
 
+    pub fn node_id(&self) -> u8 {
+        self.my_node_id
+    }
+
     pub async fn send_set_request(&self, server_id: u8, oe_id: u32, val: Vec<u32>, last_fill: u8) {
-        let (set_request_id, ide) = match self.network_ref.set_req_message().id() {
-            config::MessageId::StandardId(id) => (*id, false),
-            config::MessageId::ExtendedId(id) => (*id, true),
-        };
-        let mut frame_data: Vec<CanFrame> = vec![];
-        let frames_to_send = val.len();
-
-        for i in 0..frames_to_send {
-            // SOF
-            let mut data_curr = if i == 0 { 1u64 } else { 0u64 };
-            // EOF
-            data_curr |= if i == frames_to_send - 1 {
-                1u64 << 1
-            } else {
-                0u64
-            };
-            // toggle
-            data_curr |= ((i % 2) as u64) << 2;
-            // oe-id
-            data_curr |= (oe_id as u64) << 3;
-            // client-id
-            data_curr |= (self.my_node_id as u64) << 16;
-            // server-id
-            data_curr |= (server_id as u64) << 24;
-            // data
-            data_curr |= (val[i] as u64) << 32;
-
-            let dlc = if i == (frames_to_send - 1) {
-                4 + last_fill
-            } else {
-                8
-            };
-            frame_data.push(CanFrame::new(set_request_id, ide, false, dlc, data_curr));
-        }
+        let frame_data = set_req_frames(
+            self.network_ref.set_req_message(),
+            server_id,
+            self.my_node_id,
+            oe_id,
+            &val,
+            last_fill,
+        );
 
         fragmented_can_send(
             frame_data,
@@ -114,10 +92,32 @@ impl TxCom {
         let _ = adapter.send(CanFrame::new(msg.id().as_u32(), msg.id().ide(), false, msg.dlc(), data), true).await;
     }
 
+    /// The payload has the length of the message (see FrameSerializer::serialize_payload).
+    pub async fn send_command_req(&self, command_req_msg: &MessageRef, payload: &[u8]) {
+        let command_req_frame = message_frame(command_req_msg, payload);
+        let Some(can_adapter) = self
+            .can_adapters
+            .iter()
+            .find(|adapter| adapter.bus().id() == command_req_msg.bus().id())
+        else {
+            cprintln!(
+                "<red>Failed to send command req </red>: no can adapter for bus {}",
+                command_req_msg.bus().name()
+            );
+            return;
+        };
+
+        if let Err(err) = can_adapter.send(command_req_frame, true).await {
+            cprintln!("<red>Failed to send command req </red>: {err:?}");
+            self.connection_object
+                .set_status(ConnectionStatus::NetworkDisconnected);
+        }
+    }
+
     pub async fn send_get_req(&self, server_id: u8, object_entry_id: u16) {
         let mut data: u64 = 0;
         data |= object_entry_id as u64;
-        data |= (self.network_ref.nodes().len() as u64) << 13;
+        data |= (self.my_node_id as u64) << 13;
         data |= (server_id as u64) << 21;
 
         let get_req_frame = CanFrame::new(
@@ -186,3 +186,23 @@ async fn fragmented_can_send(
         }
     }
 }
+
+/// Payloads of more than 8 bytes are sent as CAN FD frames,
+/// with bit rate switching if the bus defines a data baudrate.
+pub fn message_frame(msg: &MessageRef, payload: &[u8]) -> CanFrame {
+    let id = msg.id().as_u32();
+    let ide = msg.id().ide();
+    if payload.len() > 8 {
+        assert!(
+            msg.bus().fd(),
+            "DETECTED INVALID CONFIG: message {} exceeds 8 bytes on a classical CAN bus",
+            msg.name()
+        );
+        let brs = msg.bus().data_baudrate().is_some();
+        CanFrame::new_fd(id, ide, brs, false, payload)
+    } else {
+        let mut data = [0u8; 8];
+        data[..payload.len()].copy_from_slice(payload);
+        CanFrame::new(id, ide, false, payload.len() as u8, u64::from_le_bytes(data))
+    }
+}
@@ -0,0 +1,47 @@
+use crate::commands::object_entry_commands::parse_value;
+use crate::state::cnl_state::CNLState;
+
+#[tauri::command]
+pub async fn invoke_command(
+    state: tauri::State<'_, CNLState>,
+    node_name: String,
+    command_name: String,
+    arguments_json: String,
+) -> Result<(), String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: invoke_command({node_name:?}, {command_name:?}, {arguments_json:?})");
+    let cnl = state.lock().await;
+
+    let Some(node) = cnl.nodes().iter().find(|no| no.name() == &node_name) else {
+        return Err(format!("node with name '{node_name}' doesn't exist"));
+    };
+    let Some(command) = node.commands().iter().find(|c| c.name() == &command_name) else {
+        return Err(format!(
+            "node '{node_name}' doesn't have a command with name '{command_name}'"
+        ));
+    };
+
+    let json_value = match serde_json::from_str::<serde_json::Value>(&arguments_json) {
+        Ok(v) => v,
+        Err(err) => return Err(format!("invalid arguments : {err}")),
+    };
+    let Some(json_map) = json_value.as_object() else {
+        return Err("invalid arguments : expected a map of argument names to values".to_owned());
+    };
+
+    let mut invoke_builder = command.build_invoke();
+    for (arg_name, arg_type) in command.arguments() {
+        let Some(json_arg) = json_map.get(arg_name) else {
+            return Err(format!("missing argument '{arg_name}'"));
+        };
+        let Ok(value) = parse_value(arg_type, json_arg) else {
+            return Err(format!("invalid value for argument '{arg_name}'"));
+        };
+        invoke_builder = invoke_builder.arg(arg_name, value);
+    }
+
+    invoke_builder
+        .invoke()
+        .await
+        .map_err(|err| err.description().to_owned())
+}
@@ -127,7 +127,12 @@ pub async fn export(
                                         values: vec![],
                                     });
                                 }
-                                Type::Array { len: _, ty: _ } => panic!("Arrays not implemented"),
+                                Type::Array { len, ty } => {
+                                    let column_name = column_name.unwrap_or("value".to_owned());
+                                    for i in 0..*len {
+                                        create_columns(ty, Some(format!("{column_name}[{i}]")), columns);
+                                    }
+                                }
                             }
                         }
                         create_columns(&&oe.ty, None, &mut columns);
@@ -159,6 +164,11 @@ pub async fn export(
                                     columns[*c].values.push(format!("{v}"));
                                     *c += 1;
                                 }
+                                Value::ArrayValue(elements) => {
+                                    for element in elements {
+                                        add_value_to_columns(element, c, columns);
+                                    }
+                                }
                             }
                         }
 
@@ -3,7 +3,9 @@ pub mod trace;
 pub mod object_entry_commands;
 pub mod connection_status;
 pub mod node_commands;
+pub mod command_commands;
 pub mod export;
 pub mod startup;
+pub mod replay;
 pub mod settings;
 pub mod search_string_storage;
@@ -1,6 +1,12 @@
 use serde::Serialize;
 
-use crate::{state::cnl_state::CNLState, cnl::network::object_entry_object::info::ObjectEntryInformation};
+use crate::{
+    cnl::network::{
+        object_entry_object::info::{ty::ObjectEntryType, ObjectEntryInformation},
+        stream_object::intervals::StreamStatus,
+    },
+    state::cnl_state::CNLState,
+};
 
 // In typescript represented as types/NetworkInformation
 #[derive(Serialize, Clone)]
@@ -27,6 +33,7 @@ pub struct NodeInformation {
     id: u8,
     object_entries: Vec<String>,
     commands: Vec<String>,
+    streams: Vec<String>,
     buses: Vec<String>,
 }
 
@@ -54,6 +61,7 @@ pub async fn node_information(
                 .iter()
                 .map(|c| c.name().to_owned())
                 .collect(),
+            streams: node.streams().iter().map(|s| s.name().to_owned()).collect(),
             buses: node.buses().iter().map(|b| b.name().to_owned()).collect(),
         }),
         None => Err(format!("node with name '{node_name}' doesn't exist")),
@@ -95,6 +103,8 @@ pub async fn object_entry_information(
 pub struct CommandInformation {
     name: String,
     description: Option<String>,
+    arguments: Vec<(String, ObjectEntryType)>,
+    event_name: String,
 }
 
 #[tauri::command]
@@ -116,9 +126,64 @@ pub async fn command_information(
         Some(command) => Ok(CommandInformation {
             name: command_name,
             description: command.description().cloned(),
+            arguments: command
+                .arguments()
+                .into_iter()
+                .map(|(arg_name, arg_type)| (arg_name.to_owned(), ObjectEntryType::new(arg_type)))
+                .collect(),
+            event_name: command.event_name().to_owned(),
         }),
         None => Err(format!(
             "node '{node_name}' doesn't have a command with name '{command_name}'"
         )),
     }
 }
+
+// In typescript represented as types/StreamInformation
+#[derive(Serialize, Clone)]
+pub struct StreamInformation {
+    name: String,
+    description: Option<String>,
+    min_interval_ms: f64,
+    max_interval_ms: f64,
+    last_interval_ms: Option<f64>,
+    observed_min_interval_ms: Option<f64>,
+    observed_max_interval_ms: Option<f64>,
+    status: StreamStatus,
+}
+
+#[tauri::command]
+pub async fn stream_information(
+    state: tauri::State<'_, CNLState>,
+    node_name: String,
+    stream_name: String,
+) -> Result<StreamInformation, String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: (get) stream_information({node_name:?}, {stream_name:?})");
+
+    let cnl = state.lock().await;
+    let node = cnl.nodes().iter().find(|n| n.name() == &node_name);
+    let Option::Some(node) = node else {
+        return Err(format!("node with name '{node_name}' doesn't exist"));
+    };
+    let stream = node.streams().iter().find(|s| s.name() == stream_name);
+    match stream {
+        Some(stream) => {
+            let intervals = stream.intervals().await;
+            let ms = |interval: &std::time::Duration| interval.as_secs_f64() * 1000.0;
+            Ok(StreamInformation {
+                name: stream_name,
+                description: stream.description().map(str::to_owned),
+                min_interval_ms: ms(intervals.min_interval()),
+                max_interval_ms: ms(intervals.max_interval()),
+                last_interval_ms: intervals.last().map(ms),
+                observed_min_interval_ms: intervals.min().map(ms),
+                observed_max_interval_ms: intervals.max().map(ms),
+                status: stream.status().await,
+            })
+        }
+        None => Err(format!(
+            "node '{node_name}' doesn't have a stream with name '{stream_name}'"
+        )),
+    }
+}
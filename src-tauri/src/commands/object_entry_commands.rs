@@ -21,131 +21,38 @@ pub async fn set_object_entry_value(
     node_name: String,
     object_entry_name: String,
     new_value_json: String,
-) -> Result<(), ()> {
+) -> Result<(), String> {
     let cnl = state.lock().await;
 
     let Some(node) = cnl.nodes().iter().find(|no| no.name() == &node_name) else {
-        return Err(());
+        return Err(format!("node {node_name} does not exist"));
     };
     let Some(object_entry_object) = node
         .object_entries()
         .iter()
         .find(|oe| oe.name() == &object_entry_name)
+        .cloned()
     else {
-        return Err(());
+        return Err(format!("object entry {node_name}::{object_entry_name} does not exist"));
     };
+    // the set request has to be awaited without holding the network lock.
+    drop(cnl);
     let oe_type = object_entry_object.ty();
 
     let json_value = match serde_json::from_str::<serde_json::Value>(&new_value_json) {
         Ok(v) => v,
-        Err(_) => return Err(()),
+        Err(_) => return Err(format!("invalid json value : {new_value_json}")),
     };
 
-    fn parse_value(oe_type: &config::TypeRef, json_value: &serde_json::Value) -> Result<Value, ()> {
-        match oe_type.as_ref() {
-            Type::Primitive(SignalType::SignedInt { size }) => {
-                if let Some(val) = json_value.as_i64() {
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    let max_ivalue: i64 = (max_uvalue >> 1) as i64;
-                    let min_ivalue: i64 = unsafe {
-                        std::mem::transmute(u64::MAX << (*size as u32 - 1))
-                    };
-                    if val <= max_ivalue && val >= min_ivalue {
-                        Ok(Value::SignedValue(val))
-                    } else {
-                        return Err(());
-                    }
-                } else {
-                    return Err(());
-                }
-            }
-            Type::Primitive(SignalType::UnsignedInt { size }) => {
-                if let Some(val) = json_value.as_u64() {
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    if val <= max_uvalue {
-                        Ok(Value::UnsignedValue(val))
-                    } else {
-                        return Err(());
-                    }
-                } else {
-                    return Err(());
-                }
-            }
-            Type::Primitive(SignalType::Decimal {
-                size,
-                offset,
-                scale,
-            }) => {
-                if let Some(val) = json_value.as_f64() {
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    let min = *offset;
-                    let max = (max_uvalue as f64) * scale + offset;
-                    if val <= max && val >= min {
-                        Ok(Value::RealValue(val))
-                    } else {
-                        return Err(());
-                    }
-                } else {
-                    return Err(());
-                }
-            }
-
-            Type::Struct {
-                name: _,
-                description: _,
-                attribs,
-                visibility: _,
-            } => {
-                if let Some(map) = json_value.as_object() {
-                    let mut attributes: Vec<Attribute> = vec![];
-
-                    for (name, attr_type) in attribs {
-                        if let Some(val) = map.get(name) {
-                            if let Ok(type_val) = parse_value(attr_type, val) {
-                                attributes.push(Attribute::new(name, type_val));
-                            } else {
-                                return Err(());
-                            }
-                        } else {
-                            return Err(());
-                        }
-                    }
-                    Ok(Value::StructValue(attributes))
-                } else {
-                    return Err(());
-                }
-            }
-            Type::Enum {
-                name: _,
-                description: _,
-                size: _,
-                entries,
-                visibility: _,
-            } => {
-                if let Some(variant_str) = json_value.as_str() {
-                    if entries.iter().any(|e| e.0 == variant_str) {
-                        Ok(Value::EnumValue(variant_str.to_string()))
-                        // Ok(TypeValue::Enum(oe_type.clone(), variant_str.to_string()))
-                    } else {
-                        return Err(());
-                    }
-                } else {
-                    return Err(());
-                }
-            }
-            Type::Array { len: _, ty: _ } => todo!(),
-        }
-    }
-
-
     let value = match parse_value(oe_type, &json_value) {
         Ok(x) => x,
-        Err(_) => return Err(()),
+        Err(_) => return Err(format!("value {new_value_json} does not match the type of {node_name}::{object_entry_name}")),
     };
 
-    object_entry_object.set_request(value).await;
-
-    Ok(())
+    object_entry_object
+        .set_request(value)
+        .await
+        .map_err(|err| format!("{} : {}", err.reason(), err.description()))
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -288,25 +195,29 @@ pub async fn request_object_entry_value(
     state: tauri::State<'_, CNLState>,
     node_name: String,
     object_entry_name: String,
-) -> Result<(), ()> {
+) -> Result<Value, String> {
     #[cfg(feature = "logging-invoke")]
     println!("invoke: (get-request) request_object_entry_value({node_name}, {object_entry_name})");
     let cnl = state.lock().await;
 
     let Some(node) = cnl.nodes().iter().find(|no| no.name() == &node_name) else {
-        return Err(());
+        return Err(format!("node {node_name} does not exist"));
     };
     let Some(object_entry) = node
         .object_entries()
         .iter()
         .find(|oe| oe.name() == &object_entry_name)
+        .cloned()
     else {
-        return Err(());
+        return Err(format!("object entry {node_name}::{object_entry_name} does not exist"));
     };
+    // the get request has to be awaited without holding the network lock.
+    drop(cnl);
 
-    object_entry.request_current_value().await;
-
-    Ok(())
+    object_entry
+        .get_request()
+        .await
+        .map_err(|err| format!("{} : {}", err.reason(), err.description()))
 }
 
 #[tauri::command]
@@ -341,3 +252,112 @@ pub fn open_floating_object_entry_window(
     .build()
     .unwrap();
 }
+
+pub fn parse_value(oe_type: &config::TypeRef, json_value: &serde_json::Value) -> Result<Value, ()> {
+    match oe_type.as_ref() {
+        Type::Primitive(SignalType::SignedInt { size }) => {
+            if let Some(val) = json_value.as_i64() {
+                let max_uvalue = u64::MAX >> (64 - *size as u32);
+                let max_ivalue: i64 = (max_uvalue >> 1) as i64;
+                let min_ivalue: i64 = unsafe {
+                    std::mem::transmute(u64::MAX << (*size as u32 - 1))
+                };
+                if val <= max_ivalue && val >= min_ivalue {
+                    Ok(Value::SignedValue(val))
+                } else {
+                    return Err(());
+                }
+            } else {
+                return Err(());
+            }
+        }
+        Type::Primitive(SignalType::UnsignedInt { size }) => {
+            if let Some(val) = json_value.as_u64() {
+                let max_uvalue = u64::MAX >> (64 - *size as u32);
+                if val <= max_uvalue {
+                    Ok(Value::UnsignedValue(val))
+                } else {
+                    return Err(());
+                }
+            } else {
+                return Err(());
+            }
+        }
+        Type::Primitive(SignalType::Decimal {
+            size,
+            offset,
+            scale,
+        }) => {
+            if let Some(val) = json_value.as_f64() {
+                let max_uvalue = u64::MAX >> (64 - *size as u32);
+                let min = *offset;
+                let max = (max_uvalue as f64) * scale + offset;
+                if val <= max && val >= min {
+                    Ok(Value::RealValue(val))
+                } else {
+                    return Err(());
+                }
+            } else {
+                return Err(());
+            }
+        }
+
+        Type::Struct {
+            name: _,
+            description: _,
+            attribs,
+            visibility: _,
+        } => {
+            if let Some(map) = json_value.as_object() {
+                let mut attributes: Vec<Attribute> = vec![];
+
+                for (name, attr_type) in attribs {
+                    if let Some(val) = map.get(name) {
+                        if let Ok(type_val) = parse_value(attr_type, val) {
+                            attributes.push(Attribute::new(name, type_val));
+                        } else {
+                            return Err(());
+                        }
+                    } else {
+                        return Err(());
+                    }
+                }
+                Ok(Value::StructValue(attributes))
+            } else {
+                return Err(());
+            }
+        }
+        Type::Enum {
+            name: _,
+            description: _,
+            size: _,
+            entries,
+            visibility: _,
+        } => {
+            if let Some(variant_str) = json_value.as_str() {
+                if entries.iter().any(|e| e.0 == variant_str) {
+                    Ok(Value::EnumValue(variant_str.to_string()))
+                    // Ok(TypeValue::Enum(oe_type.clone(), variant_str.to_string()))
+                } else {
+                    return Err(());
+                }
+            } else {
+                return Err(());
+            }
+        }
+        Type::Array { len, ty } => {
+            if let Some(array) = json_value.as_array() {
+                if array.len() != *len {
+                    return Err(());
+                }
+                let mut elements: Vec<Value> = vec![];
+                for val in array {
+                    elements.push(parse_value(ty, val)?);
+                }
+                Ok(Value::ArrayValue(elements))
+            } else {
+                return Err(());
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use crate::{cnl::can_adapter::replay::ReplayStatus, state::replay::ReplayState};
+
+#[tauri::command]
+pub async fn replay_status(state: tauri::State<'_, ReplayState>) -> Result<ReplayStatus, ()> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: replay_status()");
+    match state.controller().await {
+        Some(controller) => Ok(controller.status()),
+        None => Err(()),
+    }
+}
+
+#[tauri::command]
+pub async fn replay_pause(state: tauri::State<'_, ReplayState>) -> Result<(), ()> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: replay_pause()");
+    let Some(controller) = state.controller().await else {
+        return Err(());
+    };
+    controller.pause();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn replay_resume(state: tauri::State<'_, ReplayState>) -> Result<(), ()> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: replay_resume()");
+    let Some(controller) = state.controller().await else {
+        return Err(());
+    };
+    controller.resume();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn replay_seek(
+    state: tauri::State<'_, ReplayState>,
+    position: u64,
+) -> Result<(), ()> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: replay_seek({position})");
+    let Some(controller) = state.controller().await else {
+        return Err(());
+    };
+    controller.seek(Duration::from_millis(position));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn replay_set_speed(
+    state: tauri::State<'_, ReplayState>,
+    speed: f64,
+) -> Result<(), ()> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: replay_set_speed({speed})");
+    let Some(controller) = state.controller().await else {
+        return Err(());
+    };
+    controller.set_speed(speed);
+    Ok(())
+}
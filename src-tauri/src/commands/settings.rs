@@ -62,6 +62,18 @@ pub fn set_deadlock_lvl(lvl: WdgLevel) -> Result<WdgLevel, ()> {
     }
 }
 
+#[tauri::command]
+pub fn set_history_limit(limit: usize) -> Result<usize, ()> {
+    let app_data = AppData::read();
+    match app_data {
+        Ok(mut app_data) => {
+            app_data.set_history_limit(limit);
+            Ok(app_data.get_history_limit())
+        }
+        Err(_) => Err(()),
+    }
+}
+
 #[tauri::command]
 pub fn select_network_configuration() {
     FileDialogBuilder::new().pick_file(|path| match path {
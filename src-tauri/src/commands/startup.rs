@@ -37,6 +37,7 @@ pub async fn download_network_configuration(
 pub enum ConnectionType {
     SocketCan = 0,
     Tcp = 1,
+    Replay = 2,
 }
 
 impl Serialize for ConnectionType {
@@ -47,6 +48,7 @@ impl Serialize for ConnectionType {
         serializer.serialize_u8(match self {
             ConnectionType::SocketCan => 0,
             ConnectionType::Tcp => 1,
+            ConnectionType::Replay => 2,
         })
     }
 }
@@ -123,10 +125,29 @@ pub async fn discover_servers(
                         .collect::<Vec<&str>>()
                 ),
             },
+            NetworkConnectionCreateInfo::Replay(path) => ConnectionDescription {
+                tag: ConnectionType::Replay,
+                description: format!("Replay of {path:?}"),
+            },
         })
         .collect())
 }
 
+/// Adds a recording as a connection, returns the index that has to be passed to try_connect.
+#[tauri::command]
+pub async fn open_recording(
+    state: tauri::State<'_, StartupState>,
+    path: String,
+) -> Result<usize, String> {
+    let path = std::path::PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("Recording {path:?} does not exist"));
+    }
+    Ok(state
+        .add_connection(NetworkConnectionCreateInfo::Replay(path))
+        .await)
+}
+
 #[cfg(feature = "socket-can")]
 pub fn check_for_socketcan(
     network_ref: &canzero_config::config::NetworkRef,
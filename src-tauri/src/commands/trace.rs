@@ -1,3 +1,6 @@
+use canzero_common::trace_format::TraceFormat;
+use tauri::api::dialog::FileDialogBuilder;
+
 use crate::{
     cnl::trace::database::{SortCriteria, SortOrder},
     state::cnl_state::CNLState,
@@ -65,3 +68,51 @@ pub async fn filter_trace_by(
     state.lock().await.trace().filter_by(filter_string).await;
     Ok(())
 }
+
+/// format is either "candump" or "asc", the extension of the selected file takes precedence.
+/// Returns false if the file dialog was canceled.
+#[tauri::command]
+pub async fn start_trace_export(
+    state: tauri::State<'_, CNLState>,
+    format: String,
+) -> Result<bool, String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: start_trace_export({format:?})");
+    let Some(format) = TraceFormat::from_name(&format) else {
+        return Err(format!("invalid trace format {format}"));
+    };
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    FileDialogBuilder::new()
+        .set_title("Export trace")
+        .add_filter(format.extension(), &[format.extension()])
+        .add_filter("candump", &["log"])
+        .add_filter("ASC", &["asc"])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Ok(Some(path)) = rx.await else {
+        return Ok(false);
+    };
+    let format = TraceFormat::from_path(&path).unwrap_or(format);
+    state
+        .lock()
+        .await
+        .trace()
+        .start_export(&path, format)
+        .await
+        .map_err(|err| format!("{err:?}"))?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn stop_trace_export(state: tauri::State<'_, CNLState>) -> Result<(), String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: stop_trace_export()");
+    state
+        .lock()
+        .await
+        .trace()
+        .stop_export()
+        .await
+        .map_err(|err| format!("{err:?}"))
+}
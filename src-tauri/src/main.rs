@@ -5,7 +5,7 @@ use tauri::Manager;
 
 use crate::{
     commands::{connection_status, network_information, object_entry_commands, search_string_storage::SearchStringStorage},
-    state::startup::StartupState,
+    state::{replay::ReplayState, startup::StartupState},
 };
 
 mod cnl;
@@ -32,6 +32,7 @@ async fn main() {
                     std::process::exit(0);
                 }
                 handle.manage(StartupState::new());
+                handle.manage(ReplayState::new());
                 handle.manage(SearchStringStorage{store : Default::default()});
                 tauri::WindowBuilder::new(
                     &handle,
@@ -52,10 +53,13 @@ async fn main() {
             commands::trace::unlisten_from_trace,
             commands::trace::sort_trace_by,
             commands::trace::filter_trace_by,
+            commands::trace::start_trace_export,
+            commands::trace::stop_trace_export,
             network_information::network_information,
             network_information::node_information,
             network_information::object_entry_information,
             network_information::command_information,
+            network_information::stream_information,
             object_entry_commands::listen_to_latest_object_entry_value,
             object_entry_commands::unlisten_from_latest_object_entry_value,
             object_entry_commands::listen_to_history_of_object_entry,
@@ -76,11 +80,18 @@ async fn main() {
             commands::node_commands::get_heartbeat_status,
             commands::node_commands::listen_to_heartbeat,
             commands::node_commands::unlisten_from_heartbeat,
+            commands::command_commands::invoke_command,
             commands::export::export,
             commands::startup::download_network_configuration,
             commands::startup::discover_servers,
             commands::startup::try_connect,
             commands::startup::complete_setup,
+            commands::startup::open_recording,
+            commands::replay::replay_status,
+            commands::replay::replay_pause,
+            commands::replay::replay_resume,
+            commands::replay::replay_seek,
+            commands::replay::replay_set_speed,
             commands::startup::close_startup,
             commands::settings::open_settings,
             commands::settings::close_settings,
@@ -88,6 +99,7 @@ async fn main() {
             commands::settings::get_settings,
             commands::settings::set_frontend_lvl,
             commands::settings::set_deadlock_lvl,
+            commands::settings::set_history_limit,
             commands::search_string_storage::get_stored_search_string,
             commands::search_string_storage::store_search_string,
         ])
@@ -1,2 +1,3 @@
 pub mod cnl_state;
 pub mod startup;
+pub mod replay;
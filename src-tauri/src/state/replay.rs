@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::cnl::can_adapter::replay::ReplayController;
+
+/// Holds the controller of the currently active replay
+/// (None if connected to a live network).
+pub struct ReplayState {
+    controller: Mutex<Option<Arc<ReplayController>>>,
+}
+
+impl ReplayState {
+    pub fn new() -> Self {
+        Self {
+            controller: Mutex::new(None),
+        }
+    }
+
+    pub async fn set_controller(&self, controller: Option<Arc<ReplayController>>) {
+        *self.controller.lock().await = controller;
+    }
+
+    pub async fn controller(&self) -> Option<Arc<ReplayController>> {
+        self.controller.lock().await.as_ref().cloned()
+    }
+}
@@ -1,22 +1,25 @@
 use std::{
     future::IntoFuture,
+    path::PathBuf,
     sync::{Arc, OnceLock},
     time::Instant,
 };
 
 use canzero_config::config::NetworkRef;
 use canzero_udp::frame::NetworkDescription;
+use tauri::Manager;
 use tokio::sync::Mutex;
 
-use crate::cnl::can_adapter::CanAdapter;
+use crate::{cnl::can_adapter::CanAdapter, notification::notify_warning};
 
-use super::cnl_state::CNLState;
+use super::{cnl_state::CNLState, replay::ReplayState};
 
 #[derive(Debug, Clone)]
 pub enum NetworkConnectionCreateInfo {
     Tcp(NetworkDescription),
     #[cfg(feature = "socket-can")]
     SocketCan,
+    Replay(PathBuf),
 }
 
 pub struct StartupState {
@@ -52,6 +55,13 @@ impl StartupState {
         *self.connections.lock().await = connections;
     }
 
+    /// returns the index of the added connection
+    pub async fn add_connection(&self, connection: NetworkConnectionCreateInfo) -> usize {
+        let mut connections = self.connections.lock().await;
+        connections.push(connection);
+        connections.len() - 1
+    }
+
     pub async fn try_connect(
         &self,
         connection_index: usize,
@@ -85,11 +95,41 @@ impl StartupState {
             }
             #[cfg(feature = "socket-can")]
             NetworkConnectionCreateInfo::SocketCan => {
-                let can_adapter = CanAdapter::create_socketcan_adapters(&network_ref, app_handle)
-                    .map_err(|err| format!("{err:?}"))?;
+                let (can_adapters, timebase) =
+                    CanAdapter::create_socketcan_adapters(&network_ref, app_handle)
+                        .map_err(|err| format!("{err:?}"))?;
+                *self.established_connection.lock().await = (
+                    can_adapters.into_iter().map(Arc::new).collect(),
+                    Some(timebase),
+                    None,
+                    None,
+                );
+            }
+            NetworkConnectionCreateInfo::Replay(path) => {
+                let (can_adapters, controller, timebase) =
+                    CanAdapter::create_replay_adapters(&network_ref, app_handle, path)
+                        .map_err(|err| format!("{err:?}"))?;
+                if controller
+                    .header()
+                    .is_some_and(|header| header.config_hash != network_ref.portable_hash())
+                {
+                    notify_warning(
+                        app_handle,
+                        "Replay",
+                        &format!(
+                            "{path:?} was recorded with a different network configuration"
+                        ),
+                        chrono::Local::now(),
+                    );
+                }
+                app_handle
+                    .state::<ReplayState>()
+                    .set_controller(Some(Arc::new(controller)))
+                    .await;
                 *self.established_connection.lock().await = (
-                    can_adapter.into_iter().map(Arc::new).collect(),
-                    Some(Instant::now()),
+                    can_adapters.into_iter().map(Arc::new).collect(),
+                    Some(timebase),
+                    None,
                     None,
                 );
             }
@@ -42,6 +42,8 @@ function Content() {
         setBackendError(undefined);
       } else if (connection == "heartbeat-miss") {
         setBackendError("Heartbeat timed out");
+      } else if (connection == "bus-off") {
+        setBackendError("CAN controller is bus off");
       } else if (connection == "network-disconnected") {
         setBackendError("Network disconnected");
       } else if (connection == "frontend-wdg-timeout") {
@@ -1,6 +1,7 @@
-import { AppBar, Box, Checkbox, Container, CssBaseline, IconButton, List, ListItem, ListItemButton, ListItemIcon, ListItemText, Stack, Toolbar, Typography, useTheme } from "@mui/material";
+import { AppBar, Box, Checkbox, Container, CssBaseline, IconButton, List, ListItem, ListItemButton, ListItemIcon, ListItemText, Stack, TextField, Toolbar, Typography, useTheme } from "@mui/material";
 import CloseIcon from '@mui/icons-material/Close';
 import SettingsApplicationsIcon from '@mui/icons-material/SettingsApplications';
+import HistoryIcon from '@mui/icons-material/History';
 import { invoke } from "@tauri-apps/api";
 import { useEffect, useState } from "react";
 
@@ -14,6 +15,7 @@ interface Settings {
   configPath: string,
   frontendWdgLvl: WdgLevel,
   deadlockWdgLvl: WdgLevel,
+  historyLimit: number,
 }
 
 
@@ -22,6 +24,7 @@ function SettingsContent() {
   const [frontendLvl, setFrontendLvl] = useState<WdgLevel>(WdgLevel.Active);
   const [deadlockLvl, setDeadlockLvl] = useState<WdgLevel>(WdgLevel.Active);
   const [configPath, setConfigPath] = useState<string>();
+  const [historyLimit, setHistoryLimit] = useState<number>(100000);
 
 
   useEffect(() => {
@@ -29,6 +32,7 @@ function SettingsContent() {
       setFrontendLvl(s.frontendWdgLvl);
       setDeadlockLvl(s.deadlockWdgLvl);
       setConfigPath(s.configPath);
+      setHistoryLimit(s.historyLimit);
     }).catch(console.error);
 
   }, []);
@@ -150,6 +154,30 @@ function SettingsContent() {
                 secondary="Danger: A deadlocked backend might not lead to a shutdown" />
             </ListItemButton>
           </ListItem>
+          <ListItem disablePadding>
+            <ListItemButton>
+              <ListItemIcon sx={{ justifyContent: "center" }}>
+                <HistoryIcon />
+              </ListItemIcon>
+              <ListItemText
+                primary="History Limit"
+                secondary="Values per object entry kept in memory, older values are stored on disk (applies after reconnecting)" />
+              <TextField
+                type="number"
+                size="small"
+                sx={{ width: "10em" }}
+                value={historyLimit}
+                onChange={(evt) => {
+                  const limit = Number.parseInt(evt.target.value);
+                  if (Number.isNaN(limit) || limit < 1) {
+                    return;
+                  }
+                  invoke<number>("set_history_limit", { limit })
+                    .then(l => setHistoryLimit(l))
+                    .catch(console.error);
+                }} />
+            </ListItemButton>
+          </ListItem>
         </List>
       </Container>
 
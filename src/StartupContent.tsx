@@ -3,7 +3,9 @@ import AccessibleIcon from '@mui/icons-material/Accessible';
 import LanIcon from '@mui/icons-material/Lan';
 import CellTowerIcon from '@mui/icons-material/CellTower';
 import RefreshIcon from '@mui/icons-material/Refresh';
+import ReplayIcon from '@mui/icons-material/Replay';
 import { invoke } from "@tauri-apps/api";
+import { open } from "@tauri-apps/api/dialog";
 import { useEffect, useState } from "react";
 
 enum ConnectionState {
@@ -17,6 +19,7 @@ enum ConnectionState {
 enum ConnectionType {
   SocketCan = 0,
   Tcp = 1,
+  Replay = 2,
 }
 
 interface ConnectionDescription {
@@ -90,6 +93,25 @@ function StartupContent() {
     // register to listener in the backend 
   }, [activeStep, connectionState]);
 
+  function openRecording() {
+    open({
+      title: "Open recording",
+      filters: [{ name: "Recording", extensions: ["rec", "log", "asc"] }],
+    }).then(async path => {
+      if (typeof path !== "string") {
+        return;
+      }
+      setConnectionState(ConnectionState.Connecting);
+      const index = await invoke<number>("open_recording", { path });
+      await invoke("try_connect", { connectionIndex: index });
+      setActiveConnection(`Replay of ${path}`);
+      setActiveStep(2);
+    }).catch(err => {
+      setConnectionError(err);
+      setConnectionState(ConnectionState.Error);
+    });
+  }
+
   function reset() {
     setActiveStep(-1);
     setConnectionState(ConnectionState.Init);
@@ -123,6 +145,9 @@ function StartupContent() {
               <IconButton onClick={reset}>
                 <RefreshIcon />
               </IconButton>
+              <IconButton disabled={activeStep != 1} onClick={openRecording}>
+                <ReplayIcon />
+              </IconButton>
               <IconButton onClick={() => invoke("open_settings").catch(console.error)}>
                 <AccessibleIcon />
               </IconButton>
@@ -177,9 +202,14 @@ function StartupContent() {
                       case ConnectionState.Error:
                         return (
                           <Alert severity={connections.length == 0 ? "warning" : "error"} action={
-                            <IconButton color="inherit" size="small" onClick={reset}>
-                              <RefreshIcon />
-                            </IconButton>
+                            <Stack component="div" direction="row">
+                              <IconButton color="inherit" size="small" onClick={openRecording}>
+                                <ReplayIcon />
+                              </IconButton>
+                              <IconButton color="inherit" size="small" onClick={reset}>
+                                <RefreshIcon />
+                              </IconButton>
+                            </Stack>
                           } sx={{
                             maxWidth: "500px"
                           }}><AlertTitle>{connections.length == 0 ? "Failed to find Server" : "Failed to Connect"}</AlertTitle>{connectionError}</Alert>
@@ -212,7 +242,8 @@ function StartupContent() {
                                         setConnectionState(ConnectionState.Connecting);
                                       }}>
                                         <ListItemIcon>
-                                          {connection.tag == ConnectionType.SocketCan ? <LanIcon /> : <CellTowerIcon />}
+                                          {connection.tag == ConnectionType.SocketCan ? <LanIcon />
+                                            : connection.tag == ConnectionType.Replay ? <ReplayIcon /> : <CellTowerIcon />}
                                         </ListItemIcon>
                                         <ListItemText>
                                           {connection.description}
@@ -9,6 +9,7 @@ import { invoke } from "@tauri-apps/api";
 import { listen } from "@tauri-apps/api/event";
 import { ObjectEntryEvent } from "../object_entry/types/events/ObjectEntryEvent";
 import StateIndicatorBar from "./StateIndicatorBar.tsx";
+import ReplayControls from "./ReplayControls.tsx";
 import WarningIconDisplay from "../object_entry/vis/icons/WarningIconDisplay";
 import BatteryIconDisplay from "../object_entry/vis/icons/BatteryIconDisplay";
 import TemperatureIconDisplay from "../object_entry/vis/icons/TemperatureIconDisplay";
@@ -383,6 +384,7 @@ function CustomAppBar({ open, toggleOpen }: Readonly<CustomAppBarProps>) {
               marginRight: "1rem",
             }}
           >
+            <ReplayControls />
             <WarningIconDisplay />
             <BatteryIconDisplay />
             <TemperatureIconDisplay />
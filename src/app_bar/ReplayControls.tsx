@@ -0,0 +1,87 @@
+import { IconButton, MenuItem, Select, Slider, Stack, Typography } from "@mui/material";
+import PauseIcon from '@mui/icons-material/Pause';
+import PlayArrowIcon from '@mui/icons-material/PlayArrow';
+import { invoke } from "@tauri-apps/api";
+import { useEffect, useState } from "react";
+
+interface ReplayStatus {
+  speed: number,
+  paused: boolean,
+  // ms
+  position: number,
+  // ms
+  duration: number,
+  finished: boolean,
+}
+
+const POLL_INTERVAL_MS = 250;
+const SPEEDS = [0.25, 0.5, 1, 2, 4, 8];
+
+function formatTime(ms: number) {
+  const secs = Math.floor(ms / 1000);
+  return `${Math.floor(secs / 60)}:${(secs % 60).toString().padStart(2, "0")}`;
+}
+
+// Playback controls of a replayed recording, renders nothing for live connections.
+function ReplayControls() {
+  const [status, setStatus] = useState<ReplayStatus | null>(null);
+  // position of the slider while it is dragged.
+  const [seeking, setSeeking] = useState<number | null>(null);
+
+  useEffect(() => {
+    function poll() {
+      invoke<ReplayStatus>("replay_status")
+        .then(setStatus)
+        .catch(() => setStatus(null));
+    }
+    poll();
+    const handle = setInterval(poll, POLL_INTERVAL_MS);
+    return () => clearInterval(handle);
+  }, []);
+
+  if (status === null) {
+    return <></>;
+  }
+
+  return (
+    <Stack direction="row" alignItems="center" spacing={1} sx={{ width: "20em" }}>
+      <IconButton size="small" onClick={() => {
+        if (status.finished) {
+          // restart from the beginning.
+          invoke("replay_seek", { position: 0 })
+            .then(() => invoke("replay_resume"))
+            .catch(console.error);
+        } else {
+          invoke(status.paused ? "replay_resume" : "replay_pause").catch(console.error);
+        }
+      }}>
+        {status.paused || status.finished ? <PlayArrowIcon /> : <PauseIcon />}
+      </IconButton>
+      <Slider
+        size="small"
+        min={0}
+        max={Math.max(status.duration, 1)}
+        value={seeking ?? status.position}
+        onChange={(_, value) => setSeeking(value as number)}
+        onChangeCommitted={(_, value) => {
+          invoke("replay_seek", { position: Math.round(value as number) })
+            .catch(console.error)
+            .finally(() => setSeeking(null));
+        }} />
+      <Typography variant="caption" noWrap>
+        {formatTime(seeking ?? status.position)} / {formatTime(status.duration)}
+      </Typography>
+      <Select
+        size="small"
+        variant="standard"
+        value={status.speed}
+        onChange={(evt) => {
+          invoke("replay_set_speed", { speed: evt.target.value as number }).catch(console.error);
+        }}>
+        {SPEEDS.map(speed => <MenuItem key={speed} value={speed}>{speed}x</MenuItem>)}
+      </Select>
+    </Stack>
+  );
+}
+
+export default ReplayControls;
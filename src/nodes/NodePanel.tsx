@@ -25,6 +25,7 @@ import {TableComponents, TableVirtuoso} from "react-virtuoso";
 import SearchIcon from '@mui/icons-material/Search';
 import useFocusOnCtrlShortcut from "../trace/FocusOnKey.tsx";
 import HeartbeatStatus from "./HeartbeatStatus.tsx";
+import StreamIntervalStatus from "./StreamIntervalStatus.tsx";
 
 
 interface NodePanelProps {
@@ -149,6 +150,14 @@ function NodePanel({node}: Readonly<NodePanelProps>) {
                         </Box>
                     ))
                 }
+                {
+                    node.streams.map(stream => (
+                        <Box position={"relative"} top={"-0.10em"} key={stream}>
+                            <StreamIntervalStatus nodeName={node.name} streamName={stream}>
+                            </StreamIntervalStatus>
+                        </Box>
+                    ))
+                }
             </Stack>
 
             {node.description ? <Typography sx={{
@@ -25,7 +25,9 @@ interface ObjectEntryAttribRowProps {
 function ObjectEntryAttribRow({ name, value }: Readonly<ObjectEntryAttribRowProps>) {
   if (typeof value === "object") {
     return Object.entries(value).map(([attrib_name, attrib_value]) => {
-      return <ObjectEntryAttribRow name={`${name}.${attrib_name}`} value={attrib_value} key={`${name}.${attrib_name}`} />
+      // array elements are displayed as name[i].
+      const full_name = Array.isArray(value) ? `${name}[${attrib_name}]` : `${name}.${attrib_name}`;
+      return <ObjectEntryAttribRow name={full_name} value={attrib_value} key={full_name} />
     });
   } else {
     return (
@@ -97,7 +99,8 @@ function ObjectEntryRow({ nodeName, objectEntryName, value }: Readonly<ObjectEnt
       </TableRow >
       {value == undefined ? <></> :
         Object.entries(value).map(([attrib_name, attrib_value]) => {
-          return <ObjectEntryAttribRow name={attrib_name} value={attrib_value} key={`${attrib_name}`} />;
+          const name = Array.isArray(value) ? `[${attrib_name}]` : attrib_name;
+          return <ObjectEntryAttribRow name={name} value={attrib_value} key={name} />;
         })
       }
     </>);
@@ -0,0 +1,66 @@
+import { Tooltip } from "@mui/material";
+import { invoke } from "@tauri-apps/api";
+import { useEffect, useState } from "react"
+import { StreamInformation, StreamStatus } from "./types/StreamInformation.ts";
+
+interface StreamIntervalStatusProps {
+  nodeName: string,
+  streamName: string
+}
+
+const POLL_INTERVAL_MS = 1000;
+
+function statusToColor(status: StreamStatus) {
+  switch (status) {
+    case StreamStatus.TooFast:
+    case StreamStatus.TooSlow:
+      return "orange";
+    case StreamStatus.InActive:
+      return "grey";
+    case StreamStatus.Good:
+      return "green";
+    default:
+      console.error();
+  }
+}
+
+function formatInterval(interval_ms?: number) {
+  return interval_ms == null ? "-" : `${interval_ms.toFixed(1)}ms`;
+}
+
+function StreamIntervalStatus({ nodeName, streamName }: Readonly<StreamIntervalStatusProps>) {
+  const [information, setInformation] = useState<StreamInformation>();
+
+  useEffect(() => {
+    function poll() {
+      invoke<StreamInformation>("stream_information", { nodeName, streamName })
+        .then(setInformation)
+        .catch(console.error);
+    }
+    poll();
+    const handle = setInterval(poll, POLL_INTERVAL_MS);
+    return () => clearInterval(handle);
+  }, [nodeName, streamName]);
+
+  if (information === undefined) {
+    return <></>;
+  }
+
+  const title = `${streamName} : ${information.status}, ` +
+    `expected ${formatInterval(information.min_interval_ms)} - ${formatInterval(information.max_interval_ms)}, ` +
+    `observed ${formatInterval(information.observed_min_interval_ms)} - ${formatInterval(information.observed_max_interval_ms)}`;
+
+  return (
+    <Tooltip title={title}>
+      <div style={{
+        width: "0.6em",
+        height: "0.6em",
+        borderRadius: "50%",
+        backgroundColor: statusToColor(information.status),
+        boxShadow: "1px 1px 1px grey",
+      }}></div>
+    </Tooltip>
+  );
+}
+
+export default StreamIntervalStatus;
@@ -0,0 +1,6 @@
+
+
+export type CommandEvent =
+  { type : "Req", req_num : number }
+  | { type : "Resp", req_num : number, success : boolean }
+  | { type : "Timeout", req_num : number };
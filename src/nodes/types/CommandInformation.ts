@@ -1,6 +1,9 @@
+import { Type } from "../../object_entry/types/Type";
 
 
 export interface CommandInformation {
   name : string,
   description? : string,
+  arguments : [string, Type][],
+  event_name : string,
 }
@@ -6,5 +6,6 @@ export interface NodeInformation {
   id : number,
   object_entries : string[],
   commands : string[],
+  streams : string[],
   buses : string[],
 }
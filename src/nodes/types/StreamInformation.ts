@@ -0,0 +1,19 @@
+
+
+export enum StreamStatus {
+  Good = "Good",
+  TooFast = "TooFast",
+  TooSlow = "TooSlow",
+  InActive = "InActive",
+}
+
+export interface StreamInformation {
+  name : string,
+  description? : string,
+  min_interval_ms : number,
+  max_interval_ms : number,
+  last_interval_ms? : number,
+  observed_min_interval_ms? : number,
+  observed_max_interval_ms? : number,
+  status : StreamStatus,
+}
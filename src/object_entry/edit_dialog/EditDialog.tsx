@@ -1,7 +1,7 @@
 import { Box, Button, Modal, Paper, Stack, Typography } from "@mui/material";
 import { ObjectEntryInformation } from "../types/ObjectEntryInformation";
 import {ReactElement, useEffect, useState} from "react";
-import { EnumTypeInfo, IntTypeInfo, RealTypeInfo, StructTypeInfo, Type, UIntTypeInfo } from "../types/Type";
+import { ArrayTypeInfo, EnumTypeInfo, IntTypeInfo, RealTypeInfo, StructTypeInfo, Type, UIntTypeInfo } from "../types/Type";
 import UnsignedPropertyInputField from "./UnsignedPropertyInputField";
 import SignedPropertyInputField from "./SignedPropertyInputField";
 import RealPropertyInputField from "./RealPropertyInputField";
@@ -13,7 +13,7 @@ import { listen } from "@tauri-apps/api/event";
 import { ObjectEntryEvent } from "../types/events/ObjectEntryEvent";
 
 
-type OptionalValue = number | string | { [name: string]: OptionalValue } | null | undefined;
+type OptionalValue = number | string | { [name: string]: OptionalValue } | OptionalValue[] | null | undefined;
 
 type SetterLambda = (setter: ((old: OptionalValue) => OptionalValue)) => void;
 
@@ -32,6 +32,18 @@ function isValidValue(ty: Type, value: OptionalValue): boolean {
         }
       }
       return true;
+    case "array": {
+      if (value === null) return false;
+      if (value == undefined) return true;
+      const arrayInfo = ty.info as ArrayTypeInfo;
+      const valueAsArray = value as OptionalValue[];
+      for (let i = 0; i < arrayInfo.len; i++) {
+        if (!isValidValue(arrayInfo.ty, valueAsArray[i])) {
+          return false;
+        }
+      }
+      return true;
+    }
   }
 }
 
@@ -59,6 +71,22 @@ function sendSetRequest(nodeName: string, objectEntryName: string, value: Option
           }
           return autocompletedStruct;
         }
+      case "array":
+        if (value === undefined) {
+          return currentValue ?? null;
+        } else {
+          const arrayInfo = ty.info as ArrayTypeInfo;
+          const valueAsArray = value as OptionalValue[];
+          const currentValueAsArray = currentValue as Value[] | undefined;
+          const autocompletedArray: Value[] = [];
+          for (let i = 0; i < arrayInfo.len; i++) {
+            const auto =
+              autocompleteRec(valueAsArray[i], (currentValueAsArray !== undefined) ? currentValueAsArray[i] : undefined, arrayInfo.ty);
+            if (auto === null) return null;
+            autocompletedArray.push(auto);
+          }
+          return autocompletedArray;
+        }
     }
   }
 
@@ -77,8 +105,8 @@ function sendSetRequest(nodeName: string, objectEntryName: string, value: Option
     nodeName,
     objectEntryName,
     newValueJson: JSON.stringify(autocompletedValue),
-  }).catch((_) => {
-    console.error("Setter failed in the backend");
+  }).catch((reason) => {
+    console.error(`Set request failed: ${reason}`);
   });
 }
 
@@ -241,6 +269,27 @@ function EditDialog({ open, onClose, nodeName, objectEntryInfo }: Readonly<EditD
           }
           break;
         }
+        case "array": {
+          const typeInfo = ty.info as ArrayTypeInfo;
+          for (let i = 0; i < typeInfo.len; i++) {
+            // same as for structs, but the elements are indexed.
+            const elementOnUpdate: SetterLambda = (setter) => {
+              onUpdate(old => {
+                const asArray = [...((old ?? []) as OptionalValue[])];
+                asArray[i] = setter(asArray[i]);
+                const allUndefined = asArray.every(x => x === undefined);
+                return allUndefined ? undefined : asArray;
+              });
+            }
+            let full_name = `${name}[${i}]`;
+            if (currentValue === undefined) {
+              recBuildInputFields(typeInfo.ty, elementOnUpdate, full_name, unit);
+            } else {
+              recBuildInputFields(typeInfo.ty, elementOnUpdate, full_name, unit, (currentValue as Value[])[i]);
+            }
+          }
+          break;
+        }
       }
     }
     const rootSetter: SetterLambda = (setter) => {
@@ -249,6 +298,8 @@ function EditDialog({ open, onClose, nodeName, objectEntryInfo }: Readonly<EditD
         if (objectEntryInfo.ty.id == "struct") {
           const asStruct = (old ?? {}) as { [name: string]: OptionalValue };
           copy = { ...asStruct }; // copy struct
+        } else if (objectEntryInfo.ty.id == "array") {
+          copy = [...((old ?? []) as OptionalValue[])]; // copy array
         } else {
           copy = old; // primitive can just be copied.
         }
@@ -5,7 +5,7 @@ import { Skeleton, Stack } from "@mui/material";
 import { listen } from "@tauri-apps/api/event";
 import { ObjectEntryHistoryEvent } from "../types/events/ObjectEntryHistoryEvent";
 import { ObjectEntryEvent } from "../types/events/ObjectEntryEvent";
-import { EnumTypeInfo, StructTypeInfo, Type } from "../types/Type";
+import { ArrayTypeInfo, EnumTypeInfo, StructTypeInfo, Type } from "../types/Type";
 import { ObjectEntryListenHistoryResponse } from "../types/events/ObjectEntryListenHistoryResponse";
 import { Value } from "../types/Value";
 import NumberGraph from "../../graph/NumberGraph";
@@ -96,7 +96,7 @@ function ObjectEntryGraph({
       }
 
       // this function creates a list of Graphs
-      // for struct types it is called recursively.
+      // for struct and array types it is called recursively.
       function buildGraphList(now: number, ty: Type, property: (event: ObjectEntryEvent) => Value, unit?: string) {
         acc += 1;
         if (ty.id == "int" || ty.id == "uint" || ty.id == "real") {
@@ -138,6 +138,13 @@ function ObjectEntryGraph({
               return (property(event) as { [name: string]: Value })[attrib_name];
             });
           }
+        } else if (ty.id == "array") {
+          let arrayInfo = ty.info as ArrayTypeInfo;
+          for (let i = 0; i < arrayInfo.len; i++) {
+            buildGraphList(now, arrayInfo.ty, (event) => {
+              return (property(event) as Value[])[i];
+            }, unit);
+          }
         } else {
           console.error("INVALID TYPE ID");
         }
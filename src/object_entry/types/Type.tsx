@@ -1,5 +1,5 @@
 
-export type TypeId = "int" | "uint" | "real" | "enum" | "struct"
+export type TypeId = "int" | "uint" | "real" | "enum" | "struct" | "array"
 
 
 export interface Type {
@@ -7,7 +7,7 @@ export interface Type {
   info : TypeInfo
 }
 
-export type TypeInfo = IntTypeInfo | UIntTypeInfo | RealTypeInfo | EnumTypeInfo | StructTypeInfo;
+export type TypeInfo = IntTypeInfo | UIntTypeInfo | RealTypeInfo | EnumTypeInfo | StructTypeInfo | ArrayTypeInfo;
 
 
 export interface IntTypeInfo {
@@ -34,6 +34,11 @@ export interface StructTypeInfo {
   attributes : { [name : string] : Type}
 }
 
+export interface ArrayTypeInfo {
+  len : number,
+  ty : Type
+}
+
 export function isInt(type: TypeId): type is "int" {
   return type === "int";
 }
@@ -50,3 +55,7 @@ export function isEnum(type: TypeId): type is "enum" {
 export function isStruct(type: TypeId): type is "struct" {
   return type === "struct";
 }
+
+export function isArray(type: TypeId): type is "array" {
+  return type === "array";
+}
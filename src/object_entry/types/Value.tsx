@@ -1,2 +1,2 @@
 
-export type Value = number | string | {[name : string] : Value};
+export type Value = number | string | {[name : string] : Value} | Value[];
@@ -8,7 +8,7 @@ import {Skeleton} from "@mui/material";
 import TextField from "@mui/material/TextField";
 import interpolate from "color-interpolate";
 import {ObjectEntryGridInformation} from "./types/ObjectEntryGridInformation.tsx";
-import {isEnum, isInt, isReal, isUInt, StructTypeInfo, Type} from "../object_entry/types/Type.tsx";
+import {ArrayTypeInfo, isArray, isEnum, isInt, isReal, isUInt, StructTypeInfo, Type} from "../object_entry/types/Type.tsx";
 import {Value} from "../object_entry/types/Value.tsx";
 
 function getColorDiscrete(value: number | string, warning: number[] | string[],
@@ -67,6 +67,12 @@ function displayEntryDiscrete(ty: Type, value: Value, name: string,
             },
         }}/>
     } else {
+        if (isArray(ty.id)) {
+            const elementTy = (ty.info as ArrayTypeInfo).ty;
+            return (<>
+                {(value as Value[]).map((element, i) => displayEntryDiscrete(elementTy, element, `${name}[${i}]`, warning, ok, tooGood))}
+            </>)
+        }
         let oec: {[name : string] : Value} = value as {[name : string] : Value}
         return (<>
             {Object.entries(oec).map(([name, value]) => displayEntryDiscrete((ty.info as StructTypeInfo).attributes[name], value, name, warning, ok, tooGood))}
@@ -103,6 +109,12 @@ function displayEntryInterpolate(ty: Type, value: Value, name: string, min: numb
             },
         }}/>
     } else {
+        if (isArray(ty.id)) {
+            const elementTy = (ty.info as ArrayTypeInfo).ty;
+            return (<>
+                {(value as Value[]).map((element, i) => displayEntryInterpolate(elementTy, element, `${name}[${i}]`, min, max))}
+            </>)
+        }
         let oec: {[name : string] : Value} = value as {[name : string] : Value}
         return (<>
             {Object.entries(oec).map(([name, value]) => displayEntryInterpolate((ty.info as StructTypeInfo).attributes[name], value, name, min, max))}
@@ -128,6 +140,12 @@ function displayEntry(ty: Type, value: Value, name: string): ReactElement {
             },
         }}/>
     } else {
+        if (isArray(ty.id)) {
+            const elementTy = (ty.info as ArrayTypeInfo).ty;
+            return (<>
+                {(value as Value[]).map((element, i) => displayEntry(elementTy, element, `${name}[${i}]`))}
+            </>)
+        }
         let oec: {[name : string] : Value} = value as {[name : string] : Value}
         return (<>
             {Object.entries(oec).map(([name, value]) => displayEntry((ty.info as StructTypeInfo).attributes[name], value, name))}
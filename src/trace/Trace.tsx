@@ -2,6 +2,8 @@ import { IconButton, InputAdornment, Paper, Stack, Table, TableBody, TableCell,
 import SearchIcon from '@mui/icons-material/Search';
 import AccessTimeIcon from '@mui/icons-material/AccessTime';
 import ChangeHistoryIcon from '@mui/icons-material/ChangeHistory';
+import SaveAltIcon from '@mui/icons-material/SaveAlt';
+import StopIcon from '@mui/icons-material/Stop';
 import { useEffect, useRef, useState } from 'react';
 import Sortable, { Sorting } from './Sortable';
 import useFocusOnCtrlShortcut from './FocusOnKey';
@@ -72,6 +74,23 @@ function Trace() {
     invoke("filter_trace_by", { filterString }).catch(console.error);
   }
 
+  const [exporting, setExporting] = useState(false);
+
+  async function toggleExport() {
+    if (exporting) {
+      await invoke("stop_trace_export");
+      setExporting(false);
+    } else {
+      setExporting(await invoke<boolean>("start_trace_export", { format: "candump" }));
+    }
+  }
+
+  useEffect(() => {
+    return () => {
+      invoke("stop_trace_export").catch(console.error);
+    };
+  }, []);
+
   const searchFieldRef = useRef() as any;
   useFocusOnCtrlShortcut("f", searchFieldRef)
 
@@ -221,6 +240,13 @@ function Trace() {
                   }}
               >
               </TextField>
+              <IconButton
+                  size="small"
+                  title={exporting ? "Stop trace export" : "Export trace (candump / ASC)"}
+                  onClick={() => toggleExport().catch(console.error)}
+              >
+                {exporting ? <StopIcon fontSize="small" /> : <SaveAltIcon fontSize="small" />}
+              </IconButton>
             </Stack>
 
           </TableCell>